@@ -0,0 +1,317 @@
+//! Reports on the content of a piece of markup without rendering any SSML. Useful for
+//! auditing scripts at scale, since nothing here ever needs to talk to polly.
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::error::{Result, SsmlError};
+
+use crate::parser::{tokenize, SsmlNode, POLLY_MAX_CHARACTERS, POLLY_MAX_TOTAL_CHARACTERS};
+use crate::ssml_constants::{BreakStrength, BreakTime, ProsodyRate};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+/// Statistics about a piece of markup. All counts are based on the text of the markup,
+/// the tags themselves never count as words.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContentStats {
+    /// The amount of whitespace separated words in the text.
+    pub word_count: usize,
+    /// The amount of sentences in the text. A sentence ends with a `.`, `!`, or `?`, or at
+    /// the end of the text.
+    pub sentence_count: usize,
+    /// How many times each tag was opened, keyed by the lowercased tag key. Tags we don't
+    /// know how to render are still counted, so typos show up here.
+    pub tag_usage: BTreeMap<String, usize>,
+    /// Every language switched to with a `${lang|lang=...}` tag.
+    pub languages: BTreeSet<String>,
+    /// The sentence with the most words in it, if there is any text at all.
+    pub longest_sentence: Option<String>,
+}
+
+/// Analyzes a piece of markup, and reports statistics about its content.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::analysis::analyze;
+/// let stats = analyze("Hello there. ${lang|lang=fr-FR}Bonjour tout le monde!${/lang}").unwrap();
+/// assert_eq!(stats.word_count, 6);
+/// assert_eq!(stats.sentence_count, 2);
+/// assert_eq!(stats.tag_usage.get("lang"), Some(&1));
+/// assert_eq!(stats.longest_sentence, Some("Bonjour tout le monde!".to_owned()));
+/// ```
+pub fn analyze(markup: &str) -> Result<ContentStats> {
+    let mut stats = ContentStats::default();
+    let mut text = String::new();
+
+    for item in tokenize(markup)? {
+        if let Some(start_tag) = item.start_tag {
+            let tag_key = start_tag.tag_key.to_lowercase();
+            if tag_key == "lang" {
+                if let Some(lang) = start_tag.params.get("lang") {
+                    stats.languages.insert(lang.to_string());
+                }
+            }
+            *stats.tag_usage.entry(tag_key).or_insert(0) += 1;
+        }
+        if let Some(data) = item.data {
+            text.push_str(&data);
+        }
+    }
+
+    stats.word_count = text.split_whitespace().count();
+    let mut longest_word_count = 0;
+    for sentence in split_sentences(&text) {
+        stats.sentence_count += 1;
+        let word_count = sentence.split_whitespace().count();
+        if word_count > longest_word_count {
+            longest_word_count = word_count;
+            stats.longest_sentence =
+                Some(sentence.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Billing statistics about a rendered SSML document. Polly bills on the characters of the
+/// text being spoken, the tags themselves are never billed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SsmlStats {
+    /// The amount of characters polly bills for: every character of text, whitespace
+    /// included, with escapes resolved.
+    pub billed_characters: usize,
+    /// The amount of characters in the whole document, tags included.
+    pub total_characters: usize,
+    /// How many times each element appears, keyed by the element name as written in the
+    /// SSML, e.g. `amazon:effect`. The root <speak> tag isn't counted.
+    pub tag_counts: BTreeMap<String, usize>,
+    /// The least amount of requests the document has to be split into to stay under both of
+    /// polly's per request limits, see `parser::parse_as_ssml_chunked`.
+    pub requests_needed: usize,
+}
+
+/// Computes the billing statistics of a rendered SSML document. Returns an error if the
+/// document isn't well formed XML.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::analysis::ssml_stats;
+/// let stats = ssml_stats(r#"<speak><p>Hi &amp; bye<break time="1s"/></p></speak>"#).unwrap();
+/// assert_eq!(stats.billed_characters, 8);
+/// assert_eq!(stats.tag_counts.get("break"), Some(&1));
+/// assert_eq!(stats.requests_needed, 1);
+/// ```
+pub fn ssml_stats(ssml: &str) -> Result<SsmlStats> {
+    let mut stats = SsmlStats::default();
+    let mut reader = Reader::from_str(ssml);
+    let mut buf = Vec::new();
+
+    loop {
+        match reader
+            .read_event(&mut buf)
+            .map_err(SsmlError::InvalidSsml)?
+        {
+            Event::Eof => break,
+            Event::Start(ref start) | Event::Empty(ref start) if start.name() != b"speak" => {
+                let name = reader
+                    .decode(start.name())
+                    .map_err(SsmlError::InvalidSsml)?;
+                *stats.tag_counts.entry(name.to_owned()).or_insert(0) += 1;
+            }
+            Event::Text(ref text) => {
+                let text = text
+                    .unescape_and_decode(&reader)
+                    .map_err(SsmlError::InvalidSsml)?;
+                stats.billed_characters += text.chars().count();
+            }
+            Event::CData(ref text) => {
+                let text = reader.decode(text).map_err(SsmlError::InvalidSsml)?;
+                stats.billed_characters += text.chars().count();
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(stats.with_document(ssml))
+}
+
+impl SsmlStats {
+    /// Fills in the totals of the rendered document the characters, and elements were
+    /// counted for.
+    pub(crate) fn with_document(mut self, ssml: &str) -> SsmlStats {
+        self.total_characters = ssml.chars().count();
+        self.requests_needed = self
+            .billed_characters
+            .div_ceil(POLLY_MAX_CHARACTERS)
+            .max(self.total_characters.div_ceil(POLLY_MAX_TOTAL_CHARACTERS))
+            .max(1);
+        self
+    }
+}
+
+/// A typical speaking rate for most voices, in words per minute.
+pub const DEFAULT_WORDS_PER_MINUTE: f32 = 155.0;
+
+/// Estimates how long a markup tree takes to speak. This is a heuristic: every word takes
+/// the same time at the given rate, which is sped up, or slowed down by any
+/// `${prosody|rate=...}` tags wrapping it, and breaks, or silences add their time. The
+/// `duration`, and `max-duration` of a `${prosody}` tag override the time of its content,
+/// `${sub}` tags are estimated by their alias, and `${say-as}` tags spelling out characters
+/// by their characters.
+///
+/// Returns `None` if the rate isn't a positive number of words per minute. Estimates too
+/// long for a `Duration` are `Duration::MAX`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::analysis::estimate_duration;
+/// use text_to_polly_ssml::parser::parse_tree;
+/// use std::time::Duration;
+/// let tree = parse_tree("one two three ${break|time=2s}four five six").unwrap();
+/// assert_eq!(estimate_duration(&tree, 180.0), Some(Duration::from_secs(4)));
+/// assert_eq!(estimate_duration(&tree, 0.0), None);
+/// ```
+pub fn estimate_duration(nodes: &[SsmlNode], words_per_minute: f32) -> Option<Duration> {
+    if !(words_per_minute.is_finite() && words_per_minute > 0.0) {
+        return None;
+    }
+    let seconds = estimate_seconds(nodes, 60.0 / words_per_minute).max(0.0);
+    Some(Duration::try_from_secs_f32(seconds).unwrap_or(Duration::MAX))
+}
+
+/// Estimates the time a list of nodes takes to speak, where every word takes
+/// `seconds_per_word`.
+fn estimate_seconds(nodes: &[SsmlNode], seconds_per_word: f32) -> f32 {
+    let mut seconds = 0.0;
+
+    for node in nodes {
+        let element = match node {
+            SsmlNode::Text(text) => {
+                seconds += text.split_whitespace().count() as f32 * seconds_per_word;
+                continue;
+            }
+            SsmlNode::Element(element) => element,
+        };
+        let param = |key: &str| element.params.get(key);
+        let time_param = |key: &str| {
+            param(key)
+                .and_then(|value| value.parse::<BreakTime>().ok())
+                .map(|time| time.as_secs_f32())
+        };
+
+        seconds += match &*element.name.to_lowercase() {
+            "break" => time_param("time").unwrap_or_else(|| {
+                let strength = param("strength").and_then(|value| value.parse().ok());
+                break_strength_seconds(strength.unwrap_or(BreakStrength::Medium))
+            }),
+            "silence" => time_param("value").unwrap_or(0.0),
+            "prosody" => {
+                let speed = param("rate")
+                    .and_then(|value| value.parse().ok())
+                    .map(rate_speed)
+                    .unwrap_or(1.0);
+                let content = time_param("duration").unwrap_or_else(|| {
+                    estimate_seconds(&element.children, seconds_per_word / speed)
+                });
+                match time_param("max-duration") {
+                    Some(max_duration) => content.min(max_duration),
+                    None => content,
+                }
+            }
+            "sub" => match param("alias") {
+                Some(alias) => alias.split_whitespace().count() as f32 * seconds_per_word,
+                None => estimate_seconds(&element.children, seconds_per_word),
+            },
+            "say-as"
+                if matches!(
+                    param("interpret-as")
+                        .map(|value| value.to_lowercase())
+                        .as_deref(),
+                    Some("characters") | Some("spell-out")
+                ) =>
+            {
+                let characters = element
+                    .children
+                    .iter()
+                    .map(|child| match child {
+                        SsmlNode::Text(text) => text.chars().filter(|c| !c.is_whitespace()).count(),
+                        SsmlNode::Element(_) => 0,
+                    })
+                    .sum::<usize>();
+                characters as f32 * seconds_per_word
+            }
+            _ => estimate_seconds(&element.children, seconds_per_word),
+        };
+    }
+
+    seconds
+}
+
+/// How long a break of a certain strength roughly takes, in seconds.
+fn break_strength_seconds(strength: BreakStrength) -> f32 {
+    match strength {
+        BreakStrength::NoStrength => 0.0,
+        BreakStrength::XWeak => 0.1,
+        BreakStrength::Weak => 0.25,
+        BreakStrength::Medium => 0.5,
+        BreakStrength::Strong => 0.75,
+        BreakStrength::XStrong => 1.0,
+    }
+}
+
+/// How much faster than normal a prosody rate speaks.
+fn rate_speed(rate: ProsodyRate) -> f32 {
+    match rate {
+        ProsodyRate::XSlow => 0.5,
+        ProsodyRate::Slow => 0.75,
+        ProsodyRate::Medium => 1.0,
+        ProsodyRate::Fast => 1.25,
+        ProsodyRate::XFast => 1.5,
+    }
+}
+
+/// Splits some text into sentences. A sentence ends at a run of `.`, `!`, or `?` that is
+/// followed by whitespace (or the end of the text). Sentences without any words are dropped.
+pub(crate) fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((_, character)) = chars.next() {
+        if !matches!(character, '.' | '!' | '?') {
+            continue;
+        }
+        while let Some(&(_, next)) = chars.peek() {
+            if matches!(next, '.' | '!' | '?') {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let end = chars
+            .peek()
+            .map(|&(next_idx, _)| next_idx)
+            .unwrap_or(text.len());
+        let at_boundary = chars
+            .peek()
+            .map(|&(_, next)| next.is_whitespace())
+            .unwrap_or(true);
+        if at_boundary {
+            sentences.push(&text[start..end]);
+            start = end;
+        }
+    }
+    sentences.push(&text[start..]);
+
+    sentences
+        .into_iter()
+        .map(|sentence| sentence.trim())
+        .filter(|sentence| sentence.split_whitespace().next().is_some())
+        .collect()
+}
@@ -0,0 +1,84 @@
+//! Streaming SSML into a `tokio` `AsyncWrite`, e.g. a network connection, from an async
+//! service. The SSML is written into a buffer with the methods of `XmlWriter`, and only
+//! the buffer is sent to the sink, so nothing blocks on the sink while it is written.
+//!
+//! ```rust
+//! use text_to_polly_ssml::async_writer::AsyncXmlWriter;
+//! use text_to_polly_ssml::xml_writer::WriterOptions;
+//!
+//! async fn respond<W: tokio::io::AsyncWrite + Unpin>(sink: W) -> text_to_polly_ssml::Result<W> {
+//!     let mut xml_writer = AsyncXmlWriter::new(sink, WriterOptions::default())?;
+//!     xml_writer.start_ssml_speak(None, None)?;
+//!     xml_writer.write_text("Hello")?;
+//!     xml_writer.flush().await?;
+//!     xml_writer.write_text(" world")?;
+//!     xml_writer.end_ssml_speak()?;
+//!     xml_writer.finish().await
+//! }
+//! ```
+
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::error::{Result, SsmlError};
+use crate::xml_writer::{WriterOptions, XmlWriter};
+
+use std::io::Cursor;
+use std::ops::{Deref, DerefMut};
+
+/// An `XmlWriter` that sends what was written to an `AsyncWrite` whenever it is flushed.
+///
+/// It dereferences to the `XmlWriter` that buffers the SSML, so every method of it can be
+/// used to write. Rendering it only renders what hasn't been flushed yet.
+pub struct AsyncXmlWriter<W: AsyncWrite + Unpin> {
+    xml_writer: XmlWriter,
+    sink: W,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncXmlWriter<W> {
+    /// Creates a new writer sending the SSML to `sink`. Like with `XmlWriter::from_writer`,
+    /// `pretty` indents the SSML, and `ascii_safe` escapes it as it is written.
+    pub fn new(sink: W, options: WriterOptions) -> Result<AsyncXmlWriter<W>> {
+        Ok(AsyncXmlWriter {
+            xml_writer: XmlWriter::from_writer(Cursor::new(Vec::new()), options)?,
+            sink,
+        })
+    }
+
+    /// The amount of bytes waiting to be sent to the sink.
+    pub fn buffered_len(&mut self) -> usize {
+        self.xml_writer.written_len()
+    }
+
+    /// Sends everything written so far to the sink, and flushes it.
+    pub async fn flush(&mut self) -> Result<()> {
+        let written = self.xml_writer.take_written();
+        self.sink.write_all(&written).await.map_err(sink_error)?;
+        self.sink.flush().await.map_err(sink_error)
+    }
+
+    /// Sends everything that is left to the sink, and returns it. It is your job to close
+    /// all tags before.
+    pub async fn finish(mut self) -> Result<W> {
+        self.flush().await?;
+        Ok(self.sink)
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Deref for AsyncXmlWriter<W> {
+    type Target = XmlWriter;
+
+    fn deref(&self) -> &XmlWriter {
+        &self.xml_writer
+    }
+}
+
+impl<W: AsyncWrite + Unpin> DerefMut for AsyncXmlWriter<W> {
+    fn deref_mut(&mut self) -> &mut XmlWriter {
+        &mut self.xml_writer
+    }
+}
+
+/// Errors of the sink are reported like the errors of the sink of an `XmlWriter`.
+fn sink_error(error: std::io::Error) -> SsmlError {
+    SsmlError::WriterError(quick_xml::Error::Io(error))
+}
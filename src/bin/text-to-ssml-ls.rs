@@ -0,0 +1,9 @@
+//! A language server for the markup, see `text_to_polly_ssml::lsp`. Editors start it, and
+//! talk to it over stdin, and stdout.
+
+fn main() {
+    if let Err(error) = text_to_polly_ssml::lsp::serve_stdio() {
+        eprintln!("text-to-ssml-ls: {}", error);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,12 @@
+//! An HTTP service converting markup into SSML, see `text_to_polly_ssml::server`. Listens
+//! on the address given as the only argument, `127.0.0.1:8080` if there isn't one.
+
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_owned());
+    if let Err(error) = text_to_polly_ssml::server::serve(&address) {
+        eprintln!("text-to-ssml-server: {}", error);
+        std::process::exit(1);
+    }
+}
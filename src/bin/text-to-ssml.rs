@@ -0,0 +1,467 @@
+//! Converts markup into SSML from the command line. Reads the markup from a file, or stdin,
+//! and writes the SSML to a file, or stdout. Given multiple files, or a glob every file is
+//! converted into a `.ssml` file of the same name in an output directory. With the `jsonl`
+//! feature it can also convert a stream of JSON records, one per line.
+
+use text_to_polly_ssml::{OutputTarget, ParseOptions};
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+const USAGE: &str = "\
+Converts markup into SSML.
+
+Usage: text-to-ssml [OPTIONS] [INPUT]...
+
+Arguments:
+  [INPUT]...  The files to read the markup from, stdin if there are none, or `-`. Patterns
+              with `*`, `?`, or `**` are expanded to every file they match
+
+Options:
+  -o, --output <FILE>    The file to write the SSML to, stdout if it is missing
+  -d, --out-dir <DIR>    The directory to write a `.ssml` file for every input to, needed for
+                         more than one input
+      --lang <LANG>      The language of the document, e.g. `en-GB`
+      --target <TARGET>  The engine the SSML is for: polly, alexa, google, azure, watson, or generic
+      --strict           Fail on unknown, unbalanced, or misnested tags instead of ignoring them
+      --pretty           Indent the SSML
+      --jsonl            Read a JSON object with a `markup` field per line, and write it back with
+                         the `ssml`, and `warnings`, or an `error` added to it
+  -h, --help             Print this help
+  -V, --version          Print the version";
+
+/// Where to read the markup from, where to write the SSML to, and how to render it.
+struct Args {
+    inputs: Vec<String>,
+    output: Option<String>,
+    out_dir: Option<String>,
+    jsonl: bool,
+    options: ParseOptions,
+}
+
+fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => return,
+        Err(message) => {
+            eprintln!("text-to-ssml: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+    let result = match args.out_dir {
+        Some(ref out_dir) => run_batch(&args, Path::new(out_dir)),
+        None if args.jsonl => run_jsonl(&args),
+        None => run(&args),
+    };
+    if let Err(message) = result {
+        eprintln!("text-to-ssml: {}", message);
+        process::exit(1);
+    }
+}
+
+/// Parses the command line. Returns `None` if the help, or version was printed instead.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut parsed = Args {
+        inputs: Vec::new(),
+        output: None,
+        out_dir: None,
+        jsonl: false,
+        options: ParseOptions::default(),
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .ok_or_else(|| format!("`{}` needs a value.", flag))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(None);
+            }
+            "-V" | "--version" => {
+                println!("text-to-ssml {}", env!("CARGO_PKG_VERSION"));
+                return Ok(None);
+            }
+            "-o" | "--output" => parsed.output = Some(value(&arg)?),
+            "-d" | "--out-dir" => parsed.out_dir = Some(value(&arg)?),
+            "--lang" => parsed.options.speak.lang = Some(value(&arg)?),
+            "--target" => {
+                parsed.options.target = value(&arg)?
+                    .parse::<OutputTarget>()
+                    .map_err(|error| error.to_string())?;
+            }
+            "--strict" => parsed.options.strict = true,
+            "--pretty" => parsed.options.pretty = true,
+            "--jsonl" if cfg!(feature = "jsonl") => parsed.jsonl = true,
+            "--jsonl" => return Err("`--jsonl` needs the `jsonl` feature.".to_owned()),
+            "-" => {}
+            flag if flag.starts_with('-') => return Err(format!("Unknown option `{}`.", flag)),
+            _ => parsed.inputs.push(arg),
+        }
+    }
+
+    if parsed.output.is_some() && parsed.out_dir.is_some() {
+        return Err("`--output`, and `--out-dir` can't be used together.".to_owned());
+    }
+    if parsed.jsonl && parsed.out_dir.is_some() {
+        return Err("`--jsonl`, and `--out-dir` can't be used together.".to_owned());
+    }
+    if parsed.out_dir.is_none()
+        && (parsed.inputs.len() > 1 || parsed.inputs.iter().any(|input| is_glob(input)))
+    {
+        return Err("More than one input needs an `--out-dir`.".to_owned());
+    }
+    Ok(Some(parsed))
+}
+
+/// Reads the markup, renders it, and writes the SSML.
+fn run(args: &Args) -> Result<(), String> {
+    let mut markup = String::new();
+    open_input(args)?
+        .read_to_string(&mut markup)
+        .map_err(|error| format!("Failed to read: {}", error))?;
+
+    let ssml = text_to_polly_ssml::parse_str_with_options(&markup, &args.options)
+        .map_err(|error| describe(&error))?;
+
+    let mut output = open_output(args)?;
+    writeln!(output, "{}", ssml)
+        .and_then(|_| output.flush())
+        .map_err(|error| format!("Failed to write: {}", error))
+}
+
+/// Opens the input, or stdin.
+fn open_input(args: &Args) -> Result<Box<dyn BufRead>, String> {
+    match args.inputs.first() {
+        Some(path) => fs::File::open(path)
+            .map(|file| Box::new(BufReader::new(file)) as Box<dyn BufRead>)
+            .map_err(|error| format!("Failed to read `{}`: {}", path, error)),
+        None => Ok(Box::new(BufReader::new(io::stdin()))),
+    }
+}
+
+/// Opens the output, or stdout.
+fn open_output(args: &Args) -> Result<Box<dyn Write>, String> {
+    match args.output {
+        Some(ref path) => fs::File::create(path)
+            .map(|file| Box::new(BufWriter::new(file)) as Box<dyn Write>)
+            .map_err(|error| format!("Failed to write `{}`: {}", path, error)),
+        None => Ok(Box::new(BufWriter::new(io::stdout()))),
+    }
+}
+
+#[cfg(not(feature = "jsonl"))]
+fn run_jsonl(_: &Args) -> Result<(), String> {
+    unreachable!("`--jsonl` is rejected without the `jsonl` feature")
+}
+
+/// Renders the `markup` of every JSON object of the input, one per line, and writes the
+/// object back with the `ssml`, and `warnings` added to it. A record that can't be rendered
+/// gets an `error` instead, and doesn't stop the others from being rendered. Empty lines
+/// are skipped.
+#[cfg(feature = "jsonl")]
+fn run_jsonl(args: &Args) -> Result<(), String> {
+    use serde_json::{json, Map, Value};
+
+    let input = open_input(args)?;
+    let mut output = open_output(args)?;
+    let mut failed = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.map_err(|error| format!("Failed to read line {}: {}", index + 1, error))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut record = Map::new();
+        let result = match serde_json::from_str::<Value>(&line) {
+            Ok(Value::Object(object)) => {
+                record = object;
+                match record.get("markup") {
+                    Some(Value::String(markup)) => {
+                        text_to_polly_ssml::parse_with_report(markup, &args.options)
+                            .map_err(|error| describe(&error))
+                    }
+                    _ => Err("The record doesn't have a `markup` string.".to_owned()),
+                }
+            }
+            Ok(_) => Err("The record isn't a JSON object.".to_owned()),
+            Err(error) => Err(format!("Invalid JSON: {}", error)),
+        };
+
+        match result {
+            Ok(report) => {
+                let warnings = report
+                    .warnings
+                    .iter()
+                    .map(|warning| {
+                        json!({
+                            "kind": format!("{:?}", warning.kind),
+                            "tag": warning.tag,
+                            "attribute": warning.attribute,
+                            "message": warning.message,
+                            "line": warning.span.map(|span| span.line),
+                            "column": warning.span.map(|span| span.column),
+                        })
+                    })
+                    .collect();
+                record.insert("ssml".to_owned(), Value::String(report.ssml));
+                record.insert("warnings".to_owned(), Value::Array(warnings));
+            }
+            Err(message) => {
+                failed += 1;
+                record.insert("line".to_owned(), json!(index + 1));
+                record.insert("error".to_owned(), Value::String(message));
+            }
+        }
+        writeln!(output, "{}", Value::Object(record))
+            .and_then(|_| output.flush())
+            .map_err(|error| format!("Failed to write: {}", error))?;
+    }
+
+    if failed > 0 {
+        return Err(format!("{} record{} failed.", failed, plural(failed)));
+    }
+    Ok(())
+}
+
+/// Converts every input into a `.ssml` file of the same name inside of `out_dir`, and
+/// prints the warnings of every file, and a summary to stderr. A file that fails doesn't
+/// stop the others from being converted.
+fn run_batch(args: &Args, out_dir: &Path) -> Result<(), String> {
+    let mut inputs = Vec::new();
+    for input in &args.inputs {
+        if is_glob(input) {
+            let matches = expand_glob(input);
+            if matches.is_empty() {
+                return Err(format!("`{}` doesn't match any files.", input));
+            }
+            inputs.extend(matches);
+        } else {
+            inputs.push(PathBuf::from(input));
+        }
+    }
+    if inputs.is_empty() {
+        return Err("`--out-dir` needs at least one input file.".to_owned());
+    }
+    // A file given more than once, e.g. by a glob, and by name, is only converted once.
+    let mut seen = HashSet::new();
+    inputs.retain(|input| seen.insert(canonical(input)));
+
+    // Nothing is written if two inputs would be written to the same file, or an input would
+    // be overwritten.
+    let mut outputs: HashMap<PathBuf, &Path> = HashMap::new();
+    for input in &inputs {
+        let output = output_path(input, out_dir)?;
+        if let Some(overwritten) = inputs
+            .iter()
+            .find(|other| canonical(other) == canonical(&output))
+        {
+            return Err(format!(
+                "The output of `{}` would overwrite the input `{}`.",
+                input.display(),
+                overwritten.display()
+            ));
+        }
+        if let Some(other) = outputs.insert(output.clone(), input) {
+            return Err(format!(
+                "`{}`, and `{}` would both be written to `{}`.",
+                other.display(),
+                input.display(),
+                output.display()
+            ));
+        }
+    }
+    fs::create_dir_all(out_dir)
+        .map_err(|error| format!("Failed to create `{}`: {}", out_dir.display(), error))?;
+
+    let mut failed = 0;
+    let mut warnings = 0;
+    for input in &inputs {
+        match convert_file(input, out_dir, &args.options) {
+            Ok(file_warnings) => {
+                if !file_warnings.is_empty() {
+                    eprintln!(
+                        "{}: {} warning{}",
+                        input.display(),
+                        file_warnings.len(),
+                        plural(file_warnings.len())
+                    );
+                    for warning in &file_warnings {
+                        eprintln!("  {}", warning);
+                    }
+                }
+                warnings += file_warnings.len();
+            }
+            Err(message) => {
+                eprintln!("{}: {}", input.display(), message);
+                failed += 1;
+            }
+        }
+    }
+
+    eprintln!(
+        "Converted {} of {} file{}, {} warning{}.",
+        inputs.len() - failed,
+        inputs.len(),
+        plural(inputs.len()),
+        warnings,
+        plural(warnings)
+    );
+    if failed > 0 {
+        return Err(format!("{} file{} failed.", failed, plural(failed)));
+    }
+    Ok(())
+}
+
+/// Converts a single file, and returns the warnings of rendering it.
+fn convert_file(
+    input: &Path,
+    out_dir: &Path,
+    options: &ParseOptions,
+) -> Result<Vec<text_to_polly_ssml::Warning>, String> {
+    let markup = fs::read_to_string(input).map_err(|error| format!("Failed to read: {}", error))?;
+    let report = text_to_polly_ssml::parse_with_report(&markup, options)
+        .map_err(|error| describe(&error))?;
+    let output = output_path(input, out_dir)?;
+    fs::write(&output, format!("{}\n", report.ssml))
+        .map_err(|error| format!("Failed to write `{}`: {}", output.display(), error))?;
+    Ok(report.warnings)
+}
+
+/// Where the SSML of an input is written: its name with the extension replaced by `.ssml`.
+fn output_path(input: &Path, out_dir: &Path) -> Result<PathBuf, String> {
+    let name = input
+        .file_name()
+        .ok_or_else(|| format!("`{}` is not a file.", input.display()))?;
+    let mut output = out_dir.join(name);
+    output.set_extension("ssml");
+    Ok(output)
+}
+
+/// The path with any symlinks, and `..` resolved, or as it is if it doesn't exist.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+fn is_glob(input: &str) -> bool {
+    input.contains(['*', '?'])
+}
+
+/// Every file a glob matches, sorted. `*` matches any characters of a name, `?` a single
+/// one, and `**` any number of directories. Hidden files are only matched by patterns that
+/// start with a `.`.
+fn expand_glob(pattern: &str) -> Vec<PathBuf> {
+    let mut paths = vec![if pattern.starts_with('/') {
+        PathBuf::from("/")
+    } else {
+        PathBuf::new()
+    }];
+
+    for component in pattern.split('/').filter(|component| !component.is_empty()) {
+        let mut next = Vec::new();
+        for path in &paths {
+            if component == "**" {
+                collect_dirs(path, &mut next);
+            } else if component.contains(['*', '?']) {
+                for name in read_names(path) {
+                    let hidden = name.starts_with('.') && !component.starts_with('.');
+                    if !hidden && wildcard_match(component, &name) {
+                        next.push(path.join(name));
+                    }
+                }
+            } else {
+                next.push(path.join(component));
+            }
+        }
+        paths = next;
+    }
+
+    paths.retain(|path| path.is_file());
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// The names of the entries of a directory, or nothing if it can't be read.
+fn read_names(dir: &Path) -> Vec<String> {
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A directory, and every directory inside of it that isn't hidden.
+fn collect_dirs(dir: &Path, dirs: &mut Vec<PathBuf>) {
+    dirs.push(dir.to_owned());
+    for name in read_names(dir) {
+        let path = dir.join(&name);
+        if !name.starts_with('.') && path.is_dir() {
+            collect_dirs(&path, dirs);
+        }
+    }
+}
+
+/// If a name matches a pattern with `*`, and `?` wildcards.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where the last `*` was, and how much of the name it matched.
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&character) if character == '?' || character == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|character| *character == '*')
+}
+
+/// An error, and every error that caused it.
+fn describe(error: &dyn Error) -> String {
+    let mut description = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        description.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    description
+}
@@ -0,0 +1,384 @@
+//! A high level way of building SSML from code. Unlike the `XmlWriter` every tag that wraps
+//! content takes a closure for that content, so tags are always closed, and always closed
+//! in the right order.
+
+use crate::error::{Result, SsmlError};
+
+use crate::ssml_constants::*;
+use crate::xml_writer::{MediaOptions, ProsodyOptions, SpeakOptions, WriterOptions, XmlWriter};
+
+/// Builds an SSML document with correctly nested tags.
+///
+/// Every method returns the builder so calls can be chained. If writing a tag fails the
+/// error is remembered, everything after it is skipped, and the error is returned from
+/// `build`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::builder::SsmlBuilder;
+/// use text_to_polly_ssml::ssml_constants::ProsodyRate;
+/// use text_to_polly_ssml::xml_writer::ProsodyOptions;
+///
+/// let mut builder = SsmlBuilder::new().unwrap();
+/// builder.paragraph(|p| {
+///   p.text("Hello, ").prosody(
+///     ProsodyOptions { rate: Some(ProsodyRate::XFast), ..Default::default() },
+///     |s| { s.text("coffee coffee coffee"); },
+///   );
+/// });
+/// let ssml = builder.build().unwrap();
+/// assert!(ssml.ends_with(r#"<p>Hello, <prosody rate="x-fast">coffee coffee coffee</prosody></p></speak>"#));
+/// ```
+pub struct SsmlBuilder {
+    writer: XmlWriter,
+    error: Option<SsmlError>,
+}
+
+impl SsmlBuilder {
+    /// Creates a new builder, with the default root <speak> tag already opened.
+    pub fn new() -> Result<SsmlBuilder> {
+        SsmlBuilder::with_options(WriterOptions::default(), &SpeakOptions::default())
+    }
+
+    /// Creates a new builder with the specified writer options, and root <speak> tag.
+    pub fn with_options(
+        writer_options: WriterOptions,
+        speak_options: &SpeakOptions,
+    ) -> Result<SsmlBuilder> {
+        let mut writer = XmlWriter::with_options(writer_options)?;
+        writer.start_ssml_speak_with_options(speak_options)?;
+        Ok(SsmlBuilder {
+            writer,
+            error: None,
+        })
+    }
+
+    /// Closes the root <speak> tag, and renders the document. Returns the first error that
+    /// happened while building if there was one.
+    pub fn build(mut self) -> Result<String> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.writer.end_ssml_speak()?;
+        Ok(self.writer.into_string())
+    }
+
+    /// Writes some raw text.
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.write(|writer| writer.write_text(text))
+    }
+
+    /// Writes a <break> tag.
+    pub fn ssml_break(
+        &mut self,
+        strength: Option<BreakStrength>,
+        time: Option<BreakTime>,
+    ) -> &mut Self {
+        self.write(|writer| writer.ssml_break(strength, time))
+    }
+
+    /// Writes an Azure <mstts:silence> tag.
+    pub fn mstts_silence(&mut self, silence_type: SilenceType, value: BreakTime) -> &mut Self {
+        self.write(|writer| writer.ssml_mstts_silence(silence_type, value))
+    }
+
+    /// Writes an Azure <bookmark> tag.
+    pub fn bookmark(&mut self, mark: &str) -> &mut Self {
+        self.write(|writer| writer.ssml_bookmark(mark.to_owned()))
+    }
+
+    /// Writes an empty <mark> tag.
+    pub fn mark(&mut self, name: &str) -> &mut Self {
+        self.write(|writer| writer.ssml_mark(name.to_owned()))
+    }
+
+    /// Writes an <amazon:breath> tag.
+    pub fn amazon_breath(&mut self, volume: BreathVolumes, duration: BreathDuration) -> &mut Self {
+        self.write(|writer| writer.write_amazon_breath(volume, duration))
+    }
+
+    /// Wraps the content in an <emphasis> tag.
+    pub fn emphasis<F>(&mut self, level: Option<EmphasisLevel>, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_emphasis(level),
+            XmlWriter::end_ssml_emphasis,
+            content,
+        )
+    }
+
+    /// Wraps the content in a <lang> tag.
+    pub fn lang<F>(&mut self, lang: &str, onlangfailure: Option<&str>, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        let onlangfailure = onlangfailure.map(|value| value.to_owned());
+        self.scoped(
+            |writer| writer.start_ssml_lang(lang.to_owned(), onlangfailure),
+            XmlWriter::end_ssml_lang,
+            content,
+        )
+    }
+
+    /// Wraps the content in a <mark> tag, like older versions rendered `${mark}`. Prefer
+    /// `mark`, as the SSML spec defines <mark> as an empty element.
+    pub fn wrapping_mark<F>(&mut self, name: &str, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_mark(name.to_owned()),
+            XmlWriter::end_ssml_mark,
+            content,
+        )
+    }
+
+    /// Wraps the content in a <p> tag.
+    pub fn paragraph<F>(&mut self, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            XmlWriter::start_ssml_paragraph,
+            XmlWriter::end_ssml_paragraph,
+            content,
+        )
+    }
+
+    /// Wraps the content in a <phoneme> tag.
+    pub fn phoneme<F>(&mut self, alphabet: PhonemeAlphabet, ph: &str, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_phoneme(alphabet, ph.to_owned()),
+            XmlWriter::end_ssml_phoneme,
+            content,
+        )
+    }
+
+    /// Wraps the content in a <prosody> tag.
+    pub fn prosody<F>(&mut self, options: ProsodyOptions, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_prosody_with_options(options),
+            XmlWriter::end_ssml_prosody,
+            content,
+        )
+    }
+
+    /// Wraps the content in a <s> tag.
+    pub fn sentence<F>(&mut self, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            XmlWriter::start_ssml_sentence,
+            XmlWriter::end_ssml_sentence,
+            content,
+        )
+    }
+
+    /// Wraps the content in a <say-as> tag.
+    pub fn say_as<F>(&mut self, interpret_as: &str, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_say_as(interpret_as.to_owned()),
+            XmlWriter::end_ssml_say_as,
+            content,
+        )
+    }
+
+    /// Wraps the content in a <sub> tag.
+    pub fn sub<F>(&mut self, alias: &str, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_sub(alias.to_owned()),
+            XmlWriter::end_ssml_sub,
+            content,
+        )
+    }
+
+    /// Wraps the content in a <voice> tag.
+    pub fn voice<F>(&mut self, name: &str, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_voice(name.to_owned()),
+            XmlWriter::end_ssml_voice,
+            content,
+        )
+    }
+
+    /// Wraps the content in a <w> tag.
+    pub fn word<F>(&mut self, role: WordRole, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_w(role),
+            XmlWriter::end_ssml_w,
+            content,
+        )
+    }
+
+    /// Wraps the content in an <amazon:effect name="..."> tag.
+    pub fn amazon_effect<F>(&mut self, effect: AmazonEffect, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_amazon_effect(effect),
+            XmlWriter::end_ssml_amazon_effect,
+            content,
+        )
+    }
+
+    /// Wraps the content in an <amazon:effect vocal-tract-length="..."> tag.
+    pub fn vocal_tract_length<F>(&mut self, factor: VocalTractLength, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_vocal_tract_length(factor),
+            XmlWriter::end_ssml_amazon_effect,
+            content,
+        )
+    }
+
+    /// Wraps the content in an <amazon:effect phonation="..."> tag.
+    pub fn phonation<F>(&mut self, volume: PhonationVolume, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_phonation(volume),
+            XmlWriter::end_ssml_amazon_effect,
+            content,
+        )
+    }
+
+    /// Wraps the content in an <amazon:auto-breaths> tag.
+    pub fn auto_breaths<F>(
+        &mut self,
+        volume: BreathVolumes,
+        frequency: AutoBreathFrequency,
+        duration: BreathDuration,
+        content: F,
+    ) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_auto_breaths(volume, frequency, duration),
+            XmlWriter::end_ssml_amazon_auto_breaths,
+            content,
+        )
+    }
+
+    /// Wraps the content in an <amazon:domain> tag.
+    pub fn amazon_domain<F>(&mut self, name: AmazonDomainNames, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_amazon_domain(name),
+            XmlWriter::end_ssml_amazon_domain,
+            content,
+        )
+    }
+
+    /// Wraps the content in an <amazon:emotion> tag.
+    pub fn amazon_emotion<F>(
+        &mut self,
+        name: EmotionName,
+        intensity: EmotionIntensity,
+        content: F,
+    ) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_amazon_emotion(name, intensity),
+            XmlWriter::end_ssml_amazon_emotion,
+            content,
+        )
+    }
+
+    /// Wraps the content in a Google <par> tag.
+    pub fn par<F>(&mut self, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(XmlWriter::start_ssml_par, XmlWriter::end_ssml_par, content)
+    }
+
+    /// Wraps the content in a Google <seq> tag.
+    pub fn seq<F>(&mut self, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(XmlWriter::start_ssml_seq, XmlWriter::end_ssml_seq, content)
+    }
+
+    /// Wraps the content in a Google <media> tag.
+    pub fn media<F>(&mut self, options: MediaOptions, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_media(&options),
+            XmlWriter::end_ssml_media,
+            content,
+        )
+    }
+
+    /// Wraps the content in an IBM Watson <express-as> tag.
+    pub fn express_as<F>(&mut self, express_as_type: ExpressAsType, content: F) -> &mut Self
+    where
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.scoped(
+            |writer| writer.start_ssml_express_as(express_as_type),
+            XmlWriter::end_ssml_express_as,
+            content,
+        )
+    }
+
+    /// Runs a single write against the writer, unless an earlier write already failed.
+    fn write<W>(&mut self, write: W) -> &mut Self
+    where
+        W: FnOnce(&mut XmlWriter) -> Result<()>,
+    {
+        if self.error.is_none() {
+            if let Err(error) = write(&mut self.writer) {
+                self.error = Some(error);
+            }
+        }
+        self
+    }
+
+    /// Opens a tag, builds its content, and closes it again.
+    fn scoped<S, E, F>(&mut self, start: S, end: E, content: F) -> &mut Self
+    where
+        S: FnOnce(&mut XmlWriter) -> Result<()>,
+        E: FnOnce(&mut XmlWriter) -> Result<()>,
+        F: FnOnce(&mut SsmlBuilder),
+    {
+        self.write(start);
+        if self.error.is_none() {
+            content(self);
+        }
+        self.write(end)
+    }
+}
@@ -0,0 +1,168 @@
+//! Structural comparison of SSML documents. Two documents are considered equal if they
+//! contain the same elements, attributes, and text, no matter the order of the attributes,
+//! how the text was escaped, or insignificant whitespace.
+
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+use crate::error::{Result, SsmlError};
+
+use std::io::Cursor;
+
+/// A single piece of a document once all cosmetic differences have been removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum CanonicalEvent {
+    Start(String, Vec<(String, String)>),
+    End(String),
+    Text(String),
+}
+
+/// Reads a document into its canonical events. Self-closing elements are expanded, the
+/// attributes are sorted, everything is unescaped, whitespace is collapsed, and the XML
+/// declaration, comments, and processing instructions are dropped.
+fn canonical_events(ssml: &str) -> quick_xml::Result<Vec<CanonicalEvent>> {
+    let mut reader = Reader::from_str(ssml);
+    reader.expand_empty_elements(true);
+    let mut events = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event(&mut buf)? {
+            Event::Eof => break,
+            Event::Start(start) => {
+                let name = reader.decode(start.name())?.to_owned();
+                let mut attributes = Vec::new();
+                for attribute in start.attributes() {
+                    let attribute = attribute?;
+                    attributes.push((
+                        reader.decode(attribute.key)?.to_owned(),
+                        reader.decode(&attribute.unescaped_value()?)?.to_owned(),
+                    ));
+                }
+                attributes.sort();
+                events.push(CanonicalEvent::Start(name, attributes));
+            }
+            Event::End(end) => {
+                events.push(CanonicalEvent::End(reader.decode(end.name())?.to_owned()));
+            }
+            Event::Text(text) | Event::CData(text) => {
+                let unescaped = text.unescaped()?;
+                let collapsed = reader
+                    .decode(&unescaped)?
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if collapsed.is_empty() {
+                    continue;
+                }
+                // Text split up by a comment, or CDATA section is still one run of text.
+                if let Some(CanonicalEvent::Text(previous)) = events.last_mut() {
+                    previous.push(' ');
+                    previous.push_str(&collapsed);
+                } else {
+                    events.push(CanonicalEvent::Text(collapsed));
+                }
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(events)
+}
+
+/// Rewrites an SSML document into its canonical form: the XML declaration, comments, and
+/// processing instructions are dropped, attributes are sorted by name, empty elements are
+/// written with an explicit end tag, and whitespace in text is collapsed. Two documents are
+/// equal according to `ssml_eq` exactly when their canonical forms are the same, which
+/// makes this useful for snapshotting, or diffing documents. Returns an error if the
+/// document isn't well formed XML.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::compare::canonicalize;
+/// let canonical = canonicalize(
+///   r#"<?xml version="1.0"?><speak><prosody volume="loud" rate="fast">  hey </prosody><break/></speak>"#,
+/// );
+/// assert_eq!(
+///   canonical.unwrap(),
+///   r#"<speak><prosody rate="fast" volume="loud">hey</prosody><break></break></speak>"#,
+/// );
+/// ```
+pub fn canonicalize(ssml: &str) -> Result<String> {
+    let events = canonical_events(ssml).map_err(SsmlError::InvalidSsml)?;
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+
+    for event in events {
+        match event {
+            CanonicalEvent::Start(name, attributes) => {
+                let mut start = BytesStart::owned_name(name.into_bytes());
+                for (key, value) in &attributes {
+                    start.push_attribute((key.as_str(), value.as_str()));
+                }
+                writer.write_event(Event::Start(start))?;
+            }
+            CanonicalEvent::End(name) => {
+                writer.write_event(Event::End(BytesEnd::owned(name.into_bytes())))?;
+            }
+            CanonicalEvent::Text(text) => {
+                writer.write_event(Event::Text(BytesText::from_plain_str(&text)))?;
+            }
+        }
+    }
+
+    Ok(String::from_utf8(writer.into_inner().into_inner())?)
+}
+
+/// Compares two SSML documents structurally. Attribute order, escaping, self-closing vs
+/// explicitly closed empty elements, the XML declaration, and insignificant whitespace are
+/// all ignored. Returns an error if either document isn't well formed XML.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::compare::ssml_eq;
+/// assert!(ssml_eq(
+///   r#"<speak><prosody rate="fast" volume="loud">  hey  </prosody><break/></speak>"#,
+///   r#"<?xml version="1.0"?><speak><prosody volume="loud" rate="fast">hey</prosody><break></break></speak>"#,
+/// ).unwrap());
+/// assert!(!ssml_eq("<speak>hey</speak>", "<speak>hi</speak>").unwrap());
+/// ```
+pub fn ssml_eq(left: &str, right: &str) -> Result<bool> {
+    let left = canonical_events(left).map_err(SsmlError::InvalidSsml)?;
+    let right = canonical_events(right).map_err(SsmlError::InvalidSsml)?;
+    Ok(left == right)
+}
+
+/// Asserts two SSML documents are structurally equal (see `ssml_eq`), panicking with both
+/// documents if they aren't, or if either isn't well formed XML. Meant for snapshot tests
+/// that shouldn't break on cosmetic changes to the output.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::compare::assert_ssml_eq;
+/// assert_ssml_eq(
+///   &text_to_polly_ssml::parse_str("${s} hey ${/s}").unwrap(),
+///   r#"<speak xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns="http://www.w3.org/2001/10/synthesis"
+///     onlangfailure="processorchoice" xml:lang="en-US">
+///     <s>hey</s>
+///   </speak>"#,
+/// );
+/// ```
+#[cfg(feature = "testing")]
+#[track_caller]
+pub fn assert_ssml_eq(left: &str, right: &str) {
+    match ssml_eq(left, right) {
+        Ok(true) => {}
+        Ok(false) => panic!(
+            "SSML documents are not equal:\n  left: {}\n right: {}",
+            left, right
+        ),
+        Err(error) => panic!(
+            "SSML documents could not be compared: {:?}\n  left: {}\n right: {}",
+            error, left, right
+        ),
+    }
+}
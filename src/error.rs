@@ -0,0 +1,188 @@
+//! The errors this crate returns. Every public function returns an `SsmlError`, so callers
+//! can match on what went wrong instead of having to parse an error message.
+
+use thiserror::Error;
+
+use std::path::PathBuf;
+use std::string::FromUtf8Error;
+
+/// A `Result` defaulting to an `SsmlError`.
+pub type Result<T, E = SsmlError> = std::result::Result<T, E>;
+
+/// Everything that can go wrong while turning markup into SSML, or working with SSML.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SsmlError {
+    /// The markup couldn't be split into tags, and text. `snippet` is the text at `line`,
+    /// and `column` where parsing failed, and `raw` the error of the underlying parser.
+    #[error("Failed to parse the markup at line {line}, column {column}: `{snippet}`.")]
+    ParseError {
+        line: usize,
+        column: usize,
+        snippet: String,
+        raw: String,
+    },
+    /// A tag that isn't one we know how to render.
+    #[error("Unknown tag `{0}`.")]
+    UnknownTag(String),
+    /// A tag the output target doesn't support.
+    #[error("`{tag}` isn't supported by the {target} output target.")]
+    UnsupportedTag { tag: String, target: String },
+    /// A tag is missing a parameter it can't be rendered without.
+    #[error("`{tag}` is missing the required `{attribute}` parameter.")]
+    MissingAttribute { tag: String, attribute: String },
+    /// A tag needs at least one of a couple of parameters, but was given none of them.
+    #[error("`{tag}` needs at least one of the `{}` parameters.", .attributes.join("`, `"))]
+    MissingAnyAttribute {
+        tag: String,
+        attributes: Vec<String>,
+    },
+    /// A tag was given the same parameter more than once.
+    #[error("The `{attribute}` parameter of `{tag}` was given more than once.")]
+    DuplicateAttribute { tag: String, attribute: String },
+    /// A tag was given a parameter it doesn't have.
+    #[error("`{tag}` doesn't have a `{attribute}` parameter.")]
+    UnknownAttribute { tag: String, attribute: String },
+    /// A parameter has a value outside of the range the output target accepts.
+    #[error("`{value}` is out of range for the `{attribute}` parameter of `{tag}`.")]
+    OutOfRangeAttribute {
+        tag: String,
+        attribute: String,
+        value: String,
+    },
+    /// A tag is opened, but never closed.
+    #[error("`${{{0}}}` is never closed.")]
+    UnclosedTag(String),
+    /// A tag is closed without being opened.
+    #[error("`${{/{0}}}` was closed without being opened.")]
+    UnopenedTag(String),
+    /// A tag is closed while a tag that was opened after it is still open.
+    #[error("`${{/{tag}}}` was closed while `${{{innermost}}}` is still open.")]
+    MisnestedTag { tag: String, innermost: String },
+    /// A tag is opened inside of a tag SSML doesn't allow it in, e.g. a `${p}` inside of a
+    /// `${s}`.
+    #[error("`${{{tag}}}` isn't allowed inside of `${{{parent}}}`.")]
+    IllegalNesting { tag: String, parent: String },
+    /// Tags are nested deeper than `ParseOptions::max_depth` allows.
+    #[error("`${{{tag}}}` nests tags deeper than the maximum of {max_depth}.")]
+    NestingTooDeep { tag: String, max_depth: usize },
+    /// The input is longer than `ParseOptions::max_input_len` allows.
+    #[error("The input is {len} bytes long, more than the maximum of {max_len} bytes.")]
+    InputTooLong { len: usize, max_len: usize },
+    /// A document can't be split into documents of at most this many characters, because a
+    /// single word, or tag doesn't fit into one by itself.
+    #[error("A single word, or tag doesn't fit into a document of {0} characters.")]
+    ChunkLimitTooSmall(usize),
+    /// A name that can't be used as an element, or attribute name.
+    #[error("`{0}` is not a valid element, or attribute name.")]
+    InvalidName(String),
+    /// A phone in an ARPABET pronunciation that we don't know.
+    #[error("`{0}` is not a valid ARPABET phone.")]
+    UnknownPhone(String),
+    /// A symbol in a pronunciation that has no equivalent in the alphabet it's converted to.
+    #[error("`{symbol}` in the {alphabet} alphabet can't be converted.")]
+    UnmappableSymbol { symbol: String, alphabet: String },
+    /// Pronunciations can't be converted between these two alphabets.
+    #[error("Pronunciations can't be converted from {from} to {to}.")]
+    UnsupportedConversion { from: String, to: String },
+    /// A subtitle track isn't valid SRT, or WebVTT.
+    #[error("Failed to read the subtitles at line {line}: {message}.")]
+    InvalidSubtitles { line: usize, message: String },
+    /// A language tag isn't valid BCP-47, see `language::is_valid_language_tag`.
+    #[error("`{0}` is not a valid BCP-47 language tag.")]
+    InvalidLanguage(String),
+    /// A file couldn't be read.
+    #[error("Failed to read `{}`.", .path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// A file can't be included, because includes are disabled, or the file is outside of
+    /// the include root.
+    #[error(
+        "`{0}` can't be included, includes are disabled, or it is outside of the include root."
+    )]
+    IncludeNotAllowed(String),
+    /// Includes are nested deeper than allowed, e.g. because a file includes itself.
+    #[error("Including `{0}` nests includes deeper than allowed.")]
+    IncludeTooDeep(String),
+    /// A configuration file has the wrong format, or contents.
+    #[error("`{}` is not a valid configuration file: {message}", .path.display())]
+    InvalidConfig { path: PathBuf, message: String },
+    /// Writing the SSML failed.
+    #[error("Failed to write the SSML.")]
+    WriterError(#[from] quick_xml::Error),
+    /// Some SSML that was passed in isn't well formed XML.
+    #[error("The SSML is not well formed XML.")]
+    InvalidSsml(#[source] quick_xml::Error),
+    /// Some SSML that was passed in doesn't have a single root <speak> tag.
+    #[error("The SSML does not have a single root <speak> tag.")]
+    MissingSpeak,
+    /// The SSML isn't valid UTF-8.
+    #[error("The SSML is not valid UTF-8.")]
+    InvalidUtf8(#[from] FromUtf8Error),
+    /// The language server lost its connection to the editor, or the editor didn't follow
+    /// the protocol.
+    #[cfg(feature = "lsp")]
+    #[error("The language server failed: {0}")]
+    LanguageServer(String),
+    /// The HTTP server couldn't listen on its address, or answer a request.
+    #[cfg(feature = "server")]
+    #[error("The HTTP server failed: {0}")]
+    Server(String),
+    /// Another error, with what we were doing when it happened.
+    #[error("{context}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<SsmlError>,
+    },
+}
+
+impl SsmlError {
+    /// The error without any of the context that was added to it.
+    pub fn root_cause(&self) -> &SsmlError {
+        match self {
+            SsmlError::Context { source, .. } => source.root_cause(),
+            other => other,
+        }
+    }
+
+    /// Converts the error into a `color_eyre::Report`, with the raw parser error, and
+    /// suggestions attached where we have them.
+    #[cfg(feature = "color-eyre")]
+    pub fn into_report(self) -> color_eyre::Report {
+        use color_eyre::{Help, SectionExt};
+
+        let raw = match self.root_cause() {
+            SsmlError::ParseError { raw, .. } => Some(raw.to_owned()),
+            _ => None,
+        };
+        let unknown_tag = matches!(self.root_cause(), SsmlError::UnknownTag(_));
+
+        let mut report = color_eyre::Report::new(self);
+        if let Some(raw) = raw {
+            report = report.with_section(|| raw.header("Raw Error:"));
+        }
+        if unknown_tag {
+            report = report
+                .suggestion("See `registry::supported_tags` for every tag that is supported.");
+        }
+        report
+    }
+}
+
+/// Adds context to the error of a `Result`.
+pub(crate) trait ResultExt<T> {
+    fn with_context<C: FnOnce() -> String>(self, context: C) -> Result<T>;
+}
+
+impl<T, E: Into<SsmlError>> ResultExt<T> for std::result::Result<T, E> {
+    fn with_context<C: FnOnce() -> String>(self, context: C) -> Result<T> {
+        self.map_err(|error| SsmlError::Context {
+            context: context(),
+            source: Box::new(error.into()),
+        })
+    }
+}
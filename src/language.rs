@@ -0,0 +1,218 @@
+//! Checks language tags (e.g. `en-US`) before they end up in an `xml:lang` attribute. Polly
+//! doesn't reject a tag it can't read like `fr_FR`, it silently falls back to the language
+//! of the voice instead.
+
+/// Every language AWS Polly can synthesize, as of the time of writing.
+pub const POLLY_LANGUAGES: &[&str] = &[
+    "arb",
+    "ar-AE",
+    "ca-ES",
+    "cmn-CN",
+    "cs-CZ",
+    "cy-GB",
+    "da-DK",
+    "de-AT",
+    "de-CH",
+    "de-DE",
+    "en-AU",
+    "en-GB",
+    "en-GB-WLS",
+    "en-IE",
+    "en-IN",
+    "en-NZ",
+    "en-SG",
+    "en-US",
+    "en-ZA",
+    "es-ES",
+    "es-MX",
+    "es-US",
+    "fi-FI",
+    "fr-BE",
+    "fr-CA",
+    "fr-FR",
+    "hi-IN",
+    "is-IS",
+    "it-IT",
+    "ja-JP",
+    "ko-KR",
+    "nb-NO",
+    "nl-BE",
+    "nl-NL",
+    "pl-PL",
+    "pt-BR",
+    "pt-PT",
+    "ro-RO",
+    "ru-RU",
+    "sv-SE",
+    "tr-TR",
+    "yue-CN",
+];
+
+/// Tags registered before BCP-47 that don't follow its syntax, or only follow it by
+/// accident. They are still valid.
+const GRANDFATHERED: &[&str] = &[
+    "en-GB-oed",
+    "i-ami",
+    "i-bnn",
+    "i-default",
+    "i-enochian",
+    "i-hak",
+    "i-klingon",
+    "i-lux",
+    "i-mingo",
+    "i-navajo",
+    "i-pwn",
+    "i-tao",
+    "i-tay",
+    "i-tsu",
+    "sgn-BE-FR",
+    "sgn-BE-NL",
+    "sgn-CH-DE",
+    "art-lojban",
+    "cel-gaulish",
+    "no-bok",
+    "no-nyn",
+    "zh-guoyu",
+    "zh-hakka",
+    "zh-min",
+    "zh-min-nan",
+    "zh-xiang",
+];
+
+/// If a language tag follows the syntax of BCP-47 (RFC 5646). Only the syntax is checked,
+/// not if the subtags are actually registered, so `xx-YY` is valid while `fr_FR`, or
+/// `en-US-` are not. Case doesn't matter.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::language::is_valid_language_tag;
+/// assert!(is_valid_language_tag("en-US"));
+/// assert!(is_valid_language_tag("zh-Hant-TW"));
+/// assert!(!is_valid_language_tag("fr_FR"));
+/// ```
+pub fn is_valid_language_tag(tag: &str) -> bool {
+    if GRANDFATHERED
+        .iter()
+        .any(|grandfathered| grandfathered.eq_ignore_ascii_case(tag))
+    {
+        return true;
+    }
+    let subtags = tag.split('-').collect::<Vec<_>>();
+    if subtags
+        .iter()
+        .any(|subtag| subtag.is_empty() || !subtag.bytes().all(|b| b.is_ascii_alphanumeric()))
+    {
+        return false;
+    }
+    let mut rest = &subtags[..];
+
+    if !is_private_use(rest[0]) {
+        let language = rest[0];
+        if !is_alpha(language, 2, 8) {
+            return false;
+        }
+        rest = &rest[1..];
+        if language.len() <= 3 {
+            // Up to three extended language subtags, e.g. the `yue` of `zh-yue`.
+            let extlangs = rest
+                .iter()
+                .take(3)
+                .take_while(|subtag| is_alpha(subtag, 3, 3))
+                .count();
+            rest = &rest[extlangs..];
+        }
+        if rest.first().is_some_and(|subtag| is_alpha(subtag, 4, 4)) {
+            rest = &rest[1..];
+        }
+        if rest.first().is_some_and(|subtag| is_region(subtag)) {
+            rest = &rest[1..];
+        }
+        while rest.first().is_some_and(|subtag| is_variant(subtag)) {
+            rest = &rest[1..];
+        }
+        while rest
+            .first()
+            .is_some_and(|subtag| subtag.len() == 1 && !is_private_use(subtag))
+        {
+            let extensions = rest[1..]
+                .iter()
+                .take_while(|subtag| (2..=8).contains(&subtag.len()))
+                .count();
+            if extensions == 0 {
+                return false;
+            }
+            rest = &rest[1 + extensions..];
+        }
+    }
+
+    if rest.first().is_some_and(|subtag| is_private_use(subtag)) {
+        return rest.len() > 1 && rest[1..].iter().all(|subtag| subtag.len() <= 8);
+    }
+    rest.is_empty()
+}
+
+/// Suggests the tag that was most likely meant by an invalid language tag, e.g. `fr-FR`
+/// for `fr_FR`. Returns `None` if the tag is already valid, or we can't tell what was
+/// meant.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::language::suggest_language_tag;
+/// assert_eq!(suggest_language_tag("fr_FR"), Some("fr-FR".to_owned()));
+/// assert_eq!(suggest_language_tag("fr-FR"), None);
+/// ```
+pub fn suggest_language_tag(tag: &str) -> Option<String> {
+    if is_valid_language_tag(tag) {
+        return None;
+    }
+    let replaced = tag.trim().replace('_', "-");
+    if !is_valid_language_tag(&replaced) {
+        return None;
+    }
+    let suggestion = replaced
+        .split('-')
+        .enumerate()
+        .map(|(idx, subtag)| match subtag.len() {
+            _ if idx == 0 => subtag.to_lowercase(),
+            2 => subtag.to_uppercase(),
+            4 if is_alpha(subtag, 4, 4) => subtag[..1].to_uppercase() + &subtag[1..].to_lowercase(),
+            _ => subtag.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("-");
+    Some(suggestion)
+}
+
+/// If AWS Polly can synthesize a language, see `POLLY_LANGUAGES`. Case doesn't matter.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::language::is_polly_language;
+/// assert!(is_polly_language("de-DE"));
+/// assert!(!is_polly_language("de-LU"));
+/// ```
+pub fn is_polly_language(tag: &str) -> bool {
+    POLLY_LANGUAGES
+        .iter()
+        .any(|language| language.eq_ignore_ascii_case(tag))
+}
+
+fn is_alpha(subtag: &str, min: usize, max: usize) -> bool {
+    (min..=max).contains(&subtag.len()) && subtag.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_region(subtag: &str) -> bool {
+    is_alpha(subtag, 2, 2) || subtag.len() == 3 && subtag.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_variant(subtag: &str) -> bool {
+    (5..=8).contains(&subtag.len())
+        || subtag.len() == 4 && subtag.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn is_private_use(subtag: &str) -> bool {
+    subtag.eq_ignore_ascii_case("x")
+}
@@ -1,8 +1,34 @@
+pub mod analysis;
+#[cfg(feature = "async")]
+pub mod async_writer;
+pub mod builder;
+pub mod compare;
+pub mod error;
+pub mod language;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+pub mod merge;
 pub mod parser;
+pub mod phonetics;
+pub mod post_process;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod registry;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "server")]
+pub mod server;
 pub mod ssml_constants;
+pub mod voices;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod xml_writer;
 
-use color_eyre::Result;
+pub use error::{Result, SsmlError};
+pub use parser::{
+    Diagnostic, DiagnosticKind, Engine, OutputTarget, ParseOptions, ParseReport, Severity,
+    UnknownTagPolicy, UnterminatedPolicy, Warning, WarningKind,
+};
 
 /// Parses a String into the Unique Text to SSML Format. Useful for taking a string
 /// and making some sweet, sweet SSML.
@@ -15,3 +41,84 @@ pub fn parse_string(to_parse: String) -> Result<String> {
 pub fn parse_str(to_parse: &str) -> Result<String> {
     parser::parse_as_ssml(to_parse)
 }
+
+/// Parses a String into the Unique Text to SSML Format using the specified options.
+/// Useful when the default SSML isn't quite what you need.
+pub fn parse_str_with_options(to_parse: &str, options: &ParseOptions) -> Result<String> {
+    parser::parse_as_ssml_with_options(to_parse, options)
+}
+
+/// Parses a String containing multiple documents separated by `${---}` lines. Each document
+/// is turned into its own independent piece of SSML. Useful for keeping a whole batch of
+/// prompts inside of a single file.
+pub fn parse_documents(to_parse: &str, options: &ParseOptions) -> Result<Vec<String>> {
+    parser::parse_as_ssml_documents(to_parse, options)
+}
+
+/// Parses a String into the Unique Text to SSML Format, split into as many documents as
+/// needed to keep each one at most `max_chars` characters long. Any tag spanning two
+/// documents is re-opened in the second one. Useful for long texts that don't fit into a
+/// single polly request, see `parser::POLLY_MAX_CHARACTERS`.
+pub fn parse_str_chunked(to_parse: &str, max_chars: usize) -> Result<Vec<String>> {
+    parser::parse_as_ssml_chunked(to_parse, max_chars, &ParseOptions::default())
+}
+
+/// Parses a String into the Unique Text to SSML Format using the specified options, and
+/// reports every tag, or attribute that was ignored along the way.
+pub fn parse_with_report(to_parse: &str, options: &ParseOptions) -> Result<ParseReport> {
+    parser::parse_as_ssml_with_report(to_parse, options)
+}
+
+/// Checks a String for problems without turning it into SSML, see `parser::lint_with_options`
+/// for everything that is checked. Useful for editors, and CI checks.
+pub fn lint(to_lint: &str) -> Vec<Diagnostic> {
+    parser::lint(to_lint)
+}
+
+/// Converts HTML into SSML using the specified options, see `parser::html_to_markup` for how
+/// every tag is read. Useful for content from a CMS, which can be passed in as is.
+pub fn from_html(html: &str, options: &ParseOptions) -> Result<String> {
+    parser::parse_as_ssml_with_options(&parser::html_to_markup(html), options)
+}
+
+/// Converts an SRT, or WebVTT subtitle track into SSML using the specified options, with
+/// the gaps between cues kept as breaks, see `parser::subtitles_to_markup`. Useful for
+/// voicing a track again while keeping its timing.
+pub fn from_subtitles(subtitles: &str, options: &ParseOptions) -> Result<String> {
+    parser::parse_as_ssml_with_options(&parser::subtitles_to_markup(subtitles)?, options)
+}
+
+/// Converts a `Speaker: line` script into a single SSML document using the specified
+/// options, where every speaker is spoken the way the cast defines, see
+/// `parser::dialogue_to_markup`.
+pub fn from_dialogue(script: &str, cast: &parser::Cast, options: &ParseOptions) -> Result<String> {
+    parser::parse_as_ssml_with_options(
+        &parser::dialogue_to_markup(script, cast, options.target)?,
+        options,
+    )
+}
+
+/// Converts a `Speaker: line` script into one SSML document for every turn using the
+/// specified options, see `parser::dialogue_turns`. Useful for AWS Polly, where every turn
+/// has to be synthesized with the voice of its speaker on its own.
+pub fn from_dialogue_by_turn(
+    script: &str,
+    cast: &parser::Cast,
+    options: &ParseOptions,
+) -> Result<Vec<(parser::DialogueTurn, String)>> {
+    parser::dialogue_turns(script, cast)?
+        .into_iter()
+        .map(|turn| {
+            let ssml = parser::parse_as_ssml_with_options(&turn.markup, options)?;
+            Ok((turn, ssml))
+        })
+        .collect()
+}
+
+/// Converts Markdown into SSML using the specified options, see
+/// `parser::markdown_to_markup` for how every part of the Markdown is read. Useful for
+/// content that is already written in Markdown.
+#[cfg(feature = "markdown")]
+pub fn from_markdown(markdown: &str, options: &ParseOptions) -> Result<String> {
+    parser::parse_as_ssml_with_options(&parser::markdown_to_markup(markdown), options)
+}
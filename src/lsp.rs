@@ -0,0 +1,477 @@
+//! A language server for the markup, speaking the Language Server Protocol. It powers the
+//! `text-to-ssml-ls` binary, and publishes the diagnostics of `lint` for every open
+//! document, completes tag keys, parameter names, and parameter values from the
+//! `registry`, and shows the documentation of the tag, or parameter under the cursor.
+//!
+//! Documents are always synced in full, and linted with the default `ParseOptions`.
+//! Positions are counted in UTF-16 code units, like the protocol expects by default.
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as NotificationMethod, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, HoverRequest, Request as RequestMethod};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionTextEdit,
+    DiagnosticSeverity, DiagnosticTag, Documentation, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, MarkupContent, MarkupKind, Position, PublishDiagnosticsParams, Range,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Uri,
+};
+
+use crate::error::{Result, SsmlError};
+use crate::parser::{
+    lint_with_options, tokenize, Diagnostic, DiagnosticKind, ParseOptions, Severity, Span,
+};
+use crate::registry::{find_tag, supported_tags, ParamSpec, TagSpec};
+
+use std::collections::BTreeMap;
+
+/// What the server supports: full document sync, completion, and hover.
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(
+                ["{", "/", "|", "="]
+                    .iter()
+                    .map(|character| (*character).to_owned())
+                    .collect(),
+            ),
+            ..Default::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..Default::default()
+    }
+}
+
+/// Runs the language server over stdin, and stdout until the editor shuts it down.
+pub fn serve_stdio() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(&connection)?;
+    io_threads
+        .join()
+        .map_err(|error| SsmlError::LanguageServer(error.to_string()))
+}
+
+/// Runs the language server over a connection until the editor shuts it down, starting
+/// with the `initialize` handshake.
+pub fn serve(connection: &Connection) -> Result<()> {
+    let capabilities = serde_json::to_value(capabilities())
+        .map_err(|error| SsmlError::LanguageServer(error.to_string()))?;
+    connection
+        .initialize(capabilities)
+        .map_err(|error| SsmlError::LanguageServer(error.to_string()))?;
+
+    // The text of every open document, by its URI.
+    let mut documents: BTreeMap<String, String> = BTreeMap::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                let shutdown = connection
+                    .handle_shutdown(&request)
+                    .map_err(|error| SsmlError::LanguageServer(error.to_string()))?;
+                if shutdown {
+                    return Ok(());
+                }
+                send(connection, handle_request(&documents, request).into())?;
+            }
+            Message::Notification(notification) => {
+                if let Some(uri) = handle_notification(&mut documents, notification) {
+                    let diagnostics = documents
+                        .get(uri.as_str())
+                        .map(|text| diagnostics(text, &ParseOptions::default()))
+                        .unwrap_or_default();
+                    let params = PublishDiagnosticsParams {
+                        uri,
+                        diagnostics,
+                        version: None,
+                    };
+                    send(
+                        connection,
+                        Notification::new(PublishDiagnostics::METHOD.to_owned(), params).into(),
+                    )?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn send(connection: &Connection, message: Message) -> Result<()> {
+    connection
+        .sender
+        .send(message)
+        .map_err(|error| SsmlError::LanguageServer(error.to_string()))
+}
+
+/// Answers a completion, or hover request. Anything else isn't supported.
+fn handle_request(documents: &BTreeMap<String, String>, request: Request) -> Response {
+    let result = match request.method.as_str() {
+        Completion::METHOD => {
+            serde_json::from_value::<CompletionParams>(request.params).map(|params| {
+                let position = params.text_document_position;
+                let items = documents
+                    .get(position.text_document.uri.as_str())
+                    .map(|text| completions(text, position.position))
+                    .unwrap_or_default();
+                serde_json::to_value(items)
+            })
+        }
+        HoverRequest::METHOD => {
+            serde_json::from_value::<HoverParams>(request.params).map(|params| {
+                let position = params.text_document_position_params;
+                let hover = documents
+                    .get(position.text_document.uri.as_str())
+                    .and_then(|text| hover(text, position.position));
+                serde_json::to_value(hover)
+            })
+        }
+        _ => {
+            return Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("`{}` isn't supported.", request.method),
+            )
+        }
+    };
+    match result {
+        Ok(Ok(value)) => Response::new_ok(request.id, value),
+        Ok(Err(error)) | Err(error) => Response::new_err(
+            request.id,
+            ErrorCode::InvalidParams as i32,
+            error.to_string(),
+        ),
+    }
+}
+
+/// Keeps track of the open documents. Returns the document whose diagnostics changed.
+fn handle_notification(
+    documents: &mut BTreeMap<String, String>,
+    notification: Notification,
+) -> Option<Uri> {
+    match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params = notification
+                .extract::<<DidOpenTextDocument as NotificationMethod>::Params>(
+                    DidOpenTextDocument::METHOD,
+                )
+                .ok()?;
+            let uri = params.text_document.uri;
+            documents.insert(uri.as_str().to_owned(), params.text_document.text);
+            Some(uri)
+        }
+        DidChangeTextDocument::METHOD => {
+            let params = notification
+                .extract::<<DidChangeTextDocument as NotificationMethod>::Params>(
+                    DidChangeTextDocument::METHOD,
+                )
+                .ok()?;
+            let uri = params.text_document.uri;
+            // Documents are synced in full, so the last change is the whole document.
+            let text = params.content_changes.into_iter().last()?.text;
+            documents.insert(uri.as_str().to_owned(), text);
+            Some(uri)
+        }
+        DidCloseTextDocument::METHOD => {
+            let params = notification
+                .extract::<<DidCloseTextDocument as NotificationMethod>::Params>(
+                    DidCloseTextDocument::METHOD,
+                )
+                .ok()?;
+            let uri = params.text_document.uri;
+            documents.remove(uri.as_str());
+            Some(uri)
+        }
+        _ => None,
+    }
+}
+
+/// Lints a document, and converts every diagnostic into one for the editor.
+pub fn diagnostics(text: &str, options: &ParseOptions) -> Vec<lsp_types::Diagnostic> {
+    lint_with_options(text, options)
+        .into_iter()
+        .map(|diagnostic| to_lsp_diagnostic(text, diagnostic))
+        .collect()
+}
+
+fn to_lsp_diagnostic(text: &str, diagnostic: Diagnostic) -> lsp_types::Diagnostic {
+    let severity = match diagnostic.severity {
+        Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Hint => DiagnosticSeverity::HINT,
+    };
+    let tags = match diagnostic.kind {
+        DiagnosticKind::Deprecated => Some(vec![DiagnosticTag::DEPRECATED]),
+        _ => None,
+    };
+    lsp_types::Diagnostic {
+        range: diagnostic
+            .span
+            .map(|span| range_of(text, span))
+            .unwrap_or_default(),
+        severity: Some(severity),
+        source: Some("text-to-ssml".to_owned()),
+        message: diagnostic.message,
+        tags,
+        ..Default::default()
+    }
+}
+
+/// Completes the tag key, parameter name, or parameter value the cursor is in. End tags
+/// complete the tags that are still open first, innermost first.
+pub fn completions(text: &str, position: Position) -> Vec<CompletionItem> {
+    let offset = offset_at(text, position);
+    let before = &text[..offset];
+    let tag_start = match before.rfind("${") {
+        Some(tag_start) => tag_start,
+        None => return Vec::new(),
+    };
+    let inside = &before[tag_start + 2..];
+    // The cursor is after the end of the tag.
+    if inside.contains('}') {
+        return Vec::new();
+    }
+    // Everything from the start of the part the cursor is in, up until the cursor, is
+    // replaced.
+    let part_start = tag_start + 2 + inside.rfind(['|', '=', '/']).map_or(0, |index| index + 1);
+    let replace = Range::new(position_at(text, part_start), position);
+
+    if let Some(tag_key) = inside.strip_prefix('/') {
+        if tag_key.contains('|') {
+            return Vec::new();
+        }
+        return end_tag_completions(&text[..tag_start])
+            .into_iter()
+            .enumerate()
+            .map(|(index, tag)| {
+                let mut item = completion(tag.name, tag.description, replace);
+                item.kind = Some(CompletionItemKind::CLASS);
+                item.sort_text = Some(format!("{:04}", index));
+                item
+            })
+            .collect();
+    }
+
+    let mut parts = inside.split('|');
+    let tag_key = parts.next().unwrap_or("");
+    let params: Vec<&str> = parts.collect();
+    let current = match params.last() {
+        Some(current) => current,
+        None => {
+            return supported_tags()
+                .iter()
+                .map(|tag| {
+                    let mut item = completion(tag.name, tag.description, replace);
+                    item.kind = Some(CompletionItemKind::CLASS);
+                    item.documentation =
+                        Some(Documentation::MarkupContent(markdown(tag_docs(tag))));
+                    item
+                })
+                .collect();
+        }
+    };
+    let tag = match find_tag(tag_key) {
+        Some(tag) => tag,
+        None => return Vec::new(),
+    };
+
+    match current.split_once('=') {
+        Some((name, _)) => tag
+            .param(name)
+            .and_then(|param| param.values)
+            .unwrap_or(&[])
+            .iter()
+            .map(|value| {
+                let mut item = completion(value, "", replace);
+                item.kind = Some(CompletionItemKind::ENUM_MEMBER);
+                item
+            })
+            .collect(),
+        None => {
+            let given: Vec<&str> = params
+                .iter()
+                .filter_map(|param| param.split_once('=').map(|(name, _)| name))
+                .collect();
+            tag.params
+                .iter()
+                .filter(|param| !given.contains(&param.name))
+                .map(|param| {
+                    let mut item = completion(param.name, param.description, replace);
+                    item.kind = Some(CompletionItemKind::PROPERTY);
+                    item.documentation = Some(Documentation::MarkupContent(markdown(param_docs(
+                        tag, param,
+                    ))));
+                    if let Some(CompletionTextEdit::Edit(ref mut edit)) = item.text_edit {
+                        edit.new_text.push('=');
+                    }
+                    item
+                })
+                .collect()
+        }
+    }
+}
+
+/// The tags an end tag at the end of `before` can close: the tags that are still open,
+/// innermost first, followed by every other tag that has an end tag.
+fn end_tag_completions(before: &str) -> Vec<&'static TagSpec> {
+    let mut open: Vec<&'static TagSpec> = Vec::new();
+    for item in tokenize(before).unwrap_or_default() {
+        if let Some(tag) = item
+            .start_tag
+            .and_then(|start_tag| find_tag(&start_tag.tag_key))
+        {
+            if tag.has_closing_tag {
+                open.push(tag);
+            }
+        }
+        if let Some(end_tag) = item.end_tag {
+            if let Some(position) = open
+                .iter()
+                .rposition(|tag| tag.name.eq_ignore_ascii_case(&end_tag.tag_key))
+            {
+                open.truncate(position);
+            }
+        }
+    }
+
+    let mut tags: Vec<&'static TagSpec> = Vec::new();
+    for tag in open.into_iter().rev() {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    for tag in supported_tags() {
+        if tag.has_closing_tag && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+fn completion(label: &str, detail: &str, replace: Range) -> CompletionItem {
+    CompletionItem {
+        label: label.to_owned(),
+        detail: Some(detail.to_owned()).filter(|detail| !detail.is_empty()),
+        text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(
+            replace,
+            label.to_owned(),
+        ))),
+        ..Default::default()
+    }
+}
+
+/// The documentation of the tag, or parameter under the cursor.
+pub fn hover(text: &str, position: Position) -> Option<Hover> {
+    let offset = offset_at(text, position);
+    let items = tokenize(text).ok()?;
+    let item = items
+        .iter()
+        .find(|item| item.span.offset <= offset && offset < item.span.offset + item.span.len)?;
+
+    let (span, docs) = match (&item.start_tag, &item.end_tag) {
+        (Some(start_tag), _) => {
+            let tag = find_tag(&start_tag.tag_key)?;
+            let param = start_tag
+                .param_spans
+                .iter()
+                .find(|(_, span)| span.offset <= offset && offset < span.offset + span.len);
+            match param.and_then(|(name, span)| Some((tag.param(name)?, span))) {
+                Some((param, span)) => (*span, param_docs(tag, param)),
+                None => (item.span, tag_docs(tag)),
+            }
+        }
+        (None, Some(end_tag)) => {
+            let tag = find_tag(&end_tag.tag_key)?;
+            (item.span, tag_docs(tag))
+        }
+        (None, None) => return None,
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(markdown(docs)),
+        range: Some(range_of(text, span)),
+    })
+}
+
+fn markdown(value: String) -> MarkupContent {
+    MarkupContent {
+        kind: MarkupKind::Markdown,
+        value,
+    }
+}
+
+/// The documentation of a tag: what it does, and every parameter it has.
+fn tag_docs(tag: &TagSpec) -> String {
+    let mut docs = format!("**`${{{}}}`**\n\n{}", tag.name, tag.description);
+    if !tag.params.is_empty() {
+        docs.push_str("\n\nParameters:\n");
+        for param in tag.params {
+            docs.push_str(&format!("\n- `{}`", param.name));
+            if param.required {
+                docs.push_str(" (required)");
+            }
+            docs.push_str(&format!(": {}", param.description));
+        }
+    }
+    docs
+}
+
+/// The documentation of a parameter: what it does, and the values it accepts.
+fn param_docs(tag: &TagSpec, param: &ParamSpec) -> String {
+    let mut docs = format!(
+        "**`{}`** parameter of `${{{}}}`{}\n\n{}",
+        param.name,
+        tag.name,
+        if param.required { " (required)" } else { "" },
+        param.description
+    );
+    if let Some(values) = param.values {
+        docs.push_str(&format!("\n\nValues: `{}`", values.join("`, `")));
+    }
+    docs
+}
+
+/// The range of a span of `text`.
+fn range_of(text: &str, span: Span) -> Range {
+    Range::new(
+        position_at(text, span.offset),
+        position_at(text, span.offset + span.len),
+    )
+}
+
+/// The position of a byte offset of `text`.
+fn position_at(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    Position::new(
+        before.matches('\n').count() as u32,
+        before[line_start..].encode_utf16().count() as u32,
+    )
+}
+
+/// The byte offset of a position of `text`. Positions past the end of a line are at the
+/// end of the line.
+fn offset_at(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return text.len(),
+        }
+    }
+    let line_end = text[line_start..]
+        .find('\n')
+        .map_or(text.len(), |index| line_start + index);
+
+    let mut units = 0;
+    for (index, character) in text[line_start..line_end].char_indices() {
+        if units >= position.character as usize {
+            return line_start + index;
+        }
+        units += character.len_utf16();
+    }
+    line_end
+}
@@ -0,0 +1,111 @@
+//! Combines already rendered SSML documents into one. Every call to `parse_str` produces a
+//! complete <speak> document, which can't simply be concatenated with another one.
+
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::{Reader, Writer};
+
+use crate::error::{Result, SsmlError};
+use crate::ssml_constants::BreakTime;
+
+use std::io::Cursor;
+
+/// Merges multiple SSML documents into a single <speak> document. The XML declaration, and
+/// <speak> tag of the first document are kept, and the content of every other document is
+/// appended to it in order. If a separator is given a <break> of that length is placed
+/// between the content of every two documents.
+///
+/// Unbalanced documents (e.g. from markup that never closed a tag) are passed through as
+/// best we can: the last end tag of a document is taken to close its <speak> tag. Returns an
+/// empty string if there are no documents, and an error if any of the documents isn't
+/// well formed XML, or doesn't start with a root <speak> tag.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::merge::merge_documents;
+/// use text_to_polly_ssml::ssml_constants::BreakTime;
+/// let merged = merge_documents(
+///   &["<speak><s>One</s></speak>", "<speak><s>Two</s></speak>"],
+///   Some(BreakTime::new(500, false)),
+/// );
+/// assert_eq!(
+///   merged.unwrap(),
+///   r#"<speak><s>One</s><break time="500ms"/><s>Two</s></speak>"#,
+/// );
+/// ```
+pub fn merge_documents<S: AsRef<str>>(
+    documents: &[S],
+    separator: Option<BreakTime>,
+) -> Result<String> {
+    if documents.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+
+    for (index, document) in documents.iter().enumerate() {
+        let events = read_events(document.as_ref())?;
+        let root = events
+            .iter()
+            .position(|event| matches!(event, Event::Start(_) | Event::Empty(_)))
+            .ok_or(SsmlError::MissingSpeak)?;
+        let (root_start, is_empty) = match &events[root] {
+            Event::Start(start) => (start, false),
+            Event::Empty(start) => (start, true),
+            _ => unreachable!(),
+        };
+        if root_start.name() != b"speak" {
+            return Err(SsmlError::MissingSpeak);
+        }
+
+        if index == 0 {
+            for event in &events[..root] {
+                if let Event::Decl(_) = event {
+                    writer.write_event(event)?;
+                }
+            }
+            writer.write_event(Event::Start(root_start.to_owned()))?;
+        } else if let Some(time) = separator.as_ref() {
+            let mut elem = BytesStart::owned(b"break".to_vec(), "break".len());
+            elem.push_attribute(("time", &*format!("{}", time)));
+            writer.write_event(Event::Empty(elem))?;
+        }
+
+        if !is_empty {
+            // The last end tag closes the root, even if the content isn't balanced.
+            let root_end = events
+                .iter()
+                .rposition(|event| matches!(event, Event::End(_)))
+                .filter(|position| *position > root)
+                .unwrap_or(events.len());
+            for event in &events[root + 1..root_end] {
+                writer.write_event(event)?;
+            }
+        }
+    }
+
+    writer.write_event(Event::End(BytesEnd::borrowed(b"speak")))?;
+    Ok(String::from_utf8(writer.into_inner().into_inner())?)
+}
+
+/// Reads every event of a document, without requiring end tags to match their start tags.
+fn read_events(ssml: &str) -> Result<Vec<Event<'static>>> {
+    let mut reader = Reader::from_str(ssml);
+    reader.check_end_names(false);
+    let mut events = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader
+            .read_event(&mut buf)
+            .map_err(SsmlError::InvalidSsml)?
+            .into_owned()
+        {
+            Event::Eof => break,
+            event => events.push(event),
+        }
+        buf.clear();
+    }
+
+    Ok(events)
+}
@@ -0,0 +1,115 @@
+//! Finds acronyms in plain text, and wraps every one of them in a `${say-as}` tag so it's
+//! spelled out letter by letter, instead of being read as a word.
+
+use super::tree::{parse_tree, push_text, tree_to_markup, SsmlElement, SsmlNode};
+use super::TEXT_ONLY_TAGS;
+use crate::error::Result;
+
+/// How acronyms are found, see `spell_out_acronyms`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AcronymRules {
+    /// The fewest uppercase letters a word needs to be taken for an acronym, so words like
+    /// `I`, or `A` are left alone.
+    pub min_len: usize,
+    /// Acronyms that are spoken as a word, e.g. `NASA`. Compared with the exact same case.
+    pub exceptions: Vec<String>,
+}
+
+impl Default for AcronymRules {
+    /// Acronyms of at least 2 letters, with a handful of acronyms everyone speaks as a word
+    /// as exceptions.
+    fn default() -> AcronymRules {
+        AcronymRules {
+            min_len: 2,
+            exceptions: [
+                "NASA", "NATO", "UNESCO", "UNICEF", "LASER", "RADAR", "SCUBA", "ASAP", "GIF",
+                "JPEG", "PIN", "OPEC",
+            ]
+            .iter()
+            .map(|acronym| (*acronym).to_owned())
+            .collect(),
+        }
+    }
+}
+
+impl AcronymRules {
+    /// If a word is an acronym that should be spelled out.
+    fn is_acronym(&self, word: &str) -> bool {
+        let letters = word.chars().filter(|character| character.is_alphabetic());
+        let uppercase = letters
+            .clone()
+            .filter(|letter| letter.is_uppercase())
+            .count();
+        uppercase >= self.min_len.max(1)
+            && letters.count() == uppercase
+            && !self.exceptions.iter().any(|exception| exception == word)
+    }
+}
+
+/// Wraps every acronym in some markup in a `${say-as|interpret-as=spell-out}` tag. An
+/// acronym is a word without any lowercase letters, and at least `AcronymRules::min_len`
+/// uppercase ones (e.g. `API`, or `MP3`). Text already inside of a tag that can only
+/// contain text (e.g. `${sub}`) is left alone.
+///
+/// The markup is balanced the same way `parse_tree` balances it.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{spell_out_acronyms, AcronymRules};
+/// let markup = spell_out_acronyms("NASA has an API.", &AcronymRules::default());
+/// assert_eq!(
+///   markup.unwrap(),
+///   "NASA has an ${say-as|interpret-as=spell-out}API${/say-as}.",
+/// );
+/// ```
+pub fn spell_out_acronyms(markup: &str, rules: &AcronymRules) -> Result<String> {
+    Ok(tree_to_markup(&acronym_nodes(parse_tree(markup)?, rules)))
+}
+
+/// Wraps every acronym in the nodes in a `${say-as}` tag.
+pub(crate) fn acronym_nodes(nodes: Vec<SsmlNode>, rules: &AcronymRules) -> Vec<SsmlNode> {
+    let mut spelled = Vec::new();
+    for node in nodes {
+        match node {
+            SsmlNode::Text(text) => wrap_acronyms(&mut spelled, &text, rules),
+            SsmlNode::Element(mut element) => {
+                if !TEXT_ONLY_TAGS.contains(&&*element.name.to_lowercase()) {
+                    element.children = acronym_nodes(element.children, rules);
+                }
+                spelled.push(SsmlNode::Element(element));
+            }
+        }
+    }
+    spelled
+}
+
+/// Wraps every acronym in a single piece of text.
+fn wrap_acronyms(spelled: &mut Vec<SsmlNode>, text: &str, rules: &AcronymRules) {
+    let mut written = 0;
+    let mut idx = 0;
+
+    while idx < text.len() {
+        let word_len = text[idx..]
+            .find(|character: char| !character.is_alphanumeric())
+            .unwrap_or(text.len() - idx);
+        if word_len == 0 {
+            idx += text[idx..].chars().next().map_or(1, char::len_utf8);
+            continue;
+        }
+
+        let word = &text[idx..idx + word_len];
+        if rules.is_acronym(word) {
+            push_text(spelled, &text[written..idx]);
+            let mut element = SsmlElement::new("say-as");
+            element
+                .params
+                .insert("interpret-as".to_owned(), "spell-out".to_owned());
+            element.children = vec![SsmlNode::Text(word.to_owned())];
+            spelled.push(SsmlNode::Element(element));
+            written = idx + word_len;
+        }
+        idx += word_len;
+    }
+    push_text(spelled, &text[written..]);
+}
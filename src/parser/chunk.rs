@@ -0,0 +1,225 @@
+//! Splits a piece of markup into multiple documents that each stay under a character
+//! limit, e.g. the most characters polly accepts in a single request.
+
+use super::tree::{build_tree_with_options, render_tree, SsmlElement, SsmlNode};
+use super::{
+    check_input_len, normalize_input, prepare_items, restructure, ParseOptions, TEXT_ONLY_TAGS,
+};
+use crate::error::{Result, SsmlError};
+
+/// The most characters polly bills for in a single request. Since polly also limits a
+/// request to `POLLY_MAX_TOTAL_CHARACTERS` once tags are counted, keeping the whole document
+/// under this keeps it under both limits.
+pub const POLLY_MAX_CHARACTERS: usize = 3000;
+
+/// The most characters polly accepts in a single request, tags included.
+pub const POLLY_MAX_TOTAL_CHARACTERS: usize = 6000;
+
+/// A piece of the document that is never split up, along with every tag wrapping it.
+struct Leaf {
+    /// The tags wrapping the leaf, outermost first, each with an id unique to the element
+    /// it came from so two sibling tags of the same kind are never merged.
+    ancestors: Vec<(usize, SsmlElement)>,
+    node: SsmlNode,
+}
+
+/// Parses some text as SSML, split into as few documents as possible that are each at most
+/// `max_chars` characters long (counting the whole document, tags included). Documents are
+/// split between sentences where possible, and between words otherwise. Any tag that spans
+/// two documents is closed at the end of the first one, and opened again at the start of
+/// the next.
+///
+/// Tags whose content is replaced, or read as one unit (e.g. `${sub}`, or `${say-as}`) are
+/// never split. Returns an error if a single word, or one of those tags doesn't fit into a
+/// document by itself.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{parse_as_ssml_chunked, ParseOptions};
+/// let options = ParseOptions {
+///   target: text_to_polly_ssml::OutputTarget::Google,
+///   ..Default::default()
+/// };
+/// let chunks = parse_as_ssml_chunked("${p}One. Two. Three.${/p}", 36, &options).unwrap();
+/// assert_eq!(
+///   chunks,
+///   vec![
+///     "<speak><p>One. Two. </p></speak>",
+///     "<speak><p>Three.</p></speak>",
+///   ],
+/// );
+/// ```
+pub fn parse_as_ssml_chunked(
+    data: &str,
+    max_chars: usize,
+    options: &ParseOptions,
+) -> Result<Vec<String>> {
+    check_input_len(data, options)?;
+    let normalized = normalize_input(data, options);
+    let data = &*normalized;
+    let tree = restructure(
+        build_tree_with_options(prepare_items(data, options)?, options),
+        options,
+    );
+
+    let mut leaves = Vec::new();
+    flatten(tree, &mut Vec::new(), &mut 0, &mut leaves);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < leaves.len() {
+        let mut end = start;
+        let mut rendered = None;
+        while end < leaves.len() {
+            let candidate = render_leaves(&leaves[start..=end], options)?;
+            if candidate.chars().count() > max_chars {
+                break;
+            }
+            rendered = Some(candidate);
+            end += 1;
+        }
+
+        match rendered {
+            Some(chunk) => {
+                chunks.push(chunk);
+                start = end;
+            }
+            None => {
+                // The leaf doesn't fit into a document by itself, so it has to be split up
+                // further if it can be.
+                let words = match &leaves[start].node {
+                    SsmlNode::Text(text) => split_text(text, SplitAt::Words),
+                    SsmlNode::Element(_) => Vec::new(),
+                };
+                if words.len() < 2 {
+                    return Err(SsmlError::ChunkLimitTooSmall(max_chars));
+                }
+                let words = words
+                    .into_iter()
+                    .map(|word| Leaf {
+                        ancestors: leaves[start].ancestors.clone(),
+                        node: SsmlNode::Text(word.to_owned()),
+                    })
+                    .collect::<Vec<_>>();
+                leaves.splice(start..=start, words);
+            }
+        }
+    }
+
+    if chunks.is_empty() {
+        chunks.push(render_tree(&[], options)?);
+    }
+    Ok(chunks)
+}
+
+/// Where a piece of text may be split.
+#[derive(Clone, Copy)]
+enum SplitAt {
+    Sentences,
+    Words,
+}
+
+/// Flattens a tree into its leaves, with text split up into sentences.
+fn flatten(
+    nodes: Vec<SsmlNode>,
+    ancestors: &mut Vec<(usize, SsmlElement)>,
+    next_id: &mut usize,
+    leaves: &mut Vec<Leaf>,
+) {
+    for node in nodes {
+        match node {
+            SsmlNode::Element(mut element)
+                if !element.children.is_empty()
+                    && !TEXT_ONLY_TAGS.contains(&&*element.name.to_lowercase()) =>
+            {
+                let children = std::mem::take(&mut element.children);
+                ancestors.push((*next_id, element));
+                *next_id += 1;
+                flatten(children, ancestors, next_id, leaves);
+                ancestors.pop();
+            }
+            SsmlNode::Text(text) => {
+                for piece in split_text(&text, SplitAt::Sentences) {
+                    leaves.push(Leaf {
+                        ancestors: ancestors.clone(),
+                        node: SsmlNode::Text(piece.to_owned()),
+                    });
+                }
+            }
+            element => leaves.push(Leaf {
+                ancestors: ancestors.clone(),
+                node: element,
+            }),
+        }
+    }
+}
+
+/// Splits some text into pieces that each end right after a sentence, or word, along with
+/// the whitespace that follows it. Joining the pieces gives back the original text.
+fn split_text(text: &str, split_at: SplitAt) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut previous = None;
+
+    for (idx, character) in text.char_indices() {
+        let is_boundary = match (previous, split_at) {
+            (Some(previous), SplitAt::Sentences) => {
+                !character.is_whitespace() && previous_is_sentence_end(&text[start..idx], previous)
+            }
+            (Some(previous), SplitAt::Words) => {
+                char::is_whitespace(previous) && !character.is_whitespace()
+            }
+            (None, _) => false,
+        };
+        if is_boundary {
+            pieces.push(&text[start..idx]);
+            start = idx;
+        }
+        previous = Some(character);
+    }
+    if start < text.len() || pieces.is_empty() {
+        pieces.push(&text[start..]);
+    }
+
+    pieces
+}
+
+/// If a piece of text ends with the end of a sentence, followed by some whitespace.
+fn previous_is_sentence_end(piece: &str, previous: char) -> bool {
+    previous.is_whitespace() && piece.trim_end().ends_with(['.', '!', '?'])
+}
+
+/// Renders a run of leaves as a single document, merging leaves that share a tag back into
+/// one element.
+fn render_leaves(leaves: &[Leaf], options: &ParseOptions) -> Result<String> {
+    render_tree(&rebuild(leaves, 0), options)
+}
+
+fn rebuild(leaves: &[Leaf], depth: usize) -> Vec<SsmlNode> {
+    let mut nodes = Vec::new();
+    let mut idx = 0;
+
+    while idx < leaves.len() {
+        match leaves[idx].ancestors.get(depth) {
+            None => {
+                nodes.push(leaves[idx].node.clone());
+                idx += 1;
+            }
+            Some((id, element)) => {
+                let run = leaves[idx..]
+                    .iter()
+                    .take_while(|leaf| {
+                        leaf.ancestors.get(depth).map(|(other, _)| other) == Some(id)
+                    })
+                    .count();
+                let mut element = element.clone();
+                element.children = rebuild(&leaves[idx..idx + run], depth + 1);
+                nodes.push(SsmlNode::Element(element));
+                idx += run;
+            }
+        }
+    }
+
+    nodes
+}
@@ -0,0 +1,264 @@
+//! Finds amounts of money in plain text, and rewrites them so they're read as a number
+//! followed by the name of the currency, e.g. `$12.50` as `12.50 dollars`.
+
+use super::numbers::NumberRules;
+use super::tree::{parse_tree, push_text, tree_to_markup, SsmlElement, SsmlNode};
+use super::TEXT_ONLY_TAGS;
+use crate::error::Result;
+
+/// A currency, and how its name is read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Currency {
+    /// The symbol of the currency, e.g. `$`.
+    pub symbol: String,
+    /// The name of a single unit, e.g. `dollar`.
+    pub singular: String,
+    /// The name of any other amount, e.g. `dollars`.
+    pub plural: String,
+}
+
+impl Currency {
+    /// Creates a new currency.
+    pub fn new(symbol: &str, singular: &str, plural: &str) -> Currency {
+        Currency {
+            symbol: symbol.to_owned(),
+            singular: singular.to_owned(),
+            plural: plural.to_owned(),
+        }
+    }
+}
+
+/// How amounts of money are found, and read, see `detect_currency`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CurrencyRules {
+    /// Every currency to look for.
+    pub currencies: Vec<Currency>,
+    /// How the amounts are written. Only the separators are used, amounts are always read
+    /// as a `cardinal`.
+    pub numbers: NumberRules,
+}
+
+impl CurrencyRules {
+    /// The rules for a language, based on the language subtag of a tag like `de-DE`. Knows
+    /// the names of the dollar, euro, pound, and yen in English, German, French, and
+    /// Spanish, and uses English for every other language.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::parser::CurrencyRules;
+    /// let rules = CurrencyRules::for_locale("de-DE");
+    /// assert_eq!(rules.numbers.decimal_separator, ',');
+    /// ```
+    pub fn for_locale(locale: &str) -> CurrencyRules {
+        let language = locale.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        let (names, group_separator, decimal_separator): (&[(&str, &str, &str)], char, char) =
+            match &*language {
+                "de" => (
+                    &[
+                        ("$", "Dollar", "Dollar"),
+                        ("€", "Euro", "Euro"),
+                        ("£", "Pfund", "Pfund"),
+                        ("¥", "Yen", "Yen"),
+                    ],
+                    '.',
+                    ',',
+                ),
+                "fr" => (
+                    &[
+                        ("$", "dollar", "dollars"),
+                        ("€", "euro", "euros"),
+                        ("£", "livre", "livres"),
+                        ("¥", "yen", "yens"),
+                    ],
+                    '\u{202f}',
+                    ',',
+                ),
+                "es" => (
+                    &[
+                        ("$", "dólar", "dólares"),
+                        ("€", "euro", "euros"),
+                        ("£", "libra", "libras"),
+                        ("¥", "yen", "yenes"),
+                    ],
+                    '.',
+                    ',',
+                ),
+                _ => (
+                    &[
+                        ("$", "dollar", "dollars"),
+                        ("€", "euro", "euros"),
+                        ("£", "pound", "pounds"),
+                        ("¥", "yen", "yen"),
+                    ],
+                    ',',
+                    '.',
+                ),
+            };
+
+        CurrencyRules {
+            currencies: names
+                .iter()
+                .map(|(symbol, singular, plural)| Currency::new(symbol, singular, plural))
+                .collect(),
+            numbers: NumberRules {
+                digits_from: None,
+                group_separator,
+                decimal_separator,
+            },
+        }
+    }
+
+    /// The currency whose symbol some text starts with.
+    fn currency_at(&self, text: &str) -> Option<&Currency> {
+        self.currencies
+            .iter()
+            .filter(|currency| !currency.symbol.is_empty() && text.starts_with(&currency.symbol))
+            .max_by_key(|currency| currency.symbol.len())
+    }
+}
+
+/// An amount of money found in some text.
+struct Amount<'a> {
+    currency: &'a Currency,
+    /// Where the number is in the text.
+    number: (usize, usize),
+    /// Where the symbol is in the text.
+    symbol: (usize, usize),
+    /// Where the whole amount ends.
+    end: usize,
+}
+
+/// Rewrites every amount of money in some markup, with the symbol either before, or after
+/// the number (e.g. `$12.50`, or `3.000 €`), as its number in a
+/// `${say-as|interpret-as=cardinal}` tag, followed by its symbol in a `${sub}` tag with the
+/// name of the currency. A symbol before the number is moved after it, so it's read in
+/// the right order.
+///
+/// Amounts that are part of a longer word, and text already inside of a tag that can only
+/// contain text (e.g. `${sub}`) are left alone.
+///
+/// The markup is balanced the same way `parse_tree` balances it.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{detect_currency, CurrencyRules};
+/// let markup = detect_currency("Only $12.50!", &CurrencyRules::for_locale("en-US"));
+/// assert_eq!(
+///   markup.unwrap(),
+///   "Only ${say-as|interpret-as=cardinal}12.50${/say-as} ${sub|alias=dollars}$${/sub}!",
+/// );
+/// ```
+pub fn detect_currency(markup: &str, rules: &CurrencyRules) -> Result<String> {
+    Ok(tree_to_markup(&currency_nodes(parse_tree(markup)?, rules)))
+}
+
+/// Rewrites every amount of money in the nodes.
+pub(crate) fn currency_nodes(nodes: Vec<SsmlNode>, rules: &CurrencyRules) -> Vec<SsmlNode> {
+    let mut detected = Vec::new();
+    for node in nodes {
+        match node {
+            SsmlNode::Text(text) => wrap_amounts(&mut detected, &text, rules),
+            SsmlNode::Element(mut element) => {
+                if !TEXT_ONLY_TAGS.contains(&&*element.name.to_lowercase()) {
+                    element.children = currency_nodes(element.children, rules);
+                }
+                detected.push(SsmlNode::Element(element));
+            }
+        }
+    }
+    detected
+}
+
+/// Rewrites every amount of money in a single piece of text.
+fn wrap_amounts(detected: &mut Vec<SsmlNode>, text: &str, rules: &CurrencyRules) {
+    let mut written = 0;
+    let mut idx = 0;
+
+    while idx < text.len() {
+        let amount = if text[..idx].ends_with(char::is_alphanumeric) {
+            None
+        } else {
+            amount_at(text, idx, rules)
+        };
+
+        match amount {
+            Some(amount) => {
+                push_text(detected, &text[written..idx]);
+                let number = &text[amount.number.0..amount.number.1];
+                let mut say_as = SsmlElement::new("say-as");
+                say_as
+                    .params
+                    .insert("interpret-as".to_owned(), "cardinal".to_owned());
+                say_as.children = vec![SsmlNode::Text(number.to_owned())];
+                detected.push(SsmlNode::Element(say_as));
+
+                // Keep whatever was between the number, and a symbol after it, and put a
+                // single space between them otherwise.
+                if amount.symbol.0 >= amount.number.1 {
+                    push_text(detected, &text[amount.number.1..amount.symbol.0]);
+                } else {
+                    push_text(detected, " ");
+                }
+                let mut sub = SsmlElement::new("sub");
+                let name = if number == "1" {
+                    &amount.currency.singular
+                } else {
+                    &amount.currency.plural
+                };
+                sub.params.insert("alias".to_owned(), name.to_owned());
+                sub.children = vec![SsmlNode::Text(
+                    text[amount.symbol.0..amount.symbol.1].to_owned(),
+                )];
+                detected.push(SsmlNode::Element(sub));
+
+                idx = amount.end;
+                written = idx;
+            }
+            None => idx += text[idx..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    push_text(detected, &text[written..]);
+}
+
+/// The amount of money starting at `start` in the text, if there is one.
+fn amount_at<'a>(text: &str, start: usize, rules: &'a CurrencyRules) -> Option<Amount<'a>> {
+    let rest = &text[start..];
+
+    let amount = if let Some(currency) = rules.currency_at(rest) {
+        // The symbol comes first, e.g. `$12.50`, or `$ 12.50`.
+        let symbol_end = start + currency.symbol.len();
+        let number_start = symbol_end + inline_spaces(&text[symbol_end..]);
+        let number_end = number_start + rules.numbers.number_len(&text[number_start..])?;
+        Amount {
+            currency,
+            number: (number_start, number_end),
+            symbol: (start, symbol_end),
+            end: number_end,
+        }
+    } else {
+        // The number comes first, e.g. `12,50 €`.
+        let number_end = start + rules.numbers.number_len(rest)?;
+        let symbol_start = number_end + inline_spaces(&text[number_end..]);
+        let currency = rules.currency_at(&text[symbol_start..])?;
+        let symbol_end = symbol_start + currency.symbol.len();
+        Amount {
+            currency,
+            number: (start, number_end),
+            symbol: (symbol_start, symbol_end),
+            end: symbol_end,
+        }
+    };
+
+    if text[amount.end..].starts_with(char::is_alphanumeric) {
+        return None;
+    }
+    Some(amount)
+}
+
+/// The length of the spaces (but not newlines) at the start of some text.
+fn inline_spaces(text: &str) -> usize {
+    text.find(|character: char| !matches!(character, ' ' | '\t' | '\u{a0}' | '\u{202f}'))
+        .unwrap_or(text.len())
+}
@@ -0,0 +1,297 @@
+//! Finds dates in plain text, and wraps every one of them in a `${say-as}` tag with the
+//! order its day, month, and year are written in, so `05/01/2024` isn't read as a fraction.
+
+use super::tree::{parse_tree, push_text, tree_to_markup, SsmlElement, SsmlNode};
+use super::TEXT_ONLY_TAGS;
+use crate::error::Result;
+
+/// Which part of a numeric date like `05/01/2024` comes first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateOrder {
+    /// The month comes first, e.g. `05/01/2024` is May 1st.
+    Mdy,
+    /// The day comes first, e.g. `05/01/2024` is January 5th.
+    Dmy,
+}
+
+impl DateOrder {
+    fn format(self) -> &'static str {
+        match self {
+            DateOrder::Mdy => "mdy",
+            DateOrder::Dmy => "dmy",
+        }
+    }
+}
+
+/// How dates are found, see `detect_dates`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DateRules {
+    /// The order of numeric dates that end with the year. Dates that start with a four
+    /// digit year (e.g. `2024-05-01`) are always read as year, month, then day.
+    pub order: DateOrder,
+}
+
+impl DateRules {
+    /// The rules for a locale like `en-US`. The United States, and a few others write the
+    /// month first, everyone else writes the day first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::parser::{DateOrder, DateRules};
+    /// assert_eq!(DateRules::for_locale("en-US").order, DateOrder::Mdy);
+    /// assert_eq!(DateRules::for_locale("en-GB").order, DateOrder::Dmy);
+    /// ```
+    pub fn for_locale(locale: &str) -> DateRules {
+        let mut subtags = locale.split(['-', '_']).map(str::to_lowercase);
+        let language = subtags.next().unwrap_or_default();
+        let region = subtags.next().unwrap_or_default();
+        let month_first = match &*language {
+            "en" => matches!(&*region, "" | "us" | "ph" | "fm" | "pr"),
+            _ => false,
+        };
+
+        DateRules {
+            order: if month_first {
+                DateOrder::Mdy
+            } else {
+                DateOrder::Dmy
+            },
+        }
+    }
+}
+
+/// The English names of the months, each with its abbreviations.
+const MONTHS: &[&[&str]] = &[
+    &["january", "jan"],
+    &["february", "feb"],
+    &["march", "mar"],
+    &["april", "apr"],
+    &["may"],
+    &["june", "jun"],
+    &["july", "jul"],
+    &["august", "aug"],
+    &["september", "sep", "sept"],
+    &["october", "oct"],
+    &["november", "nov"],
+    &["december", "dec"],
+];
+
+/// Wraps every date in some markup in a `${say-as|interpret-as=date|format=...}` tag.
+/// Dates are found in these forms:
+///
+/// - `2024-05-01`, or `2024/05/01`, read as `ymd`.
+/// - `05/01/2024`, `05-01-24`, or `05.01.2024`, read in the order of the rules.
+/// - `May 1`, `May 1st, 2024`, `1 May`, or `1st May 2024` (with capitalized English month
+///   names, or their abbreviations), read as `md`, `mdy`, `dm`, or `dmy`.
+///
+/// Numbers that can't be a date (e.g. a 13th month), text that is part of a longer word,
+/// and text already inside of a tag that can only contain text (e.g. `${sub}`) are left
+/// alone.
+///
+/// The markup is balanced the same way `parse_tree` balances it.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{detect_dates, DateRules};
+/// let markup = detect_dates("Due 05/01/2024, not May 2.", &DateRules::for_locale("en-US"));
+/// assert_eq!(
+///   markup.unwrap(),
+///   "Due ${say-as|format=mdy|interpret-as=date}05/01/2024${/say-as}, not ${say-as|format=md|interpret-as=date}May 2${/say-as}.",
+/// );
+/// ```
+pub fn detect_dates(markup: &str, rules: &DateRules) -> Result<String> {
+    Ok(tree_to_markup(&date_nodes(parse_tree(markup)?, rules)))
+}
+
+/// Wraps every date in the nodes in a `${say-as}` tag.
+pub(crate) fn date_nodes(nodes: Vec<SsmlNode>, rules: &DateRules) -> Vec<SsmlNode> {
+    let mut detected = Vec::new();
+    for node in nodes {
+        match node {
+            SsmlNode::Text(text) => wrap_dates(&mut detected, &text, rules),
+            SsmlNode::Element(mut element) => {
+                if !TEXT_ONLY_TAGS.contains(&&*element.name.to_lowercase()) {
+                    element.children = date_nodes(element.children, rules);
+                }
+                detected.push(SsmlNode::Element(element));
+            }
+        }
+    }
+    detected
+}
+
+/// Wraps every date in a single piece of text.
+fn wrap_dates(detected: &mut Vec<SsmlNode>, text: &str, rules: &DateRules) {
+    let mut written = 0;
+    let mut idx = 0;
+
+    while idx < text.len() {
+        let date = if text[..idx].ends_with(char::is_alphanumeric) {
+            None
+        } else {
+            let rest = &text[idx..];
+            numeric_date(rest, rules)
+                .or_else(|| month_first_date(rest))
+                .or_else(|| day_first_date(rest))
+                .filter(|(len, _)| !rest[*len..].starts_with(char::is_alphanumeric))
+        };
+
+        match date {
+            Some((len, format)) => {
+                push_text(detected, &text[written..idx]);
+                let mut element = SsmlElement::new("say-as");
+                element
+                    .params
+                    .insert("interpret-as".to_owned(), "date".to_owned());
+                element
+                    .params
+                    .insert("format".to_owned(), format.to_owned());
+                element.children = vec![SsmlNode::Text(text[idx..idx + len].to_owned())];
+                detected.push(SsmlNode::Element(element));
+                idx += len;
+                written = idx;
+            }
+            None => idx += text[idx..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    push_text(detected, &text[written..]);
+}
+
+/// A date like `2024-05-01`, or `05/01/2024` at the start of some text, its length, and
+/// format.
+fn numeric_date(text: &str, rules: &DateRules) -> Option<(usize, &'static str)> {
+    let (first, first_len) = number(text, 4)?;
+    let mut end = first_len;
+    let separator = text[end..]
+        .chars()
+        .next()
+        .filter(|c| ['-', '/', '.'].contains(c))?;
+    end += 1;
+    let (second, len) = number(&text[end..], 2)?;
+    end += len;
+    if !text[end..].starts_with(separator) {
+        return None;
+    }
+    end += 1;
+    let (third, len) = number(&text[end..], 4)?;
+    end += len;
+
+    if first_len == 4 {
+        if len > 2 || !is_valid(second, third) {
+            return None;
+        }
+        return Some((end, "ymd"));
+    }
+    if first_len > 2 || (len != 2 && len != 4) {
+        return None;
+    }
+    let (month, day) = match rules.order {
+        DateOrder::Mdy => (first, second),
+        DateOrder::Dmy => (second, first),
+    };
+    if !is_valid(month, day) {
+        return None;
+    }
+    Some((end, rules.order.format()))
+}
+
+/// A date like `May 1st, 2024` at the start of some text, its length, and format.
+fn month_first_date(text: &str) -> Option<(usize, &'static str)> {
+    let (month, mut end) = month_name(text)?;
+    end += spaces(&text[end..])?;
+    let (day, len) = day(&text[end..])?;
+    end += len;
+    if !is_valid(month, day) {
+        return None;
+    }
+
+    let mut year_start = end;
+    if text[year_start..].starts_with(',') {
+        year_start += 1;
+    }
+    match spaces(&text[year_start..])
+        .and_then(|len| year(&text[year_start + len..]).map(|year| len + year))
+    {
+        Some(len) => Some((year_start + len, "mdy")),
+        None => Some((end, "md")),
+    }
+}
+
+/// A date like `1st May 2024` at the start of some text, its length, and format.
+fn day_first_date(text: &str) -> Option<(usize, &'static str)> {
+    let (day, mut end) = day(text)?;
+    end += spaces(&text[end..])?;
+    let (month, len) = month_name(&text[end..])?;
+    end += len;
+    if !is_valid(month, day) {
+        return None;
+    }
+
+    match spaces(&text[end..]).and_then(|len| year(&text[end + len..]).map(|year| len + year)) {
+        Some(len) => Some((end + len, "dmy")),
+        None => Some((end, "dm")),
+    }
+}
+
+/// The number at the start of some text, and its length, if it has at most `max_digits`
+/// digits.
+fn number(text: &str, max_digits: usize) -> Option<(u32, usize)> {
+    let len = text
+        .find(|character: char| !character.is_ascii_digit())
+        .unwrap_or(text.len());
+    if len == 0 || len > max_digits {
+        return None;
+    }
+    Some((text[..len].parse().ok()?, len))
+}
+
+/// The day at the start of some text, with an optional ordinal suffix (e.g. `1st`).
+fn day(text: &str) -> Option<(u32, usize)> {
+    let (day, mut len) = number(text, 2)?;
+    let suffix = text[len..].get(..2).map(str::to_lowercase);
+    if matches!(suffix.as_deref(), Some("st" | "nd" | "rd" | "th")) {
+        len += 2;
+    }
+    Some((day, len))
+}
+
+/// A four digit year at the start of some text, and its length.
+fn year(text: &str) -> Option<usize> {
+    match number(text, 4)? {
+        (_, 4) => Some(4),
+        _ => None,
+    }
+}
+
+/// The month at the start of some text (`1` for January), and the length of its name. The
+/// name has to be capitalized, so e.g. the verb in `you may 2` isn't taken for a month.
+fn month_name(text: &str) -> Option<(u32, usize)> {
+    if !text.starts_with(char::is_uppercase) {
+        return None;
+    }
+    let len = text
+        .find(|character: char| !character.is_alphabetic())
+        .unwrap_or(text.len());
+    let word = text[..len].to_lowercase();
+    let month = MONTHS.iter().position(|names| names.contains(&&*word))?;
+    Some((month as u32 + 1, len))
+}
+
+/// The length of the spaces (but not newlines) at the start of some text, if there are any.
+fn spaces(text: &str) -> Option<usize> {
+    let len = text
+        .find(|character: char| character != ' ' && character != '\t')
+        .unwrap_or(text.len());
+    if len == 0 {
+        None
+    } else {
+        Some(len)
+    }
+}
+
+/// If a month, and day could be a date.
+fn is_valid(month: u32, day: u32) -> bool {
+    (1..=12).contains(&month) && (1..=31).contains(&day)
+}
@@ -0,0 +1,327 @@
+//! Reads scripts written as `Speaker: line`, so a dialogue can be voiced with a different
+//! voice, or style for every speaker.
+
+use super::tree::{parse_tree, push_text, tree_to_markup, SsmlElement, SsmlNode};
+use crate::error::Result;
+#[cfg(feature = "config")]
+use crate::error::SsmlError;
+use crate::ssml_constants::{OutputTarget, PossibleOpenTags};
+
+use std::collections::BTreeMap;
+#[cfg(feature = "config")]
+use std::path::Path;
+
+/// How the lines of a speaker are spoken.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpeakerStyle {
+    /// The voice to speak in, with a `${voice}` tag. Only used for output targets that
+    /// support switching voices within a document, i.e. every target besides AWS Polly.
+    pub voice: Option<String>,
+    /// The `rate` of a `${prosody}` tag, used whenever the voice isn't.
+    pub rate: Option<String>,
+    /// The `pitch` of a `${prosody}` tag, used whenever the voice isn't.
+    pub pitch: Option<String>,
+    /// The `volume` of a `${prosody}` tag, used whenever the voice isn't.
+    pub volume: Option<String>,
+}
+
+impl SpeakerStyle {
+    /// A style that speaks in a voice, e.g. `Brian`.
+    pub fn voice(name: &str) -> SpeakerStyle {
+        SpeakerStyle {
+            voice: Some(name.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    /// The `${prosody}` tag of the style, if it has any of its parameters.
+    fn prosody(&self) -> Option<SsmlElement> {
+        let mut element = SsmlElement::new("prosody");
+        for (param, value) in [
+            ("rate", &self.rate),
+            ("pitch", &self.pitch),
+            ("volume", &self.volume),
+        ] {
+            if let Some(value) = value {
+                element.params.insert(param.to_owned(), value.clone());
+            }
+        }
+        (!element.params.is_empty()).then_some(element)
+    }
+}
+
+/// The speakers of a dialogue, and how each of them is spoken. Speakers are matched
+/// without regard to case, and speakers that aren't in the cast are spoken without any
+/// style.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{Cast, SpeakerStyle};
+/// let mut cast = Cast::default();
+/// cast.define("Alice", SpeakerStyle::voice("Joanna"));
+/// assert_eq!(cast.get("ALICE").unwrap().voice.as_deref(), Some("Joanna"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Cast {
+    speakers: BTreeMap<String, SpeakerStyle>,
+}
+
+impl Cast {
+    /// Defines how a speaker is spoken, replacing any style defined for them before.
+    pub fn define(&mut self, speaker: &str, style: SpeakerStyle) -> &mut Self {
+        self.speakers.insert(speaker.trim().to_lowercase(), style);
+        self
+    }
+
+    /// How a speaker is spoken, if they're defined.
+    pub fn get(&self, speaker: &str) -> Option<&SpeakerStyle> {
+        self.speakers.get(&speaker.trim().to_lowercase())
+    }
+
+    /// Defines every speaker in a TOML, or JSON file (picked by the extension of the file).
+    /// The file has to be a table of speakers, each with any of a `voice`, `rate`, `pitch`,
+    /// and `volume`:
+    ///
+    /// ```toml
+    /// Alice = { voice = "Joanna", rate = "slow" }
+    /// Bob = { pitch = "low" }
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn define_from_path<P: AsRef<Path>>(&mut self, path: P) -> Result<&mut Self> {
+        let path = path.as_ref();
+        let entries: BTreeMap<String, BTreeMap<String, String>> = super::macros::read_table(path)?;
+        for (speaker, entry) in entries {
+            let mut style = SpeakerStyle::default();
+            for (key, value) in entry {
+                let field = match &*key {
+                    "voice" => &mut style.voice,
+                    "rate" => &mut style.rate,
+                    "pitch" => &mut style.pitch,
+                    "volume" => &mut style.volume,
+                    _ => {
+                        return Err(SsmlError::InvalidConfig {
+                            path: path.to_owned(),
+                            message: format!("`{}` has an unknown key `{}`", speaker, key),
+                        })
+                    }
+                };
+                *field = Some(value);
+            }
+            self.define(&speaker, style);
+        }
+        Ok(self)
+    }
+
+    /// If no speakers are defined.
+    pub fn is_empty(&self) -> bool {
+        self.speakers.is_empty()
+    }
+}
+
+/// A single line of a script.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DialogueLine {
+    /// Who speaks the line, as written in the script. `None` for lines before the first
+    /// speaker.
+    pub speaker: Option<String>,
+    /// The markup of the line, with any lines continuing it joined to it by a newline.
+    pub text: String,
+}
+
+/// Consecutive lines of a script spoken by the same speaker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DialogueTurn {
+    /// Who speaks, as written in the first of the lines.
+    pub speaker: Option<String>,
+    /// The voice of the speaker in the cast, if they have one.
+    pub voice: Option<String>,
+    /// The markup of the lines, wrapped in the `${prosody}` of the speaker if they have one,
+    /// but never in a `${voice}`, as the voice is meant to be picked when synthesizing the
+    /// turn.
+    pub markup: String,
+}
+
+/// Reads the lines of a script. A line starts with the name of its speaker followed by a
+/// `:`, and a space. Names start with a letter, and only contain letters, digits, spaces,
+/// and `.`, `'`, `-`, or `_`. Lines that don't start with a name continue the line before
+/// them, and blank lines are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::parse_dialogue;
+/// let lines = parse_dialogue("Alice: Hi Bob.\nBob: Hi.\nIt's 10:30 already!");
+/// assert_eq!(lines[1].speaker.as_deref(), Some("Bob"));
+/// assert_eq!(lines[1].text, "Hi.\nIt's 10:30 already!");
+/// ```
+pub fn parse_dialogue(script: &str) -> Vec<DialogueLine> {
+    let mut lines: Vec<DialogueLine> = Vec::new();
+
+    for line in script.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match split_speaker(line) {
+            Some((speaker, text)) => lines.push(DialogueLine {
+                speaker: Some(speaker.to_owned()),
+                text: text.to_owned(),
+            }),
+            None => match lines.last_mut() {
+                Some(previous) => {
+                    if !previous.text.is_empty() {
+                        previous.text.push('\n');
+                    }
+                    previous.text.push_str(line);
+                }
+                None => lines.push(DialogueLine {
+                    speaker: None,
+                    text: line.to_owned(),
+                }),
+            },
+        }
+    }
+
+    lines
+}
+
+/// Splits a script into turns, for synthesizing every turn on its own, e.g. with AWS Polly,
+/// which can only speak a whole document in one voice.
+///
+/// The markup of every line is balanced the same way `parse_tree` balances it.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{dialogue_turns, Cast, SpeakerStyle};
+/// let mut cast = Cast::default();
+/// cast.define("Bob", SpeakerStyle {
+///   rate: Some("slow".to_owned()),
+///   ..SpeakerStyle::voice("Matthew")
+/// });
+/// let turns = dialogue_turns("Bob: Hi.\nBob: It's me.\nAlice: Oh.", &cast).unwrap();
+/// assert_eq!(turns.len(), 2);
+/// assert_eq!(turns[0].voice.as_deref(), Some("Matthew"));
+/// assert_eq!(turns[0].markup, "${prosody|rate=slow}Hi.\nIt's me.${/prosody}");
+/// assert_eq!(turns[1].markup, "Oh.");
+/// ```
+pub fn dialogue_turns(script: &str, cast: &Cast) -> Result<Vec<DialogueTurn>> {
+    group_turns(script)?
+        .into_iter()
+        .map(|(speaker, nodes)| {
+            let style = speaker.as_deref().and_then(|speaker| cast.get(speaker));
+            Ok(DialogueTurn {
+                voice: style.and_then(|style| style.voice.clone()),
+                markup: tree_to_markup(&with_prosody(nodes, style)),
+                speaker,
+            })
+        })
+        .collect()
+}
+
+/// Converts a script into a single document, where every turn is a paragraph spoken in the
+/// voice of its speaker if the target supports switching voices, and with the prosody of
+/// its speaker otherwise.
+///
+/// The markup of every line is balanced the same way `parse_tree` balances it.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::OutputTarget;
+/// use text_to_polly_ssml::parser::{dialogue_to_markup, Cast, SpeakerStyle};
+/// let mut cast = Cast::default();
+/// cast.define("Bob", SpeakerStyle {
+///   pitch: Some("low".to_owned()),
+///   ..SpeakerStyle::voice("Matthew")
+/// });
+/// let script = "Bob: Hi.\nAlice: Oh.";
+/// assert_eq!(
+///   dialogue_to_markup(script, &cast, OutputTarget::Alexa).unwrap(),
+///   "${voice|name=Matthew}${p}Hi.${/p}${/voice}${p}Oh.${/p}",
+/// );
+/// assert_eq!(
+///   dialogue_to_markup(script, &cast, OutputTarget::Polly).unwrap(),
+///   "${p}${prosody|pitch=low}Hi.${/prosody}${/p}${p}Oh.${/p}",
+/// );
+/// ```
+pub fn dialogue_to_markup(script: &str, cast: &Cast, target: OutputTarget) -> Result<String> {
+    let supports_voice = target.supports_tag(&PossibleOpenTags::Voice);
+    let mut nodes = Vec::new();
+    for (speaker, turn) in group_turns(script)? {
+        let style = speaker.as_deref().and_then(|speaker| cast.get(speaker));
+        let voice = style
+            .and_then(|style| style.voice.as_ref())
+            .filter(|_| supports_voice);
+
+        let mut paragraph = SsmlElement::new("p");
+        paragraph.children = match voice {
+            Some(_) => turn,
+            None => with_prosody(turn, style),
+        };
+        match voice {
+            Some(voice) => {
+                // Azure only accepts <voice> right inside of <speak>, so it goes around the
+                // paragraph rather than inside of it.
+                let mut element = SsmlElement::new("voice");
+                element.params.insert("name".to_owned(), voice.clone());
+                element.children.push(SsmlNode::Element(paragraph));
+                nodes.push(SsmlNode::Element(element));
+            }
+            None => nodes.push(SsmlNode::Element(paragraph)),
+        }
+    }
+    Ok(tree_to_markup(&nodes))
+}
+
+/// The nodes of every turn of a script, along with its speaker.
+fn group_turns(script: &str) -> Result<Vec<(Option<String>, Vec<SsmlNode>)>> {
+    let mut turns: Vec<(Option<String>, Vec<SsmlNode>)> = Vec::new();
+    for line in parse_dialogue(script) {
+        let nodes = parse_tree(&line.text)?;
+        let same_speaker = turns.last().is_some_and(|(speaker, _)| {
+            speaker.as_deref().map(str::to_lowercase)
+                == line.speaker.as_deref().map(str::to_lowercase)
+        });
+        match turns.last_mut() {
+            Some((_, turn)) if same_speaker => {
+                push_text(turn, "\n");
+                for node in nodes {
+                    match node {
+                        SsmlNode::Text(text) => push_text(turn, &text),
+                        element => turn.push(element),
+                    }
+                }
+            }
+            _ => turns.push((line.speaker, nodes)),
+        }
+    }
+    Ok(turns)
+}
+
+/// Wraps some nodes in the `${prosody}` of a speaker, if they have one.
+fn with_prosody(nodes: Vec<SsmlNode>, style: Option<&SpeakerStyle>) -> Vec<SsmlNode> {
+    match style.and_then(SpeakerStyle::prosody) {
+        Some(mut prosody) => {
+            prosody.children = nodes;
+            vec![SsmlNode::Element(prosody)]
+        }
+        None => nodes,
+    }
+}
+
+/// Splits a line into its speaker, and its text, if it starts with a speaker.
+fn split_speaker(line: &str) -> Option<(&str, &str)> {
+    let (speaker, text) = line.split_once(':')?;
+    let speaker = speaker.trim_end();
+    if !text.is_empty() && !text.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let is_name = speaker.starts_with(char::is_alphabetic)
+        && speaker.chars().count() <= 32
+        && speaker.chars().all(|character| {
+            character.is_alphanumeric() || matches!(character, ' ' | '.' | '\'' | '-' | '_')
+        });
+    is_name.then(|| (speaker, text.trim()))
+}
@@ -0,0 +1,92 @@
+//! The engines of AWS Polly, and which tags each of them supports. The full table is in
+//! the AWS docs: [HERE](https://docs.aws.amazon.com/polly/latest/dg/supportedtags.html).
+
+use crate::ssml_constants::{AmazonEffect, PossibleOpenTags};
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The AWS Polly engine the SSML is synthesized with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Engine {
+    /// The standard (concatenative) voices.
+    #[default]
+    Standard,
+    /// The neural voices.
+    Neural,
+    /// The long-form voices.
+    LongForm,
+    /// The generative voices.
+    Generative,
+}
+
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Engine::Standard => write!(f, "standard"),
+            Engine::Neural => write!(f, "neural"),
+            Engine::LongForm => write!(f, "long-form"),
+            Engine::Generative => write!(f, "generative"),
+        }
+    }
+}
+
+const ALL: &[Engine] = &[
+    Engine::Standard,
+    Engine::Neural,
+    Engine::LongForm,
+    Engine::Generative,
+];
+const STANDARD: &[Engine] = &[Engine::Standard];
+const ALL_BUT_GENERATIVE: &[Engine] = &[Engine::Standard, Engine::Neural, Engine::LongForm];
+const NONE: &[Engine] = &[];
+
+/// The engines that support a tag, ignoring its parameters.
+fn tag_engines(tag: &PossibleOpenTags) -> &'static [Engine] {
+    match tag {
+        PossibleOpenTags::Emphasis
+        | PossibleOpenTags::AmazonAutoBreaths
+        | PossibleOpenTags::AmazonBreath => STANDARD,
+        PossibleOpenTags::AmazonDomain => &[Engine::Neural],
+        PossibleOpenTags::Voice
+        | PossibleOpenTags::AmazonEmotion
+        | PossibleOpenTags::Silence
+        | PossibleOpenTags::Bookmark
+        | PossibleOpenTags::Par
+        | PossibleOpenTags::Seq
+        | PossibleOpenTags::Media
+        | PossibleOpenTags::ExpressAs => NONE,
+        _ => ALL,
+    }
+}
+
+/// The engines that support a parameter of a tag.
+fn param_engines(tag: &PossibleOpenTags, name: &str, value: &str) -> &'static [Engine] {
+    match (tag, name) {
+        (PossibleOpenTags::Prosody, "pitch") => STANDARD,
+        (PossibleOpenTags::AmazonEffect, "name") => match value.parse::<AmazonEffect>() {
+            Ok(AmazonEffect::Drc) => ALL_BUT_GENERATIVE,
+            _ => STANDARD,
+        },
+        (PossibleOpenTags::AmazonEffect, _) => STANDARD,
+        _ => ALL,
+    }
+}
+
+/// What of a tag the engine doesn't support: `Some(None)` if it doesn't support the tag at
+/// all, `Some(Some(parameter))` for the first parameter it doesn't support, and `None` if
+/// it supports everything.
+pub(crate) fn unsupported<'a>(
+    tag: &PossibleOpenTags,
+    params: &'a BTreeMap<Cow<str>, Cow<str>>,
+    engine: Engine,
+) -> Option<Option<&'a str>> {
+    if !tag_engines(tag).contains(&engine) {
+        return Some(None);
+    }
+    params
+        .iter()
+        .find(|(name, value)| !param_engines(tag, name, value).contains(&engine))
+        .map(|(name, _)| Some(name.as_ref()))
+}
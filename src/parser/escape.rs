@@ -0,0 +1,31 @@
+//! Escaping text, so it can be put into markup without being read as a tag.
+//!
+//! In text `\${` is a literal `${`, and `\\` a literal backslash. Any other backslash is
+//! kept as is. Inside of a tag a backslash escapes whatever character follows it, so
+//! `${sub|alias=a\|b\}}` has an alias of `a|b}`.
+
+/// Escapes text so it is read back exactly as it is.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::escape_text;
+///
+/// assert_eq!(escape_text("costs ${price}"), "costs \\${price}");
+/// ```
+pub fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace("${", "\\${")
+}
+
+/// Escapes a tag key, parameter name, or parameter value so it is read back exactly as it
+/// is.
+pub fn escape_param(param: &str) -> String {
+    let mut escaped = String::with_capacity(param.len());
+    for character in param.chars() {
+        if matches!(character, '\\' | '$' | '|' | '=' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
@@ -0,0 +1,154 @@
+//! Tokenizing markup lazily, one start tag, end tag, or run of text at a time, for
+//! consumers that want to handle the markup themselves without collecting all of it first.
+
+use nom::error::ErrorKind;
+use nom::Err as NomErr;
+
+use super::{
+    append_text, one_item, parse_error, EndTag, OneItem, Span, StartTag, UnterminatedPolicy,
+};
+use crate::error::Result;
+
+use std::borrow::Cow;
+
+/// A start tag, end tag, or run of text of the markup.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SsmlEvent<'a> {
+    /// A `${key|name=value}` tag.
+    Start(StartTag<'a>),
+    /// A `${/key}` tag.
+    End(EndTag<'a>),
+    /// Text with escapes replaced.
+    Text(Cow<'a, str>),
+}
+
+impl<'a> From<OneItem<'a>> for SsmlEvent<'a> {
+    fn from(item: OneItem<'a>) -> SsmlEvent<'a> {
+        match (item.start_tag, item.end_tag, item.data) {
+            (Some(start_tag), _, _) => SsmlEvent::Start(start_tag),
+            (_, Some(end_tag), _) => SsmlEvent::End(end_tag),
+            (_, _, data) => SsmlEvent::Text(data.unwrap_or_default()),
+        }
+    }
+}
+
+/// An iterator over the events of some markup, and where they are in it. It yields exactly
+/// what `tokenize_with_policy` returns, but only tokenizes as far as it is iterated.
+/// Markup that can't be tokenized yields a single `SsmlError::ParseError`, and ends there.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{SsmlEvent, SsmlEvents};
+/// let mut words = 0;
+/// for event in SsmlEvents::new("${s}Hello there${/s}") {
+///     if let (SsmlEvent::Text(text), _) = event.unwrap() {
+///         words += text.split_whitespace().count();
+///     }
+/// }
+/// assert_eq!(words, 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SsmlEvents<'a> {
+    source: &'a str,
+    remaining: &'a str,
+    policy: UnterminatedPolicy,
+    done: bool,
+}
+
+impl<'a> SsmlEvents<'a> {
+    /// Iterates over the events of some markup. A `${` that is never closed is kept as
+    /// literal text.
+    pub fn new(data: &'a str) -> SsmlEvents<'a> {
+        SsmlEvents::with_policy(data, UnterminatedPolicy::default())
+    }
+
+    /// Iterates over the events of some markup with control over what happens to a `${`
+    /// that is never closed.
+    pub fn with_policy(data: &'a str, policy: UnterminatedPolicy) -> SsmlEvents<'a> {
+        SsmlEvents {
+            source: data,
+            remaining: data,
+            policy,
+            done: false,
+        }
+    }
+
+    /// The markup that hasn't been tokenized yet.
+    pub fn remaining(&self) -> &'a str {
+        self.remaining
+    }
+
+    fn next_item(&mut self) -> Option<Result<OneItem<'a>>> {
+        if self.done {
+            return None;
+        }
+        // Like `tokenize` markup without any tags is a single run of text as it is.
+        if !self.source.contains("${") {
+            self.done = true;
+            let data = std::mem::take(&mut self.remaining);
+            return Some(Ok(OneItem {
+                start_tag: None,
+                end_tag: None,
+                data: Some(Cow::Borrowed(data)),
+                span: Span::at(self.source, 0, data.len()),
+            }));
+        }
+
+        let mut item = match self.parse_item() {
+            Ok(item) => item,
+            Err(error) => {
+                self.done = true;
+                return Some(Err(error));
+            }
+        };
+        // A `${` kept as literal text is its own item, which is joined with the text around
+        // it. Whatever comes after the text is tokenized again on the next call.
+        if item.data.is_some() {
+            while let Ok((remaining, next)) =
+                one_item::<(&str, ErrorKind)>(self.source, self.policy)(self.remaining)
+            {
+                if next.data.is_none() {
+                    break;
+                }
+                append_text(self.source, &mut item, &next);
+                self.remaining = remaining;
+            }
+        }
+        self.done = self.remaining.is_empty();
+        Some(Ok(item))
+    }
+
+    fn parse_item(&mut self) -> Result<OneItem<'a>> {
+        match one_item::<(&str, ErrorKind)>(self.source, self.policy)(self.remaining) {
+            Ok((remaining, item)) => {
+                self.remaining = remaining;
+                Ok(item)
+            }
+            Err(NomErr::Error((remaining, kind))) | Err(NomErr::Failure((remaining, kind))) => {
+                Err(parse_error(self.source, remaining, format!("{:?}", kind)))
+            }
+            Err(NomErr::Incomplete(needed)) => Err(parse_error(
+                self.source,
+                &self.source[self.source.len()..],
+                format!("{:?}", needed),
+            )),
+        }
+    }
+}
+
+impl<'a> Iterator for SsmlEvents<'a> {
+    type Item = Result<(SsmlEvent<'a>, Span)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_item().map(|item| {
+            item.map(|item| {
+                let span = item.span;
+                (SsmlEvent::from(item), span)
+            })
+        })
+    }
+}
+
+impl std::iter::FusedIterator for SsmlEvents<'_> {}
@@ -0,0 +1,142 @@
+//! Masks flagged words, so the engine bleeps them instead of speaking them.
+
+use super::tree::{parse_tree, push_text, tree_to_markup, SsmlElement, SsmlNode};
+use super::TEXT_ONLY_TAGS;
+use crate::error::Result;
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// Flags a word that should be masked.
+type WordFilter = dyn Fn(&str) -> bool + Send + Sync;
+
+/// The words to mask, passed into parsing through `ParseOptions::expletives`.
+///
+/// Every flagged word in the text is wrapped in a `${say-as|interpret-as=expletive}` tag.
+/// Words are flagged if they're in the word list (compared without regard to case), or if
+/// the filter flags them, e.g. to look them up in a list maintained somewhere else.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::ParseOptions;
+///
+/// let mut options = ParseOptions::default();
+/// options.expletives.add("darn").set_filter(|word| word.starts_with("heck"));
+/// let ssml = text_to_polly_ssml::parse_str_with_options("Darn, heckin cold", &options).unwrap();
+/// assert!(ssml.ends_with(
+///   r#"<say-as interpret-as="expletive">Darn</say-as>, <say-as interpret-as="expletive">heckin</say-as> cold</speak>"#
+/// ));
+/// ```
+#[derive(Clone, Default)]
+pub struct Expletives {
+    words: BTreeSet<String>,
+    filter: Option<Arc<WordFilter>>,
+}
+
+impl Expletives {
+    /// Adds a word to the word list.
+    pub fn add(&mut self, word: &str) -> &mut Self {
+        self.words.insert(word.to_lowercase());
+        self
+    }
+
+    /// Sets the filter that flags words beyond the word list, replacing any filter set
+    /// before. It's called with every word of the text that isn't in the word list.
+    pub fn set_filter<F>(&mut self, filter: F) -> &mut Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// If a word is flagged, either by the word list, or the filter.
+    pub fn is_expletive(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+            || self.filter.as_ref().is_some_and(|filter| filter(word))
+    }
+
+    /// If no word can be flagged, as there are no words in the list, and no filter.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty() && self.filter.is_none()
+    }
+
+    /// Wraps every flagged word in some markup in a `${say-as|interpret-as=expletive}` tag.
+    /// Text already inside of a tag that can only contain text (e.g. `${sub}`) is left
+    /// alone.
+    ///
+    /// The markup is balanced the same way `parse_tree` balances it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::parser::Expletives;
+    /// let mut expletives = Expletives::default();
+    /// expletives.add("darn");
+    /// assert_eq!(
+    ///   expletives.apply("Darn it, darnation.").unwrap(),
+    ///   "${say-as|interpret-as=expletive}Darn${/say-as} it, darnation.",
+    /// );
+    /// ```
+    pub fn apply(&self, markup: &str) -> Result<String> {
+        Ok(tree_to_markup(&self.mask(parse_tree(markup)?)))
+    }
+
+    /// Wraps every flagged word in the nodes in a `${say-as}` tag.
+    pub(crate) fn mask(&self, nodes: Vec<SsmlNode>) -> Vec<SsmlNode> {
+        let mut masked = Vec::new();
+        for node in nodes {
+            match node {
+                SsmlNode::Text(text) => self.mask_text(&mut masked, &text),
+                SsmlNode::Element(mut element) => {
+                    if !TEXT_ONLY_TAGS.contains(&&*element.name.to_lowercase()) {
+                        element.children = self.mask(element.children);
+                    }
+                    masked.push(SsmlNode::Element(element));
+                }
+            }
+        }
+        masked
+    }
+
+    /// Wraps every flagged word in a single piece of text.
+    fn mask_text(&self, masked: &mut Vec<SsmlNode>, text: &str) {
+        let mut written = 0;
+        let mut idx = 0;
+
+        while idx < text.len() {
+            let word_len = text[idx..]
+                .find(|character: char| !character.is_alphanumeric())
+                .unwrap_or(text.len() - idx);
+            if word_len == 0 {
+                idx += text[idx..].chars().next().map_or(1, char::len_utf8);
+                continue;
+            }
+
+            let word = &text[idx..idx + word_len];
+            if self.is_expletive(word) {
+                push_text(masked, &text[written..idx]);
+                let mut element = SsmlElement::new("say-as");
+                element
+                    .params
+                    .insert("interpret-as".to_owned(), "expletive".to_owned());
+                element.children = vec![SsmlNode::Text(word.to_owned())];
+                masked.push(SsmlNode::Element(element));
+                written = idx + word_len;
+            }
+            idx += word_len;
+        }
+        push_text(masked, &text[written..]);
+    }
+}
+
+impl fmt::Debug for Expletives {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Expletives")
+            .field("words", &self.words)
+            .field("filter", &self.filter.is_some())
+            .finish()
+    }
+}
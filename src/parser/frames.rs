@@ -0,0 +1,138 @@
+//! Building a tree out of the events of another document format (e.g. Markdown), where
+//! every element that is open is one frame on a stack.
+
+use super::tree::{push_text, SsmlElement, SsmlNode};
+
+/// An element that is currently open while walking a document.
+pub(crate) enum Frame {
+    /// Becomes a tag of the markup.
+    Element(SsmlElement),
+    /// Only its content is kept, e.g. a link is read as its text.
+    Transparent(Vec<SsmlNode>),
+    /// Nothing of it is kept, e.g. a code block, or an image.
+    Skipped,
+}
+
+impl Frame {
+    /// A frame for a tag with a single parameter, e.g. `${emphasis|level=strong}`.
+    pub(crate) fn with_param(name: &str, param: &str, value: &str) -> Frame {
+        Frame::Element(element_with_param(name, param, value))
+    }
+
+    fn children(&mut self) -> Option<&mut Vec<SsmlNode>> {
+        match self {
+            Frame::Element(element) => Some(&mut element.children),
+            Frame::Transparent(children) => Some(children),
+            Frame::Skipped => None,
+        }
+    }
+}
+
+/// A stack of open frames, starting out with a single frame for the whole document.
+pub(crate) struct FrameStack {
+    frames: Vec<Frame>,
+}
+
+impl FrameStack {
+    pub(crate) fn new() -> FrameStack {
+        FrameStack {
+            frames: vec![Frame::Transparent(Vec::new())],
+        }
+    }
+
+    pub(crate) fn open(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    /// Closes the innermost frame, and adds what is kept of it to the frame around it.
+    pub(crate) fn close(&mut self) {
+        if self.frames.len() < 2 {
+            return;
+        }
+        if let Some(frame) = self.frames.pop() {
+            match frame {
+                Frame::Element(element) => self.push_node(SsmlNode::Element(element)),
+                Frame::Transparent(children) => {
+                    for node in children {
+                        match node {
+                            SsmlNode::Text(text) => self.push_text(&text),
+                            element => self.push_node(element),
+                        }
+                    }
+                }
+                Frame::Skipped => {}
+            }
+        }
+    }
+
+    /// Closes the innermost frame as a heading: its element is put into a paragraph of its
+    /// own, followed by a strong break.
+    pub(crate) fn close_heading(&mut self) {
+        if self.frames.len() < 2 {
+            return;
+        }
+        match self.frames.pop() {
+            Some(Frame::Element(element)) => {
+                let mut paragraph = SsmlElement::new("p");
+                paragraph.children.push(SsmlNode::Element(element));
+                self.push_node(SsmlNode::Element(paragraph));
+                self.push_node(break_node("strong"));
+            }
+            Some(frame) => {
+                self.frames.push(frame);
+                self.close();
+            }
+            None => {}
+        }
+    }
+
+    /// If any of the open frames is skipped, so nothing is kept right now.
+    pub(crate) fn is_skipping(&self) -> bool {
+        self.frames
+            .iter()
+            .any(|frame| matches!(frame, Frame::Skipped))
+    }
+
+    /// Adds a node to the innermost frame, unless it's skipped.
+    pub(crate) fn push_node(&mut self, node: SsmlNode) {
+        if self.is_skipping() {
+            return;
+        }
+        if let Some(children) = self.frames.last_mut().and_then(Frame::children) {
+            children.push(node);
+        }
+    }
+
+    /// Adds some text to the innermost frame, unless it's skipped.
+    pub(crate) fn push_text(&mut self, text: &str) {
+        if self.is_skipping() {
+            return;
+        }
+        if let Some(children) = self.frames.last_mut().and_then(Frame::children) {
+            push_text(children, text);
+        }
+    }
+
+    /// Closes every frame that is still open, and returns the whole document.
+    pub(crate) fn finish(mut self) -> Vec<SsmlNode> {
+        while self.frames.len() > 1 {
+            self.close();
+        }
+        match self.frames.pop() {
+            Some(Frame::Transparent(children)) => children,
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// An element with a single parameter, e.g. `${break|strength=strong}`.
+pub(crate) fn element_with_param(name: &str, param: &str, value: &str) -> SsmlElement {
+    let mut element = SsmlElement::new(name);
+    element.params.insert(param.to_owned(), value.to_owned());
+    element
+}
+
+/// A `${break}` of some strength.
+pub(crate) fn break_node(strength: &str) -> SsmlNode {
+    SsmlNode::Element(element_with_param("break", "strength", strength))
+}
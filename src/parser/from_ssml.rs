@@ -0,0 +1,171 @@
+//! Reads existing SSML back into the markup tree, so documents that were written by hand,
+//! or exported from a vendor's tooling can be edited in the simpler markup.
+
+use super::tree::{push_node, push_text, tree_to_markup, SsmlElement, SsmlNode};
+use crate::error::{Result, SsmlError};
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// Element names that are written differently in SSML than they are in the markup.
+const ELEMENT_NAMES: &[(&str, &str)] = &[("mstts:silence", "silence")];
+
+/// Attribute names that are written differently in SSML than they are in the markup.
+const ATTRIBUTE_NAMES: &[(&str, &str)] = &[
+    ("xml:lang", "lang"),
+    ("xml:id", "id"),
+    ("amazon:max-duration", "max-duration"),
+];
+
+/// Parses an SSML document into a markup tree. The root `<speak>` tag is unwrapped (with the
+/// whitespace at the start, and end of it trimmed), and the XML declaration, comments, and
+/// processing instructions are dropped. Elements we don't know are kept as they are, so they
+/// can still be rendered as unknown tags.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{parse_ssml_tree, SsmlNode};
+/// let tree = parse_ssml_tree(r#"<speak><s>Hello</s></speak>"#).unwrap();
+/// match &tree[0] {
+///   SsmlNode::Element(s) => assert_eq!(s.name, "s"),
+///   SsmlNode::Text(_) => panic!("expected an element"),
+/// }
+/// ```
+pub fn parse_ssml_tree(ssml: &str) -> Result<Vec<SsmlNode>> {
+    let mut reader = Reader::from_str(ssml);
+    let mut root: Vec<SsmlNode> = Vec::new();
+    let mut open: Vec<SsmlElement> = Vec::new();
+    let mut has_speak_root = false;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader
+            .read_event(&mut buf)
+            .map_err(SsmlError::InvalidSsml)?
+        {
+            Event::Eof => break,
+            Event::Start(ref start) => {
+                let element = element_from_start(&reader, start)?;
+                if open.is_empty() && element.name == "speak" {
+                    // Anything outside of the root is only there for formatting.
+                    root.retain(|node| !is_whitespace(node));
+                    has_speak_root = true;
+                }
+                open.push(element);
+            }
+            Event::Empty(ref start) => {
+                let element = element_from_start(&reader, start)?;
+                push_node(&mut root, &mut open, SsmlNode::Element(element));
+            }
+            Event::End(_) => {
+                if let Some(element) = open.pop() {
+                    if open.is_empty() && element.name == "speak" {
+                        unwrap_speak(&mut root, element.children);
+                    } else {
+                        push_node(&mut root, &mut open, SsmlNode::Element(element));
+                    }
+                }
+            }
+            Event::Text(ref text) => {
+                let text = text
+                    .unescape_and_decode(&reader)
+                    .map_err(SsmlError::InvalidSsml)?;
+                if !(has_speak_root && open.is_empty() && text.trim().is_empty()) {
+                    let siblings = match open.last_mut() {
+                        Some(parent) => &mut parent.children,
+                        None => &mut root,
+                    };
+                    push_text(siblings, &text);
+                }
+            }
+            Event::CData(ref data) => {
+                let text = reader.decode(data).map_err(SsmlError::InvalidSsml)?;
+                let siblings = match open.last_mut() {
+                    Some(parent) => &mut parent.children,
+                    None => &mut root,
+                };
+                push_text(siblings, text);
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    while let Some(element) = open.pop() {
+        if open.is_empty() && element.name == "speak" {
+            unwrap_speak(&mut root, element.children);
+        } else {
+            push_node(&mut root, &mut open, SsmlNode::Element(element));
+        }
+    }
+
+    Ok(root)
+}
+
+/// Converts an SSML document into the markup, so it can be edited, and rendered again with
+/// `parse_as_ssml`. See `parse_ssml_tree` for what is kept from the document.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::ssml_to_markup;
+/// let markup = ssml_to_markup(
+///   r#"<?xml version="1.0"?><speak><p xml:lang="fr-FR">Bonjour<break time="1s"/></p></speak>"#,
+/// );
+/// assert_eq!(markup.unwrap(), "${p|lang=fr-FR}Bonjour${break|time=1s}${/p}");
+/// ```
+pub fn ssml_to_markup(ssml: &str) -> Result<String> {
+    Ok(tree_to_markup(&parse_ssml_tree(ssml)?))
+}
+
+/// Builds an element, with its attributes renamed into their markup form.
+fn element_from_start(reader: &Reader<&[u8]>, start: &BytesStart) -> Result<SsmlElement> {
+    let name = reader
+        .decode(start.name())
+        .map_err(SsmlError::InvalidSsml)?;
+    let mut element = SsmlElement::new(rename(ELEMENT_NAMES, name));
+
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(SsmlError::InvalidSsml)?;
+        let key = reader
+            .decode(attribute.key)
+            .map_err(SsmlError::InvalidSsml)?;
+        if key == "xmlns" || key.starts_with("xmlns:") {
+            continue;
+        }
+        let value = attribute
+            .unescape_and_decode_value(reader)
+            .map_err(SsmlError::InvalidSsml)?;
+        element
+            .params
+            .insert(rename(ATTRIBUTE_NAMES, key).to_owned(), value);
+    }
+
+    Ok(element)
+}
+
+/// Moves the content of the root `<speak>` tag into the root of the tree, without the
+/// whitespace it was indented with.
+fn unwrap_speak(root: &mut Vec<SsmlNode>, mut children: Vec<SsmlNode>) {
+    if let Some(SsmlNode::Text(text)) = children.first_mut() {
+        *text = text.trim_start().to_owned();
+    }
+    if let Some(SsmlNode::Text(text)) = children.last_mut() {
+        *text = text.trim_end().to_owned();
+    }
+    children.retain(|node| !matches!(node, SsmlNode::Text(text) if text.is_empty()));
+    root.extend(children);
+}
+
+fn is_whitespace(node: &SsmlNode) -> bool {
+    matches!(node, SsmlNode::Text(text) if text.trim().is_empty())
+}
+
+fn rename<'a>(names: &[(&str, &'a str)], name: &'a str) -> &'a str {
+    names
+        .iter()
+        .find(|(ssml, _)| *ssml == name)
+        .map(|(_, markup)| *markup)
+        .unwrap_or(name)
+}
@@ -0,0 +1,98 @@
+//! Custom tags. Applications can register their own tag keys, with their own rendering,
+//! without the parser having to know about them.
+
+use crate::error::Result;
+use crate::xml_writer::XmlWriter;
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Renders a custom tag against the `XmlWriter`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::borrow::Cow;
+/// use std::collections::BTreeMap;
+/// use text_to_polly_ssml::parser::TagHandler;
+/// use text_to_polly_ssml::xml_writer::XmlWriter;
+/// use text_to_polly_ssml::{ParseOptions, Result};
+///
+/// struct SoundEffect;
+///
+/// impl TagHandler for SoundEffect {
+///   fn start(&self, writer: &mut XmlWriter, params: &BTreeMap<Cow<str>, Cow<str>>) -> Result<bool> {
+///     match params.get("name") {
+///       Some(name) => {
+///         let mut attributes = BTreeMap::new();
+///         attributes.insert("src".to_owned(), format!("https://example.com/{}.mp3", name));
+///         writer.start_generic_element("audio", &attributes)?;
+///         writer.end_generic_element("audio")?;
+///         Ok(true)
+///       }
+///       None => Ok(false),
+///     }
+///   }
+///
+///   fn has_closing_tag(&self) -> bool {
+///     false
+///   }
+/// }
+///
+/// let mut options = ParseOptions::default();
+/// options.handlers.register("sfx", SoundEffect);
+/// let ssml = text_to_polly_ssml::parse_str_with_options("Ding ${sfx|name=bell}", &options).unwrap();
+/// assert!(ssml.ends_with(r#">Ding <audio src="https://example.com/bell.mp3"></audio></speak>"#));
+/// ```
+pub trait TagHandler: Send + Sync {
+    /// Writes the start of the tag. Returns if anything was written, if not the end of the
+    /// tag is skipped as well.
+    fn start(&self, writer: &mut XmlWriter, params: &BTreeMap<Cow<str>, Cow<str>>) -> Result<bool>;
+
+    /// Writes the end of the tag. Only called if `start` wrote something.
+    fn end(&self, _writer: &mut XmlWriter) -> Result<()> {
+        Ok(())
+    }
+
+    /// If the tag wraps content, and as such has an end tag. Tags without an end tag are
+    /// never expected to be closed in strict mode.
+    fn has_closing_tag(&self) -> bool {
+        true
+    }
+}
+
+/// The custom tags registered for parsing. Tag keys are matched case-insensitively, and a
+/// custom tag takes precedence over a built in tag with the same key.
+#[derive(Clone, Default)]
+pub struct TagHandlers {
+    handlers: BTreeMap<String, Arc<dyn TagHandler>>,
+}
+
+impl TagHandlers {
+    /// Registers a handler for a tag key, replacing any handler registered for it before.
+    pub fn register<H: TagHandler + 'static>(&mut self, tag_key: &str, handler: H) -> &mut Self {
+        self.handlers
+            .insert(tag_key.to_lowercase(), Arc::new(handler));
+        self
+    }
+
+    /// The handler registered for a tag key, if there is one.
+    pub fn get(&self, tag_key: &str) -> Option<&dyn TagHandler> {
+        self.handlers
+            .get(&tag_key.to_lowercase())
+            .map(|handler| handler.as_ref())
+    }
+
+    /// If a handler is registered for a tag key.
+    pub fn contains(&self, tag_key: &str) -> bool {
+        self.handlers.contains_key(&tag_key.to_lowercase())
+    }
+}
+
+impl fmt::Debug for TagHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
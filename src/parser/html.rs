@@ -0,0 +1,394 @@
+//! Converts HTML into markup, so content from a CMS, or any other HTML can be turned into
+//! SSML without being cleaned up by hand first.
+
+use super::frames::{break_node, Frame, FrameStack};
+use super::tree::{tree_to_markup, SsmlElement};
+
+/// Tags that are left out along with everything inside of them, as they aren't meant to
+/// be read, or may contain markup of their own.
+const SKIPPED_TAGS: &[&str] = &[
+    "applet", "audio", "canvas", "embed", "head", "iframe", "math", "noscript", "object", "script",
+    "select", "style", "svg", "template", "textarea", "title", "video",
+];
+
+/// Tags whose content is never parsed as HTML, so they end at their end tag only.
+const RAW_TEXT_TAGS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Tags that never have an end tag.
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Tags whose content is read as its own block of text, so words on both sides of them
+/// never run together.
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "header",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// Converts HTML into markup:
+///
+/// - `<p>` tags become `${p}` tags.
+/// - `<em>`, and `<i>` become `${emphasis|level=moderate}`, `<strong>`, and `<b>` become
+///   `${emphasis|level=strong}` tags.
+/// - `<br>`, and `<hr>` become a medium, and an extra strong break.
+/// - Every item of a `<ul>`, or `<ol>` becomes a `${s}` tag, inside of a `${p}` for the
+///   whole list.
+/// - Headings become a paragraph with strong emphasis, followed by a strong break.
+/// - Scripts, styles, images, embedded content, comments, and the `<head>` are left out.
+/// - Any other tag is read as its text.
+///
+/// Character references are decoded, and whitespace is collapsed the way browsers collapse
+/// it. The HTML doesn't have to be well formed: end tags without a start tag are ignored,
+/// and tags that are never closed end with the document. Text is escaped, so nothing in it
+/// is read as a tag.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::html_to_markup;
+/// assert_eq!(
+///   html_to_markup("<p>Read <em>the</em>\n  <a href=\"/docs\">docs</a>.<script>x()</script></p>"),
+///   "${p}Read ${emphasis|level=moderate}the${/emphasis} docs.${/p}",
+/// );
+/// ```
+pub fn html_to_markup(html: &str) -> String {
+    let mut converter = Converter {
+        stack: FrameStack::new(),
+        open: Vec::new(),
+        at_space: true,
+    };
+
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some((tag, len)) = read_tag(rest) {
+            rest = &rest[len..];
+            if tag.is_end {
+                converter.end_tag(&tag.name);
+            } else if RAW_TEXT_TAGS.contains(&&*tag.name) {
+                // Raw text is always skipped, so there is no need to open a frame for it.
+                rest = skip_raw_text(rest, &tag.name);
+            } else {
+                converter.start_tag(&tag.name, tag.self_closing);
+            }
+        } else {
+            // A `<` that doesn't start a tag is part of the text.
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, character)| *character == '<')
+                .map_or(rest.len(), |(idx, _)| idx);
+            converter.text(&decode_entities(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+
+    while !converter.open.is_empty() {
+        converter.close_innermost();
+    }
+    tree_to_markup(&converter.stack.finish())
+}
+
+/// How a tag that is currently open was converted.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Opened {
+    /// A heading, which is wrapped in a paragraph once it's closed.
+    Heading,
+    /// An element that is a block of its own, e.g. a `${p}`.
+    Element,
+    /// A tag that is read as its text, which is separated from the text around it.
+    Block,
+    /// A tag that is part of the text around it.
+    Inline,
+}
+
+struct Converter {
+    stack: FrameStack,
+    /// The name of every open tag, along with how it was converted. Every one of them has a
+    /// frame on the stack.
+    open: Vec<(String, Opened)>,
+    /// If the text so far ends with whitespace, or at the start of a block, so any
+    /// whitespace that comes next is dropped.
+    at_space: bool,
+}
+
+impl Converter {
+    fn start_tag(&mut self, name: &str, self_closing: bool) {
+        match name {
+            "br" => return self.push_break("medium"),
+            "hr" => return self.push_break("x-strong"),
+            _ if VOID_TAGS.contains(&name) => return,
+            _ => {}
+        }
+
+        // A paragraph ends at the start of any block, and a list item at the start of the
+        // next item in the same list.
+        let is_heading = matches!(name, "h1" | "h2" | "h3" | "h4" | "h5" | "h6");
+        if BLOCK_TAGS.contains(&name) || is_heading {
+            if let Some(paragraph) = self.position(&["p"]) {
+                if self.position(&["li", "ul", "ol"]) < Some(paragraph) {
+                    self.close_through(paragraph);
+                }
+            }
+        }
+        if name == "li" {
+            if let Some(item) = self.position(&["li"]) {
+                if self.position(&["ul", "ol"]) < Some(item) {
+                    self.close_through(item);
+                }
+            }
+        }
+
+        let in_item = self.position(&["li"]).is_some();
+        let in_list = self.position(&["ul", "ol"]).is_some();
+        let (frame, opened) = match name {
+            _ if SKIPPED_TAGS.contains(&name) => (Frame::Skipped, Opened::Inline),
+            "p" | "ul" | "ol" if !in_item => {
+                (Frame::Element(SsmlElement::new("p")), Opened::Element)
+            }
+            "li" if in_list && !in_item => (Frame::Element(SsmlElement::new("s")), Opened::Element),
+            _ if is_heading && !in_item => (
+                Frame::with_param("emphasis", "level", "strong"),
+                Opened::Heading,
+            ),
+            "em" | "i" => (
+                Frame::with_param("emphasis", "level", "moderate"),
+                Opened::Inline,
+            ),
+            "strong" | "b" => (
+                Frame::with_param("emphasis", "level", "strong"),
+                Opened::Inline,
+            ),
+            _ if BLOCK_TAGS.contains(&name) || is_heading => {
+                self.push_space();
+                (Frame::Transparent(Vec::new()), Opened::Block)
+            }
+            _ => (Frame::Transparent(Vec::new()), Opened::Inline),
+        };
+
+        if opened != Opened::Inline {
+            self.at_space = true;
+        }
+        self.stack.open(frame);
+        self.open.push((name.to_owned(), opened));
+        if self_closing {
+            self.close_innermost();
+        }
+    }
+
+    fn end_tag(&mut self, name: &str) {
+        if let Some(idx) = self.position(&[name]) {
+            self.close_through(idx);
+        }
+    }
+
+    /// The index of the innermost open tag with one of some names.
+    fn position(&self, names: &[&str]) -> Option<usize> {
+        self.open
+            .iter()
+            .rposition(|(name, _)| names.contains(&name.as_str()))
+    }
+
+    /// Closes every open tag from the innermost one up to, and including the one at `idx`.
+    fn close_through(&mut self, idx: usize) {
+        while self.open.len() > idx {
+            self.close_innermost();
+        }
+    }
+
+    fn close_innermost(&mut self) {
+        match self.open.pop() {
+            Some((_, Opened::Heading)) => self.stack.close_heading(),
+            Some((_, Opened::Element)) => {
+                self.stack.close();
+                self.at_space = true;
+            }
+            Some((_, Opened::Block)) => {
+                self.stack.close();
+                self.push_space();
+            }
+            Some((_, Opened::Inline)) => self.stack.close(),
+            None => {}
+        }
+    }
+
+    fn push_break(&mut self, strength: &str) {
+        self.stack.push_node(break_node(strength));
+        self.at_space = true;
+    }
+
+    /// Separates the text before a block from the text after it.
+    fn push_space(&mut self) {
+        if !self.at_space {
+            self.stack.push_text(" ");
+            self.at_space = true;
+        }
+    }
+
+    /// Adds some text, with every run of whitespace in it collapsed into a single space.
+    fn text(&mut self, text: &str) {
+        if self.stack.is_skipping() {
+            return;
+        }
+        let mut collapsed = String::with_capacity(text.len());
+        for character in text.chars() {
+            if character.is_ascii_whitespace() {
+                if !self.at_space {
+                    collapsed.push(' ');
+                    self.at_space = true;
+                }
+            } else {
+                collapsed.push(character);
+                self.at_space = false;
+            }
+        }
+        self.stack.push_text(&collapsed);
+    }
+}
+
+/// A start, or end tag, with its attributes left out.
+struct HtmlTag {
+    /// The name of the tag in lowercase.
+    name: String,
+    is_end: bool,
+    self_closing: bool,
+}
+
+/// Reads the tag at the start of some HTML, along with how long it is. Returns `None` if
+/// the HTML doesn't start with a tag, e.g. when a `<` is followed by a space.
+fn read_tag(html: &str) -> Option<(HtmlTag, usize)> {
+    let after_bracket = html.strip_prefix('<')?;
+    let (is_end, after_slash) = match after_bracket.strip_prefix('/') {
+        Some(after_slash) => (true, after_slash),
+        None => (false, after_bracket),
+    };
+    if !after_slash.starts_with(|character: char| character.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name_len = after_slash
+        .find(|character: char| character.is_ascii_whitespace() || matches!(character, '/' | '>'))
+        .unwrap_or(after_slash.len());
+
+    // Attributes are skipped, but a `>` inside of a quoted value doesn't end the tag.
+    let mut quote = None;
+    let mut previous = None;
+    for (idx, character) in after_slash[name_len..].char_indices() {
+        match quote {
+            Some(open) if character == open => quote = None,
+            Some(_) => {}
+            None if character == '"' || character == '\'' => quote = Some(character),
+            None if character == '>' => {
+                let len = html.len() - after_slash.len() + name_len + idx + 1;
+                let tag = HtmlTag {
+                    name: after_slash[..name_len].to_ascii_lowercase(),
+                    is_end,
+                    self_closing: previous == Some('/'),
+                };
+                return Some((tag, len));
+            }
+            None => {}
+        }
+        previous = Some(character);
+    }
+    None
+}
+
+/// Skips everything up to, and including the end tag of a tag whose content is raw text.
+fn skip_raw_text<'a>(html: &'a str, name: &str) -> &'a str {
+    let end_tag = format!("</{}", name);
+    match html.to_ascii_lowercase().find(&end_tag) {
+        Some(start) => {
+            let rest = &html[start..];
+            rest.find('>').map_or("", |end| &rest[end + 1..])
+        }
+        None => "",
+    }
+}
+
+/// Decodes the character references in some text, e.g. `&amp;`, or `&#8217;`. References
+/// we don't know are kept as they are.
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let character = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 32)
+            .and_then(|end| decode_entity(&rest[1..=end]).map(|character| (character, end)));
+        match character {
+            Some((character, end)) => {
+                decoded.push(character);
+                rest = &rest[end + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// The character a reference stands for, from its name without the `&`, and `;`.
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "euro" => '€',
+        "pound" => '£',
+        "yen" => '¥',
+        "cent" => '¢',
+        _ => return None,
+    })
+}
@@ -0,0 +1,100 @@
+//! The `${include|file=...}` directive, which pulls other markup fragments in from disk.
+
+use super::{tokenize_with_policy, OneItem, UnterminatedPolicy};
+use crate::error::{Result, SsmlError};
+
+use std::fs;
+use std::path::PathBuf;
+
+/// The tag key of the include directive.
+pub const INCLUDE_TAG: &str = "include";
+
+/// Controls the `${include|file=...}` directive.
+#[derive(Clone, Debug)]
+pub struct IncludeOptions {
+    /// The directory included files are resolved against. Files outside of it can't be
+    /// included. If it isn't set every include is an error.
+    pub root: Option<PathBuf>,
+    /// How deep includes can be nested, an included file including another file is a
+    /// depth of 2.
+    pub max_depth: usize,
+}
+
+impl Default for IncludeOptions {
+    fn default() -> IncludeOptions {
+        IncludeOptions {
+            root: None,
+            max_depth: 8,
+        }
+    }
+}
+
+/// Replaces every include directive in the tokenized markup with the tokenized contents
+/// of the file it includes. Included items point at the include directive, as they don't
+/// exist in the original text.
+pub(crate) fn expand_includes<'a>(
+    items: Vec<OneItem<'a>>,
+    options: &IncludeOptions,
+    unterminated: UnterminatedPolicy,
+    depth: usize,
+) -> Result<Vec<OneItem<'a>>> {
+    let mut expanded = Vec::with_capacity(items.len());
+    for item in items {
+        let is_include = item
+            .start_tag
+            .as_ref()
+            .map(|start_tag| start_tag.tag_key.eq_ignore_ascii_case(INCLUDE_TAG))
+            .unwrap_or(false);
+        if !is_include {
+            expanded.push(item);
+            continue;
+        }
+
+        let file = match item
+            .start_tag
+            .as_ref()
+            .and_then(|tag| tag.params.get("file"))
+        {
+            Some(file) => file,
+            None => {
+                return Err(SsmlError::MissingAttribute {
+                    tag: INCLUDE_TAG.to_owned(),
+                    attribute: "file".to_owned(),
+                })
+            }
+        };
+        if depth >= options.max_depth {
+            return Err(SsmlError::IncludeTooDeep(file.to_string()));
+        }
+        let contents = read_include(file, options)?;
+        let included = tokenize_with_policy(&contents, unterminated)?;
+        for mut included_item in expand_includes(included, options, unterminated, depth + 1)? {
+            included_item.span = item.span;
+            if let Some(ref mut included_tag) = included_item.start_tag {
+                included_tag.param_spans.clear();
+            }
+            expanded.push(included_item.into_owned());
+        }
+    }
+    Ok(expanded)
+}
+
+/// Reads an included file, making sure it is inside of the include root.
+fn read_include(file: &str, options: &IncludeOptions) -> Result<String> {
+    let root = match options.root {
+        Some(ref root) => root,
+        None => return Err(SsmlError::IncludeNotAllowed(file.to_owned())),
+    };
+    let io_error = |path: &PathBuf| {
+        let path = path.to_owned();
+        move |source| SsmlError::Io { path, source }
+    };
+
+    let root = root.canonicalize().map_err(io_error(root))?;
+    let requested = root.join(file);
+    let path = requested.canonicalize().map_err(io_error(&requested))?;
+    if !path.starts_with(&root) {
+        return Err(SsmlError::IncludeNotAllowed(file.to_owned()));
+    }
+    fs::read_to_string(&path).map_err(io_error(&path))
+}
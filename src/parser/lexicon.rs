@@ -0,0 +1,187 @@
+//! A pronunciation lexicon, so domain jargon is pronounced the same way everywhere without
+//! every use of it having to be wrapped in a `${phoneme}` by hand.
+
+use super::terms::{wrap_terms, Term, TermMatching};
+use super::tree::{parse_tree, tree_to_markup, SsmlElement, SsmlNode};
+use crate::error::Result;
+#[cfg(feature = "config")]
+use crate::error::SsmlError;
+use crate::phonetics::convert_phonemes;
+use crate::ssml_constants::PhonemeAlphabet;
+
+use std::collections::BTreeMap;
+#[cfg(feature = "config")]
+use std::path::Path;
+
+/// How a word is pronounced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pronunciation {
+    /// The alphabet `ph` is written in.
+    pub alphabet: PhonemeAlphabet,
+    /// The phonetic spelling of the word.
+    pub ph: String,
+}
+
+/// A lexicon of words, and how each of them is pronounced, passed into parsing through
+/// `ParseOptions::lexicon`.
+///
+/// Every use of a word in the text is wrapped in a `${phoneme|alphabet=...|ph=...}` tag. By
+/// default words are matched without regard to case, and only as whole words.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::ParseOptions;
+/// use text_to_polly_ssml::ssml_constants::PhonemeAlphabet;
+///
+/// let mut options = ParseOptions::default();
+/// options.lexicon.define("pecan", PhonemeAlphabet::Ipa, "pɪˈkɑːn");
+/// let ssml = text_to_polly_ssml::parse_str_with_options("Pecan pie", &options).unwrap();
+/// assert!(ssml.ends_with(r#"<phoneme alphabet="ipa" ph="pɪˈkɑːn">Pecan</phoneme> pie</speak>"#));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Lexicon {
+    pronunciations: BTreeMap<String, Pronunciation>,
+    /// Only match words with the exact same case as they were defined with.
+    pub case_sensitive: bool,
+    /// Only match words that aren't part of a longer word, e.g. `pecan` in `pecans`.
+    pub whole_word: bool,
+}
+
+impl Default for Lexicon {
+    fn default() -> Lexicon {
+        Lexicon {
+            pronunciations: BTreeMap::new(),
+            case_sensitive: false,
+            whole_word: true,
+        }
+    }
+}
+
+impl Lexicon {
+    /// Defines how a word is pronounced, replacing any pronunciation defined for it before.
+    pub fn define(&mut self, word: &str, alphabet: PhonemeAlphabet, ph: &str) -> &mut Self {
+        self.pronunciations.insert(
+            word.to_owned(),
+            Pronunciation {
+                alphabet,
+                ph: ph.to_owned(),
+            },
+        );
+        self
+    }
+
+    /// How a word is pronounced, if it's defined with the exact same case.
+    pub fn get(&self, word: &str) -> Option<&Pronunciation> {
+        self.pronunciations.get(word)
+    }
+
+    /// Defines every pronunciation in a TOML, or JSON file (picked by the extension of the
+    /// file). The file has to be a table of words, each with an `alphabet`, and a `ph`:
+    ///
+    /// ```toml
+    /// pecan = { alphabet = "ipa", ph = "pɪˈkɑːn" }
+    /// tomato = { alphabet = "x-sampa", ph = "t@meItoU" }
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn define_from_path<P: AsRef<Path>>(&mut self, path: P) -> Result<&mut Self> {
+        let path = path.as_ref();
+        let entries: BTreeMap<String, BTreeMap<String, String>> = super::macros::read_table(path)?;
+        for (word, entry) in entries {
+            let invalid = |message: String| SsmlError::InvalidConfig {
+                path: path.to_owned(),
+                message,
+            };
+            let alphabet = entry
+                .get("alphabet")
+                .ok_or_else(|| invalid(format!("`{}` is missing an `alphabet`", word)))?;
+            let alphabet = alphabet.parse::<PhonemeAlphabet>().map_err(|_| {
+                invalid(format!("`{}` has an unknown alphabet `{}`", word, alphabet))
+            })?;
+            let ph = entry
+                .get("ph")
+                .ok_or_else(|| invalid(format!("`{}` is missing a `ph`", word)))?;
+            self.define(&word, alphabet, ph);
+        }
+        Ok(self)
+    }
+
+    /// If no pronunciations are defined.
+    pub fn is_empty(&self) -> bool {
+        self.pronunciations.is_empty()
+    }
+
+    /// The same lexicon, with every pronunciation converted into another alphabet (see
+    /// `phonetics::convert_phonemes`). Returns an error if any of them can't be converted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::parser::Lexicon;
+    /// use text_to_polly_ssml::ssml_constants::PhonemeAlphabet;
+    /// let mut lexicon = Lexicon::default();
+    /// lexicon.define("pecan", PhonemeAlphabet::Ipa, "pɪˈkɑːn");
+    /// let converted = lexicon.to_alphabet(PhonemeAlphabet::XSampa).unwrap();
+    /// assert_eq!(converted.get("pecan").unwrap().ph, "pI\"kA:n");
+    /// ```
+    pub fn to_alphabet(&self, alphabet: PhonemeAlphabet) -> Result<Lexicon> {
+        let mut converted = self.clone();
+        for pronunciation in converted.pronunciations.values_mut() {
+            pronunciation.ph =
+                convert_phonemes(&pronunciation.ph, pronunciation.alphabet, alphabet)?;
+            pronunciation.alphabet = alphabet;
+        }
+        Ok(converted)
+    }
+
+    /// Wraps every word of the lexicon in some markup in a `${phoneme}` tag with its
+    /// pronunciation. Text already inside of a tag that can only contain text (e.g.
+    /// `${sub}`) is left alone.
+    ///
+    /// The markup is balanced the same way `parse_tree` balances it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::parser::Lexicon;
+    /// use text_to_polly_ssml::ssml_constants::PhonemeAlphabet;
+    /// let mut lexicon = Lexicon::default();
+    /// lexicon.define("tomato", PhonemeAlphabet::XSampa, "t@meItoU");
+    /// assert_eq!(
+    ///   lexicon.apply("Tomato, tomatoes.").unwrap(),
+    ///   "${phoneme|alphabet=x-sampa|ph=t@meItoU}Tomato${/phoneme}, tomatoes.",
+    /// );
+    /// ```
+    pub fn apply(&self, markup: &str) -> Result<String> {
+        Ok(tree_to_markup(&self.pronounce(parse_tree(markup)?)))
+    }
+
+    /// Wraps every word of the lexicon in the nodes in a `${phoneme}` tag.
+    pub(crate) fn pronounce(&self, nodes: Vec<SsmlNode>) -> Vec<SsmlNode> {
+        let terms = self
+            .pronunciations
+            .iter()
+            .map(|(word, pronunciation)| {
+                let mut element = SsmlElement::new("phoneme");
+                element
+                    .params
+                    .insert("alphabet".to_owned(), pronunciation.alphabet.to_string());
+                element
+                    .params
+                    .insert("ph".to_owned(), pronunciation.ph.to_owned());
+                Term {
+                    text: word.to_owned(),
+                    element,
+                }
+            })
+            .collect::<Vec<_>>();
+        wrap_terms(
+            nodes,
+            &terms,
+            TermMatching {
+                case_sensitive: self.case_sensitive,
+                whole_word: self.whole_word,
+            },
+        )
+    }
+}
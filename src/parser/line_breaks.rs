@@ -0,0 +1,136 @@
+//! Turns single newlines into breaks, for scripts that are written with one line per beat.
+
+use super::tree::{parse_tree, push_text, tree_to_markup, SsmlElement, SsmlNode};
+use super::TEXT_ONLY_TAGS;
+use crate::error::Result;
+use crate::ssml_constants::{BreakStrength, BreakTime};
+
+/// The break placed at every single newline, see `break_lines`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LineBreaks {
+    /// The `strength` of the break.
+    pub strength: Option<BreakStrength>,
+    /// The `time` of the break.
+    pub time: Option<BreakTime>,
+}
+
+impl Default for LineBreaks {
+    /// A `${break|strength=medium}`.
+    fn default() -> LineBreaks {
+        LineBreaks {
+            strength: Some(BreakStrength::Medium),
+            time: None,
+        }
+    }
+}
+
+impl LineBreaks {
+    fn element(&self) -> SsmlElement {
+        let mut element = SsmlElement::new("break");
+        if let Some(strength) = self.strength {
+            element
+                .params
+                .insert("strength".to_owned(), strength.to_string());
+        }
+        if let Some(time) = self.time {
+            element.params.insert("time".to_owned(), time.to_string());
+        }
+        element
+    }
+}
+
+/// Places a break after every single newline in some markup. Blank lines are left alone,
+/// as they separate paragraphs instead (see `wrap_paragraphs`), as are newlines at the very
+/// start, or end of the content of a tag, and newlines inside of a tag that can only contain
+/// text (e.g. `${sub}`). The newline itself is kept.
+///
+/// The markup is balanced the same way `parse_tree` balances it.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{break_lines, LineBreaks};
+/// let markup = break_lines("One beat.\nAnother.\n", &LineBreaks::default());
+/// assert_eq!(
+///   markup.unwrap(),
+///   "One beat.\n${break|strength=medium}Another.\n",
+/// );
+/// ```
+pub fn break_lines(markup: &str, line_breaks: &LineBreaks) -> Result<String> {
+    Ok(tree_to_markup(&line_break_nodes(
+        parse_tree(markup)?,
+        line_breaks,
+    )))
+}
+
+/// Places a break after every single newline between the nodes.
+pub(crate) fn line_break_nodes(nodes: Vec<SsmlNode>, line_breaks: &LineBreaks) -> Vec<SsmlNode> {
+    let last = nodes.len().saturating_sub(1);
+    let mut broken = Vec::new();
+
+    for (idx, node) in nodes.into_iter().enumerate() {
+        match node {
+            SsmlNode::Text(text) => {
+                let content_start = if idx == 0 {
+                    text.len() - text.trim_start().len()
+                } else {
+                    0
+                };
+                let content_end = if idx == last {
+                    text.trim_end().len()
+                } else {
+                    text.len()
+                };
+                if content_start >= content_end {
+                    push_text(&mut broken, &text);
+                    continue;
+                }
+
+                push_text(&mut broken, &text[..content_start]);
+                let mut written = content_start;
+                for end in line_ends(&text[content_start..content_end], idx != last) {
+                    push_text(&mut broken, &text[written..content_start + end]);
+                    broken.push(SsmlNode::Element(line_breaks.element()));
+                    written = content_start + end;
+                }
+                push_text(&mut broken, &text[written..]);
+            }
+            SsmlNode::Element(mut element) => {
+                if !TEXT_ONLY_TAGS.contains(&&*element.name.to_lowercase()) {
+                    element.children = line_break_nodes(element.children, line_breaks);
+                }
+                broken.push(SsmlNode::Element(element));
+            }
+        }
+    }
+
+    broken
+}
+
+/// The byte offsets right after every run of whitespace in some text that contains exactly
+/// one newline. A run that ends the text only counts if more content follows the text.
+fn line_ends(text: &str, followed_by_content: bool) -> Vec<usize> {
+    let mut ends = Vec::new();
+    let mut newlines = 0;
+    let mut in_whitespace = false;
+
+    for (idx, character) in text.char_indices() {
+        if character.is_whitespace() {
+            in_whitespace = true;
+            if character == '\n' {
+                newlines += 1;
+            }
+            continue;
+        }
+        if in_whitespace && newlines == 1 {
+            ends.push(idx);
+        }
+        in_whitespace = false;
+        newlines = 0;
+    }
+    if followed_by_content && in_whitespace && newlines == 1 {
+        ends.push(text.len());
+    }
+
+    ends
+}
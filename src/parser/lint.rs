@@ -0,0 +1,368 @@
+//! Checking markup for problems without rendering it, e.g. for an editor, or a CI check.
+
+use super::render::{check_language, write_start_tag};
+use super::{
+    expand_items, has_closing_tag, is_unknown_end_tag, is_unknown_start_tag, normalize_input,
+    validate, ParseOptions, Span, UnknownTagPolicy, Warning, WarningKind,
+};
+use crate::error::{Result, SsmlError};
+use crate::registry::find_tag;
+use crate::xml_writer::{DeclarationOptions, WriterOptions, XmlWriter};
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// How bad a problem found by `lint` is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The markup can't be rendered, or renders into SSML that isn't valid.
+    Error,
+    /// Part of the markup is ignored, or might not do what was intended.
+    Warning,
+    /// The markup works, but there's a better way to write it.
+    Hint,
+}
+
+/// What kind of problem `lint` found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// The markup couldn't be tokenized, or an include, or macro couldn't be expanded.
+    Syntax,
+    /// A tag is opened, but never closed.
+    UnclosedTag,
+    /// A tag is closed, but was never opened.
+    UnopenedTag,
+    /// A tag is closed while a tag opened after it is still open.
+    MisnestedTag,
+    /// The markup uses a spelling older versions accepted, e.g. the `$\{` escape, or the
+    /// `whisper` effect.
+    Deprecated,
+    /// Something rendering would report as a `Warning`.
+    Render(WarningKind),
+}
+
+/// A single problem `lint` found in a piece of markup.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// How bad the problem is.
+    pub severity: Severity,
+    /// What kind of problem it is.
+    pub kind: DiagnosticKind,
+    /// The key of the tag involved, if the problem is about a tag.
+    pub tag: Option<String>,
+    /// The parameter involved, if the problem is about a single parameter.
+    pub attribute: Option<String>,
+    /// A human readable description of the problem.
+    pub message: String,
+    /// Where the problem is in the markup, if it's known. Just the `name=value` of the
+    /// parameter for problems with a single parameter.
+    pub span: Option<Span>,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, kind: DiagnosticKind, tag: &str, message: String) -> Diagnostic {
+        Diagnostic {
+            severity,
+            kind,
+            tag: Some(tag.to_owned()),
+            attribute: None,
+            message,
+            span: None,
+        }
+    }
+
+    fn from_warning(warning: Warning) -> Diagnostic {
+        let severity = match warning.kind {
+            WarningKind::MissingAttribute | WarningKind::IllegalNesting => Severity::Error,
+            _ => Severity::Warning,
+        };
+        Diagnostic {
+            severity,
+            kind: DiagnosticKind::Render(warning.kind),
+            tag: Some(warning.tag),
+            attribute: warning.attribute,
+            message: warning.message,
+            span: warning.span,
+        }
+    }
+
+    fn from_error(data: &str, error: &SsmlError) -> Diagnostic {
+        let span = match error.root_cause() {
+            SsmlError::ParseError { line, column, .. } => span_at(data, *line, *column),
+            _ => None,
+        };
+        Diagnostic {
+            severity: Severity::Error,
+            kind: DiagnosticKind::Syntax,
+            tag: None,
+            attribute: None,
+            message: error.to_string(),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Hint => "hint",
+        };
+        match self.span {
+            Some(span) => write!(
+                f,
+                "{}: {} (line {}, column {})",
+                severity, self.message, span.line, span.column
+            ),
+            None => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+/// Checks some markup for problems without rendering it, with the default options. See
+/// `lint_with_options`.
+pub fn lint(data: &str) -> Vec<Diagnostic> {
+    lint_with_options(data, &ParseOptions::default())
+}
+
+/// Checks some markup for problems without rendering it: tags that aren't closed, or
+/// opened, unknown tags, and parameters, values the output target doesn't accept, tags
+/// nested where SSML doesn't allow them, and spellings older versions used. Everything
+/// `parse_as_ssml_with_report` would warn about is included. Markup that can't be tokenized
+/// is reported as a single `DiagnosticKind::Syntax` error. Diagnostics are in the order they
+/// appear in the markup, followed by any tags that are never closed.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{lint, DiagnosticKind, Severity};
+/// let diagnostics = lint("${s}Hello ${break|time=1s}");
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].severity, Severity::Error);
+/// assert_eq!(diagnostics[0].kind, DiagnosticKind::UnclosedTag);
+/// ```
+pub fn lint_with_options(data: &str, options: &ParseOptions) -> Vec<Diagnostic> {
+    let normalized = normalize_input(data, options);
+    let data = &*normalized;
+    match run(data, options) {
+        Ok(diagnostics) => diagnostics,
+        Err(error) => vec![Diagnostic::from_error(data, &error)],
+    }
+}
+
+fn run(data: &str, options: &ParseOptions) -> Result<Vec<Diagnostic>> {
+    let items = expand_items(data, options)?;
+    // Tags are written to a scratch writer only to find out what rendering would warn
+    // about, the output is thrown away.
+    let mut xml_writer = XmlWriter::with_options(WriterOptions {
+        declaration: DeclarationOptions {
+            omit: true,
+            ..Default::default()
+        },
+        validate_languages: false,
+        ..Default::default()
+    })?;
+    let mut diagnostics = Vec::new();
+    let mut warnings = Vec::new();
+    if let Some(ref lang) = options.speak.lang {
+        if !options.target.is_bare() {
+            check_language("speak", "lang", lang, options, &mut warnings);
+        }
+    }
+    diagnostics.extend(warnings.drain(..).map(Diagnostic::from_warning));
+    // Every tag that wraps content that is currently open, if it would be written, and
+    // where it was opened.
+    let mut open: Vec<(String, bool, Span)> = Vec::new();
+
+    for item in items {
+        let first_diagnostic = diagnostics.len();
+
+        if let Some(ref start_tag) = item.start_tag {
+            let tag_key = &start_tag.tag_key;
+            let unknown = is_unknown_start_tag(tag_key, options);
+            let mut hints = Vec::new();
+            let written = match options.unknown_tags {
+                // Written out as text, so there's nothing to check.
+                UnknownTagPolicy::Literal if unknown => continue,
+                UnknownTagPolicy::Error if unknown => {
+                    let mut diagnostic = Diagnostic::from_warning(Warning::unknown_tag(tag_key));
+                    diagnostic.severity = Severity::Error;
+                    diagnostic.message = SsmlError::UnknownTag(tag_key.to_string()).to_string();
+                    diagnostics.push(diagnostic);
+                    false
+                }
+                UnknownTagPolicy::Passthrough if unknown => true,
+                _ if options.handlers.contains(tag_key) => true,
+                _ => {
+                    for attribute in &start_tag.duplicate_params {
+                        warnings.push(Warning::duplicate_attribute(tag_key, attribute));
+                    }
+                    for attribute in validate::unknown_params(start_tag) {
+                        warnings.push(Warning::unknown_attribute(tag_key, attribute));
+                    }
+                    let written = write_start_tag(
+                        &mut xml_writer,
+                        tag_key,
+                        &start_tag.params,
+                        options,
+                        &mut warnings,
+                    )?;
+                    hints = deprecated_values(tag_key, &start_tag.params, &warnings);
+                    written
+                }
+            };
+            let is_element = !options.handlers.contains(tag_key)
+                && (!unknown || options.unknown_tags == UnknownTagPolicy::Passthrough);
+            if is_element {
+                let written_open = open
+                    .iter()
+                    .filter(|(_, written, _)| *written)
+                    .map(|(tag_key, _, _)| tag_key.as_str());
+                if let Some(parent) = validate::illegal_parent(written_open, tag_key) {
+                    warnings.push(Warning::illegal_nesting(tag_key, parent));
+                }
+            }
+            diagnostics.extend(warnings.drain(..).map(Diagnostic::from_warning));
+            diagnostics.extend(hints);
+            if has_closing_tag(tag_key, options) {
+                open.push((tag_key.to_lowercase(), written, item.span));
+            }
+        }
+
+        if let Some(ref end_tag) = item.end_tag {
+            if options.unknown_tags == UnknownTagPolicy::Literal
+                && is_unknown_end_tag(&end_tag.tag_key, options)
+            {
+                continue;
+            }
+            let tag_key = end_tag.tag_key.to_lowercase();
+            match open
+                .iter()
+                .rposition(|(open_key, _, _)| *open_key == tag_key)
+            {
+                Some(position) => {
+                    for (innermost, _, _) in open.drain(position + 1..).rev() {
+                        diagnostics.push(Diagnostic::new(
+                            Severity::Error,
+                            DiagnosticKind::MisnestedTag,
+                            &end_tag.tag_key,
+                            format!(
+                                "`{}` is closed while `{}` is still open inside of it.",
+                                end_tag.tag_key, innermost
+                            ),
+                        ));
+                    }
+                    open.truncate(position);
+                }
+                None => {
+                    if is_unknown_end_tag(&end_tag.tag_key, options)
+                        && options.unknown_tags == UnknownTagPolicy::Drop
+                    {
+                        diagnostics.push(Diagnostic::from_warning(Warning::unknown_tag(
+                            &end_tag.tag_key,
+                        )));
+                    }
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Error,
+                        DiagnosticKind::UnopenedTag,
+                        &end_tag.tag_key,
+                        format!("`{}` is closed, but was never opened.", end_tag.tag_key),
+                    ));
+                }
+            }
+        }
+
+        if item.data.is_some() && item.span.snippet(data).contains("$\\{") {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Hint,
+                kind: DiagnosticKind::Deprecated,
+                tag: None,
+                attribute: None,
+                message: "`$\\{` is a deprecated escape, use `\\${` instead.".to_owned(),
+                span: None,
+            });
+        }
+
+        // Problems with a single parameter point at just that parameter, where it's known.
+        for diagnostic in &mut diagnostics[first_diagnostic..] {
+            let param_span = match (&item.start_tag, &diagnostic.attribute) {
+                (Some(start_tag), Some(attribute)) => start_tag.param_spans.get(attribute.as_str()),
+                _ => None,
+            };
+            diagnostic.span = Some(param_span.copied().unwrap_or(item.span));
+        }
+    }
+
+    for (tag_key, _, span) in open {
+        let mut diagnostic = Diagnostic::new(
+            Severity::Error,
+            DiagnosticKind::UnclosedTag,
+            &tag_key,
+            format!("`{}` is opened, but never closed.", tag_key),
+        );
+        diagnostic.span = Some(span);
+        diagnostics.push(diagnostic);
+    }
+
+    Ok(diagnostics)
+}
+
+/// Hints for every parameter of a built in tag with a value that was accepted, but isn't
+/// one of the values the registry lists, e.g. `whisper` instead of `whispered`.
+fn deprecated_values(
+    tag_key: &str,
+    params: &BTreeMap<Cow<str>, Cow<str>>,
+    warnings: &[Warning],
+) -> Vec<Diagnostic> {
+    let tag = match find_tag(tag_key) {
+        Some(tag) => tag,
+        None => return Vec::new(),
+    };
+    params
+        .iter()
+        .filter_map(|(name, value)| {
+            let values = tag.param(name)?.values?;
+            let rejected = warnings.iter().any(|warning| {
+                warning.kind == WarningKind::InvalidAttributeValue
+                    && warning.attribute.as_deref() == Some(name.as_ref())
+            });
+            if rejected || values.contains(&value.to_lowercase().as_str()) {
+                return None;
+            }
+            let mut diagnostic = Diagnostic::new(
+                Severity::Hint,
+                DiagnosticKind::Deprecated,
+                tag_key,
+                format!(
+                    "`{}` is a deprecated value for the `{}` parameter of `{}`, use one of: {}.",
+                    value,
+                    name,
+                    tag_key,
+                    values.join(", ")
+                ),
+            );
+            diagnostic.attribute = Some(name.to_string());
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+/// The span of the rest of the line at `line`, and `column` of `data`, if there is one.
+fn span_at(data: &str, line: usize, column: usize) -> Option<Span> {
+    let line_start = if line <= 1 {
+        0
+    } else {
+        data.match_indices('\n').nth(line - 2)?.0 + 1
+    };
+    let rest = &data[line_start..];
+    let offset = line_start
+        + rest
+            .char_indices()
+            .nth(column.saturating_sub(1))
+            .map(|(index, _)| index)
+            .unwrap_or(rest.len());
+    let len = data[offset..].find('\n').unwrap_or(data.len() - offset);
+    Some(Span::at(data, offset, len))
+}
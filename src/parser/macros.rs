@@ -0,0 +1,179 @@
+//! Markup macros. A macro is a tag key that expands into other markup, so a combination of
+//! tags that is used all the time can be written as a single tag.
+
+use super::{tokenize, EndTag, OneItem};
+use crate::error::Result;
+#[cfg(feature = "config")]
+use crate::error::SsmlError;
+use crate::registry::find_tag;
+
+#[cfg(feature = "config")]
+use serde::de::DeserializeOwned;
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+#[cfg(feature = "config")]
+use std::fs;
+#[cfg(feature = "config")]
+use std::path::Path;
+
+/// A table of macros, passed into parsing through `ParseOptions::macros`.
+///
+/// Using a macro (e.g. `${shout}`) inserts its expansion, and closing it (`${/shout}`)
+/// closes every tag the expansion left open, in reverse order. Parameters passed to the
+/// macro are added to the first tag of the expansion, replacing any with the same name.
+/// Expansions are not expanded again, so macros can't use other macros.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::ParseOptions;
+///
+/// let mut options = ParseOptions::default();
+/// options.macros.define("shout", "${prosody|volume=x-loud|rate=fast}");
+/// let ssml = text_to_polly_ssml::parse_str_with_options("${shout}hey${/shout}", &options).unwrap();
+/// assert!(ssml.ends_with(r#"<prosody volume="x-loud" rate="fast">hey</prosody></speak>"#));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Macros {
+    expansions: BTreeMap<String, String>,
+}
+
+impl Macros {
+    /// Defines a macro, replacing any macro defined with the same name before. Names are
+    /// matched case-insensitively.
+    pub fn define(&mut self, name: &str, expansion: &str) -> &mut Self {
+        self.expansions
+            .insert(name.to_lowercase(), expansion.to_owned());
+        self
+    }
+
+    /// The expansion of a macro, if there is one with that name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.expansions
+            .get(&name.to_lowercase())
+            .map(|expansion| expansion.as_str())
+    }
+
+    /// Defines every macro in a TOML, or JSON file (picked by the extension of the file).
+    /// The file has to be a flat table of macro names, and their expansions:
+    ///
+    /// ```toml
+    /// shout = "${prosody|volume=x-loud|rate=fast}"
+    /// aside = "${p}${amazon:effect|name=whispered}"
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn define_from_path<P: AsRef<Path>>(&mut self, path: P) -> Result<&mut Self> {
+        let expansions: BTreeMap<String, String> = read_table(path.as_ref())?;
+        for (name, expansion) in expansions {
+            self.define(&name, &expansion);
+        }
+        Ok(self)
+    }
+
+    /// If no macros are defined.
+    pub fn is_empty(&self) -> bool {
+        self.expansions.is_empty()
+    }
+
+    /// Replaces every use of a macro in the tokenized markup with its expansion.
+    pub(crate) fn expand<'a>(&self, items: Vec<OneItem<'a>>) -> Result<Vec<OneItem<'a>>> {
+        if self.is_empty() {
+            return Ok(items);
+        }
+
+        let mut expanded = Vec::with_capacity(items.len());
+        for item in items {
+            if let Some(ref start_tag) = item.start_tag {
+                if let Some(expansion) = self.get(&start_tag.tag_key) {
+                    let mut expansion_items: Vec<OneItem<'a>> = tokenize(expansion)?
+                        .into_iter()
+                        .map(OneItem::into_owned)
+                        .collect();
+                    // Only the parameters given to the macro are in the markup.
+                    for expansion_tag in expansion_items
+                        .iter_mut()
+                        .filter_map(|expansion_item| expansion_item.start_tag.as_mut())
+                    {
+                        expansion_tag.param_spans.clear();
+                    }
+                    if let Some(first_tag) = expansion_items
+                        .iter_mut()
+                        .find_map(|expansion_item| expansion_item.start_tag.as_mut())
+                    {
+                        first_tag.params.extend(start_tag.params.clone());
+                        first_tag.param_spans = start_tag.param_spans.clone();
+                    }
+                    for mut expansion_item in expansion_items {
+                        expansion_item.span = item.span;
+                        expanded.push(expansion_item);
+                    }
+                    continue;
+                }
+            }
+
+            if let Some(ref end_tag) = item.end_tag {
+                if let Some(expansion) = self.get(&end_tag.tag_key) {
+                    for tag_key in left_open(&tokenize(expansion)?).into_iter().rev() {
+                        expanded.push(OneItem {
+                            start_tag: None,
+                            end_tag: Some(EndTag {
+                                tag_key: Cow::Owned(tag_key),
+                            }),
+                            data: None,
+                            span: item.span,
+                        });
+                    }
+                    continue;
+                }
+            }
+
+            expanded.push(item);
+        }
+
+        Ok(expanded)
+    }
+}
+
+/// Reads a table from a TOML, or JSON file, picked by the extension of the file.
+#[cfg(feature = "config")]
+pub(crate) fn read_table<T: DeserializeOwned>(path: &Path) -> Result<BTreeMap<String, T>> {
+    let contents = fs::read_to_string(path).map_err(|source| SsmlError::Io {
+        path: path.to_owned(),
+        source,
+    })?;
+    let invalid = |message: String| SsmlError::InvalidConfig {
+        path: path.to_owned(),
+        message,
+    };
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("toml") => toml::from_str(&contents).map_err(|error| invalid(error.to_string())),
+        Some("json") => serde_json::from_str(&contents).map_err(|error| invalid(error.to_string())),
+        _ => Err(invalid("expected a `.toml`, or `.json` file".to_owned())),
+    }
+}
+
+/// The keys of the tags that wrap content, and are still open at the end of the items, in
+/// the order they were opened.
+fn left_open(items: &[OneItem]) -> Vec<String> {
+    let mut open: Vec<String> = Vec::new();
+    for item in items {
+        if let Some(ref start_tag) = item.start_tag {
+            if find_tag(&start_tag.tag_key)
+                .map(|tag| tag.has_closing_tag)
+                .unwrap_or(true)
+            {
+                open.push(start_tag.tag_key.to_string());
+            }
+        }
+        if let Some(ref end_tag) = item.end_tag {
+            if let Some(position) = open
+                .iter()
+                .rposition(|tag_key| tag_key.eq_ignore_ascii_case(&end_tag.tag_key))
+            {
+                open.truncate(position);
+            }
+        }
+    }
+    open
+}
@@ -0,0 +1,68 @@
+//! Converts Markdown into markup, so content that is already written in Markdown can be
+//! turned into SSML without being rewritten by hand.
+
+use super::frames::{break_node, Frame, FrameStack};
+use super::tree::{tree_to_markup, SsmlElement};
+
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+/// Converts Markdown into markup:
+///
+/// - Paragraphs become `${p}` tags.
+/// - Emphasis, and strong emphasis become `${emphasis|level=moderate}`, and
+///   `${emphasis|level=strong}` tags.
+/// - Headings become a paragraph with strong emphasis, followed by a strong break.
+/// - Every item of a list becomes a `${s}` tag, inside of a `${p}` for the whole list.
+/// - Line breaks, and thematic breaks become a medium, and an extra strong break.
+/// - Links, block quotes, and inline code are read as their text.
+/// - Code blocks, images, and HTML are left out.
+///
+/// Text is escaped, so nothing in it is read as a tag.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::markdown_to_markup;
+/// assert_eq!(
+///   markdown_to_markup("# Hi\n\nRead *the* [docs](https://example.com)."),
+///   "${p}${emphasis|level=strong}Hi${/emphasis}${/p}${break|strength=strong}${p}Read ${emphasis|level=moderate}the${/emphasis} docs.${/p}",
+/// );
+/// ```
+pub fn markdown_to_markup(markdown: &str) -> String {
+    let mut stack = FrameStack::new();
+    // How many list items are open, as a paragraph inside of one is part of its sentence.
+    let mut items = 0;
+
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Start(tag) => stack.open(match tag {
+                Tag::Paragraph if items == 0 => Frame::Element(SsmlElement::new("p")),
+                Tag::Heading { .. } => Frame::with_param("emphasis", "level", "strong"),
+                Tag::List(_) if items == 0 => Frame::Element(SsmlElement::new("p")),
+                Tag::Item => {
+                    items += 1;
+                    Frame::Element(SsmlElement::new("s"))
+                }
+                Tag::Emphasis => Frame::with_param("emphasis", "level", "moderate"),
+                Tag::Strong => Frame::with_param("emphasis", "level", "strong"),
+                Tag::CodeBlock(_) | Tag::HtmlBlock | Tag::Image { .. } => Frame::Skipped,
+                Tag::MetadataBlock(_) | Tag::FootnoteDefinition(_) => Frame::Skipped,
+                _ => Frame::Transparent(Vec::new()),
+            }),
+            Event::End(TagEnd::Heading(_)) => stack.close_heading(),
+            Event::End(tag) => {
+                if tag == TagEnd::Item {
+                    items -= 1;
+                }
+                stack.close();
+            }
+            Event::Text(text) | Event::Code(text) => stack.push_text(&text),
+            Event::SoftBreak => stack.push_text(" "),
+            Event::HardBreak => stack.push_node(break_node("medium")),
+            Event::Rule => stack.push_node(break_node("x-strong")),
+            _ => {}
+        }
+    }
+
+    tree_to_markup(&stack.finish())
+}
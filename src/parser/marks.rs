@@ -0,0 +1,112 @@
+//! Places <mark> tags in the text automatically, so speech marks can drive highlighting
+//! without every mark having to be written by hand.
+
+use super::TEXT_ONLY_TAGS;
+use crate::error::Result;
+use crate::xml_writer::XmlWriter;
+
+/// Where to automatically place a <mark> in the text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AutoMarks {
+    /// Never place a mark, only the marks in the markup are rendered.
+    #[default]
+    Off,
+    /// Place a mark before every word, named `w0`, `w1`, and so on.
+    Words,
+    /// Place a mark before every sentence, named `s0`, `s1`, and so on. A sentence starts
+    /// at the start of the document, and every `${s}`, or `${p}`, and after a `.`, `!`, or
+    /// `?` that is followed by whitespace.
+    Sentences,
+}
+
+/// Keeps track of where the next mark goes while a document is being rendered.
+pub(crate) struct AutoMarker {
+    mode: AutoMarks,
+    next: usize,
+    at_sentence_start: bool,
+    after_sentence_end: bool,
+    /// How many tags that can only contain text (e.g. `${sub}`) are currently open.
+    text_only: usize,
+}
+
+impl AutoMarker {
+    pub(crate) fn new(mode: AutoMarks) -> AutoMarker {
+        AutoMarker {
+            mode,
+            next: 0,
+            at_sentence_start: true,
+            after_sentence_end: false,
+            text_only: 0,
+        }
+    }
+
+    /// Tracks a start tag that wraps content.
+    pub(crate) fn start_tag(&mut self, tag_key: &str) {
+        self.tag(tag_key, true);
+    }
+
+    /// Tracks an end tag.
+    pub(crate) fn end_tag(&mut self, tag_key: &str) {
+        self.tag(tag_key, false);
+    }
+
+    fn tag(&mut self, tag_key: &str, is_start: bool) {
+        let tag_key = tag_key.to_lowercase();
+        if tag_key == "s" || tag_key == "p" {
+            self.at_sentence_start = true;
+        }
+        if TEXT_ONLY_TAGS.contains(&tag_key.as_str()) {
+            if is_start {
+                self.text_only += 1;
+            } else {
+                self.text_only = self.text_only.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Writes some text, with a mark before every word, or sentence in it.
+    pub(crate) fn write_text(&mut self, xml_writer: &mut XmlWriter, text: &str) -> Result<()> {
+        if self.mode == AutoMarks::Off || self.text_only > 0 {
+            return xml_writer.write_text(text);
+        }
+
+        let mut written = 0;
+        let mut previous_is_whitespace = true;
+        for (idx, character) in text.char_indices() {
+            if character.is_whitespace() {
+                if self.after_sentence_end {
+                    self.at_sentence_start = true;
+                }
+                previous_is_whitespace = true;
+                continue;
+            }
+
+            let needs_mark = match self.mode {
+                AutoMarks::Words => previous_is_whitespace,
+                AutoMarks::Sentences => self.at_sentence_start,
+                AutoMarks::Off => false,
+            };
+            if needs_mark {
+                if idx > written {
+                    xml_writer.write_text(&text[written..idx])?;
+                    written = idx;
+                }
+                let prefix = if self.mode == AutoMarks::Words {
+                    "w"
+                } else {
+                    "s"
+                };
+                xml_writer.ssml_mark(format!("{}{}", prefix, self.next))?;
+                self.next += 1;
+            }
+            self.at_sentence_start = false;
+            self.after_sentence_end = matches!(character, '.' | '!' | '?');
+            previous_is_whitespace = false;
+        }
+
+        if written < text.len() {
+            xml_writer.write_text(&text[written..])?;
+        }
+        Ok(())
+    }
+}
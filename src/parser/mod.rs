@@ -0,0 +1,1065 @@
+use nom::{
+    branch::*,
+    bytes::streaming::*,
+    character::streaming::*,
+    combinator::*,
+    error::{ErrorKind, ParseError},
+    multi::*,
+    sequence::*,
+    Err as NomErr, IResult,
+};
+
+use crate::error::{Result, ResultExt, SsmlError};
+use crate::merge::merge_documents;
+use crate::post_process::{drop_empty_elements, pretty_print};
+use crate::registry::find_tag;
+pub use crate::ssml_constants::OutputTarget;
+use crate::ssml_constants::{BreakTime, PossibleClosingTags, PossibleOpenTags};
+use crate::voices::Voice;
+use crate::xml_writer::{
+    DeclarationOptions, SpeakOptions, WriterOptions, XmlWriter, PRETTY_INDENT_SIZE,
+};
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::str;
+
+mod acronyms;
+mod chunk;
+mod currency;
+mod dates;
+mod dialogue;
+pub(crate) mod engine;
+mod escape;
+mod events;
+mod expletives;
+mod frames;
+mod from_ssml;
+mod handler;
+mod html;
+mod include;
+mod lexicon;
+mod line_breaks;
+mod lint;
+mod macros;
+#[cfg(feature = "markdown")]
+mod markdown;
+mod marks;
+mod numbers;
+mod paragraphs;
+mod render;
+mod report;
+mod sentences;
+mod span;
+mod substitutions;
+mod subtitles;
+mod terms;
+mod tree;
+mod unicode;
+mod validate;
+mod whitespace;
+
+use self::acronyms::acronym_nodes;
+pub use self::acronyms::{spell_out_acronyms, AcronymRules};
+pub use self::chunk::{parse_as_ssml_chunked, POLLY_MAX_CHARACTERS, POLLY_MAX_TOTAL_CHARACTERS};
+use self::currency::currency_nodes;
+pub use self::currency::{detect_currency, Currency, CurrencyRules};
+use self::dates::date_nodes;
+pub use self::dates::{detect_dates, DateOrder, DateRules};
+pub use self::dialogue::{
+    dialogue_to_markup, dialogue_turns, parse_dialogue, Cast, DialogueLine, DialogueTurn,
+    SpeakerStyle,
+};
+pub use self::engine::Engine;
+pub use self::escape::*;
+pub use self::events::{SsmlEvent, SsmlEvents};
+pub use self::expletives::Expletives;
+pub use self::from_ssml::{parse_ssml_tree, ssml_to_markup};
+pub use self::handler::*;
+pub use self::html::html_to_markup;
+pub use self::include::{IncludeOptions, INCLUDE_TAG};
+pub use self::lexicon::{Lexicon, Pronunciation};
+use self::line_breaks::line_break_nodes;
+pub use self::line_breaks::{break_lines, LineBreaks};
+pub use self::lint::{lint, lint_with_options, Diagnostic, DiagnosticKind, Severity};
+pub use self::macros::*;
+#[cfg(feature = "markdown")]
+pub use self::markdown::markdown_to_markup;
+use self::marks::AutoMarker;
+pub use self::marks::AutoMarks;
+use self::numbers::number_nodes;
+pub use self::numbers::{normalize_numbers, NumberRules};
+use self::paragraphs::paragraph_nodes;
+pub use self::paragraphs::wrap_paragraphs;
+use self::render::{
+    check_language, write_end_tag, write_start_tag, write_unknown_end_tag, write_unknown_start_tag,
+};
+pub use self::report::*;
+use self::sentences::segment_nodes;
+pub use self::sentences::{segment_sentences, SentenceRules};
+pub use self::span::*;
+pub use self::substitutions::Substitutions;
+pub use self::subtitles::{parse_subtitles, subtitles_to_markup, Cue};
+pub use self::tree::*;
+pub use self::unicode::NormalizationForm;
+use self::whitespace::whitespace_nodes;
+pub use self::whitespace::{normalize_whitespace, WhitespaceOptions};
+
+/// Options that change how a piece of text gets turned into SSML. The default options
+/// produce the exact same output as `parse_as_ssml`.
+#[derive(Clone, Debug, Default)]
+pub struct ParseOptions {
+    /// Write every non-ASCII character (IPA phonemes, accented text, etc.) as a numeric
+    /// character reference instead of raw UTF-8.
+    pub ascii_safe: bool,
+    /// Bring the input into a Unicode normalization form before it's tokenized. Fragments
+    /// read by `${include}` are left as they are. The spans of warnings, and the source map
+    /// then point into the normalized input instead.
+    pub normalization: Option<NormalizationForm>,
+    /// The engine the SSML is written for.
+    pub target: OutputTarget,
+    /// The AWS Polly engine the SSML is synthesized with. If set, every tag, or parameter
+    /// the engine doesn't support is reported as a `WarningKind::UnsupportedByEngine`. They
+    /// are still rendered.
+    pub engine: Option<Engine>,
+    /// The AWS Polly voice the SSML is synthesized with. If set, every tag, or parameter
+    /// none of the voice's engines support, and every `${amazon:domain}` the voice doesn't
+    /// have is reported as a `WarningKind::UnsupportedByVoice`. They are still rendered.
+    pub voice: Option<Voice>,
+    /// Also report every language AWS Polly doesn't support as a
+    /// `WarningKind::InvalidLanguage`, not just the ones that aren't valid BCP-47. See
+    /// `language::POLLY_LANGUAGES`.
+    pub polly_languages: bool,
+    /// The `<?xml ...?>` declaration at the start of the document. It is always omitted
+    /// when the target wants a bare <speak> fragment, see `OutputTarget::is_bare`.
+    pub declaration: DeclarationOptions,
+    /// The attributes, and namespace declarations of the root <speak> tag. Ignored when
+    /// the target wants a bare <speak> fragment, see `XmlWriter::start_ssml_speak_for_target`
+    /// for what each target adds.
+    pub speak: SpeakOptions,
+    /// Remove elements that end up without any content (e.g. a template left an optional
+    /// `${s}${/s}` empty). See `post_process::drop_empty_elements`.
+    pub drop_empty_elements: bool,
+    /// Put every element on its own line, and indent it by how deeply it is nested, so
+    /// long documents are easier to review. See `post_process::pretty_print`.
+    pub pretty: bool,
+    /// Return an error for tags that are never closed, closed without being opened, nested
+    /// where SSML doesn't allow them, or that we don't know, instead of rendering SSML
+    /// polly will reject.
+    pub strict: bool,
+    /// How deeply tags can be nested, e.g. `Some(64)` for markup from untrusted sources.
+    /// A tag that is never closed counts as wrapping everything after it. Markup that is
+    /// nested deeper is rejected with an `SsmlError::NestingTooDeep`. Unlimited if it isn't
+    /// set.
+    pub max_depth: Option<usize>,
+    /// The most bytes of input that are parsed, e.g. for markup from untrusted sources.
+    /// Longer input is rejected with an `SsmlError::InputTooLong` before it is tokenized.
+    /// Only the input itself counts, not any fragments it includes. Unlimited if it isn't
+    /// set.
+    pub max_input_len: Option<usize>,
+    /// What to do with a `${` that is never closed by a `}`.
+    pub unterminated: UnterminatedPolicy,
+    /// What to do with tags we don't know, e.g. a typo like `${brake}`.
+    pub unknown_tags: UnknownTagPolicy,
+    /// Custom tags, with their own rendering.
+    pub handlers: TagHandlers,
+    /// Tags that expand into other markup, e.g. `${shout}` into
+    /// `${prosody|volume=x-loud|rate=fast}`.
+    pub macros: Macros,
+    /// Terms that are always read as something else, e.g. `NaCl` as `sodium chloride`.
+    /// Every use of them is wrapped in a `${sub}` tag automatically.
+    pub substitutions: Substitutions,
+    /// Words the engine should bleep. Every use of them is wrapped in a
+    /// `${say-as|interpret-as=expletive}` tag automatically, before any other words are
+    /// looked up.
+    pub expletives: Expletives,
+    /// How words are pronounced, e.g. domain jargon. Every use of them is wrapped in a
+    /// `${phoneme}` tag automatically.
+    pub lexicon: Lexicon,
+    /// Wrap every number in the text in a `${say-as}` tag automatically, reading them as
+    /// these rules ask for, see `normalize_numbers`.
+    pub numbers: Option<NumberRules>,
+    /// Wrap every date in the text in a `${say-as|interpret-as=date}` tag automatically,
+    /// finding dates with these rules, see `detect_dates`. Dates are found before numbers.
+    pub dates: Option<DateRules>,
+    /// Rewrite every amount of money in the text so it's read as a number, followed by the
+    /// name of its currency automatically, see `detect_currency`. Amounts are found before
+    /// dates, and numbers.
+    pub currency: Option<CurrencyRules>,
+    /// Wrap every acronym in the text in a `${say-as|interpret-as=spell-out}` tag
+    /// automatically, finding acronyms with these rules, see `spell_out_acronyms`. Acronyms
+    /// are found after amounts of money, dates, and numbers.
+    pub acronyms: Option<AcronymRules>,
+    /// Where `${include|file=...}` directives read other markup fragments from.
+    pub include: IncludeOptions,
+    /// Render `${mark|name=...}` as a <mark> that wraps everything up until `${/mark}`, like
+    /// older versions did. By default it is an empty element as the SSML spec defines it,
+    /// and `${/mark}` is ignored.
+    pub wrapping_marks: bool,
+    /// Place a <mark> before every word, or sentence automatically, so speech marks can be
+    /// used for highlighting without placing every mark by hand.
+    pub auto_marks: AutoMarks,
+    /// Wrap every block of text separated by a blank line in a `${p}` tag automatically,
+    /// see `wrap_paragraphs`.
+    pub paragraphs: bool,
+    /// Place a break after every single newline automatically, see `break_lines`.
+    pub line_breaks: Option<LineBreaks>,
+    /// Wrap every sentence of the text in a `${s}` tag automatically, finding sentences with
+    /// these rules, see `segment_sentences`.
+    pub sentences: Option<SentenceRules>,
+    /// Collapse, or trim the whitespace in the text automatically, see
+    /// `normalize_whitespace`.
+    pub whitespace: WhitespaceOptions,
+}
+
+/// Tags that can only contain text, as their content is replaced, or read as a single unit.
+pub(crate) const TEXT_ONLY_TAGS: &[&str] = &["phoneme", "say-as", "sub", "w"];
+
+/// What to do with a tag we don't know.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownTagPolicy {
+    /// Drop the tag, but keep its content. A warning is reported for it.
+    #[default]
+    Drop,
+    /// Keep the tag, exactly as it was written, as literal text.
+    Literal,
+    /// Render the tag as an element with the same name, and its parameters as attributes,
+    /// e.g. `${x:custom|attr=val}` becomes `<x:custom attr="val">`. Useful for vendor
+    /// extensions this crate doesn't know about yet.
+    Passthrough,
+    /// Fail the whole parse with an `SsmlError::UnknownTag`.
+    Error,
+}
+
+impl ParseOptions {
+    /// Adds every macro defined in a TOML, or JSON file to the options. See
+    /// `Macros::define_from_path` for the format of the file.
+    #[cfg(feature = "config")]
+    pub fn with_aliases_from_path<P: AsRef<std::path::Path>>(
+        mut self,
+        path: P,
+    ) -> Result<ParseOptions> {
+        self.macros.define_from_path(path)?;
+        Ok(self)
+    }
+
+    /// Adds every substitution defined in a TOML, or JSON file to the options. See
+    /// `Substitutions::define_from_path` for the format of the file.
+    #[cfg(feature = "config")]
+    pub fn with_substitutions_from_path<P: AsRef<std::path::Path>>(
+        mut self,
+        path: P,
+    ) -> Result<ParseOptions> {
+        self.substitutions.define_from_path(path)?;
+        Ok(self)
+    }
+
+    /// Adds every pronunciation defined in a TOML, or JSON file to the options. See
+    /// `Lexicon::define_from_path` for the format of the file.
+    #[cfg(feature = "config")]
+    pub fn with_lexicon_from_path<P: AsRef<std::path::Path>>(
+        mut self,
+        path: P,
+    ) -> Result<ParseOptions> {
+        self.lexicon.define_from_path(path)?;
+        Ok(self)
+    }
+}
+
+/// What to do with a `${` that is never closed by a `}`, e.g. `price is ${10`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnterminatedPolicy {
+    /// Keep the `${` as literal text.
+    #[default]
+    Literal,
+    /// Fail the whole parse with an `SsmlError::ParseError` pointing at the `${`.
+    Error,
+}
+
+/// A `${key|name=value}` tag. The key, and parameters borrow from the markup, unless they
+/// contained escapes.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StartTag<'a> {
+    pub tag_key: Cow<'a, str>,
+    pub params: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+    /// The parameters that were given more than once, the last value is the one in
+    /// `params`.
+    pub duplicate_params: Vec<Cow<'a, str>>,
+    /// Where the `name=value` of every parameter in `params` is in the original text.
+    /// Parameters that come from a macro definition, or an included file don't have one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub param_spans: BTreeMap<Cow<'a, str>, Span>,
+}
+
+impl StartTag<'_> {
+    /// The tag without any borrows from the markup.
+    pub fn into_owned(self) -> StartTag<'static> {
+        StartTag {
+            tag_key: Cow::Owned(self.tag_key.into_owned()),
+            params: self
+                .params
+                .into_iter()
+                .map(|(name, value)| {
+                    (
+                        Cow::Owned(name.into_owned()),
+                        Cow::Owned(value.into_owned()),
+                    )
+                })
+                .collect(),
+            duplicate_params: self
+                .duplicate_params
+                .into_iter()
+                .map(|name| Cow::Owned(name.into_owned()))
+                .collect(),
+            param_spans: self
+                .param_spans
+                .into_iter()
+                .map(|(name, span)| (Cow::Owned(name.into_owned()), span))
+                .collect(),
+        }
+    }
+}
+
+/// A `${/key}` tag.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EndTag<'a> {
+    pub tag_key: Cow<'a, str>,
+}
+
+impl EndTag<'_> {
+    /// The tag without any borrows from the markup.
+    pub fn into_owned(self) -> EndTag<'static> {
+        EndTag {
+            tag_key: Cow::Owned(self.tag_key.into_owned()),
+        }
+    }
+}
+
+/// A single start tag, end tag, or run of text of the markup.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OneItem<'a> {
+    pub start_tag: Option<StartTag<'a>>,
+    pub end_tag: Option<EndTag<'a>>,
+    /// The text with escapes replaced. It borrows from the markup unless it contained any.
+    pub data: Option<Cow<'a, str>>,
+    /// Where the item is in the original text.
+    pub span: Span,
+}
+
+impl OneItem<'_> {
+    /// The item without any borrows from the markup, e.g. to keep it around longer than
+    /// the markup it was tokenized from.
+    pub fn into_owned(self) -> OneItem<'static> {
+        OneItem {
+            start_tag: self.start_tag.map(StartTag::into_owned),
+            end_tag: self.end_tag.map(EndTag::into_owned),
+            data: self.data.map(|data| Cow::Owned(data.into_owned())),
+            span: self.span,
+        }
+    }
+}
+
+/// An escaped `${`, or backslash in text. `$\{` is the escape older versions used, and is
+/// still accepted.
+fn text_escape<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, &'a str, E> {
+    use nom::bytes::complete::tag;
+
+    alt((
+        value("${", tag("\\${")),
+        value("\\", tag("\\\\")),
+        value("${", tag("$\\{")),
+    ))(input)
+}
+
+/// Text up until the next tag, or the end of the input, with escapes replaced. Unlike the
+/// tags this can't be incomplete, the end of the input simply ends the text.
+fn string<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, Cow<'a, str>, E> {
+    use nom::bytes::complete::{is_not, tag};
+
+    map(
+        recognize(many1_count(alt((
+            text_escape,
+            is_not("$\\"),
+            terminated(tag("$"), not(tag("{"))),
+            tag("\\"),
+        )))),
+        unescape_text,
+    )(input)
+}
+
+/// Replaces the escapes in some text `string` recognized. Only text with escapes is copied.
+fn unescape_text(raw: &str) -> Cow<'_, str> {
+    if !raw.contains('\\') {
+        return Cow::Borrowed(raw);
+    }
+
+    let mut text = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(character) = rest.chars().next() {
+        if let Ok((after, replaced)) = text_escape::<(&str, ErrorKind)>(rest) {
+            text.push_str(replaced);
+            rest = after;
+        } else {
+            text.push(character);
+            rest = &rest[character.len_utf8()..];
+        }
+    }
+    Cow::Owned(text)
+}
+
+/// A `${` that isn't the start of a tag, because it's never closed.
+fn unterminated<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, Cow<'a, str>, E> {
+    map(nom::bytes::complete::tag("${"), Cow::Borrowed)(input)
+}
+
+/// A single character of a tag, that isn't one of `stop`. A backslash escapes the character
+/// after it, and another `${` before the tag is closed means the first one never was.
+fn tag_char<'a, E: ParseError<&'a str>>(
+    stop: &'static str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, char, E> {
+    alt((
+        preceded(char('\\'), anychar),
+        terminated(char('$'), not(char('{'))),
+        none_of(stop),
+    ))
+}
+
+/// Part of a tag (its key, a parameter name, or a parameter value) with escapes replaced.
+/// Only parts with escapes are copied.
+fn tag_part<'a, E: ParseError<&'a str>>(
+    stop: &'static str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Cow<'a, str>, E> {
+    map(recognize(many0_count(tag_char(stop))), |raw: &'a str| {
+        if !raw.contains('\\') {
+            return Cow::Borrowed(raw);
+        }
+        let mut part = String::with_capacity(raw.len());
+        let mut characters = raw.chars();
+        while let Some(character) = characters.next() {
+            match character {
+                '\\' => part.extend(characters.next()),
+                character => part.push(character),
+            }
+        }
+        Cow::Owned(part)
+    })
+}
+
+/// A `|name=value` parameter of a start tag, and the `name=value` as it was written.
+/// Parameters without a value are ignored.
+#[allow(clippy::type_complexity)]
+fn param<'a, E: ParseError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, Option<(Cow<'a, str>, Cow<'a, str>, &'a str)>, E> {
+    preceded(
+        char('|'),
+        map(
+            consumed(pair(
+                tag_part("\\$}|="),
+                opt(preceded(char('='), tag_part("\\$}|"))),
+            )),
+            |(raw, (name, value))| value.map(|value| (name, value, raw)),
+        ),
+    )(input)
+}
+
+fn start_tag_info<'a, E: ParseError<&'a str>>(
+    source: &'a str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, StartTag<'a>, E> {
+    move |input| {
+        let (left_input, (_, _, tag_key, params, _)) = tuple((
+            tag("${"),
+            not(char('/')),
+            tag_part("\\$}|"),
+            many0(param),
+            char('}'),
+        ))(input)?;
+
+        let mut start_tag = StartTag {
+            tag_key,
+            params: BTreeMap::new(),
+            duplicate_params: Vec::new(),
+            param_spans: BTreeMap::new(),
+        };
+        for (name, value, raw) in params.into_iter().flatten() {
+            start_tag
+                .param_spans
+                .insert(name.clone(), Span::of(source, raw));
+            if start_tag.params.insert(name.clone(), value).is_some()
+                && !start_tag.duplicate_params.contains(&name)
+            {
+                start_tag.duplicate_params.push(name);
+            }
+        }
+
+        Ok((left_input, start_tag))
+    }
+}
+
+fn end_tag_info<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, EndTag<'a>, E> {
+    let (left_input, (_, tag_key, _)) = tuple((tag("${/"), tag_part("\\$}"), char('}')))(input)?;
+    Ok((left_input, EndTag { tag_key }))
+}
+
+fn text_to_ssml_parser<'a, E: ParseError<&'a str>>(
+    source: &'a str,
+    policy: UnterminatedPolicy,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<OneItem<'a>>, E> {
+    many1(one_item(source, policy))
+}
+
+/// A single start tag, end tag, or run of text.
+fn one_item<'a, E: ParseError<&'a str>>(
+    source: &'a str,
+    policy: UnterminatedPolicy,
+) -> impl FnMut(&'a str) -> IResult<&'a str, OneItem<'a>, E> {
+    alt((
+        map(
+            complete(consumed(start_tag_info(source))),
+            move |(raw, start_tag)| OneItem {
+                start_tag: Some(start_tag),
+                end_tag: None,
+                data: None,
+                span: Span::of(source, raw),
+            },
+        ),
+        map(complete(consumed(end_tag_info)), move |(raw, end_tag)| {
+            OneItem {
+                start_tag: None,
+                end_tag: Some(end_tag),
+                data: None,
+                span: Span::of(source, raw),
+            }
+        }),
+        map(
+            consumed(alt((
+                string,
+                verify(unterminated, move |_: &str| {
+                    policy == UnterminatedPolicy::Literal
+                }),
+            ))),
+            move |(raw, text)| OneItem {
+                start_tag: None,
+                end_tag: None,
+                data: Some(text),
+                span: Span::of(source, raw),
+            },
+        ),
+    ))
+}
+
+/// Joins text items that directly follow each other, which happens when a `${` is kept as
+/// literal text. Text without escapes keeps borrowing from the source.
+fn merge_text<'a>(source: &'a str, items: Vec<OneItem<'a>>) -> Vec<OneItem<'a>> {
+    let mut merged: Vec<OneItem<'a>> = Vec::with_capacity(items.len());
+    for item in items {
+        if let Some(previous) = merged.last_mut() {
+            if previous.data.is_some() && item.data.is_some() {
+                append_text(source, previous, &item);
+                continue;
+            }
+        }
+        merged.push(item);
+    }
+    merged
+}
+
+/// Appends the text of `item` to the text item right before it.
+fn append_text<'a>(source: &'a str, previous: &mut OneItem<'a>, item: &OneItem<'a>) {
+    if let (Some(previous_data), Some(data)) = (previous.data.as_mut(), item.data.as_ref()) {
+        previous.span.len += item.span.len;
+        match (&*previous_data, data) {
+            (Cow::Borrowed(_), Cow::Borrowed(_)) => {
+                let start = previous.span.offset;
+                *previous_data = Cow::Borrowed(&source[start..start + previous.span.len]);
+            }
+            _ => previous_data.to_mut().push_str(data),
+        }
+    }
+}
+
+/// The most characters of the text a parse error quotes.
+const ERROR_SNIPPET_LENGTH: usize = 32;
+
+/// Splits some text up into the flat list of start tags, end tags, and text that the
+/// renderer walks over. No validation of the tags happens here, this is purely the
+/// `${key|param=value}` syntax. A `${` that is never closed is kept as literal text.
+pub fn tokenize(data: &str) -> Result<Vec<OneItem<'_>>> {
+    tokenize_with_policy(data, UnterminatedPolicy::default())
+}
+
+/// Splits some text up exactly like `tokenize`, but with control over what happens to a
+/// `${` that is never closed.
+pub fn tokenize_with_policy(data: &str, policy: UnterminatedPolicy) -> Result<Vec<OneItem<'_>>> {
+    if data.contains("${") {
+        let parsed = text_to_ssml_parser::<(&str, ErrorKind)>(data, policy)(data);
+        let (remaining, raw) = match parsed {
+            Ok(("", items)) => return Ok(merge_text(data, items)),
+            Ok((remaining, _)) => (remaining, format!("Unparsed input: {:?}", remaining)),
+            Err(NomErr::Error((remaining, kind))) | Err(NomErr::Failure((remaining, kind))) => {
+                (remaining, format!("{:?}", kind))
+            }
+            Err(NomErr::Incomplete(needed)) => (&data[data.len()..], format!("{:?}", needed)),
+        };
+        Err(parse_error(data, remaining, raw))
+    } else {
+        Ok(vec![OneItem {
+            start_tag: None,
+            end_tag: None,
+            data: Some(Cow::Borrowed(data)),
+            span: Span::at(data, 0, data.len()),
+        }])
+    }
+}
+
+/// The error for markup that couldn't be tokenized any further than `remaining`.
+fn parse_error(data: &str, remaining: &str, raw: String) -> SsmlError {
+    let span = Span::of(data, remaining);
+    SsmlError::ParseError {
+        line: span.line,
+        column: span.column,
+        snippet: remaining
+            .lines()
+            .next()
+            .unwrap_or("")
+            .chars()
+            .take(ERROR_SNIPPET_LENGTH)
+            .collect(),
+        raw,
+    }
+}
+
+/// The directive that separates documents when parsing multiple documents at once. It has
+/// to be on a line of its own.
+pub const DOCUMENT_SEPARATOR: &str = "${---}";
+
+/// Parses some text containing multiple documents as SSML. Documents are separated by a
+/// line containing only `${---}`, and each one is rendered as its own independent <speak>
+/// document. Documents containing nothing but whitespace are skipped.
+pub fn parse_as_ssml_documents(data: &str, options: &ParseOptions) -> Result<Vec<String>> {
+    check_input_len(data, options)?;
+    split_documents(data)
+        .into_iter()
+        .map(|document| parse_as_ssml_with_options(&document, options))
+        .collect()
+}
+
+/// Parses multiple pieces of text as SSML, and joins them into a single <speak> document,
+/// optionally with a <break> of the given length between every two of them. Every piece is
+/// parsed on its own, and then merged with `merge::merge_documents`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{parse_as_ssml_joined, ParseOptions};
+/// use text_to_polly_ssml::ssml_constants::BreakTime;
+/// let result = parse_as_ssml_joined(
+///   &["${s}One${/s}", "${s}Two${/s}"],
+///   Some(BreakTime::new(1, true)),
+///   &ParseOptions::default(),
+/// );
+/// assert!(result.unwrap().ends_with(r#"<s>One</s><break time="1s"/><s>Two</s></speak>"#));
+/// ```
+pub fn parse_as_ssml_joined<S: AsRef<str>>(
+    pieces: &[S],
+    separator: Option<BreakTime>,
+    options: &ParseOptions,
+) -> Result<String> {
+    // Pretty printing happens once everything is merged, like it does in `finish_document`.
+    let piece_options = ParseOptions {
+        pretty: false,
+        ..options.clone()
+    };
+    let documents = pieces
+        .iter()
+        .map(|piece| parse_as_ssml_with_options(piece.as_ref(), &piece_options))
+        .collect::<Result<Vec<String>>>()?;
+    if documents.is_empty() {
+        return parse_as_ssml_with_options("", options);
+    }
+
+    let ssml = merge_documents(&documents, separator)?;
+    if options.pretty {
+        pretty_print(&ssml, PRETTY_INDENT_SIZE)
+    } else {
+        Ok(ssml)
+    }
+}
+
+/// Splits some text into documents on every `${---}` line.
+fn split_documents(data: &str) -> Vec<String> {
+    let mut documents = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in data.split('\n') {
+        if line.trim() == DOCUMENT_SEPARATOR {
+            documents.push(current.join("\n"));
+            current.clear();
+        } else {
+            current.push(line);
+        }
+    }
+    documents.push(current.join("\n"));
+
+    documents
+        .into_iter()
+        .filter(|document| !document.trim().is_empty())
+        .collect()
+}
+
+/// Parses some text as SSML. It should note the error here allows for a lot of wiggle room.
+/// It's still totally possible to generate invalid SSML with this. This simply does what the
+/// user tells it too. If a user doesn't close a tag, we won't close a tag. If they close a
+/// tag without opening one we won't close it. If they include a paragraph tag inside a paragraph
+/// tag we'll still render it. All of these are invalid SSML, but don't trigger an error.
+/// This is meant to be that way as you can try anything with SSML, since polly doesn't fully
+/// follow the SSML v1.1 spec, now you can play around as much as you want.
+pub fn parse_as_ssml(data: &str) -> Result<String> {
+    parse_as_ssml_with_options(data, &ParseOptions::default())
+}
+
+/// Parses some text as SSML, with the specified options. See `parse_as_ssml` for the
+/// details on what is, and isn't validated. If `strict` is set unbalanced, and unknown
+/// tags are returned as errors instead.
+pub fn parse_as_ssml_with_options(data: &str, options: &ParseOptions) -> Result<String> {
+    Ok(parse_as_ssml_with_report(data, options)?.ssml)
+}
+
+/// Parses some text as SSML exactly like `parse_as_ssml_with_options`, but also reports
+/// every tag, or attribute that was ignored while rendering, and why.
+pub fn parse_as_ssml_with_report(data: &str, options: &ParseOptions) -> Result<ParseReport> {
+    check_input_len(data, options)?;
+    let normalized = normalize_input(data, options);
+    let data = &*normalized;
+    let parsed = prepare_items(data, options)?;
+
+    let mut xml_writer = start_document(options)?;
+    let mut warnings = Vec::new();
+    if let Some(ref lang) = options.speak.lang {
+        if !options.target.is_bare() {
+            check_language("speak", "lang", lang, options, &mut warnings);
+        }
+    }
+    let mut source_map = if restructures(options) {
+        let tree = restructure(build_tree_with_options(parsed, options), options);
+        let mut renderer =
+            TreeRenderer::new(&mut xml_writer, options, &mut warnings).with_source(data);
+        renderer.render(&tree)?;
+        renderer.finish()
+    } else {
+        render_items(&mut xml_writer, parsed, data, options, &mut warnings)?
+    };
+    // Any post processing moves the output around, so the offsets wouldn't line up anymore.
+    if options.ascii_safe || options.pretty || options.drop_empty_elements {
+        source_map.clear();
+    }
+
+    let stats = xml_writer.take_stats();
+    let ssml = finish_document(xml_writer, options)?;
+    Ok(ParseReport {
+        stats: stats.with_document(&ssml),
+        ssml,
+        warnings,
+        source_map,
+    })
+}
+
+/// Renders the items of some markup one after the other, and returns where the output of
+/// every item came from.
+fn render_items(
+    xml_writer: &mut XmlWriter,
+    parsed: Vec<OneItem>,
+    data: &str,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> Result<Vec<SourceMapping>> {
+    // Every tag that wraps content that is currently open, and if it was actually written.
+    // Used to also drop the end tag of any start tag that was dropped.
+    let mut open: Vec<(String, bool)> = Vec::new();
+    let mut source_map = Vec::new();
+    let mut auto_marker = AutoMarker::new(options.auto_marks);
+    // The item that was written last, and where its output started.
+    let mut last_written: Option<(Span, usize)> = None;
+
+    for item in parsed {
+        let first_warning = warnings.len();
+        push_source_mapping(&mut source_map, last_written, xml_writer);
+        last_written = Some((item.span, xml_writer.written_len()));
+
+        // Custom tags, and unknown tags that are dropped aren't written as an element of
+        // their own, so they can be nested anywhere.
+        if let Some(ref start_tag) = item.start_tag {
+            let is_element = !options.handlers.contains(&start_tag.tag_key)
+                && (!is_unknown_start_tag(&start_tag.tag_key, options)
+                    || options.unknown_tags == UnknownTagPolicy::Passthrough);
+            if is_element {
+                let written_open = open
+                    .iter()
+                    .filter(|(_, written)| *written)
+                    .map(|(tag_key, _)| tag_key.as_str());
+                if let Some(parent) = validate::illegal_parent(written_open, &start_tag.tag_key) {
+                    warnings.push(Warning::illegal_nesting(&start_tag.tag_key, parent));
+                }
+            }
+        }
+
+        if options.unknown_tags != UnknownTagPolicy::Drop {
+            if let Some(ref start_tag) = item.start_tag {
+                if is_unknown_start_tag(&start_tag.tag_key, options) {
+                    write_unknown_start_tag(
+                        xml_writer,
+                        options.unknown_tags,
+                        start_tag,
+                        item.span.snippet(data),
+                    )?;
+                    continue;
+                }
+            }
+            if let Some(ref end_tag) = item.end_tag {
+                if is_unknown_end_tag(&end_tag.tag_key, options) {
+                    write_unknown_end_tag(
+                        xml_writer,
+                        options.unknown_tags,
+                        &end_tag.tag_key,
+                        item.span.snippet(data),
+                    )?;
+                    continue;
+                }
+            }
+        }
+
+        if let Some(ref start_tag) = item.start_tag {
+            let written = match options.handlers.get(&start_tag.tag_key) {
+                Some(handler) => handler.start(xml_writer, &start_tag.params),
+                None => {
+                    for attribute in &start_tag.duplicate_params {
+                        warnings.push(Warning::duplicate_attribute(&start_tag.tag_key, attribute));
+                    }
+                    for attribute in validate::unknown_params(start_tag) {
+                        warnings.push(Warning::unknown_attribute(&start_tag.tag_key, attribute));
+                    }
+                    write_start_tag(
+                        xml_writer,
+                        &start_tag.tag_key,
+                        &start_tag.params,
+                        options,
+                        warnings,
+                    )
+                }
+            };
+            let written = written.with_context(|| {
+                format!("Failed to write the start tag `${{{}}}`", start_tag.tag_key)
+            })?;
+            if has_closing_tag(&start_tag.tag_key, options) {
+                open.push((start_tag.tag_key.to_lowercase(), written));
+                auto_marker.start_tag(&start_tag.tag_key);
+            }
+        }
+
+        if let Some(ref end_tag) = item.end_tag {
+            auto_marker.end_tag(&end_tag.tag_key);
+            let tag_key = end_tag.tag_key.to_lowercase();
+            let written = match open.iter().rposition(|(open_key, _)| *open_key == tag_key) {
+                Some(position) => {
+                    let (_, written) = open[position];
+                    open.truncate(position);
+                    written
+                }
+                None => true,
+            };
+            if written {
+                match options.handlers.get(&end_tag.tag_key) {
+                    Some(handler) => handler.end(xml_writer),
+                    None => write_end_tag(xml_writer, &end_tag.tag_key, options, warnings),
+                }
+                .with_context(|| {
+                    format!("Failed to write the end tag `${{/{}}}`", end_tag.tag_key)
+                })?;
+            }
+        }
+
+        if let Some(ref data) = item.data {
+            auto_marker
+                .write_text(xml_writer, data)
+                .with_context(|| format!("Failed to write the text {:?}", data))?;
+        }
+
+        for warning in &mut warnings[first_warning..] {
+            warning.span = Some(item.span);
+        }
+    }
+
+    push_source_mapping(&mut source_map, last_written, xml_writer);
+    Ok(source_map)
+}
+
+/// Records the output of the item that was written last, if it wrote anything.
+fn push_source_mapping(
+    source_map: &mut Vec<SourceMapping>,
+    last_written: Option<(Span, usize)>,
+    xml_writer: &mut XmlWriter,
+) {
+    if let Some((input, output_start)) = last_written {
+        let output_end = xml_writer.written_len();
+        if output_end > output_start {
+            source_map.push(SourceMapping {
+                output: output_start..output_end,
+                input,
+            });
+        }
+    }
+}
+
+/// Makes sure the input isn't longer than `max_input_len`.
+fn check_input_len(data: &str, options: &ParseOptions) -> Result<()> {
+    match options.max_input_len {
+        Some(max_len) if data.len() > max_len => Err(SsmlError::InputTooLong {
+            len: data.len(),
+            max_len,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// The input in the normalization form the options ask for.
+fn normalize_input<'a>(data: &'a str, options: &ParseOptions) -> Cow<'a, str> {
+    match options.normalization {
+        Some(form) => Cow::Owned(form.normalize(data)),
+        None => Cow::Borrowed(data),
+    }
+}
+
+/// Tokenizes some markup, and expands any includes, and macros in it.
+fn expand_items<'a>(data: &'a str, options: &ParseOptions) -> Result<Vec<OneItem<'a>>> {
+    let parsed = tokenize_with_policy(data, options.unterminated)?;
+    let parsed = include::expand_includes(parsed, &options.include, options.unterminated, 0)?;
+    options.macros.expand(parsed)
+}
+
+/// Tokenizes some markup, expands any includes, and macros in it, and validates it if
+/// `strict` is set.
+fn prepare_items<'a>(data: &'a str, options: &ParseOptions) -> Result<Vec<OneItem<'a>>> {
+    let parsed = expand_items(data, options)?;
+    if let Some(max_depth) = options.max_depth {
+        validate::check_depth(&parsed, options, max_depth)?;
+    }
+    if options.strict {
+        validate::check_strict(&parsed, options)?;
+    }
+    Ok(parsed)
+}
+
+/// If the options ask for the markup to be restructured before it is rendered.
+fn restructures(options: &ParseOptions) -> bool {
+    options.paragraphs
+        || options.line_breaks.is_some()
+        || !options.expletives.is_empty()
+        || !options.substitutions.is_empty()
+        || !options.lexicon.is_empty()
+        || options.currency.is_some()
+        || options.dates.is_some()
+        || options.numbers.is_some()
+        || options.acronyms.is_some()
+        || options.sentences.is_some()
+        || options.whitespace.collapse
+        || options.whitespace.trim
+}
+
+/// Wraps paragraphs, sentences, expletives, substitutions, pronunciations, amounts of money,
+/// dates, numbers, and acronyms in their tags, breaks lines, and cleans up whitespace, as
+/// far as the options ask for it. Whitespace is cleaned up last, as blank lines, and
+/// newlines mean something before that.
+fn restructure(mut tree: Vec<SsmlNode>, options: &ParseOptions) -> Vec<SsmlNode> {
+    if options.paragraphs {
+        tree = paragraph_nodes(tree);
+    }
+    if let Some(ref line_breaks) = options.line_breaks {
+        tree = line_break_nodes(tree, line_breaks);
+    }
+    if !options.expletives.is_empty() {
+        tree = options.expletives.mask(tree);
+    }
+    if !options.substitutions.is_empty() {
+        tree = options.substitutions.substitute(tree);
+    }
+    if !options.lexicon.is_empty() {
+        tree = options.lexicon.pronounce(tree);
+    }
+    if let Some(ref rules) = options.currency {
+        tree = currency_nodes(tree, rules);
+    }
+    if let Some(ref rules) = options.dates {
+        tree = date_nodes(tree, rules);
+    }
+    if let Some(ref rules) = options.numbers {
+        tree = number_nodes(tree, rules);
+    }
+    if let Some(ref rules) = options.acronyms {
+        tree = acronym_nodes(tree, rules);
+    }
+    if let Some(ref rules) = options.sentences {
+        tree = segment_nodes(tree, rules);
+    }
+    if options.whitespace.collapse || options.whitespace.trim {
+        tree = whitespace_nodes(tree, &options.whitespace);
+    }
+    tree
+}
+
+/// If a start tag is neither a built in, nor a custom tag.
+fn is_unknown_start_tag(tag_key: &str, options: &ParseOptions) -> bool {
+    tag_key.parse::<PossibleOpenTags>().is_err() && !options.handlers.contains(tag_key)
+}
+
+/// If a start tag wraps content, and as such is closed by an end tag. Tags we don't know
+/// are assumed to wrap content.
+pub(crate) fn has_closing_tag(tag_key: &str, options: &ParseOptions) -> bool {
+    match options.handlers.get(tag_key) {
+        Some(handler) => handler.has_closing_tag(),
+        None if tag_key.eq_ignore_ascii_case("mark") => options.wrapping_marks,
+        None => find_tag(tag_key)
+            .map(|tag| tag.has_closing_tag)
+            .unwrap_or(true),
+    }
+}
+
+/// If an end tag is neither a built in, nor a custom tag.
+fn is_unknown_end_tag(tag_key: &str, options: &ParseOptions) -> bool {
+    tag_key.parse::<PossibleClosingTags>().is_err() && !options.handlers.contains(tag_key)
+}
+
+/// Creates a writer for the options, and opens the root <speak> tag.
+fn start_document(options: &ParseOptions) -> Result<XmlWriter> {
+    let mut xml_writer = XmlWriter::with_options(WriterOptions {
+        ascii_safe: options.ascii_safe,
+        declaration: DeclarationOptions {
+            omit: options.declaration.omit || options.target.is_bare(),
+            ..options.declaration.clone()
+        },
+        // Pretty printed in `finish_document`, after any other post processing.
+        pretty: false,
+        // Invalid languages are reported as warnings instead.
+        validate_languages: false,
+    })?;
+    xml_writer.start_ssml_speak_for_target(&options.speak, options.target)?;
+    Ok(xml_writer)
+}
+
+/// Closes the root <speak> tag, and renders the document with any post processing the
+/// options ask for.
+fn finish_document(mut xml_writer: XmlWriter, options: &ParseOptions) -> Result<String> {
+    xml_writer.end_ssml_speak()?;
+
+    let mut ssml = xml_writer.into_string();
+    if options.drop_empty_elements {
+        ssml = drop_empty_elements(&ssml)?;
+    }
+    // Pretty printing happens last, the indentation would otherwise count as content when
+    // dropping empty elements.
+    if options.pretty {
+        ssml = pretty_print(&ssml, PRETTY_INDENT_SIZE)?;
+    }
+    Ok(ssml)
+}
@@ -0,0 +1,165 @@
+//! Finds numbers in plain text, and wraps every one of them in a `${say-as}` tag, so IDs are
+//! read digit by digit, and everything else as a number.
+
+use super::tree::{parse_tree, push_text, tree_to_markup, SsmlElement, SsmlNode};
+use super::TEXT_ONLY_TAGS;
+use crate::error::Result;
+
+/// How numbers are found, and read, see `normalize_numbers`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NumberRules {
+    /// Integers with at least this many digits (and no group separators) are read digit by
+    /// digit, as they're most likely an ID, or a code. If `None` they're always read as a
+    /// number.
+    pub digits_from: Option<usize>,
+    /// The character that groups the digits of large numbers, e.g. `,` in `1,000,000`.
+    pub group_separator: char,
+    /// The character that separates the decimals of a number, e.g. `.` in `3.14`.
+    pub decimal_separator: char,
+}
+
+impl Default for NumberRules {
+    /// Integers with 4 or more digits are read digit by digit, and numbers are written like
+    /// `1,000.5`.
+    fn default() -> NumberRules {
+        NumberRules {
+            digits_from: Some(4),
+            group_separator: ',',
+            decimal_separator: '.',
+        }
+    }
+}
+
+impl NumberRules {
+    /// The length of the number at the very start of some text, if it starts with one.
+    pub(crate) fn number_len(&self, text: &str) -> Option<usize> {
+        let integer = digits_len(text);
+        if integer == 0 {
+            return None;
+        }
+
+        let mut end = integer;
+        if integer <= 3 {
+            // Groups of exactly 3 digits, e.g. `,000` in `1,000`.
+            loop {
+                let rest = &text[end..];
+                let group = rest
+                    .strip_prefix(self.group_separator)
+                    .map(digits_len)
+                    .unwrap_or(0);
+                if group != 3 {
+                    break;
+                }
+                end += self.group_separator.len_utf8() + group;
+            }
+        }
+        if let Some(rest) = text[end..].strip_prefix(self.decimal_separator) {
+            let decimals = digits_len(rest);
+            if decimals > 0 {
+                end += self.decimal_separator.len_utf8() + decimals;
+            }
+        }
+
+        Some(end)
+    }
+
+    /// How a number is read, the `interpret-as` of its `${say-as}` tag.
+    fn interpret_as(&self, number: &str) -> &'static str {
+        let is_integer = number.chars().all(|character| character.is_ascii_digit());
+        let has_leading_zero = number.len() > 1 && number.starts_with('0');
+        let is_long = self
+            .digits_from
+            .is_some_and(|digits_from| number.len() >= digits_from);
+        if is_integer && (has_leading_zero || is_long) {
+            "digits"
+        } else {
+            "cardinal"
+        }
+    }
+}
+
+/// Wraps every number in some markup in a `${say-as}` tag. Integers with as many digits as
+/// `NumberRules::digits_from` asks for, or with a leading zero are read as `digits`, and
+/// everything else (including decimals, and grouped numbers like `1,000`) as a `cardinal`.
+/// Numbers that are part of a word (e.g. `3rd`, or `A380`), and text already inside of a
+/// tag that can only contain text (e.g. `${sub}`) are left alone.
+///
+/// The markup is balanced the same way `parse_tree` balances it.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{normalize_numbers, NumberRules};
+/// let markup = normalize_numbers("Order 12345 costs 1,299.99 for 3rd class.", &NumberRules::default());
+/// assert_eq!(
+///   markup.unwrap(),
+///   "Order ${say-as|interpret-as=digits}12345${/say-as} costs ${say-as|interpret-as=cardinal}1,299.99${/say-as} for 3rd class.",
+/// );
+/// ```
+pub fn normalize_numbers(markup: &str, rules: &NumberRules) -> Result<String> {
+    Ok(tree_to_markup(&number_nodes(parse_tree(markup)?, rules)))
+}
+
+/// Wraps every number in the nodes in a `${say-as}` tag.
+pub(crate) fn number_nodes(nodes: Vec<SsmlNode>, rules: &NumberRules) -> Vec<SsmlNode> {
+    let mut normalized = Vec::new();
+    for node in nodes {
+        match node {
+            SsmlNode::Text(text) => wrap_numbers(&mut normalized, &text, rules),
+            SsmlNode::Element(mut element) => {
+                if !TEXT_ONLY_TAGS.contains(&&*element.name.to_lowercase()) {
+                    element.children = number_nodes(element.children, rules);
+                }
+                normalized.push(SsmlNode::Element(element));
+            }
+        }
+    }
+    normalized
+}
+
+/// Wraps every number in a single piece of text.
+fn wrap_numbers(normalized: &mut Vec<SsmlNode>, text: &str, rules: &NumberRules) {
+    let mut written = 0;
+    let mut idx = 0;
+
+    while idx < text.len() {
+        let starts_word = !text[..idx].ends_with(char::is_alphanumeric);
+        let number = if starts_word {
+            rules
+                .number_len(&text[idx..])
+                .map(|len| idx + len)
+                .filter(|end| !text[*end..].starts_with(char::is_alphanumeric))
+        } else {
+            None
+        };
+
+        match number {
+            Some(end) => {
+                push_text(normalized, &text[written..idx]);
+                let mut element = SsmlElement::new("say-as");
+                element.params.insert(
+                    "interpret-as".to_owned(),
+                    rules.interpret_as(&text[idx..end]).to_owned(),
+                );
+                element.children = vec![SsmlNode::Text(text[idx..end].to_owned())];
+                normalized.push(SsmlNode::Element(element));
+                written = end;
+                idx = end;
+            }
+            None => {
+                // Skip the rest of the word, so no number is found in the middle of it.
+                let word_len = text[idx..]
+                    .find(|character: char| !character.is_alphanumeric())
+                    .unwrap_or(text.len() - idx);
+                idx += word_len.max(text[idx..].chars().next().map_or(1, char::len_utf8));
+            }
+        }
+    }
+    push_text(normalized, &text[written..]);
+}
+
+/// How many ASCII digits some text starts with.
+fn digits_len(text: &str) -> usize {
+    text.find(|character: char| !character.is_ascii_digit())
+        .unwrap_or(text.len())
+}
@@ -0,0 +1,108 @@
+//! Turns blocks of text separated by blank lines into paragraphs, so plain prose doesn't
+//! render as one long run of text.
+
+use super::tree::{parse_tree, push_text, tree_to_markup, SsmlElement, SsmlNode};
+use crate::error::Result;
+
+/// Wraps every block of markup separated by a blank line in a `${p}` tag. Only blank lines
+/// outside of any tag separate blocks, and blocks that already contain a `${p}` tag, or
+/// no text at all are left alone.
+///
+/// The markup is balanced the same way `parse_tree` balances it.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::wrap_paragraphs;
+/// let markup = wrap_paragraphs("First ${emphasis}one${/emphasis}.\n\nSecond one.\n");
+/// assert_eq!(
+///   markup.unwrap(),
+///   "${p}First ${emphasis}one${/emphasis}.${/p}\n\n${p}Second one.${/p}\n",
+/// );
+/// ```
+pub fn wrap_paragraphs(markup: &str) -> Result<String> {
+    Ok(tree_to_markup(&paragraph_nodes(parse_tree(markup)?)))
+}
+
+/// Wraps every block of the nodes separated by a blank line in a paragraph.
+pub(crate) fn paragraph_nodes(nodes: Vec<SsmlNode>) -> Vec<SsmlNode> {
+    let mut wrapped = Vec::new();
+    let mut block: Vec<SsmlNode> = Vec::new();
+
+    for node in nodes {
+        match node {
+            SsmlNode::Text(text) => {
+                let mut rest = text.as_str();
+                while let Some((start, end)) = find_blank_line(rest) {
+                    push_text(&mut block, &rest[..start]);
+                    finish_block(&mut wrapped, &mut block);
+                    push_text(&mut wrapped, &rest[start..end]);
+                    rest = &rest[end..];
+                }
+                push_text(&mut block, rest);
+            }
+            element => block.push(element),
+        }
+    }
+    finish_block(&mut wrapped, &mut block);
+
+    wrapped
+}
+
+/// Finds the first blank line in some text, along with all of the whitespace around it.
+fn find_blank_line(text: &str) -> Option<(usize, usize)> {
+    let mut newline: Option<usize> = None;
+    for (idx, character) in text.char_indices() {
+        if character == '\n' {
+            if let Some(first) = newline {
+                let start = text[..first].trim_end().len();
+                let end = text.len() - text[idx..].trim_start().len();
+                return Some((start, end));
+            }
+            newline = Some(idx);
+        } else if !character.is_whitespace() {
+            newline = None;
+        }
+    }
+    None
+}
+
+/// Wraps a block in a paragraph, with the whitespace around it left outside of it.
+fn finish_block(wrapped: &mut Vec<SsmlNode>, block: &mut Vec<SsmlNode>) {
+    let mut nodes = std::mem::take(block);
+    let has_text = nodes.iter().any(|node| match node {
+        SsmlNode::Text(text) => !text.trim().is_empty(),
+        SsmlNode::Element(element) => !element.children.is_empty(),
+    });
+    let has_paragraph = nodes.iter().any(|node| match node {
+        SsmlNode::Element(element) => element.name.eq_ignore_ascii_case("p"),
+        SsmlNode::Text(_) => false,
+    });
+    if !has_text || has_paragraph {
+        for node in nodes {
+            match node {
+                SsmlNode::Text(text) => push_text(wrapped, &text),
+                element => wrapped.push(element),
+            }
+        }
+        return;
+    }
+
+    let mut leading = String::new();
+    if let Some(SsmlNode::Text(text)) = nodes.first_mut() {
+        let content_start = text.len() - text.trim_start().len();
+        leading = text.drain(..content_start).collect();
+    }
+    let mut trailing = String::new();
+    if let Some(SsmlNode::Text(text)) = nodes.last_mut() {
+        let content_len = text.trim_end().len();
+        trailing = text.split_off(content_len);
+    }
+    nodes.retain(|node| !matches!(node, SsmlNode::Text(text) if text.is_empty()));
+
+    let mut paragraph = SsmlElement::new("p");
+    paragraph.children = nodes;
+    push_text(wrapped, &leading);
+    wrapped.push(SsmlNode::Element(paragraph));
+    push_text(wrapped, &trailing);
+}
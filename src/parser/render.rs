@@ -0,0 +1,518 @@
+//! Maps the tags of the markup onto the `XmlWriter`.
+
+use super::engine;
+use super::report::Warning;
+use super::{OutputTarget, ParseOptions, StartTag, UnknownTagPolicy};
+use crate::error::{Result, SsmlError};
+use crate::language::{is_polly_language, is_valid_language_tag};
+use crate::ssml_constants::*;
+use crate::xml_writer::{MediaOptions, ProsodyOptions, XmlWriter};
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Reads an optional parameter, and parses it. Returns `Ok(None)` if the parameter wasn't
+/// passed, and `Err(())` (after recording a warning) if it couldn't be parsed.
+fn optional_param<T: FromStr<Err = InvalidValue>>(
+    tag_key: &str,
+    params: &BTreeMap<Cow<str>, Cow<str>>,
+    name: &str,
+    warnings: &mut Vec<Warning>,
+) -> Result<Option<T>, ()> {
+    match params.get(name) {
+        Some(value) => match value.parse::<T>() {
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(error) => {
+                warnings.push(Warning::invalid_value(tag_key, name, &error));
+                Err(())
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+/// Reads an optional parameter that only some output targets support, and parses it. If the
+/// target doesn't support it a warning is recorded, and it is ignored.
+fn target_param<T: FromStr<Err = InvalidValue>>(
+    tag_key: &str,
+    params: &BTreeMap<Cow<str>, Cow<str>>,
+    name: &str,
+    supported: bool,
+    warnings: &mut Vec<Warning>,
+) -> Option<T> {
+    if !supported && params.contains_key(name) {
+        warnings.push(Warning::unsupported_attribute(tag_key, name));
+        return None;
+    }
+    optional_param(tag_key, params, name, warnings).unwrap_or(None)
+}
+
+/// Reads a required parameter, recording a warning if it wasn't passed.
+fn required_param<'a>(
+    tag_key: &str,
+    params: &'a BTreeMap<Cow<str>, Cow<str>>,
+    name: &str,
+    warnings: &mut Vec<Warning>,
+) -> Option<&'a str> {
+    let value = params.get(name).map(|value| value.as_ref());
+    if value.is_none() {
+        warnings.push(Warning::missing_attribute(tag_key, name));
+    }
+    value
+}
+
+/// Reads a required parameter, and parses it. Records a warning if it wasn't passed, or
+/// couldn't be parsed.
+fn required_typed_param<T: FromStr<Err = InvalidValue>>(
+    tag_key: &str,
+    params: &BTreeMap<Cow<str>, Cow<str>>,
+    name: &str,
+    warnings: &mut Vec<Warning>,
+) -> Option<T> {
+    match optional_param(tag_key, params, name, warnings) {
+        Ok(Some(parsed)) => Some(parsed),
+        Ok(None) => {
+            warnings.push(Warning::missing_attribute(tag_key, name));
+            None
+        }
+        Err(()) => None,
+    }
+}
+
+/// Records a warning for a language that isn't valid BCP-47, or that AWS Polly doesn't
+/// support if the options ask for that. The language is still written either way.
+pub(crate) fn check_language(
+    tag_key: &str,
+    attribute: &str,
+    lang: &str,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) {
+    if !is_valid_language_tag(lang) {
+        warnings.push(Warning::invalid_language(tag_key, attribute, lang));
+    } else if options.polly_languages && !is_polly_language(lang) {
+        warnings.push(Warning::unsupported_language(tag_key, attribute, lang));
+    }
+}
+
+/// If a break time is longer than the output target accepts. AWS Polly, and Alexa cap breaks
+/// at `BreakTime::MAX_POLLY_SECONDS`, the other targets are left to their engines.
+pub(crate) fn break_time_out_of_range(time: &BreakTime, target: OutputTarget) -> bool {
+    matches!(target, OutputTarget::Polly | OutputTarget::Alexa)
+        && time.as_secs_f32() > BreakTime::MAX_POLLY_SECONDS
+}
+
+/// If a pitch is one the output target doesn't accept. AWS Polly, and Alexa only accept the
+/// pitches `ProsodyPitch::is_polly` allows, the other targets take anything SSML 1.1 does.
+pub(crate) fn pitch_out_of_range(pitch: &ProsodyPitch, target: OutputTarget) -> bool {
+    matches!(target, OutputTarget::Polly | OutputTarget::Alexa) && !pitch.is_polly()
+}
+
+/// Writes the SSML for a single start tag. Tags we don't know, or that are missing
+/// required parameters are skipped, and a warning is recorded for everything that was
+/// ignored. Returns if the tag was actually written, or an error if the writer failed.
+pub(crate) fn write_start_tag(
+    xml_writer: &mut XmlWriter,
+    tag_key: &str,
+    params: &BTreeMap<Cow<str>, Cow<str>>,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> Result<bool> {
+    let tag_frd = match tag_key.parse::<PossibleOpenTags>() {
+        Ok(tag_frd) => tag_frd,
+        Err(_) => {
+            warnings.push(Warning::unknown_tag(tag_key));
+            return Ok(false);
+        }
+    };
+    if !options.target.supports_tag(&tag_frd) {
+        warnings.push(Warning::unsupported_tag(tag_key, options.target));
+        return Ok(false);
+    }
+    if let Some(engine) = options.engine {
+        if let Some(attribute) = engine::unsupported(&tag_frd, params, engine) {
+            warnings.push(Warning::unsupported_by_engine(tag_key, attribute, engine));
+        }
+    }
+    if let Some(ref voice) = options.voice {
+        if let Some(attribute) = voice.unsupported(&tag_frd, params) {
+            warnings.push(Warning::unsupported_by_voice(tag_key, attribute, voice.id));
+        }
+    }
+
+    match tag_frd {
+        PossibleOpenTags::Break => {
+            let strength = optional_param::<BreakStrength>(tag_key, params, "strength", warnings)
+                .unwrap_or(None);
+            let mut time =
+                optional_param::<BreakTime>(tag_key, params, "time", warnings).unwrap_or(None);
+            if let Some(ref mut time) = time {
+                if break_time_out_of_range(time, options.target) {
+                    let clamped = BreakTime::from_secs_f32(BreakTime::MAX_POLLY_SECONDS);
+                    warnings.push(Warning::out_of_range_value(
+                        tag_key,
+                        "time",
+                        &params["time"],
+                        &clamped.to_string(),
+                    ));
+                    *time = clamped;
+                }
+            }
+            xml_writer.ssml_break(strength, time)?;
+        }
+        PossibleOpenTags::Emphasis => {
+            let level =
+                optional_param::<EmphasisLevel>(tag_key, params, "level", warnings).unwrap_or(None);
+            xml_writer.start_ssml_emphasis(level)?;
+        }
+        PossibleOpenTags::LangTag => {
+            let lang = match required_param(tag_key, params, "lang", warnings) {
+                Some(lang) => lang.to_string(),
+                None => return Ok(false),
+            };
+            check_language(tag_key, "lang", &lang, options, warnings);
+            let onlangfailure = params.get("onlangfailure").map(|value| value.to_string());
+            xml_writer.start_ssml_lang(lang, onlangfailure)?;
+        }
+        PossibleOpenTags::Mark => {
+            let name = match required_param(tag_key, params, "name", warnings) {
+                Some(name) => name.to_string(),
+                None => return Ok(false),
+            };
+            if options.wrapping_marks {
+                xml_writer.start_ssml_mark(name)?;
+            } else {
+                xml_writer.ssml_mark(name)?;
+            }
+        }
+        PossibleOpenTags::Paragraph => match params.get("lang") {
+            Some(lang) => {
+                check_language(tag_key, "lang", lang, options, warnings);
+                xml_writer.start_ssml_paragraph_with_lang(lang.to_string())?
+            }
+            None => xml_writer.start_ssml_paragraph()?,
+        },
+        PossibleOpenTags::Phoneme => {
+            let alphabet =
+                required_typed_param::<PhonemeAlphabet>(tag_key, params, "alphabet", warnings);
+            let ph = required_param(tag_key, params, "ph", warnings);
+            if alphabet.as_ref().map(PhonemeAlphabet::is_amazon) == Some(true)
+                && options.target == OutputTarget::Generic
+            {
+                warnings.push(Warning::unsupported_attribute(tag_key, "alphabet"));
+                return Ok(false);
+            }
+            match (alphabet, ph) {
+                (Some(alphabet), Some(ph)) => {
+                    xml_writer.start_ssml_phoneme(alphabet, ph.to_string())?;
+                }
+                _ => return Ok(false),
+            }
+        }
+        PossibleOpenTags::Prosody => {
+            let volume = optional_param::<ProsodyVolume>(tag_key, params, "volume", warnings)
+                .unwrap_or(None);
+            let rate =
+                optional_param::<ProsodyRate>(tag_key, params, "rate", warnings).unwrap_or(None);
+            let mut pitch =
+                optional_param::<ProsodyPitch>(tag_key, params, "pitch", warnings).unwrap_or(None);
+            if pitch.map(|pitch| pitch_out_of_range(&pitch, options.target)) == Some(true) {
+                let error = ProsodyPitch::invalid_for_polly(&params["pitch"]);
+                warnings.push(Warning::invalid_value(tag_key, "pitch", &error));
+                pitch = None;
+            }
+            let duration = target_param::<BreakTime>(
+                tag_key,
+                params,
+                "duration",
+                options.target == OutputTarget::Generic,
+                warnings,
+            );
+            let max_duration = target_param::<BreakTime>(
+                tag_key,
+                params,
+                "max-duration",
+                options.target == OutputTarget::Alexa,
+                warnings,
+            );
+
+            if volume.is_none()
+                && rate.is_none()
+                && pitch.is_none()
+                && duration.is_none()
+                && max_duration.is_none()
+            {
+                warnings.push(Warning::missing_any_attribute(
+                    tag_key,
+                    &["volume", "rate", "pitch", "duration", "max-duration"],
+                ));
+                return Ok(false);
+            }
+            xml_writer.start_ssml_prosody_with_options(ProsodyOptions {
+                volume,
+                rate,
+                pitch,
+                duration,
+                max_duration,
+            })?;
+        }
+        PossibleOpenTags::Sentence => match params.get("lang") {
+            Some(lang) => {
+                check_language(tag_key, "lang", lang, options, warnings);
+                xml_writer.start_ssml_sentence_with_lang(lang.to_string())?
+            }
+            None => xml_writer.start_ssml_sentence()?,
+        },
+        PossibleOpenTags::SayAs => {
+            let interpret_as = match required_param(tag_key, params, "interpret-as", warnings) {
+                Some(interpret_as) => interpret_as.to_string(),
+                None => return Ok(false),
+            };
+            match params.get("format") {
+                Some(format) => {
+                    xml_writer.start_ssml_say_as_with_format(interpret_as, format.to_string())?
+                }
+                None => xml_writer.start_ssml_say_as(interpret_as)?,
+            }
+        }
+        PossibleOpenTags::Sub => {
+            let alias = match required_param(tag_key, params, "alias", warnings) {
+                Some(alias) => alias.to_string(),
+                None => return Ok(false),
+            };
+            xml_writer.start_ssml_sub(alias)?;
+        }
+        PossibleOpenTags::Voice => {
+            let name = match required_param(tag_key, params, "name", warnings) {
+                Some(name) => name.to_string(),
+                None => return Ok(false),
+            };
+            xml_writer.start_ssml_voice(name)?;
+        }
+        PossibleOpenTags::Word => {
+            match required_typed_param::<WordRole>(tag_key, params, "role", warnings) {
+                Some(role) => {
+                    xml_writer.start_ssml_w(role)?;
+                }
+                None => return Ok(false),
+            }
+        }
+        PossibleOpenTags::AmazonEffect => {
+            if params.contains_key("name") {
+                match required_typed_param::<AmazonEffect>(tag_key, params, "name", warnings) {
+                    Some(effect) => {
+                        xml_writer.start_ssml_amazon_effect(effect)?;
+                    }
+                    None => return Ok(false),
+                }
+            } else if params.contains_key("vocal-tract-length") {
+                match required_typed_param::<VocalTractLength>(
+                    tag_key,
+                    params,
+                    "vocal-tract-length",
+                    warnings,
+                ) {
+                    Some(mut factor) => {
+                        if !factor.is_in_range() {
+                            let clamped = factor.clamped();
+                            warnings.push(Warning::out_of_range_value(
+                                tag_key,
+                                "vocal-tract-length",
+                                &params["vocal-tract-length"],
+                                &clamped.to_string(),
+                            ));
+                            factor = clamped;
+                        }
+                        xml_writer.start_ssml_vocal_tract_length(factor)?;
+                    }
+                    None => return Ok(false),
+                }
+            } else if params.contains_key("phonation") {
+                match required_typed_param::<PhonationVolume>(
+                    tag_key,
+                    params,
+                    "phonation",
+                    warnings,
+                ) {
+                    Some(phonation) => {
+                        xml_writer.start_ssml_phonation(phonation)?;
+                    }
+                    None => return Ok(false),
+                }
+            } else {
+                warnings.push(Warning::missing_any_attribute(
+                    tag_key,
+                    &["name", "vocal-tract-length", "phonation"],
+                ));
+                return Ok(false);
+            }
+        }
+        PossibleOpenTags::AmazonAutoBreaths => {
+            let volume = optional_param::<BreathVolumes>(tag_key, params, "volume", warnings);
+            let frequency =
+                optional_param::<AutoBreathFrequency>(tag_key, params, "frequency", warnings);
+            let duration = optional_param::<BreathDuration>(tag_key, params, "duration", warnings);
+
+            if let (Ok(volume), Ok(frequency), Ok(duration)) = (volume, frequency, duration) {
+                xml_writer.start_ssml_auto_breaths(
+                    volume.unwrap_or(BreathVolumes::Def),
+                    frequency.unwrap_or(AutoBreathFrequency::Def),
+                    duration.unwrap_or(BreathDuration::Def),
+                )?;
+            } else {
+                return Ok(false);
+            }
+        }
+        PossibleOpenTags::AmazonBreath => {
+            let volume = optional_param::<BreathVolumes>(tag_key, params, "volume", warnings);
+            let duration = optional_param::<BreathDuration>(tag_key, params, "duration", warnings);
+
+            if let (Ok(volume), Ok(duration)) = (volume, duration) {
+                xml_writer.write_amazon_breath(
+                    volume.unwrap_or(BreathVolumes::Def),
+                    duration.unwrap_or(BreathDuration::Def),
+                )?;
+            } else {
+                return Ok(false);
+            }
+        }
+        PossibleOpenTags::AmazonDomain => {
+            match required_typed_param::<AmazonDomainNames>(tag_key, params, "name", warnings) {
+                Some(name) => {
+                    xml_writer.start_ssml_amazon_domain(name)?;
+                }
+                None => return Ok(false),
+            }
+        }
+        PossibleOpenTags::Silence => {
+            let silence_type =
+                required_typed_param::<SilenceType>(tag_key, params, "type", warnings);
+            let value = required_typed_param::<BreakTime>(tag_key, params, "value", warnings);
+            match (silence_type, value) {
+                (Some(silence_type), Some(value)) => {
+                    xml_writer.ssml_mstts_silence(silence_type, value)?;
+                }
+                _ => return Ok(false),
+            }
+        }
+        PossibleOpenTags::Bookmark => {
+            let mark = match required_param(tag_key, params, "mark", warnings) {
+                Some(mark) => mark.to_string(),
+                None => return Ok(false),
+            };
+            xml_writer.ssml_bookmark(mark)?;
+        }
+        PossibleOpenTags::Par => xml_writer.start_ssml_par()?,
+        PossibleOpenTags::Seq => xml_writer.start_ssml_seq()?,
+        PossibleOpenTags::Media => {
+            let param = |name: &str| params.get(name).map(|value| value.to_string());
+            xml_writer.start_ssml_media(&MediaOptions {
+                id: param("id"),
+                begin: param("begin"),
+                end: param("end"),
+                repeat_count: param("repeatCount"),
+                repeat_dur: param("repeatDur"),
+                sound_level: param("soundLevel"),
+                fade_in_dur: param("fadeInDur"),
+                fade_out_dur: param("fadeOutDur"),
+            })?;
+        }
+        PossibleOpenTags::ExpressAs => {
+            match required_typed_param::<ExpressAsType>(tag_key, params, "type", warnings) {
+                Some(express_as_type) => {
+                    xml_writer.start_ssml_express_as(express_as_type)?;
+                }
+                None => return Ok(false),
+            }
+        }
+        PossibleOpenTags::AmazonEmotion => {
+            let name = required_typed_param::<EmotionName>(tag_key, params, "name", warnings);
+            let intensity =
+                required_typed_param::<EmotionIntensity>(tag_key, params, "intensity", warnings);
+            match (name, intensity) {
+                (Some(name), Some(intensity)) => {
+                    xml_writer.start_ssml_amazon_emotion(name, intensity)?;
+                }
+                _ => return Ok(false),
+            }
+        }
+    };
+
+    Ok(true)
+}
+
+/// Handles the start of a tag we don't know according to a policy other than
+/// `UnknownTagPolicy::Drop`. `markup` is the tag as it was written.
+pub(crate) fn write_unknown_start_tag(
+    xml_writer: &mut XmlWriter,
+    policy: UnknownTagPolicy,
+    start_tag: &StartTag,
+    markup: &str,
+) -> Result<()> {
+    match policy {
+        UnknownTagPolicy::Drop => Ok(()),
+        UnknownTagPolicy::Literal => xml_writer.write_text(markup),
+        UnknownTagPolicy::Passthrough => {
+            xml_writer.start_generic_element(&start_tag.tag_key, &start_tag.params)
+        }
+        UnknownTagPolicy::Error => Err(SsmlError::UnknownTag(start_tag.tag_key.to_string())),
+    }
+}
+
+/// Handles the end of a tag we don't know, exactly like `write_unknown_start_tag`.
+pub(crate) fn write_unknown_end_tag(
+    xml_writer: &mut XmlWriter,
+    policy: UnknownTagPolicy,
+    tag_key: &str,
+    markup: &str,
+) -> Result<()> {
+    match policy {
+        UnknownTagPolicy::Drop => Ok(()),
+        UnknownTagPolicy::Literal => xml_writer.write_text(markup),
+        UnknownTagPolicy::Passthrough => xml_writer.end_generic_element(tag_key),
+        UnknownTagPolicy::Error => Err(SsmlError::UnknownTag(tag_key.to_owned())),
+    }
+}
+
+/// Writes the SSML for a single end tag. Tags we don't know are skipped, and recorded as a
+/// warning.
+pub(crate) fn write_end_tag(
+    xml_writer: &mut XmlWriter,
+    tag_key: &str,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> Result<()> {
+    let tag_frd = match tag_key.parse::<PossibleClosingTags>() {
+        Ok(tag_frd) => tag_frd,
+        Err(_) => {
+            warnings.push(Warning::unknown_tag(tag_key));
+            return Ok(());
+        }
+    };
+
+    match tag_frd {
+        PossibleClosingTags::Emphasis => xml_writer.end_ssml_emphasis(),
+        PossibleClosingTags::LangTag => xml_writer.end_ssml_lang(),
+        // A mark is an empty element, unless we're asked to render marks like older
+        // versions did.
+        PossibleClosingTags::Mark if options.wrapping_marks => xml_writer.end_ssml_mark(),
+        PossibleClosingTags::Mark => Ok(()),
+        PossibleClosingTags::Paragraph => xml_writer.end_ssml_paragraph(),
+        PossibleClosingTags::Phoneme => xml_writer.end_ssml_phoneme(),
+        PossibleClosingTags::Prosody => xml_writer.end_ssml_prosody(),
+        PossibleClosingTags::Sentence => xml_writer.end_ssml_sentence(),
+        PossibleClosingTags::SayAs => xml_writer.end_ssml_say_as(),
+        PossibleClosingTags::Sub => xml_writer.end_ssml_sub(),
+        PossibleClosingTags::Voice => xml_writer.end_ssml_voice(),
+        PossibleClosingTags::Word => xml_writer.end_ssml_w(),
+        PossibleClosingTags::AmazonEffect => xml_writer.end_ssml_amazon_effect(),
+        PossibleClosingTags::AmazonAutoBreaths => xml_writer.end_ssml_amazon_auto_breaths(),
+        PossibleClosingTags::AmazonDomain => xml_writer.end_ssml_amazon_domain(),
+        PossibleClosingTags::AmazonEmotion => xml_writer.end_ssml_amazon_emotion(),
+        PossibleClosingTags::Par => xml_writer.end_ssml_par(),
+        PossibleClosingTags::Seq => xml_writer.end_ssml_seq(),
+        PossibleClosingTags::Media => xml_writer.end_ssml_media(),
+        PossibleClosingTags::ExpressAs => xml_writer.end_ssml_express_as(),
+    }
+}
@@ -0,0 +1,316 @@
+//! Everything the parser has to say about a piece of markup besides the SSML itself.
+
+use super::{Engine, OutputTarget, SourceMapping, Span};
+use crate::analysis::SsmlStats;
+use crate::language::suggest_language_tag;
+use crate::ssml_constants::InvalidValue;
+
+use std::fmt;
+
+/// Why a tag, or attribute was ignored while rendering.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// The tag isn't one we know how to render, so it was dropped.
+    UnknownTag,
+    /// The tag isn't supported by the `OutputTarget`, so it was dropped.
+    UnsupportedTag,
+    /// A parameter the tag needs was missing, so the tag was dropped.
+    MissingAttribute,
+    /// A parameter had a value we couldn't understand. Depending on the tag either the
+    /// parameter, or the whole tag was dropped.
+    InvalidAttributeValue,
+    /// A parameter was given more than once, only the last value was used.
+    DuplicateAttribute,
+    /// The tag doesn't have a parameter with this name, so it was ignored.
+    UnknownAttribute,
+    /// The parameter isn't supported by the `OutputTarget`, so it was ignored.
+    UnsupportedAttribute,
+    /// A parameter was outside of the range the `OutputTarget` accepts, so it was clamped
+    /// to the closest value that is accepted.
+    OutOfRangeAttributeValue,
+    /// The tag, or parameter isn't supported by the `Engine`. It was still rendered.
+    UnsupportedByEngine,
+    /// The tag, or parameter isn't supported by the `Voice`. It was still rendered.
+    UnsupportedByVoice,
+    /// The tag is inside of a tag SSML doesn't allow it in, e.g. a `${p}` inside of a
+    /// `${s}`. It was still rendered.
+    IllegalNesting,
+    /// A language isn't a valid BCP-47 tag (e.g. `fr_FR` instead of `fr-FR`), or isn't one
+    /// AWS Polly supports when `ParseOptions::polly_languages` is set. It was still rendered.
+    InvalidLanguage,
+}
+
+/// A tag, or attribute that was ignored while rendering, and why.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+    /// Why it was ignored.
+    pub kind: WarningKind,
+    /// The key of the tag as written in the markup.
+    pub tag: String,
+    /// The parameter involved, if the warning is about a single parameter.
+    pub attribute: Option<String>,
+    /// A human readable description of the problem.
+    pub message: String,
+    /// Where the tag is in the original text. Only known when parsing text, not when
+    /// rendering a tree.
+    pub span: Option<Span>,
+}
+
+impl Warning {
+    pub(crate) fn unknown_tag(tag: &str) -> Warning {
+        Warning {
+            kind: WarningKind::UnknownTag,
+            tag: tag.to_owned(),
+            attribute: None,
+            message: format!("`{}` is not a known tag, and was dropped.", tag),
+            span: None,
+        }
+    }
+
+    pub(crate) fn unsupported_tag(tag: &str, target: OutputTarget) -> Warning {
+        Warning {
+            kind: WarningKind::UnsupportedTag,
+            tag: tag.to_owned(),
+            attribute: None,
+            message: format!(
+                "`{}` isn't supported by the {} output target, and was dropped.",
+                tag, target
+            ),
+            span: None,
+        }
+    }
+
+    pub(crate) fn missing_attribute(tag: &str, attribute: &str) -> Warning {
+        Warning {
+            kind: WarningKind::MissingAttribute,
+            tag: tag.to_owned(),
+            attribute: Some(attribute.to_owned()),
+            message: format!(
+                "`{}` is missing the required `{}` parameter, and was dropped.",
+                tag, attribute
+            ),
+            span: None,
+        }
+    }
+
+    pub(crate) fn missing_any_attribute(tag: &str, attributes: &[&str]) -> Warning {
+        Warning {
+            kind: WarningKind::MissingAttribute,
+            tag: tag.to_owned(),
+            attribute: None,
+            message: format!(
+                "`{}` needs at least one of the `{}` parameters, and was dropped.",
+                tag,
+                attributes.join("`, `")
+            ),
+            span: None,
+        }
+    }
+
+    pub(crate) fn invalid_value(tag: &str, attribute: &str, error: &InvalidValue) -> Warning {
+        Warning {
+            kind: WarningKind::InvalidAttributeValue,
+            tag: tag.to_owned(),
+            attribute: Some(attribute.to_owned()),
+            message: format!(
+                "`{}` is not a valid value for the `{}` parameter of `{}`, expected one of: {}.",
+                error.value,
+                attribute,
+                tag,
+                error.accepted.join(", ")
+            ),
+            span: None,
+        }
+    }
+
+    pub(crate) fn invalid_language(tag: &str, attribute: &str, lang: &str) -> Warning {
+        let message = match suggest_language_tag(lang) {
+            Some(suggestion) => format!(
+                "`{}` is not a valid language for the `{}` parameter of `{}`, did you mean `{}`?",
+                lang, attribute, tag, suggestion
+            ),
+            None => format!(
+                "`{}` is not a valid language for the `{}` parameter of `{}`.",
+                lang, attribute, tag
+            ),
+        };
+        Warning {
+            kind: WarningKind::InvalidLanguage,
+            tag: tag.to_owned(),
+            attribute: Some(attribute.to_owned()),
+            message,
+            span: None,
+        }
+    }
+
+    pub(crate) fn unsupported_language(tag: &str, attribute: &str, lang: &str) -> Warning {
+        Warning {
+            kind: WarningKind::InvalidLanguage,
+            tag: tag.to_owned(),
+            attribute: Some(attribute.to_owned()),
+            message: format!(
+                "`{}` for the `{}` parameter of `{}` isn't a language AWS Polly supports.",
+                lang, attribute, tag
+            ),
+            span: None,
+        }
+    }
+
+    pub(crate) fn out_of_range_value(
+        tag: &str,
+        attribute: &str,
+        value: &str,
+        clamped: &str,
+    ) -> Warning {
+        Warning {
+            kind: WarningKind::OutOfRangeAttributeValue,
+            tag: tag.to_owned(),
+            attribute: Some(attribute.to_owned()),
+            message: format!(
+                "`{}` is out of range for the `{}` parameter of `{}`, and was clamped to `{}`.",
+                value, attribute, tag, clamped
+            ),
+            span: None,
+        }
+    }
+
+    pub(crate) fn unsupported_by_engine(
+        tag: &str,
+        attribute: Option<&str>,
+        engine: Engine,
+    ) -> Warning {
+        let message = match attribute {
+            Some(attribute) => format!(
+                "The `{}` parameter of `{}` isn't supported by the {} engine.",
+                attribute, tag, engine
+            ),
+            None => format!("`{}` isn't supported by the {} engine.", tag, engine),
+        };
+        Warning {
+            kind: WarningKind::UnsupportedByEngine,
+            tag: tag.to_owned(),
+            attribute: attribute.map(|attribute| attribute.to_owned()),
+            message,
+            span: None,
+        }
+    }
+
+    pub(crate) fn unsupported_by_voice(tag: &str, attribute: Option<&str>, voice: &str) -> Warning {
+        let message = match attribute {
+            Some(attribute) => format!(
+                "The `{}` parameter of `{}` isn't supported by the {} voice.",
+                attribute, tag, voice
+            ),
+            None => format!("`{}` isn't supported by the {} voice.", tag, voice),
+        };
+        Warning {
+            kind: WarningKind::UnsupportedByVoice,
+            tag: tag.to_owned(),
+            attribute: attribute.map(|attribute| attribute.to_owned()),
+            message,
+            span: None,
+        }
+    }
+
+    pub(crate) fn illegal_nesting(tag: &str, parent: &str) -> Warning {
+        Warning {
+            kind: WarningKind::IllegalNesting,
+            tag: tag.to_owned(),
+            attribute: None,
+            message: format!("`{}` isn't allowed inside of `{}`.", tag, parent),
+            span: None,
+        }
+    }
+
+    pub(crate) fn duplicate_attribute(tag: &str, attribute: &str) -> Warning {
+        Warning {
+            kind: WarningKind::DuplicateAttribute,
+            tag: tag.to_owned(),
+            attribute: Some(attribute.to_owned()),
+            message: format!(
+                "The `{}` parameter of `{}` was given more than once, only the last value was used.",
+                attribute, tag
+            ),
+            span: None,
+        }
+    }
+
+    pub(crate) fn unsupported_attribute(tag: &str, attribute: &str) -> Warning {
+        Warning {
+            kind: WarningKind::UnsupportedAttribute,
+            tag: tag.to_owned(),
+            attribute: Some(attribute.to_owned()),
+            message: format!(
+                "The `{}` parameter of `{}` isn't supported by the output target, and it was ignored.",
+                attribute, tag
+            ),
+            span: None,
+        }
+    }
+
+    pub(crate) fn unknown_attribute(tag: &str, attribute: &str) -> Warning {
+        Warning {
+            kind: WarningKind::UnknownAttribute,
+            tag: tag.to_owned(),
+            attribute: Some(attribute.to_owned()),
+            message: format!(
+                "`{}` doesn't have a `{}` parameter, and it was ignored.",
+                tag, attribute
+            ),
+            span: None,
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(
+                f,
+                "{} (line {}, column {})",
+                self.message, span.line, span.column
+            ),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// The result of parsing with a report: the SSML, and everything that was ignored along
+/// the way.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseReport {
+    /// The rendered SSML document.
+    pub ssml: String,
+    /// Every tag, or attribute that was ignored, in the order they appear in the markup.
+    pub warnings: Vec<Warning>,
+    /// What polly will bill for the document, see `analysis::ssml_stats`. It is counted
+    /// while rendering, so elements removed by `drop_empty_elements` are still counted, and
+    /// the indentation of `pretty` isn't.
+    pub stats: SsmlStats,
+    /// Where every tag, and piece of text of the SSML came from in the markup, in the order
+    /// they appear in the SSML. Always empty when `ascii_safe`, `pretty`, or
+    /// `drop_empty_elements` is set, since those move the output around after rendering.
+    pub source_map: Vec<SourceMapping>,
+}
+
+impl ParseReport {
+    /// The markup that the byte at `offset` of the SSML was rendered from, e.g. to find the
+    /// word a speech mark from polly points at.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::{parse_with_report, ParseOptions};
+    /// let markup = "${s}Hello${/s}";
+    /// let report = parse_with_report(markup, &ParseOptions::default()).unwrap();
+    /// let offset = report.ssml.find("Hello").unwrap();
+    /// let span = report.source_span(offset).unwrap();
+    /// assert_eq!(span.snippet(markup), "Hello");
+    /// ```
+    pub fn source_span(&self, offset: usize) -> Option<Span> {
+        self.source_map
+            .iter()
+            .find(|mapping| mapping.output.contains(&offset))
+            .map(|mapping| mapping.input)
+    }
+}
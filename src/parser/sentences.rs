@@ -0,0 +1,262 @@
+//! Finds the sentences in plain text, and wraps every one of them in a `${s}` tag, so
+//! engines get the sentence boundaries right without them having to be marked by hand.
+
+use super::tree::{parse_tree, push_text, tree_to_markup, SsmlElement, SsmlNode};
+use super::TEXT_ONLY_TAGS;
+use crate::error::Result;
+
+/// Where sentences end in the text of a certain language.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SentenceRules {
+    /// Characters that end a sentence when whitespace follows them, e.g. `.`.
+    pub terminators: Vec<char>,
+    /// Characters that end a sentence even without whitespace after them, e.g. the
+    /// ideographic full stop `。`.
+    pub full_stops: Vec<char>,
+    /// Words that end with a `.` without ending the sentence, e.g. `Mr.`. Compared without
+    /// regard to case.
+    pub abbreviations: Vec<String>,
+}
+
+/// Characters that may follow the end of a sentence while still being part of it.
+const CLOSING_CHARACTERS: &[char] = &['"', '\'', ')', ']', '}', '»', '”', '’', '」', '』'];
+
+impl SentenceRules {
+    /// The rules for a language, based on the language subtag of a tag like `de-DE`.
+    /// Languages without rules of their own only end sentences at `.`, `!`, and `?`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::parser::SentenceRules;
+    /// let rules = SentenceRules::for_locale("en-GB");
+    /// assert!(rules.abbreviations.contains(&"mr.".to_owned()));
+    /// ```
+    pub fn for_locale(locale: &str) -> SentenceRules {
+        let language = locale.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        let (full_stops, abbreviations): (&[char], &[&str]) = match &*language {
+            "en" => (
+                &[],
+                &[
+                    "mr.", "mrs.", "ms.", "dr.", "prof.", "sr.", "jr.", "st.", "vs.", "etc.",
+                    "e.g.", "i.e.", "inc.", "ltd.", "no.",
+                ],
+            ),
+            "de" => (
+                &[],
+                &[
+                    "z.b.", "bzw.", "usw.", "ca.", "dr.", "prof.", "nr.", "hr.", "fr.", "str.",
+                ],
+            ),
+            "fr" => (&[], &["m.", "mme.", "mlle.", "dr.", "etc.", "p.ex."]),
+            "es" => (
+                &[],
+                &["sr.", "sra.", "srta.", "dr.", "dra.", "etc.", "p.ej."],
+            ),
+            "ja" | "zh" => (&['。', '！', '？'], &[]),
+            _ => (&[], &[]),
+        };
+
+        SentenceRules {
+            terminators: vec!['.', '!', '?'],
+            full_stops: full_stops.to_vec(),
+            abbreviations: abbreviations
+                .iter()
+                .map(|word| (*word).to_owned())
+                .collect(),
+        }
+    }
+
+    /// Every byte offset in `text` right after the end of a sentence, where the sentence is
+    /// followed by more text. `pending` is if the text before `text` ended a sentence, in
+    /// which case a sentence ends at the start of `text` if it starts with whitespace.
+    fn sentence_ends(&self, text: &str, pending: bool) -> Vec<usize> {
+        let mut ends = Vec::new();
+        if pending && text.starts_with(char::is_whitespace) {
+            ends.push(0);
+        }
+
+        let mut chars = text.char_indices().peekable();
+        while let Some((idx, character)) = chars.next() {
+            let is_full_stop = self.full_stops.contains(&character);
+            if !is_full_stop && !self.terminators.contains(&character) {
+                continue;
+            }
+            let mut end = idx + character.len_utf8();
+            while let Some(&(next_idx, next)) = chars.peek() {
+                if CLOSING_CHARACTERS.contains(&next)
+                    || self.terminators.contains(&next)
+                    || self.full_stops.contains(&next)
+                {
+                    end = next_idx + next.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let followed_by_whitespace = text[end..].starts_with(char::is_whitespace);
+            if (is_full_stop || followed_by_whitespace)
+                && end < text.len()
+                && !self.is_abbreviation(&text[..idx + character.len_utf8()])
+            {
+                ends.push(end);
+            }
+        }
+
+        ends
+    }
+
+    /// If a `.` ends the text, as part of an abbreviation, or an initial like `J.`.
+    fn is_abbreviation(&self, text: &str) -> bool {
+        if !text.ends_with('.') {
+            return false;
+        }
+        let word = text
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or("")
+            .trim_start_matches(|character: char| !character.is_alphanumeric())
+            .to_lowercase();
+        let is_initial = word.chars().count() == 2 && word.starts_with(char::is_alphabetic);
+        is_initial || self.abbreviations.contains(&word)
+    }
+
+    /// If text ends a sentence at its very end, so the next text starting with whitespace
+    /// starts a new sentence.
+    fn ends_sentence(&self, text: &str) -> bool {
+        let trimmed = text.trim_end_matches(CLOSING_CHARACTERS);
+        trimmed
+            .ends_with(|last| self.terminators.contains(&last) || self.full_stops.contains(&last))
+            && !self.is_abbreviation(trimmed)
+    }
+}
+
+/// Wraps every sentence in some markup in a `${s}` tag. Text that is already inside of a
+/// `${s}` tag, or a tag that can only contain text (e.g. `${sub}`) is left alone, and
+/// paragraphs are segmented on their own. Other tags stay inside of the sentence they're
+/// in, unless they contain the end of a sentence themselves, in which case their content
+/// is segmented on its own.
+///
+/// The markup is balanced the same way `parse_tree` balances it.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{segment_sentences, SentenceRules};
+/// let markup = segment_sentences(
+///   "Hi Mr. Smith. How are ${emphasis}you${/emphasis}?",
+///   &SentenceRules::for_locale("en-US"),
+/// );
+/// assert_eq!(
+///   markup.unwrap(),
+///   "${s}Hi Mr. Smith.${/s} ${s}How are ${emphasis}you${/emphasis}?${/s}",
+/// );
+/// ```
+pub fn segment_sentences(markup: &str, rules: &SentenceRules) -> Result<String> {
+    Ok(tree_to_markup(&segment_nodes(parse_tree(markup)?, rules)))
+}
+
+/// Segments the nodes of a paragraph, or any other tag containing multiple sentences.
+pub(crate) fn segment_nodes(nodes: Vec<SsmlNode>, rules: &SentenceRules) -> Vec<SsmlNode> {
+    let mut segmented = Vec::new();
+    let mut sentence: Vec<SsmlNode> = Vec::new();
+    let mut pending = false;
+
+    for node in nodes {
+        match node {
+            SsmlNode::Text(text) => {
+                let mut start = 0;
+                for end in rules.sentence_ends(&text, pending) {
+                    push_sentence_text(&mut segmented, &mut sentence, &text[start..end]);
+                    finish_sentence(&mut segmented, &mut sentence);
+                    start = end;
+                }
+                push_sentence_text(&mut segmented, &mut sentence, &text[start..]);
+                if !text.is_empty() {
+                    pending = rules.ends_sentence(&text);
+                }
+            }
+            SsmlNode::Element(mut element) => {
+                let name = element.name.to_lowercase();
+                if name == "s" {
+                    finish_sentence(&mut segmented, &mut sentence);
+                    segmented.push(SsmlNode::Element(element));
+                    pending = false;
+                } else if name == "p"
+                    || !TEXT_ONLY_TAGS.contains(&name.as_str())
+                        && contains_sentence_end(&element, rules)
+                {
+                    finish_sentence(&mut segmented, &mut sentence);
+                    element.children = segment_nodes(element.children, rules);
+                    segmented.push(SsmlNode::Element(element));
+                    pending = false;
+                } else if sentence.is_empty() && !has_text(&element) {
+                    // Breaks, and marks between sentences stay outside of them.
+                    segmented.push(SsmlNode::Element(element));
+                } else {
+                    sentence.push(SsmlNode::Element(element));
+                }
+            }
+        }
+    }
+    finish_sentence(&mut segmented, &mut sentence);
+
+    segmented
+}
+
+/// Wraps the nodes of a sentence in a `${s}` tag, and moves any whitespace at its end out
+/// of it.
+fn finish_sentence(segmented: &mut Vec<SsmlNode>, sentence: &mut Vec<SsmlNode>) {
+    if sentence.is_empty() {
+        return;
+    }
+    let mut trailing = String::new();
+    if let Some(SsmlNode::Text(text)) = sentence.last_mut() {
+        let content_len = text.trim_end().len();
+        trailing = text.split_off(content_len);
+        if text.is_empty() {
+            sentence.pop();
+        }
+    }
+
+    let mut element = SsmlElement::new("s");
+    element.children = std::mem::take(sentence);
+    segmented.push(SsmlNode::Element(element));
+    push_text(segmented, &trailing);
+}
+
+/// Adds some text to the current sentence. Whitespace before the start of a sentence stays
+/// outside of it.
+fn push_sentence_text(segmented: &mut Vec<SsmlNode>, sentence: &mut Vec<SsmlNode>, text: &str) {
+    if sentence.is_empty() {
+        let content = text.trim_start();
+        push_text(segmented, &text[..text.len() - content.len()]);
+        push_text(sentence, content);
+    } else {
+        push_text(sentence, text);
+    }
+}
+
+/// If any text inside of an element ends a sentence before the end of the element.
+fn contains_sentence_end(element: &SsmlElement, rules: &SentenceRules) -> bool {
+    !rules
+        .sentence_ends(&text_of(&element.children), false)
+        .is_empty()
+}
+
+/// If an element contains any text that isn't whitespace.
+fn has_text(element: &SsmlElement) -> bool {
+    !text_of(&element.children).trim().is_empty()
+}
+
+/// All of the text inside of some nodes.
+fn text_of(nodes: &[SsmlNode]) -> String {
+    let mut text = String::new();
+    for node in nodes {
+        match node {
+            SsmlNode::Text(content) => text.push_str(content),
+            SsmlNode::Element(element) => text.push_str(&text_of(&element.children)),
+        }
+    }
+    text
+}
@@ -0,0 +1,55 @@
+//! Positions of the tokenized markup in the original text.
+
+use nom::Offset;
+
+use std::ops::Range;
+
+/// Where a piece of markup is in the original text. Lines, and columns start at 1, and
+/// columns are counted in characters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    /// The byte offset of the start of the markup.
+    pub offset: usize,
+    /// The length of the markup in bytes.
+    pub len: usize,
+    /// The line the markup starts on.
+    pub line: usize,
+    /// The column the markup starts at.
+    pub column: usize,
+}
+
+impl Span {
+    /// Locates `fragment`, which has to be a slice of `source`, inside of `source`.
+    pub(crate) fn of(source: &str, fragment: &str) -> Span {
+        Span::at(source, source.offset(fragment), fragment.len())
+    }
+
+    /// Creates a span for `len` bytes starting at `offset` of `source`.
+    pub(crate) fn at(source: &str, offset: usize, len: usize) -> Span {
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+        Span {
+            offset,
+            len,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// The markup this span points at inside of the original text.
+    pub fn snippet<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.offset..self.offset + self.len]
+    }
+}
+
+/// Where a piece of the rendered SSML came from in the markup. Polly reports the position of
+/// every speech mark as a byte offset into the SSML, which this maps back to the markup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceMapping {
+    /// The bytes of the rendered SSML.
+    pub output: Range<usize>,
+    /// The markup the bytes were rendered from. Tags expanded from a macro, or include map
+    /// to the macro, or include tag.
+    pub input: Span,
+}
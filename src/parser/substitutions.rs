@@ -0,0 +1,109 @@
+//! A dictionary of terms that are always read as something else, so product names, and
+//! symbols don't have to be wrapped in a `${sub}` by hand everywhere they're used.
+
+use super::terms::{wrap_terms, Term, TermMatching};
+use super::tree::{parse_tree, tree_to_markup, SsmlElement, SsmlNode};
+use crate::error::Result;
+
+use std::collections::BTreeMap;
+#[cfg(feature = "config")]
+use std::path::Path;
+
+/// A dictionary of terms, and the alias each of them is read as, passed into parsing
+/// through `ParseOptions::substitutions`.
+///
+/// Every use of a term in the text is wrapped in a `${sub|alias=...}` tag. Terms are only
+/// matched as whole words, with the exact same case, so `CO` doesn't match `Co`, or `COST`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::ParseOptions;
+///
+/// let mut options = ParseOptions::default();
+/// options.substitutions.define("NaCl", "sodium chloride");
+/// let ssml = text_to_polly_ssml::parse_str_with_options("Add NaCl.", &options).unwrap();
+/// assert!(ssml.ends_with(r#"Add <sub alias="sodium chloride">NaCl</sub>.</speak>"#));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Substitutions {
+    aliases: BTreeMap<String, String>,
+}
+
+impl Substitutions {
+    /// Defines the alias of a term, replacing any alias defined for it before.
+    pub fn define(&mut self, term: &str, alias: &str) -> &mut Self {
+        self.aliases.insert(term.to_owned(), alias.to_owned());
+        self
+    }
+
+    /// The alias of a term, if it has one.
+    pub fn get(&self, term: &str) -> Option<&str> {
+        self.aliases.get(term).map(|alias| alias.as_str())
+    }
+
+    /// Defines every alias in a TOML, or JSON file (picked by the extension of the file).
+    /// The file has to be a flat table of terms, and their aliases:
+    ///
+    /// ```toml
+    /// NaCl = "sodium chloride"
+    /// "W3C" = "World Wide Web Consortium"
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn define_from_path<P: AsRef<Path>>(&mut self, path: P) -> Result<&mut Self> {
+        let aliases: BTreeMap<String, String> = super::macros::read_table(path.as_ref())?;
+        for (term, alias) in aliases {
+            self.define(&term, &alias);
+        }
+        Ok(self)
+    }
+
+    /// If no aliases are defined.
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// Wraps every term in some markup in a `${sub}` tag with its alias. Text already inside
+    /// of a tag that can only contain text (e.g. `${sub}`) is left alone.
+    ///
+    /// The markup is balanced the same way `parse_tree` balances it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::parser::Substitutions;
+    /// let mut substitutions = Substitutions::default();
+    /// substitutions.define("W3C", "World Wide Web Consortium");
+    /// assert_eq!(
+    ///   substitutions.apply("The W3C, not W3Cs.").unwrap(),
+    ///   "The ${sub|alias=World Wide Web Consortium}W3C${/sub}, not W3Cs.",
+    /// );
+    /// ```
+    pub fn apply(&self, markup: &str) -> Result<String> {
+        Ok(tree_to_markup(&self.substitute(parse_tree(markup)?)))
+    }
+
+    /// Wraps every term in the nodes in a `${sub}` tag with its alias.
+    pub(crate) fn substitute(&self, nodes: Vec<SsmlNode>) -> Vec<SsmlNode> {
+        let terms = self
+            .aliases
+            .iter()
+            .map(|(term, alias)| {
+                let mut element = SsmlElement::new("sub");
+                element.params.insert("alias".to_owned(), alias.to_owned());
+                Term {
+                    text: term.to_owned(),
+                    element,
+                }
+            })
+            .collect::<Vec<_>>();
+        wrap_terms(
+            nodes,
+            &terms,
+            TermMatching {
+                case_sensitive: true,
+                whole_word: true,
+            },
+        )
+    }
+}
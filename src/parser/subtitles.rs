@@ -0,0 +1,181 @@
+//! Reads subtitle tracks (SRT, or WebVTT), so a track can be voiced again with the same
+//! timing as the original.
+
+use super::escape_text;
+use super::html::decode_entities;
+use crate::error::{Result, SsmlError};
+use crate::ssml_constants::BreakTime;
+
+use std::time::Duration;
+
+/// A single cue of a subtitle track.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cue {
+    /// When the cue is shown, from the start of the track.
+    pub start: Duration,
+    /// When the cue is hidden again, from the start of the track.
+    pub end: Duration,
+    /// The text of the cue, with its lines joined by spaces, and any tags (e.g. `<i>`, or a
+    /// WebVTT voice) left out.
+    pub text: String,
+}
+
+/// Reads every cue of an SRT, or WebVTT subtitle track, in the order they appear in.
+/// WebVTT headers, comments, style, and region blocks are skipped. Returns an error if a
+/// block isn't any of those, and doesn't have a valid timing line either.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use text_to_polly_ssml::parser::parse_subtitles;
+/// let cues = parse_subtitles("1\n00:00:01,500 --> 00:00:03,000\n<i>Hello</i>\nthere!\n").unwrap();
+/// assert_eq!(cues[0].start, Duration::from_millis(1500));
+/// assert_eq!(cues[0].text, "Hello there!");
+/// ```
+pub fn parse_subtitles(subtitles: &str) -> Result<Vec<Cue>> {
+    let subtitles = subtitles.trim_start_matches('\u{feff}');
+    let is_vtt = subtitles.starts_with("WEBVTT");
+    let mut cues = Vec::new();
+    let mut block: Vec<(usize, &str)> = Vec::new();
+
+    for (idx, line) in subtitles.lines().enumerate() {
+        if line.trim().is_empty() {
+            read_block(&block, is_vtt, &mut cues)?;
+            block.clear();
+        } else {
+            block.push((idx + 1, line));
+        }
+    }
+    read_block(&block, is_vtt, &mut cues)?;
+
+    Ok(cues)
+}
+
+/// Converts an SRT, or WebVTT subtitle track into markup, where the gap between two cues
+/// becomes a `${break}` of the same length. Gaps longer than polly accepts in a single
+/// break are split into multiple breaks.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::subtitles_to_markup;
+/// let markup = subtitles_to_markup(
+///   "WEBVTT\n\n00:01.000 --> 00:02.000\nOne.\n\n00:02.250 --> 00:04.000\nTwo.\n",
+/// );
+/// assert_eq!(markup.unwrap(), "One.${break|time=250ms}Two.");
+/// ```
+pub fn subtitles_to_markup(subtitles: &str) -> Result<String> {
+    let max_break = Duration::from_secs_f32(BreakTime::MAX_POLLY_SECONDS);
+    let mut markup = String::new();
+    let mut previous_end: Option<Duration> = None;
+
+    for cue in parse_subtitles(subtitles)? {
+        if let Some(previous_end) = previous_end {
+            let mut gap = cue.start.saturating_sub(previous_end);
+            if gap.is_zero() {
+                markup.push(' ');
+            }
+            while !gap.is_zero() {
+                let time = gap.min(max_break);
+                markup.push_str(&format!("${{break|time={}}}", BreakTime::from(time)));
+                gap -= time;
+            }
+        }
+        markup.push_str(&escape_text(&cue.text));
+        previous_end = Some(cue.end);
+    }
+
+    Ok(markup)
+}
+
+/// Reads a block of lines that were separated by blank lines, adding it to the cues if it
+/// is one.
+fn read_block(block: &[(usize, &str)], is_vtt: bool, cues: &mut Vec<Cue>) -> Result<()> {
+    let (first_line, first) = match block.first() {
+        Some(first) => *first,
+        None => return Ok(()),
+    };
+    if is_vtt
+        && ["WEBVTT", "NOTE", "STYLE", "REGION"]
+            .iter()
+            .any(|keyword| first.starts_with(keyword))
+    {
+        return Ok(());
+    }
+
+    // The timing line may follow an identifier, e.g. the index of the cue in SRT.
+    let timing = block
+        .iter()
+        .take(2)
+        .position(|(_, line)| line.contains("-->"))
+        .ok_or_else(|| invalid_subtitles(first_line, "expected the timing of a cue"))?;
+    let (line_number, line) = block[timing];
+    let (start, rest) = line.split_once("-->").unwrap_or_default();
+    // WebVTT cue settings may follow the end time.
+    let end = rest.split_whitespace().next().unwrap_or_default();
+
+    let text = block[timing + 1..]
+        .iter()
+        .map(|(_, line)| strip_tags(line.trim()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    cues.push(Cue {
+        start: parse_timestamp(start.trim())
+            .ok_or_else(|| invalid_subtitles(line_number, "invalid start time"))?,
+        end: parse_timestamp(end)
+            .ok_or_else(|| invalid_subtitles(line_number, "invalid end time"))?,
+        text: decode_entities(&text),
+    });
+    Ok(())
+}
+
+/// Reads a timestamp like `01:02:03,456` (SRT), or `02:03.456` (WebVTT).
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let (clock, fraction) = timestamp.split_once([',', '.']).unwrap_or((timestamp, "0"));
+    let parts = clock
+        .split(':')
+        .map(|part| {
+            if part.is_empty() || !part.bytes().all(|byte| byte.is_ascii_digit()) {
+                return None;
+            }
+            part.parse::<u64>().ok()
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let seconds = match parts.as_slice() {
+        [hours, minutes, seconds] => hours * 3600 + minutes * 60 + seconds,
+        [minutes, seconds] => minutes * 60 + seconds,
+        _ => return None,
+    };
+    if fraction.is_empty() || fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let millis = fraction.parse::<u64>().ok()? * 10_u64.pow(3 - fraction.len() as u32);
+
+    Some(Duration::from_secs(seconds) + Duration::from_millis(millis))
+}
+
+/// Leaves out every tag in a line of cue text, e.g. `<i>`, `<v Speaker>`, or a timestamp.
+fn strip_tags(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find('<') {
+        stripped.push_str(&rest[..start]);
+        match rest[start..].find('>') {
+            Some(end) => rest = &rest[start + end + 1..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+fn invalid_subtitles(line: usize, message: &str) -> SsmlError {
+    SsmlError::InvalidSubtitles {
+        line,
+        message: message.to_owned(),
+    }
+}
@@ -0,0 +1,105 @@
+//! Finds terms from a dictionary in the text of a tree, and wraps every one of them in a
+//! tag, e.g. the `${sub}` of a substitution.
+
+use super::tree::{push_text, SsmlElement, SsmlNode};
+use super::TEXT_ONLY_TAGS;
+
+/// A term to look for, along with the tag to wrap it in.
+pub(crate) struct Term {
+    pub(crate) text: String,
+    pub(crate) element: SsmlElement,
+}
+
+/// How terms are matched against the text.
+#[derive(Clone, Copy)]
+pub(crate) struct TermMatching {
+    /// Only match terms with the exact same case.
+    pub(crate) case_sensitive: bool,
+    /// Only match terms that aren't part of a longer word, e.g. `NaCl` in `NaCls`.
+    pub(crate) whole_word: bool,
+}
+
+/// Wraps every term found in the text of the nodes in its tag. Where terms overlap the one
+/// that starts first wins, then the longest one. Text inside of a tag that can only contain
+/// text (e.g. `${sub}`) is left alone, as is any term split up by a tag.
+pub(crate) fn wrap_terms(
+    nodes: Vec<SsmlNode>,
+    terms: &[Term],
+    matching: TermMatching,
+) -> Vec<SsmlNode> {
+    if terms.is_empty() {
+        return nodes;
+    }
+
+    let mut wrapped = Vec::new();
+    for node in nodes {
+        match node {
+            SsmlNode::Text(text) => wrap_text(&mut wrapped, &text, terms, matching),
+            SsmlNode::Element(mut element) => {
+                if !TEXT_ONLY_TAGS.contains(&&*element.name.to_lowercase()) {
+                    element.children = wrap_terms(element.children, terms, matching);
+                }
+                wrapped.push(SsmlNode::Element(element));
+            }
+        }
+    }
+
+    wrapped
+}
+
+/// Wraps every term found in a single piece of text.
+fn wrap_text(wrapped: &mut Vec<SsmlNode>, text: &str, terms: &[Term], matching: TermMatching) {
+    let mut written = 0;
+    let mut idx = 0;
+
+    while idx < text.len() {
+        let found = terms
+            .iter()
+            .filter_map(|term| match_at(text, idx, term, matching).map(|end| (end, term)))
+            .max_by_key(|(end, _)| *end);
+        match found {
+            Some((end, term)) => {
+                push_text(wrapped, &text[written..idx]);
+                let mut element = term.element.clone();
+                element.children = vec![SsmlNode::Text(text[idx..end].to_owned())];
+                wrapped.push(SsmlNode::Element(element));
+                written = end;
+                idx = end;
+            }
+            None => {
+                idx += text[idx..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+    }
+    push_text(wrapped, &text[written..]);
+}
+
+/// Where a term ends if it starts at `start` in the text.
+fn match_at(text: &str, start: usize, term: &Term, matching: TermMatching) -> Option<usize> {
+    if term.text.is_empty() {
+        return None;
+    }
+    if matching.whole_word && text[..start].ends_with(char::is_alphanumeric) {
+        return None;
+    }
+
+    let mut rest = text[start..].char_indices();
+    let mut end = start;
+    for expected in term.text.chars() {
+        let (offset, actual) = rest.next()?;
+        let is_same = if matching.case_sensitive {
+            actual == expected
+        } else {
+            actual.to_lowercase().eq(expected.to_lowercase())
+        };
+        if !is_same {
+            return None;
+        }
+        end = start + offset + actual.len_utf8();
+    }
+
+    if matching.whole_word && text[end..].starts_with(char::is_alphanumeric) {
+        return None;
+    }
+    Some(end)
+}
@@ -0,0 +1,538 @@
+//! A tree shaped view of the markup. Unlike the flat list of start tags, end tags, and text
+//! the tokenizer produces, every element here owns its children, so the document can be
+//! inspected, and changed before it is rendered.
+
+use super::marks::AutoMarker;
+use super::{
+    escape_param, escape_text, finish_document, has_closing_tag, start_document, tokenize,
+    validate, write_end_tag, write_start_tag, write_unknown_end_tag, write_unknown_start_tag,
+    OneItem, ParseOptions, SourceMapping, Span, StartTag, UnknownTagPolicy, Warning,
+};
+use crate::error::{Result, ResultExt};
+use crate::ssml_constants::PossibleOpenTags;
+use crate::xml_writer::XmlWriter;
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// A single node of the markup tree. With the `serde` feature enabled a tree can be
+/// serialized (e.g. as JSON), and rendered with `render_tree` later on, without the markup
+/// having to be parsed again.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SsmlNode {
+    /// A tag, and everything inside of it.
+    Element(SsmlElement),
+    /// Some plain text, with escapes already resolved. Text doesn't keep where it came
+    /// from, it's whatever is between the spans of the elements around it.
+    Text(String),
+}
+
+/// A single tag of the markup tree. Elements parsed from markup know where their tags, and
+/// parameters are in it, so tooling can point at the exact markup of a problem. Elements
+/// created in code, or by any of the transformations don't have spans. Spans, and duplicate
+/// parameters are ignored when comparing elements, and aren't serialized, since they're
+/// meaningless without the markup.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SsmlElement {
+    /// The tag key as written in the markup, e.g. `prosody`.
+    pub name: String,
+    /// The `|key=value` parameters of the tag.
+    pub params: BTreeMap<String, String>,
+    /// Everything inside of the tag. Always empty for tags that can't wrap content.
+    pub children: Vec<SsmlNode>,
+    /// Where the start tag is in the markup.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub span: Option<Span>,
+    /// Where the end tag is in the markup. `None` for tags that were never closed, or
+    /// closed by the end tag of a tag around them.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub end_span: Option<Span>,
+    /// Where the `name=value` of every parameter is in the markup, see
+    /// `StartTag::param_spans`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub param_spans: BTreeMap<String, Span>,
+    /// The parameters that were given more than once in the markup, see
+    /// `StartTag::duplicate_params`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub duplicate_params: Vec<String>,
+}
+
+impl PartialEq for SsmlElement {
+    fn eq(&self, other: &SsmlElement) -> bool {
+        self.name == other.name && self.params == other.params && self.children == other.children
+    }
+}
+
+impl SsmlElement {
+    /// Creates a new element without any parameters, or children.
+    pub fn new(name: &str) -> SsmlElement {
+        SsmlElement {
+            name: name.to_owned(),
+            params: BTreeMap::new(),
+            children: Vec::new(),
+            span: None,
+            end_span: None,
+            param_spans: BTreeMap::new(),
+            duplicate_params: Vec::new(),
+        }
+    }
+
+    /// The kind of tag this is, or `None` if it's not a tag we know how to render.
+    pub fn kind(&self) -> Option<PossibleOpenTags> {
+        self.name.parse::<PossibleOpenTags>().ok()
+    }
+
+    /// The start tag as it would be written in the markup, e.g. `${prosody|rate=fast}`.
+    /// Parameters are escaped, so the markup is read back as the same tag.
+    pub fn markup(&self) -> String {
+        let mut markup = format!("${{{}", escape_param(&self.name));
+        for (key, value) in &self.params {
+            markup.push_str(&format!("|{}={}", escape_param(key), escape_param(value)));
+        }
+        markup.push('}');
+        markup
+    }
+
+    /// Where the whole element is in the markup, from its start tag up until the end of its
+    /// end tag. Only the start tag if it has no end tag.
+    pub fn full_span(&self) -> Option<Span> {
+        let start = self.span?;
+        Some(match self.end_span {
+            Some(end) if end.offset >= start.offset => Span {
+                len: end.offset + end.len - start.offset,
+                ..start
+            },
+            _ => start,
+        })
+    }
+
+    /// If this tag wraps content, and as such is rendered with an end tag. Tags we don't
+    /// know are assumed to wrap content, and custom tags, and `wrapping_marks` are taken
+    /// from the options.
+    pub fn has_closing_tag(&self, options: &ParseOptions) -> bool {
+        has_closing_tag(&self.name, options)
+    }
+}
+
+/// Parses some markup into a tree.
+///
+/// The tree is always balanced: a tag that is never closed wraps everything up until the
+/// end of its parent, an end tag closes any tags that were opened after its start tag, and
+/// end tags without a start tag are dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{parse_tree, SsmlNode};
+/// let tree = parse_tree("${p}Hello ${break} world${/p}").unwrap();
+/// match &tree[0] {
+///   SsmlNode::Element(p) => {
+///     assert_eq!(p.name, "p");
+///     assert_eq!(p.children.len(), 3);
+///   }
+///   SsmlNode::Text(_) => panic!("expected an element"),
+/// }
+/// ```
+pub fn parse_tree(data: &str) -> Result<Vec<SsmlNode>> {
+    Ok(build_tree(tokenize(data)?))
+}
+
+/// Builds a tree out of the flat list of items the tokenizer produces. See `parse_tree` for
+/// how unbalanced tags are handled.
+pub fn build_tree(items: Vec<OneItem>) -> Vec<SsmlNode> {
+    build_tree_with_options(items, &ParseOptions::default())
+}
+
+/// Builds a tree exactly like `build_tree`, but with the custom tags, and `wrapping_marks`
+/// of the options deciding which tags wrap content.
+pub fn build_tree_with_options(items: Vec<OneItem>, options: &ParseOptions) -> Vec<SsmlNode> {
+    let mut root: Vec<SsmlNode> = Vec::new();
+    let mut open: Vec<SsmlElement> = Vec::new();
+
+    for item in items {
+        if let Some(start_tag) = item.start_tag {
+            let element = SsmlElement {
+                name: start_tag.tag_key.into_owned(),
+                params: start_tag
+                    .params
+                    .into_iter()
+                    .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                    .collect(),
+                children: Vec::new(),
+                span: Some(item.span),
+                end_span: None,
+                param_spans: start_tag
+                    .param_spans
+                    .into_iter()
+                    .map(|(name, span)| (name.into_owned(), span))
+                    .collect(),
+                duplicate_params: start_tag
+                    .duplicate_params
+                    .into_iter()
+                    .map(Cow::into_owned)
+                    .collect(),
+            };
+            if element.has_closing_tag(options) {
+                open.push(element);
+            } else {
+                push_node(&mut root, &mut open, SsmlNode::Element(element));
+            }
+        }
+
+        if let Some(end_tag) = item.end_tag {
+            let position = open
+                .iter()
+                .rposition(|element| element.name.eq_ignore_ascii_case(&end_tag.tag_key));
+            if let Some(position) = position {
+                open[position].end_span = Some(item.span);
+                while open.len() > position {
+                    let element = open.pop().unwrap();
+                    push_node(&mut root, &mut open, SsmlNode::Element(element));
+                }
+            }
+        }
+
+        if let Some(data) = item.data {
+            push_node(&mut root, &mut open, SsmlNode::Text(data.into_owned()));
+        }
+    }
+
+    while let Some(element) = open.pop() {
+        push_node(&mut root, &mut open, SsmlNode::Element(element));
+    }
+
+    root
+}
+
+/// Adds a node to the innermost open element, or the root if nothing is open.
+pub(crate) fn push_node(root: &mut Vec<SsmlNode>, open: &mut [SsmlElement], node: SsmlNode) {
+    match open.last_mut() {
+        Some(parent) => parent.children.push(node),
+        None => root.push(node),
+    }
+}
+
+/// Writes a tree back out as markup. Parsing the result with `parse_tree` gives back the
+/// same tree.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{parse_tree, tree_to_markup};
+/// let tree = parse_tree("${p}Hello ${break|time=1s}world").unwrap();
+/// assert_eq!(tree_to_markup(&tree), "${p}Hello ${break|time=1s}world${/p}");
+/// ```
+pub fn tree_to_markup(nodes: &[SsmlNode]) -> String {
+    tree_to_markup_with_options(nodes, &ParseOptions::default())
+}
+
+/// Writes a tree back out as markup exactly like `tree_to_markup`, but with the custom tags,
+/// and `wrapping_marks` of the options deciding which tags get an end tag.
+pub fn tree_to_markup_with_options(nodes: &[SsmlNode], options: &ParseOptions) -> String {
+    let mut markup = String::new();
+    for node in nodes {
+        match node {
+            SsmlNode::Element(element) => {
+                markup.push_str(&element.markup());
+                markup.push_str(&tree_to_markup_with_options(&element.children, options));
+                if element.has_closing_tag(options) {
+                    markup.push_str(&format!("${{/{}}}", escape_param(&element.name)));
+                }
+            }
+            SsmlNode::Text(text) => markup.push_str(&escape_text(text)),
+        }
+    }
+    markup
+}
+
+/// Adds some text, merging it into the text right before it if there is any.
+pub(crate) fn push_text(nodes: &mut Vec<SsmlNode>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match nodes.last_mut() {
+        Some(SsmlNode::Text(previous)) => previous.push_str(text),
+        _ => nodes.push(SsmlNode::Text(text.to_owned())),
+    }
+}
+
+/// Renders a tree as an SSML document. Elements are rendered with the exact same rules as
+/// `parse_as_ssml`, so tags we don't know, or with missing parameters are skipped (their
+/// children are still rendered).
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{parse_tree, render_tree, SsmlNode};
+/// let mut tree = parse_tree("${s}Hello${/s}").unwrap();
+/// tree.push(SsmlNode::Text(" world".to_owned()));
+/// let ssml = render_tree(&tree, &Default::default()).unwrap();
+/// assert!(ssml.ends_with("<s>Hello</s> world</speak>"));
+/// ```
+pub fn render_tree(nodes: &[SsmlNode], options: &ParseOptions) -> Result<String> {
+    let mut xml_writer = start_document(options)?;
+    let mut warnings = Vec::new();
+    TreeRenderer::new(&mut xml_writer, options, &mut warnings).render(nodes)?;
+    finish_document(xml_writer, options)
+}
+
+/// Renders the nodes of a tree into a document that is being written. Warnings point at the
+/// tags of the elements they are about, and with the markup the tree was parsed from it also
+/// records where the output of every tag, and of the text between them came from.
+pub(crate) struct TreeRenderer<'a> {
+    xml_writer: &'a mut XmlWriter,
+    options: &'a ParseOptions,
+    warnings: &'a mut Vec<Warning>,
+    auto_marker: AutoMarker,
+    /// Every tag that wraps content that is currently open, and was actually written.
+    open: Vec<String>,
+    source: Option<&'a str>,
+    source_map: Vec<SourceMapping>,
+    /// Where the last tag with a span ends in the markup.
+    cursor: usize,
+    /// Where the output of the text written since the last tag with a span starts.
+    text_start: Option<usize>,
+}
+
+impl<'a> TreeRenderer<'a> {
+    pub(crate) fn new(
+        xml_writer: &'a mut XmlWriter,
+        options: &'a ParseOptions,
+        warnings: &'a mut Vec<Warning>,
+    ) -> TreeRenderer<'a> {
+        TreeRenderer {
+            xml_writer,
+            options,
+            warnings,
+            auto_marker: AutoMarker::new(options.auto_marks),
+            open: Vec::new(),
+            source: None,
+            source_map: Vec::new(),
+            cursor: 0,
+            text_start: None,
+        }
+    }
+
+    /// Records a source map against the markup the tree was parsed from.
+    pub(crate) fn with_source(mut self, source: &'a str) -> TreeRenderer<'a> {
+        self.source = Some(source);
+        self
+    }
+
+    /// The source map of everything that was rendered. Empty without a source.
+    pub(crate) fn finish(mut self) -> Vec<SourceMapping> {
+        if let Some(source) = self.source {
+            self.map_text(source.len());
+        }
+        self.source_map
+    }
+
+    pub(crate) fn render(&mut self, nodes: &[SsmlNode]) -> Result<()> {
+        for node in nodes {
+            match node {
+                SsmlNode::Element(element) => self.render_element(element)?,
+                SsmlNode::Text(text) => {
+                    if self.text_start.is_none() {
+                        self.text_start = Some(self.xml_writer.written_len());
+                    }
+                    self.auto_marker
+                        .write_text(self.xml_writer, text)
+                        .with_context(|| format!("Failed to write the text {:?}", text))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_element(&mut self, element: &SsmlElement) -> Result<()> {
+        let options = self.options;
+        let handler = options.handlers.get(&element.name);
+        let is_unknown = handler.is_none() && element.kind().is_none();
+        let passed_through = is_unknown && options.unknown_tags != UnknownTagPolicy::Drop;
+        let params = borrowed_params(&element.params);
+
+        if let Some(span) = element.span {
+            self.map_text(span.offset);
+        }
+        let output_start = self.xml_writer.written_len();
+        let first_warning = self.warnings.len();
+        // Custom tags, and unknown tags that are dropped aren't written as an element of
+        // their own, so they can be nested anywhere.
+        if handler.is_none()
+            && (!is_unknown || options.unknown_tags == UnknownTagPolicy::Passthrough)
+        {
+            let open = self.open.iter().map(String::as_str);
+            if let Some(parent) = validate::illegal_parent(open, &element.name) {
+                self.warnings
+                    .push(Warning::illegal_nesting(&element.name, parent));
+            }
+        }
+        let closes = if let Some(handler) = handler {
+            let written = handler.start(self.xml_writer, &params).with_context(|| {
+                format!("Failed to write the start tag `${{{}}}`", element.name)
+            })?;
+            written && handler.has_closing_tag()
+        } else if passed_through {
+            let start_tag = StartTag {
+                tag_key: Cow::Borrowed(&element.name),
+                params,
+                duplicate_params: borrowed_duplicates(&element.duplicate_params),
+                param_spans: element
+                    .param_spans
+                    .iter()
+                    .map(|(name, span)| (Cow::Borrowed(name.as_str()), *span))
+                    .collect(),
+            };
+            let markup = match self.snippet(element.span) {
+                Some(markup) => Cow::Borrowed(markup),
+                None => Cow::Owned(element.markup()),
+            };
+            write_unknown_start_tag(self.xml_writer, options.unknown_tags, &start_tag, &markup)?;
+            true
+        } else {
+            let start_tag = StartTag {
+                tag_key: Cow::Borrowed(&element.name),
+                params,
+                duplicate_params: borrowed_duplicates(&element.duplicate_params),
+                param_spans: BTreeMap::new(),
+            };
+            for attribute in &start_tag.duplicate_params {
+                self.warnings
+                    .push(Warning::duplicate_attribute(&element.name, attribute));
+            }
+            for attribute in validate::unknown_params(&start_tag) {
+                self.warnings
+                    .push(Warning::unknown_attribute(&element.name, attribute));
+            }
+            let written = write_start_tag(
+                self.xml_writer,
+                &element.name,
+                &start_tag.params,
+                options,
+                self.warnings,
+            )
+            .with_context(|| format!("Failed to write the start tag `${{{}}}`", element.name))?;
+            written && has_closing_tag(&element.name, options)
+        };
+        self.locate(element.span, output_start, first_warning);
+
+        let is_open = closes && !passed_through;
+        if is_open {
+            self.open.push(element.name.clone());
+        }
+        self.auto_marker.start_tag(&element.name);
+        self.render(&element.children)?;
+        self.auto_marker.end_tag(&element.name);
+        if is_open {
+            self.open.pop();
+        }
+
+        if let Some(span) = element.end_span {
+            self.map_text(span.offset);
+        }
+        let output_start = self.xml_writer.written_len();
+        let first_warning = self.warnings.len();
+        if let Some(handler) = handler {
+            if closes {
+                handler.end(self.xml_writer).with_context(|| {
+                    format!("Failed to write the end tag `${{/{}}}`", element.name)
+                })?;
+            }
+        } else if passed_through {
+            // Literal tags are kept as they were written, so a start tag that was never
+            // closed doesn't get an end tag either.
+            let end_markup = match self.snippet(element.end_span) {
+                Some(markup) => Cow::Borrowed(markup),
+                None => Cow::Owned(format!("${{/{}}}", element.name)),
+            };
+            let unclosed = element.span.is_some() && element.end_span.is_none();
+            if !(unclosed && options.unknown_tags == UnknownTagPolicy::Literal) {
+                write_unknown_end_tag(
+                    self.xml_writer,
+                    options.unknown_tags,
+                    &element.name,
+                    &end_markup,
+                )?;
+            }
+        } else if closes {
+            write_end_tag(self.xml_writer, &element.name, options, self.warnings)
+                .with_context(|| format!("Failed to write the end tag `${{/{}}}`", element.name))?;
+        }
+        // A tag that was never closed is closed because of its start tag.
+        self.locate(
+            element.end_span.or(element.span),
+            output_start,
+            first_warning,
+        );
+
+        Ok(())
+    }
+
+    /// The markup of a tag, if the source is known.
+    fn snippet(&self, span: Option<Span>) -> Option<&'a str> {
+        Some(span?.snippet(self.source?))
+    }
+
+    /// Points the warnings since `first_warning` at a tag, and maps the output written since
+    /// `output_start` to it.
+    fn locate(&mut self, span: Option<Span>, output_start: usize, first_warning: usize) {
+        let span = match span {
+            Some(span) => span,
+            None => return,
+        };
+        for warning in &mut self.warnings[first_warning..] {
+            warning.span = Some(span);
+        }
+        if self.source.is_none() {
+            return;
+        }
+        let output_end = self.xml_writer.written_len();
+        if output_end > output_start {
+            self.source_map.push(SourceMapping {
+                output: output_start..output_end,
+                input: span,
+            });
+        }
+        self.cursor = self.cursor.max(span.offset + span.len);
+    }
+
+    /// Maps the output of the text written since the last tag with a span to the markup
+    /// between that tag, and `end`. Text can be moved around by the transformations, so it
+    /// isn't mapped if the tags around it are out of order.
+    fn map_text(&mut self, end: usize) {
+        let output_start = match self.text_start.take() {
+            Some(output_start) => output_start,
+            None => return,
+        };
+        let source = match self.source {
+            Some(source) => source,
+            None => return,
+        };
+        let output_end = self.xml_writer.written_len();
+        if end > self.cursor && output_end > output_start {
+            self.source_map.push(SourceMapping {
+                output: output_start..output_end,
+                input: Span::at(source, self.cursor, end - self.cursor),
+            });
+        }
+    }
+}
+
+/// The parameters of an element in the shape the renderer takes them.
+fn borrowed_params(params: &BTreeMap<String, String>) -> BTreeMap<Cow<'_, str>, Cow<'_, str>> {
+    params
+        .iter()
+        .map(|(name, value)| (Cow::Borrowed(name.as_str()), Cow::Borrowed(value.as_str())))
+        .collect()
+}
+
+/// The duplicate parameters of an element in the shape the renderer takes them.
+fn borrowed_duplicates(names: &[String]) -> Vec<Cow<'_, str>> {
+    names
+        .iter()
+        .map(|name| Cow::Borrowed(name.as_str()))
+        .collect()
+}
@@ -0,0 +1,33 @@
+//! Unicode normalization of the input, so text that looks the same is also written the same,
+//! e.g. an IPA `ph` typed with a combining diacritic, or with a precomposed character.
+
+use unicode_normalization::UnicodeNormalization as _;
+
+/// The Unicode normalization form to bring the input into, see `ParseOptions::normalization`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// Canonical composition, e.g. `e` followed by a combining acute accent becomes `é`.
+    Nfc,
+    /// Compatibility composition, which also replaces characters that only differ in how
+    /// they look with their plain form, e.g. the ligature `ﬁ` becomes `fi`, and a fullwidth
+    /// `Ａ` becomes `A`.
+    Nfkc,
+}
+
+impl NormalizationForm {
+    /// Brings some text into this normalization form.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::parser::NormalizationForm;
+    /// assert_eq!(NormalizationForm::Nfc.normalize("e\u{301}"), "\u{e9}");
+    /// assert_eq!(NormalizationForm::Nfkc.normalize("\u{fb01}ne"), "fine");
+    /// ```
+    pub fn normalize(&self, text: &str) -> String {
+        match self {
+            NormalizationForm::Nfc => text.nfc().collect(),
+            NormalizationForm::Nfkc => text.nfkc().collect(),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Checks on the tokenized markup that go beyond what the renderer needs.
+
+use super::render::{break_time_out_of_range, pitch_out_of_range};
+use super::{has_closing_tag, OneItem, ParseOptions, StartTag, TEXT_ONLY_TAGS};
+use crate::error::{Result, SsmlError};
+use crate::registry::find_tag;
+use crate::ssml_constants::{
+    BreakTime, PossibleClosingTags, PossibleOpenTags, ProsodyPitch, VocalTractLength,
+};
+
+/// The parameters of a start tag that the tag doesn't have, in the order they were written.
+/// Only built in tags are checked, what a custom tag accepts is up to its handler.
+pub(crate) fn unknown_params<'a>(start_tag: &'a StartTag) -> Vec<&'a str> {
+    match find_tag(&start_tag.tag_key) {
+        Some(tag) => start_tag
+            .params
+            .keys()
+            .filter(|name| tag.param(name).is_none())
+            .map(|name| name.as_ref())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// The tag a start tag isn't allowed to be nested inside of, if it is in one. `open` are the
+/// tags that are currently open, outermost first. Follows the nesting rules of SSML: a
+/// `${p}` can't be inside of another paragraph, or a sentence, a `${s}` can't be inside of
+/// another sentence, tags that can only contain text (e.g. `${sub}`) can't contain any
+/// tags, and the <speak> root can't be opened again.
+pub(crate) fn illegal_parent<'a>(
+    open: impl IntoIterator<Item = &'a str>,
+    tag_key: &str,
+) -> Option<&'a str> {
+    let tag_key = tag_key.to_lowercase();
+    if tag_key == "speak" {
+        return Some("speak");
+    }
+    open.into_iter().find(|parent| {
+        let parent = parent.to_lowercase();
+        TEXT_ONLY_TAGS.contains(&parent.as_str())
+            || matches!(
+                (parent.as_str(), tag_key.as_str()),
+                ("p" | "s", "p") | ("s", "s")
+            )
+    })
+}
+
+/// The parameters of a start tag with a value outside of the range the output target
+/// accepts, and their values. Rendering clamps, or drops these, strict mode rejects them.
+fn out_of_range_params<'a>(
+    start_tag: &'a StartTag,
+    options: &ParseOptions,
+) -> Vec<(&'a str, &'a str)> {
+    let mut out_of_range = Vec::new();
+    match start_tag.tag_key.parse::<PossibleOpenTags>() {
+        Ok(PossibleOpenTags::Break) => {
+            if let Some(value) = start_tag.params.get("time") {
+                if let Ok(time) = value.parse::<BreakTime>() {
+                    if break_time_out_of_range(&time, options.target) {
+                        out_of_range.push(("time", value.as_ref()));
+                    }
+                }
+            }
+        }
+        Ok(PossibleOpenTags::Prosody) => {
+            if let Some(value) = start_tag.params.get("pitch") {
+                if let Ok(pitch) = value.parse::<ProsodyPitch>() {
+                    if pitch_out_of_range(&pitch, options.target) {
+                        out_of_range.push(("pitch", value.as_ref()));
+                    }
+                }
+            }
+        }
+        Ok(PossibleOpenTags::AmazonEffect) => {
+            if let Some(value) = start_tag.params.get("vocal-tract-length") {
+                if let Ok(factor) = value.parse::<VocalTractLength>() {
+                    if !factor.is_in_range() {
+                        out_of_range.push(("vocal-tract-length", value.as_ref()));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    out_of_range
+}
+
+/// Makes sure tags aren't nested more than `max_depth` deep. An end tag closes the innermost
+/// open tag with the same name, and every tag opened after it, the same way rendering does.
+pub(crate) fn check_depth(
+    items: &[OneItem],
+    options: &ParseOptions,
+    max_depth: usize,
+) -> Result<()> {
+    let mut open: Vec<String> = Vec::new();
+
+    for item in items {
+        if let Some(ref start_tag) = item.start_tag {
+            if has_closing_tag(&start_tag.tag_key, options) {
+                if open.len() >= max_depth {
+                    return Err(SsmlError::NestingTooDeep {
+                        tag: start_tag.tag_key.to_string(),
+                        max_depth,
+                    });
+                }
+                open.push(start_tag.tag_key.to_lowercase());
+            }
+        }
+        if let Some(ref end_tag) = item.end_tag {
+            let tag_key = end_tag.tag_key.to_lowercase();
+            if let Some(position) = open.iter().rposition(|open_key| *open_key == tag_key) {
+                open.truncate(position);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Makes sure every tag is known, every tag that is opened is closed, and every tag that is
+/// closed was opened (in the right order). Built in tags also can't have parameters they
+/// don't support, the same parameter twice, or values the output target doesn't accept, and
+/// have to be supported by the output target, and nested where SSML allows them (see
+/// `illegal_parent`). This is what `ParseOptions::strict` enforces. Custom tags count as
+/// known.
+pub(crate) fn check_strict(items: &[OneItem], options: &ParseOptions) -> Result<()> {
+    let mut open: Vec<String> = Vec::new();
+
+    for item in items {
+        if let Some(ref start_tag) = item.start_tag {
+            if !options.handlers.contains(&start_tag.tag_key) {
+                let tag = match start_tag.tag_key.parse::<PossibleOpenTags>() {
+                    Ok(tag) => tag,
+                    Err(_) => return Err(SsmlError::UnknownTag(start_tag.tag_key.to_string())),
+                };
+                if !options.target.supports_tag(&tag) {
+                    return Err(SsmlError::UnsupportedTag {
+                        tag: start_tag.tag_key.to_string(),
+                        target: options.target.to_string(),
+                    });
+                }
+                if let Some(attribute) = start_tag.duplicate_params.first() {
+                    return Err(SsmlError::DuplicateAttribute {
+                        tag: start_tag.tag_key.to_string(),
+                        attribute: attribute.to_string(),
+                    });
+                }
+                if let Some(attribute) = unknown_params(start_tag).first() {
+                    return Err(SsmlError::UnknownAttribute {
+                        tag: start_tag.tag_key.to_string(),
+                        attribute: (*attribute).to_owned(),
+                    });
+                }
+                if let Some((attribute, value)) = out_of_range_params(start_tag, options).first() {
+                    return Err(SsmlError::OutOfRangeAttribute {
+                        tag: start_tag.tag_key.to_string(),
+                        attribute: (*attribute).to_owned(),
+                        value: (*value).to_owned(),
+                    });
+                }
+                if let Some(parent) =
+                    illegal_parent(open.iter().map(String::as_str), &start_tag.tag_key)
+                {
+                    return Err(SsmlError::IllegalNesting {
+                        tag: start_tag.tag_key.to_string(),
+                        parent: parent.to_owned(),
+                    });
+                }
+            }
+            if has_closing_tag(&start_tag.tag_key, options) {
+                open.push(start_tag.tag_key.to_lowercase());
+            }
+        }
+
+        if let Some(ref end_tag) = item.end_tag {
+            if end_tag.tag_key.parse::<PossibleClosingTags>().is_err()
+                && !options.handlers.contains(&end_tag.tag_key)
+            {
+                return Err(SsmlError::UnknownTag(end_tag.tag_key.to_string()));
+            }
+            let tag_key = end_tag.tag_key.to_lowercase();
+            match open.pop() {
+                Some(ref innermost) if *innermost == tag_key => {}
+                Some(innermost) => {
+                    if open.contains(&tag_key) {
+                        return Err(SsmlError::MisnestedTag {
+                            tag: tag_key,
+                            innermost,
+                        });
+                    }
+                    return Err(SsmlError::UnopenedTag(tag_key));
+                }
+                None => return Err(SsmlError::UnopenedTag(tag_key)),
+            }
+        }
+    }
+
+    if let Some(unclosed) = open.pop() {
+        return Err(SsmlError::UnclosedTag(unclosed));
+    }
+
+    Ok(())
+}
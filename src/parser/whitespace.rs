@@ -0,0 +1,87 @@
+//! Cleans up the whitespace in the text of some markup, so spaces that only exist to make
+//! the markup readable aren't carried into the audio.
+
+use super::tree::{parse_tree, tree_to_markup, SsmlNode};
+use crate::error::Result;
+
+/// How to clean up the whitespace in the text, see `normalize_whitespace`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WhitespaceOptions {
+    /// Replace every run of whitespace inside of a piece of text with a single space.
+    pub collapse: bool,
+    /// Remove the whitespace right after a start tag, and right before an end tag, as well
+    /// as at the very start, and end of the document.
+    pub trim: bool,
+}
+
+/// Cleans up the whitespace in the text of some markup. Whitespace between two tags that
+/// follow each other (e.g. `${/s} ${s}`) is only ever collapsed, never removed.
+///
+/// The markup is balanced the same way `parse_tree` balances it.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::parser::{normalize_whitespace, WhitespaceOptions};
+/// let options = WhitespaceOptions {
+///   collapse: true,
+///   trim: true,
+/// };
+/// let markup = normalize_whitespace("${s} some   words. ${/s}\n\n${s}More.${/s}", &options);
+/// assert_eq!(markup.unwrap(), "${s}some words.${/s} ${s}More.${/s}");
+/// ```
+pub fn normalize_whitespace(markup: &str, options: &WhitespaceOptions) -> Result<String> {
+    Ok(tree_to_markup(&whitespace_nodes(
+        parse_tree(markup)?,
+        options,
+    )))
+}
+
+/// Cleans up the whitespace of the nodes inside of a single tag, or the whole document.
+pub(crate) fn whitespace_nodes(nodes: Vec<SsmlNode>, options: &WhitespaceOptions) -> Vec<SsmlNode> {
+    let last = nodes.len().saturating_sub(1);
+    let mut cleaned = Vec::new();
+
+    for (idx, node) in nodes.into_iter().enumerate() {
+        match node {
+            SsmlNode::Text(mut text) => {
+                if options.collapse {
+                    text = collapse(&text);
+                }
+                if options.trim && idx == last {
+                    text.truncate(text.trim_end().len());
+                }
+                if options.trim && idx == 0 {
+                    text = text.trim_start().to_owned();
+                }
+                if !text.is_empty() {
+                    cleaned.push(SsmlNode::Text(text));
+                }
+            }
+            SsmlNode::Element(mut element) => {
+                element.children = whitespace_nodes(element.children, options);
+                cleaned.push(SsmlNode::Element(element));
+            }
+        }
+    }
+
+    cleaned
+}
+
+/// Replaces every run of whitespace in some text with a single space.
+fn collapse(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for character in text.chars() {
+        if character.is_whitespace() {
+            if !in_whitespace {
+                collapsed.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            collapsed.push(character);
+            in_whitespace = false;
+        }
+    }
+    collapsed
+}
@@ -0,0 +1,460 @@
+//! Converts between phonetic alphabets. Most freely available pronunciation data (e.g. the
+//! CMU Pronouncing Dictionary) is written in ARPABET, while polly only accepts IPA, or
+//! X-SAMPA in a <phoneme> tag.
+
+use crate::error::{Result, SsmlError};
+use crate::ssml_constants::PhonemeAlphabet;
+
+/// Every ARPABET phone, and the IPA it is written as. Vowels are listed with the IPA they
+/// have when they're stressed, see `UNSTRESSED_VOWELS` for the ones that change.
+const PHONES: &[(&str, &str)] = &[
+    // Vowels.
+    ("AA", "ɑ"),
+    ("AE", "æ"),
+    ("AH", "ʌ"),
+    ("AO", "ɔ"),
+    ("AW", "aʊ"),
+    ("AX", "ə"),
+    ("AXR", "ɚ"),
+    ("AY", "aɪ"),
+    ("EH", "ɛ"),
+    ("ER", "ɝ"),
+    ("EY", "eɪ"),
+    ("IH", "ɪ"),
+    ("IX", "ɨ"),
+    ("IY", "i"),
+    ("OW", "oʊ"),
+    ("OY", "ɔɪ"),
+    ("UH", "ʊ"),
+    ("UW", "u"),
+    ("UX", "ʉ"),
+    // Consonants.
+    ("B", "b"),
+    ("CH", "tʃ"),
+    ("D", "d"),
+    ("DH", "ð"),
+    ("DX", "ɾ"),
+    ("EL", "l̩"),
+    ("EM", "m̩"),
+    ("EN", "n̩"),
+    ("F", "f"),
+    ("G", "ɡ"),
+    ("HH", "h"),
+    ("JH", "dʒ"),
+    ("K", "k"),
+    ("L", "l"),
+    ("M", "m"),
+    ("N", "n"),
+    ("NG", "ŋ"),
+    ("P", "p"),
+    ("Q", "ʔ"),
+    ("R", "ɹ"),
+    ("S", "s"),
+    ("SH", "ʃ"),
+    ("T", "t"),
+    ("TH", "θ"),
+    ("V", "v"),
+    ("W", "w"),
+    ("WH", "ʍ"),
+    ("Y", "j"),
+    ("Z", "z"),
+    ("ZH", "ʒ"),
+];
+
+/// Vowels that are written differently when they're unstressed (stress `0`).
+const UNSTRESSED_VOWELS: &[(&str, &str)] = &[("AH", "ə"), ("ER", "ɚ")];
+
+/// Every vowel, all of which form the nucleus of a syllable.
+const VOWELS: &[&str] = &[
+    "AA", "AE", "AH", "AO", "AW", "AX", "AXR", "AY", "EH", "ER", "EY", "IH", "IX", "IY", "OW",
+    "OY", "UH", "UW", "UX",
+];
+
+/// Clusters of consonants that can start an English syllable. Any single consonant other
+/// than `NG` can start one too.
+const ONSETS: &[&[&str]] = &[
+    &["P", "L"],
+    &["B", "L"],
+    &["K", "L"],
+    &["G", "L"],
+    &["F", "L"],
+    &["S", "L"],
+    &["P", "R"],
+    &["B", "R"],
+    &["T", "R"],
+    &["D", "R"],
+    &["K", "R"],
+    &["G", "R"],
+    &["F", "R"],
+    &["TH", "R"],
+    &["SH", "R"],
+    &["P", "Y"],
+    &["B", "Y"],
+    &["K", "Y"],
+    &["F", "Y"],
+    &["M", "Y"],
+    &["V", "Y"],
+    &["HH", "Y"],
+    &["T", "W"],
+    &["D", "W"],
+    &["K", "W"],
+    &["G", "W"],
+    &["S", "W"],
+    &["S", "P"],
+    &["S", "T"],
+    &["S", "K"],
+    &["S", "M"],
+    &["S", "N"],
+    &["S", "F"],
+    &["S", "P", "L"],
+    &["S", "P", "R"],
+    &["S", "P", "Y"],
+    &["S", "T", "R"],
+    &["S", "K", "L"],
+    &["S", "K", "R"],
+    &["S", "K", "W"],
+    &["S", "K", "Y"],
+];
+
+/// A single phone of an ARPABET pronunciation.
+struct Phone<'a> {
+    symbol: &'a str,
+    /// The stress of a vowel: `0` for none, `1` for primary, and `2` for secondary stress.
+    stress: Option<u8>,
+}
+
+impl Phone<'_> {
+    fn is_vowel(&self) -> bool {
+        VOWELS.contains(&self.symbol)
+    }
+
+    fn to_ipa(&self) -> &'static str {
+        if self.stress == Some(0) {
+            if let Some((_, ipa)) = UNSTRESSED_VOWELS
+                .iter()
+                .find(|(symbol, _)| *symbol == self.symbol)
+            {
+                return ipa;
+            }
+        }
+        PHONES
+            .iter()
+            .find(|(symbol, _)| *symbol == self.symbol)
+            .map(|(_, ipa)| *ipa)
+            .unwrap_or("")
+    }
+}
+
+/// Converts a CMUdict style ARPABET pronunciation (phones separated by whitespace, with
+/// the stress of vowels as a trailing digit) into IPA, for use with
+/// `XmlWriter::start_ssml_phoneme`.
+///
+/// Stress is marked with `ˈ`, or `ˌ` at the start of the stressed syllable, where each
+/// syllable starts with as many of the consonants before its vowel as can start an English
+/// syllable. Phones are matched without regard to case, and an unknown phone returns an
+/// `SsmlError::UnknownPhone`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::phonetics::arpabet_to_ipa;
+/// assert_eq!(arpabet_to_ipa("T AH0 M EY1 T OW2").unwrap(), "təˈmeɪˌtoʊ");
+/// assert_eq!(arpabet_to_ipa("EH1 K S T R AH0").unwrap(), "ˈɛkstɹə");
+/// ```
+pub fn arpabet_to_ipa(arpabet: &str) -> Result<String> {
+    let uppercase = arpabet.to_uppercase();
+    let phones = uppercase
+        .split_whitespace()
+        .map(parse_phone)
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut ipa = String::new();
+    for (idx, phone) in phones.iter().enumerate() {
+        if let Some(mark) = stress_mark_before(&phones, idx) {
+            ipa.push(mark);
+        }
+        ipa.push_str(phone.to_ipa());
+    }
+    Ok(ipa)
+}
+
+/// Splits a phone into its symbol, and stress.
+fn parse_phone(phone: &str) -> Result<Phone<'_>> {
+    let (symbol, stress) = match phone.char_indices().last() {
+        Some((idx, digit @ '0'..='2')) => (&phone[..idx], Some(digit as u8 - b'0')),
+        _ => (phone, None),
+    };
+    let is_known = PHONES.iter().any(|(known, _)| *known == symbol);
+    if !is_known || (stress.is_some() && !VOWELS.contains(&symbol)) {
+        return Err(SsmlError::UnknownPhone(phone.to_owned()));
+    }
+    Ok(Phone { symbol, stress })
+}
+
+/// The stress mark that goes right before a phone, if it starts a stressed syllable.
+fn stress_mark_before(phones: &[Phone<'_>], idx: usize) -> Option<char> {
+    // The vowel of the syllable this phone would start, if it starts one.
+    let nucleus = phones[idx..].iter().position(Phone::is_vowel)? + idx;
+    let mark = match phones[nucleus].stress {
+        Some(1) => 'ˈ',
+        Some(2) => 'ˌ',
+        _ => return None,
+    };
+
+    let cluster_start = phones[..nucleus]
+        .iter()
+        .rposition(Phone::is_vowel)
+        .map_or(0, |previous| previous + 1);
+    let onset_start = if cluster_start == 0 {
+        0
+    } else {
+        (cluster_start..=nucleus)
+            .find(|start| is_onset(&phones[*start..nucleus]))
+            .unwrap_or(nucleus)
+    };
+
+    if idx == onset_start {
+        Some(mark)
+    } else {
+        None
+    }
+}
+
+/// If a cluster of consonants can start an English syllable.
+fn is_onset(cluster: &[Phone<'_>]) -> bool {
+    match cluster {
+        [] => true,
+        [single] => single.symbol != "NG",
+        _ => ONSETS.iter().any(|onset| {
+            onset.len() == cluster.len()
+                && onset
+                    .iter()
+                    .zip(cluster)
+                    .all(|(symbol, phone)| *symbol == phone.symbol)
+        }),
+    }
+}
+
+/// Every X-SAMPA symbol, and the IPA it stands for. Where multiple symbols stand for the
+/// same IPA the first one is used when converting IPA into X-SAMPA.
+const XSAMPA_SYMBOLS: &[(&str, &str)] = &[
+    // Lowercase letters.
+    ("a", "a"),
+    ("b", "b"),
+    ("b_<", "ɓ"),
+    ("c", "c"),
+    ("d", "d"),
+    ("d`", "ɖ"),
+    ("d_<", "ɗ"),
+    ("e", "e"),
+    ("f", "f"),
+    ("g", "ɡ"),
+    ("g", "g"),
+    ("g_<", "ɠ"),
+    ("h", "h"),
+    ("h\\", "ɦ"),
+    ("i", "i"),
+    ("j", "j"),
+    ("j\\", "ʝ"),
+    ("k", "k"),
+    ("l", "l"),
+    ("l`", "ɭ"),
+    ("l\\", "ɺ"),
+    ("m", "m"),
+    ("n", "n"),
+    ("n`", "ɳ"),
+    ("o", "o"),
+    ("p", "p"),
+    ("p\\", "ɸ"),
+    ("q", "q"),
+    ("r", "r"),
+    ("r`", "ɽ"),
+    ("r\\", "ɹ"),
+    ("r\\`", "ɻ"),
+    ("s", "s"),
+    ("s`", "ʂ"),
+    ("s\\", "ɕ"),
+    ("t", "t"),
+    ("t`", "ʈ"),
+    ("u", "u"),
+    ("v", "v"),
+    ("v\\", "ʋ"),
+    ("w", "w"),
+    ("x", "x"),
+    ("x\\", "ɧ"),
+    ("y", "y"),
+    ("z", "z"),
+    ("z`", "ʐ"),
+    ("z\\", "ʑ"),
+    // Uppercase letters.
+    ("A", "ɑ"),
+    ("B", "β"),
+    ("B\\", "ʙ"),
+    ("C", "ç"),
+    ("D", "ð"),
+    ("E", "ɛ"),
+    ("F", "ɱ"),
+    ("G", "ɣ"),
+    ("G\\", "ɢ"),
+    ("G\\_<", "ʛ"),
+    ("H", "ɥ"),
+    ("H\\", "ʜ"),
+    ("I", "ɪ"),
+    ("I\\", "ᵻ"),
+    ("J", "ɲ"),
+    ("J\\", "ɟ"),
+    ("J\\_<", "ʄ"),
+    ("K", "ɬ"),
+    ("K\\", "ɮ"),
+    ("L", "ʎ"),
+    ("L\\", "ʟ"),
+    ("M", "ɯ"),
+    ("M\\", "ɰ"),
+    ("N", "ŋ"),
+    ("N\\", "ɴ"),
+    ("O", "ɔ"),
+    ("O\\", "ʘ"),
+    ("P", "ʋ"),
+    ("Q", "ɒ"),
+    ("R", "ʁ"),
+    ("R\\", "ʀ"),
+    ("S", "ʃ"),
+    ("T", "θ"),
+    ("U", "ʊ"),
+    ("U\\", "ᵿ"),
+    ("V", "ʌ"),
+    ("W", "ʍ"),
+    ("X", "χ"),
+    ("X\\", "ħ"),
+    ("Y", "ʏ"),
+    ("Z", "ʒ"),
+    // Other symbols.
+    (".", "."),
+    ("\"", "ˈ"),
+    ("%", "ˌ"),
+    ("'", "ʲ"),
+    ("_j", "ʲ"),
+    (":", "ː"),
+    (":\\", "ˑ"),
+    ("@", "ə"),
+    ("@\\", "ɘ"),
+    ("@`", "ɚ"),
+    ("{", "æ"),
+    ("}", "ʉ"),
+    ("1", "ɨ"),
+    ("2", "ø"),
+    ("3", "ɜ"),
+    ("3\\", "ɞ"),
+    ("3`", "ɝ"),
+    ("4", "ɾ"),
+    ("5", "ɫ"),
+    ("6", "ɐ"),
+    ("7", "ɤ"),
+    ("8", "ɵ"),
+    ("9", "œ"),
+    ("&", "ɶ"),
+    ("?", "ʔ"),
+    ("?\\", "ʕ"),
+    ("<\\", "ʢ"),
+    (">\\", "ʡ"),
+    ("!\\", "ǃ"),
+    ("|", "|"),
+    ("||", "‖"),
+    ("|\\", "ǀ"),
+    ("|\\|\\", "ǁ"),
+    ("=\\", "ǂ"),
+    ("-\\", "‿"),
+    // Diacritics.
+    ("_h", "ʰ"),
+    ("_w", "ʷ"),
+    ("~", "\u{303}"),
+    ("_~", "\u{303}"),
+    ("=", "\u{329}"),
+    ("_=", "\u{329}"),
+    ("_0", "\u{325}"),
+    ("_\"", "\u{308}"),
+    ("`", "˞"),
+];
+
+/// Converts an X-SAMPA pronunciation into IPA. Whitespace is kept as is, and a symbol
+/// without an IPA equivalent returns an `SsmlError::UnmappableSymbol`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::phonetics::xsampa_to_ipa;
+/// assert_eq!(xsampa_to_ipa("t@\"meItoU").unwrap(), "təˈmeɪtoʊ");
+/// ```
+pub fn xsampa_to_ipa(xsampa: &str) -> Result<String> {
+    convert_symbols(xsampa, PhonemeAlphabet::XSampa, |(symbol, ipa)| {
+        (symbol, ipa)
+    })
+}
+
+/// Converts an IPA pronunciation into X-SAMPA. Whitespace is kept as is, and a symbol
+/// without an X-SAMPA equivalent returns an `SsmlError::UnmappableSymbol`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::phonetics::ipa_to_xsampa;
+/// assert_eq!(ipa_to_xsampa("təˈmeɪtoʊ").unwrap(), "t@\"meItoU");
+/// ```
+pub fn ipa_to_xsampa(ipa: &str) -> Result<String> {
+    convert_symbols(ipa, PhonemeAlphabet::Ipa, |(symbol, ipa)| (ipa, symbol))
+}
+
+/// Converts a pronunciation from one alphabet into another. Only IPA, and X-SAMPA can be
+/// converted into each other, any other conversion returns an
+/// `SsmlError::UnsupportedConversion`, unless both alphabets are the same.
+pub fn convert_phonemes(ph: &str, from: PhonemeAlphabet, to: PhonemeAlphabet) -> Result<String> {
+    match (from, to) {
+        (from, to) if from == to => Ok(ph.to_owned()),
+        (PhonemeAlphabet::XSampa, PhonemeAlphabet::Ipa) => xsampa_to_ipa(ph),
+        (PhonemeAlphabet::Ipa, PhonemeAlphabet::XSampa) => ipa_to_xsampa(ph),
+        (from, to) => Err(SsmlError::UnsupportedConversion {
+            from: from.to_string(),
+            to: to.to_string(),
+        }),
+    }
+}
+
+/// Converts a pronunciation symbol by symbol, always taking the longest symbol that
+/// matches. `direction` picks which side of `XSAMPA_SYMBOLS` is read, and which one is
+/// written.
+fn convert_symbols<F>(ph: &str, alphabet: PhonemeAlphabet, direction: F) -> Result<String>
+where
+    F: Fn((&'static str, &'static str)) -> (&'static str, &'static str),
+{
+    let mut converted = String::with_capacity(ph.len());
+    let mut rest = ph;
+
+    while let Some(character) = rest.chars().next() {
+        if character.is_whitespace() {
+            converted.push(character);
+            rest = &rest[character.len_utf8()..];
+            continue;
+        }
+
+        let mut longest: Option<(&str, &str)> = None;
+        for (from, to) in XSAMPA_SYMBOLS.iter().map(|pair| direction(*pair)) {
+            if rest.starts_with(from) && longest.is_none_or(|(known, _)| from.len() > known.len()) {
+                longest = Some((from, to));
+            }
+        }
+        match longest {
+            Some((from, to)) => {
+                converted.push_str(to);
+                rest = &rest[from.len()..];
+            }
+            None => {
+                return Err(SsmlError::UnmappableSymbol {
+                    symbol: character.to_string(),
+                    alphabet: alphabet.to_string(),
+                })
+            }
+        }
+    }
+
+    Ok(converted)
+}
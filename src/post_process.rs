@@ -0,0 +1,141 @@
+//! Passes that run over already rendered SSML. These never change what gets spoken, they
+//! only tidy up the document.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, Writer};
+
+use crate::error::{Result, SsmlError};
+
+use std::io::Cursor;
+
+/// Elements that still mean something when they have no content, and as such are collapsed
+/// into their self-closing form instead of being removed.
+const MEANINGFUL_WHEN_EMPTY: &[&[u8]] = &[b"mark"];
+
+/// An element we've seen the start of, but not the end of yet. Its content is held back
+/// until we know whether there was any.
+struct PendingElement {
+    start: BytesStart<'static>,
+    content: Vec<Event<'static>>,
+}
+
+/// Removes every element that has no text, or child content from a rendered SSML document.
+/// Elements that are meaningful while empty (e.g. <mark>) are collapsed into their
+/// self-closing form instead. Removal happens bottom up, so an element that only contained
+/// empty elements is removed as well. The root <speak> tag is always kept.
+///
+/// Unbalanced documents are passed through as best we can: stray end tags are kept as is,
+/// and unclosed tags are written out without being closed.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::post_process::drop_empty_elements;
+/// let result = drop_empty_elements(
+///   r#"<speak><p><s></s></p><mark name="a"></mark>hey</speak>"#,
+/// );
+/// assert_eq!(result.unwrap(), r#"<speak><mark name="a"/>hey</speak>"#);
+/// ```
+pub fn drop_empty_elements(ssml: &str) -> Result<String> {
+    let mut reader = Reader::from_str(ssml);
+    reader.check_end_names(false);
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    let mut pending: Vec<PendingElement> = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event(&mut buf)
+            .map_err(SsmlError::InvalidSsml)?
+            .into_owned();
+        buf.clear();
+
+        match event {
+            Event::Eof => break,
+            Event::Start(start) => pending.push(PendingElement {
+                start,
+                content: Vec::new(),
+            }),
+            Event::End(end) => match pending.pop() {
+                Some(element) => {
+                    let name = element.start.name().to_vec();
+                    let collapsed = if !element.content.is_empty() || name == b"speak" {
+                        let mut events = vec![Event::Start(element.start)];
+                        events.extend(element.content);
+                        events.push(Event::End(end));
+                        events
+                    } else if MEANINGFUL_WHEN_EMPTY.contains(&name.as_slice()) {
+                        vec![Event::Empty(element.start)]
+                    } else {
+                        Vec::new()
+                    };
+                    for collapsed_event in collapsed {
+                        emit(&mut pending, &mut writer, collapsed_event)?;
+                    }
+                }
+                None => writer.write_event(Event::End(end))?,
+            },
+            Event::Text(ref text) if text.is_empty() => {}
+            other => emit(&mut pending, &mut writer, other)?,
+        }
+    }
+
+    for element in pending {
+        writer.write_event(Event::Start(element.start))?;
+        for event in element.content {
+            writer.write_event(event)?;
+        }
+    }
+
+    Ok(String::from_utf8(writer.into_inner().into_inner())?)
+}
+
+/// Hands an event to the innermost pending element, or writes it out if there isn't one.
+fn emit(
+    pending: &mut [PendingElement],
+    writer: &mut Writer<Cursor<Vec<u8>>>,
+    event: Event<'static>,
+) -> Result<()> {
+    match pending.last_mut() {
+        Some(parent) => parent.content.push(event),
+        None => {
+            writer.write_event(event)?;
+        }
+    }
+    Ok(())
+}
+
+/// Pretty prints a rendered SSML document, putting every element on its own line indented
+/// by `indent_size` spaces per level of nesting. Text is never touched, so elements inside
+/// of text stay on the same line as that text, and nothing that gets spoken changes.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::post_process::pretty_print;
+/// let result = pretty_print(r#"<speak><p><s>Hello</s><s>world</s></p></speak>"#, 2);
+/// assert_eq!(
+///   result.unwrap(),
+///   "<speak>\n  <p>\n    <s>Hello</s>\n    <s>world</s>\n  </p>\n</speak>",
+/// );
+/// ```
+pub fn pretty_print(ssml: &str, indent_size: usize) -> Result<String> {
+    let mut reader = Reader::from_str(ssml);
+    reader.check_end_names(false);
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', indent_size);
+    let mut buf = Vec::new();
+
+    loop {
+        match reader
+            .read_event(&mut buf)
+            .map_err(SsmlError::InvalidSsml)?
+        {
+            Event::Eof => break,
+            Event::Text(ref text) if text.is_empty() => {}
+            event => writer.write_event(event)?,
+        }
+        buf.clear();
+    }
+
+    Ok(String::from_utf8(writer.into_inner().into_inner())?)
+}
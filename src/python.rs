@@ -0,0 +1,210 @@
+//! Bindings for Python through PyO3, so SSML can be generated outside of Rust with the same
+//! rules. Build the module with `maturin build`, which enables the `python` feature, and it
+//! imports as `text_to_polly_ssml`:
+//!
+//! ```python
+//! import text_to_polly_ssml as ssml
+//!
+//! options = ssml.ParseOptions(target="alexa", lang="en-GB")
+//! print(ssml.parse_str("${s}Hello${/s}", options))
+//! for diagnostic in ssml.lint("${p}Hi"):
+//!     print(diagnostic.line, diagnostic.column, diagnostic.message)
+//! ```
+//!
+//! Errors are raised as `text_to_polly_ssml.SsmlError`, with the message of the `SsmlError`.
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+
+use crate::parser::{lint_with_options, DiagnosticKind, OutputTarget, Severity};
+
+create_exception!(
+    text_to_polly_ssml,
+    SsmlError,
+    PyException,
+    "The markup couldn't be parsed, or rendered."
+);
+
+/// The options of `parse_str`, and `lint` that can be set from Python. Everything else
+/// keeps the defaults of `ParseOptions`.
+#[pyclass(module = "text_to_polly_ssml")]
+#[derive(Clone, Debug, Default)]
+pub struct ParseOptions {
+    target: OutputTarget,
+    /// The `xml:lang` of the `<speak>` tag, e.g. `en-GB`.
+    #[pyo3(get, set)]
+    lang: Option<String>,
+    /// Fail on unknown, unbalanced, or misnested tags instead of ignoring them.
+    #[pyo3(get, set)]
+    strict: bool,
+    /// Indent the SSML.
+    #[pyo3(get, set)]
+    pretty: bool,
+    /// Write every non-ASCII character as a numeric character reference.
+    #[pyo3(get, set)]
+    ascii_safe: bool,
+}
+
+#[pymethods]
+impl ParseOptions {
+    #[new]
+    #[pyo3(signature = (target = None, lang = None, strict = false, pretty = false, ascii_safe = false))]
+    fn new(
+        target: Option<&str>,
+        lang: Option<String>,
+        strict: bool,
+        pretty: bool,
+        ascii_safe: bool,
+    ) -> PyResult<ParseOptions> {
+        let mut options = ParseOptions {
+            lang,
+            strict,
+            pretty,
+            ascii_safe,
+            ..Default::default()
+        };
+        if let Some(target) = target {
+            options.set_target(target)?;
+        }
+        Ok(options)
+    }
+
+    /// The engine the SSML is for, one of `polly`, `alexa`, `google`, `azure`, `watson`, or
+    /// `generic`.
+    #[getter]
+    fn target(&self) -> String {
+        self.target.to_string()
+    }
+
+    #[setter]
+    fn set_target(&mut self, target: &str) -> PyResult<()> {
+        self.target = target
+            .parse::<OutputTarget>()
+            .map_err(|error| PyValueError::new_err(error.to_string()))?;
+        Ok(())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ParseOptions(target={:?}, lang={}, strict={}, pretty={}, ascii_safe={})",
+            self.target.to_string(),
+            self.lang
+                .as_ref()
+                .map_or_else(|| "None".to_owned(), |lang| format!("{:?}", lang)),
+            python_bool(self.strict),
+            python_bool(self.pretty),
+            python_bool(self.ascii_safe)
+        )
+    }
+}
+
+impl ParseOptions {
+    fn to_options(&self) -> crate::ParseOptions {
+        let mut options = crate::ParseOptions {
+            target: self.target,
+            strict: self.strict,
+            pretty: self.pretty,
+            ascii_safe: self.ascii_safe,
+            ..Default::default()
+        };
+        options.speak.lang = self.lang.clone();
+        options
+    }
+}
+
+/// A problem `lint` found in the markup.
+#[pyclass(module = "text_to_polly_ssml", frozen, get_all)]
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    /// `error`, `warning`, or `hint`.
+    severity: String,
+    /// What kind of problem it is, e.g. `UnclosedTag`, or `InvalidAttributeValue`.
+    kind: String,
+    /// The key of the tag involved, if there is one.
+    tag: Option<String>,
+    /// The parameter involved, if it is about a single parameter.
+    attribute: Option<String>,
+    /// A human readable description of the problem.
+    message: String,
+    /// The line of the markup it is on, starting at 1.
+    line: Option<usize>,
+    /// The column of the line it is at, starting at 1.
+    column: Option<usize>,
+}
+
+#[pymethods]
+impl Diagnostic {
+    fn __repr__(&self) -> String {
+        format!(
+            "Diagnostic(severity={:?}, kind={:?}, message={:?})",
+            self.severity, self.kind, self.message
+        )
+    }
+
+    fn __str__(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl From<crate::parser::Diagnostic> for Diagnostic {
+    fn from(diagnostic: crate::parser::Diagnostic) -> Diagnostic {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Hint => "hint",
+        };
+        let kind = match diagnostic.kind {
+            DiagnosticKind::Render(kind) => format!("{:?}", kind),
+            kind => format!("{:?}", kind),
+        };
+        Diagnostic {
+            severity: severity.to_owned(),
+            kind,
+            tag: diagnostic.tag,
+            attribute: diagnostic.attribute,
+            message: diagnostic.message,
+            line: diagnostic.span.map(|span| span.line),
+            column: diagnostic.span.map(|span| span.column),
+        }
+    }
+}
+
+/// Parses markup into SSML, with the default options if there aren't any.
+#[pyfunction]
+#[pyo3(signature = (markup, options = None))]
+pub fn parse_str(markup: &str, options: Option<&ParseOptions>) -> PyResult<String> {
+    let options = options.map(ParseOptions::to_options).unwrap_or_default();
+    crate::parse_str_with_options(markup, &options)
+        .map_err(|error| SsmlError::new_err(error.to_string()))
+}
+
+/// Every problem in the markup, with the default options if there aren't any.
+#[pyfunction]
+#[pyo3(signature = (markup, options = None))]
+pub fn lint(markup: &str, options: Option<&ParseOptions>) -> Vec<Diagnostic> {
+    let options = options.map(ParseOptions::to_options).unwrap_or_default();
+    lint_with_options(markup, &options)
+        .into_iter()
+        .map(Diagnostic::from)
+        .collect()
+}
+
+/// The `text_to_polly_ssml` Python module.
+#[pymodule]
+pub fn text_to_polly_ssml(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(parse_str, module)?)?;
+    module.add_function(wrap_pyfunction!(lint, module)?)?;
+    module.add_class::<ParseOptions>()?;
+    module.add_class::<Diagnostic>()?;
+    module.add("SsmlError", module.py().get_type::<SsmlError>())?;
+    Ok(())
+}
+
+fn python_bool(value: bool) -> &'static str {
+    if value {
+        "True"
+    } else {
+        "False"
+    }
+}
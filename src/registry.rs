@@ -0,0 +1,536 @@
+//! A description of every tag, and parameter the markup supports. This is what the parser
+//! understands, laid out as data so editors can offer autocomplete, and validation without
+//! hard-coding what this crate can do.
+
+/// Where a tag comes from. Standard tags are in the W3C SSML v1.1 Spec, the rest are vendor
+/// extensions that only some engines understand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// Defined by the W3C SSML v1.1 Standard.
+    W3c,
+    /// An `amazon:*` extension (or an amazon specific attribute value).
+    Amazon,
+    /// An `mstts:*` extension of Azure.
+    Microsoft,
+    /// An extension of Google Cloud Text-to-Speech.
+    Google,
+    /// An extension of IBM Watson Text to Speech.
+    Ibm,
+}
+
+/// Describes a single `|key=value` parameter of a tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParamSpec {
+    /// The key of the parameter as written in the markup.
+    pub name: &'static str,
+    /// If the tag is dropped when this parameter is missing.
+    pub required: bool,
+    /// Every value the parameter accepts. `None` means the value is free form.
+    pub values: Option<&'static [&'static str]>,
+    /// A short human readable description of the parameter.
+    pub description: &'static str,
+}
+
+/// Describes a single tag of the markup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TagSpec {
+    /// The key of the tag as written in the markup (`${key}`).
+    pub name: &'static str,
+    /// Every parameter the tag understands.
+    pub params: &'static [ParamSpec],
+    /// If the tag wraps content, and as such should be closed with `${/key}`.
+    pub has_closing_tag: bool,
+    /// Where the tag comes from.
+    pub dialect: Dialect,
+    /// A short human readable description of the tag.
+    pub description: &'static str,
+}
+
+impl TagSpec {
+    /// Looks up a parameter of this tag by its key.
+    pub fn param(&self, name: &str) -> Option<&'static ParamSpec> {
+        self.params.iter().find(|param| param.name == name)
+    }
+}
+
+const BREATH_VOLUMES: &[&str] = &["default", "x-soft", "soft", "medium", "loud", "x-loud"];
+const BREATH_DURATIONS: &[&str] = &["default", "x-short", "short", "medium", "long", "x-long"];
+
+const SUPPORTED_TAGS: &[TagSpec] = &[
+    TagSpec {
+        name: "break",
+        params: &[
+            ParamSpec {
+                name: "strength",
+                required: false,
+                values: Some(&["none", "x-weak", "weak", "medium", "strong", "x-strong"]),
+                description: "How strong the pause should be.",
+            },
+            ParamSpec {
+                name: "time",
+                required: false,
+                values: None,
+                description: "How long to pause for, e.g. `3s` or `250ms`.",
+            },
+        ],
+        has_closing_tag: false,
+        dialect: Dialect::W3c,
+        description: "Pauses the speech.",
+    },
+    TagSpec {
+        name: "emphasis",
+        params: &[ParamSpec {
+            name: "level",
+            required: false,
+            values: Some(&["strong", "moderate", "reduced"]),
+            description: "How strongly to stress the content, `moderate` if it isn't set.",
+        }],
+        has_closing_tag: true,
+        dialect: Dialect::W3c,
+        description: "Stresses, or de-emphasizes the content.",
+    },
+    TagSpec {
+        name: "lang",
+        params: &[
+            ParamSpec {
+                name: "lang",
+                required: true,
+                values: None,
+                description: "The language to speak in, e.g. `fr-FR`.",
+            },
+            ParamSpec {
+                name: "onlangfailure",
+                required: false,
+                values: None,
+                description: "What to do when the language isn't supported.",
+            },
+        ],
+        has_closing_tag: true,
+        dialect: Dialect::W3c,
+        description: "Speaks the content in another language.",
+    },
+    TagSpec {
+        name: "mark",
+        params: &[ParamSpec {
+            name: "name",
+            required: true,
+            values: None,
+            description: "The name of the mark in the speech marks.",
+        }],
+        has_closing_tag: false,
+        dialect: Dialect::W3c,
+        description: "Places a custom marker in the speech marks.",
+    },
+    TagSpec {
+        name: "p",
+        params: &[ParamSpec {
+            name: "lang",
+            required: false,
+            values: None,
+            description: "The language the content is in, e.g. `fr-FR`.",
+        }],
+        has_closing_tag: true,
+        dialect: Dialect::W3c,
+        description: "A paragraph of text.",
+    },
+    TagSpec {
+        name: "phoneme",
+        params: &[
+            ParamSpec {
+                name: "alphabet",
+                required: true,
+                values: Some(&[
+                    "ipa",
+                    "x-sampa",
+                    "x-amazon-pron-kana",
+                    "x-amazon-yomigana",
+                    "x-amazon-pinyin",
+                ]),
+                description: "The phonetic alphabet `ph` is written in.",
+            },
+            ParamSpec {
+                name: "ph",
+                required: true,
+                values: None,
+                description: "The phonetic pronunciation.",
+            },
+        ],
+        has_closing_tag: true,
+        dialect: Dialect::W3c,
+        description: "Uses a custom pronunciation for the content.",
+    },
+    TagSpec {
+        name: "prosody",
+        params: &[
+            ParamSpec {
+                name: "volume",
+                required: false,
+                values: None,
+                description: "The volume of the speech, e.g. `loud` or `+6dB`.",
+            },
+            ParamSpec {
+                name: "rate",
+                required: false,
+                values: Some(&["x-slow", "slow", "medium", "fast", "x-fast"]),
+                description: "The speaking rate of the speech.",
+            },
+            ParamSpec {
+                name: "pitch",
+                required: false,
+                values: None,
+                description: "The pitch of the speech, e.g. `high`, `+10%`, or `200Hz`.",
+            },
+            ParamSpec {
+                name: "duration",
+                required: false,
+                values: None,
+                description: "How long to take to speak the content, e.g. `2500ms`. Only for the generic target.",
+            },
+            ParamSpec {
+                name: "max-duration",
+                required: false,
+                values: None,
+                description: "The longest the content may take to speak, e.g. `1500ms`. Only for the Alexa target.",
+            },
+        ],
+        has_closing_tag: true,
+        dialect: Dialect::W3c,
+        description: "Controls the volume, rate, and pitch of the content.",
+    },
+    TagSpec {
+        name: "s",
+        params: &[ParamSpec {
+            name: "lang",
+            required: false,
+            values: None,
+            description: "The language the content is in, e.g. `fr-FR`.",
+        }],
+        has_closing_tag: true,
+        dialect: Dialect::W3c,
+        description: "A sentence of text.",
+    },
+    TagSpec {
+        name: "say-as",
+        params: &[
+            ParamSpec {
+                name: "interpret-as",
+                required: true,
+                values: None,
+                description: "How to interpret the content, e.g. `characters` or `date`.",
+            },
+            ParamSpec {
+                name: "format",
+                required: false,
+                values: None,
+                description: "How the content is written, e.g. `mdy` for a date.",
+            },
+        ],
+        has_closing_tag: true,
+        dialect: Dialect::W3c,
+        description: "Controls how the content is interpreted.",
+    },
+    TagSpec {
+        name: "sub",
+        params: &[ParamSpec {
+            name: "alias",
+            required: true,
+            values: None,
+            description: "The text to speak instead of the content.",
+        }],
+        has_closing_tag: true,
+        dialect: Dialect::W3c,
+        description: "Substitutes the content with other spoken text.",
+    },
+    TagSpec {
+        name: "voice",
+        params: &[ParamSpec {
+            name: "name",
+            required: true,
+            values: None,
+            description: "The name of the voice to speak in, e.g. `Brian`.",
+        }],
+        has_closing_tag: true,
+        dialect: Dialect::W3c,
+        description: "Speaks the content in another voice. Not supported by AWS Polly.",
+    },
+    TagSpec {
+        name: "w",
+        params: &[ParamSpec {
+            name: "role",
+            required: true,
+            values: Some(&[
+                "amazon:DEFAULT",
+                "amazon:NN",
+                "amazon:VB",
+                "amazon:VBD",
+                "amazon:SENSE_1",
+            ]),
+            description: "The part of speech of the word. `amazon:SENSE_<n>` picks a specific \
+                          sense, and any other `amazon:` role is passed through.",
+        }],
+        has_closing_tag: true,
+        dialect: Dialect::W3c,
+        description: "Disambiguates how a word should be pronounced.",
+    },
+    TagSpec {
+        name: "amazon:effect",
+        params: &[
+            ParamSpec {
+                name: "name",
+                required: false,
+                values: Some(&["whispered", "drc"]),
+                description: "The effect to apply.",
+            },
+            ParamSpec {
+                name: "vocal-tract-length",
+                required: false,
+                values: None,
+                description: "Changes the timbre of the voice, e.g. `+10%`, or `150%`. Lengths \
+                              have to be between `50%`, and `200%`.",
+            },
+            ParamSpec {
+                name: "phonation",
+                required: false,
+                values: Some(&["soft"]),
+                description: "Changes the phonation of the voice.",
+            },
+        ],
+        has_closing_tag: true,
+        dialect: Dialect::Amazon,
+        description: "Applies an amazon specific effect to the content.",
+    },
+    TagSpec {
+        name: "amazon:auto-breaths",
+        params: &[
+            ParamSpec {
+                name: "volume",
+                required: false,
+                values: Some(BREATH_VOLUMES),
+                description: "The volume of the breaths.",
+            },
+            ParamSpec {
+                name: "frequency",
+                required: false,
+                values: Some(&["default", "x-low", "low", "medium", "high", "x-high"]),
+                description: "How often to breathe.",
+            },
+            ParamSpec {
+                name: "duration",
+                required: false,
+                values: Some(BREATH_DURATIONS),
+                description: "How long each breath lasts.",
+            },
+        ],
+        has_closing_tag: true,
+        dialect: Dialect::Amazon,
+        description: "Automatically adds breathing sounds to the content.",
+    },
+    TagSpec {
+        name: "amazon:breath",
+        params: &[
+            ParamSpec {
+                name: "volume",
+                required: false,
+                values: Some(BREATH_VOLUMES),
+                description: "The volume of the breath.",
+            },
+            ParamSpec {
+                name: "duration",
+                required: false,
+                values: Some(BREATH_DURATIONS),
+                description: "How long the breath lasts.",
+            },
+        ],
+        has_closing_tag: false,
+        dialect: Dialect::Amazon,
+        description: "Adds a single breathing sound.",
+    },
+    TagSpec {
+        name: "amazon:domain",
+        params: &[ParamSpec {
+            name: "name",
+            required: true,
+            values: Some(&["news"]),
+            description: "The speaking style to use.",
+        }],
+        has_closing_tag: true,
+        dialect: Dialect::Amazon,
+        description: "Speaks the content in a domain specific style.",
+    },
+    TagSpec {
+        name: "amazon:emotion",
+        params: &[
+            ParamSpec {
+                name: "name",
+                required: true,
+                values: Some(&["excited", "disappointed"]),
+                description: "The emotion to speak with.",
+            },
+            ParamSpec {
+                name: "intensity",
+                required: true,
+                values: Some(&["low", "medium", "high"]),
+                description: "How strongly the emotion is expressed.",
+            },
+        ],
+        has_closing_tag: true,
+        dialect: Dialect::Amazon,
+        description: "Speaks the content with an emotion. Only supported by Alexa's neural voices.",
+    },
+    TagSpec {
+        name: "silence",
+        params: &[
+            ParamSpec {
+                name: "type",
+                required: true,
+                values: Some(&[
+                    "Leading",
+                    "Leading-exact",
+                    "Tailing",
+                    "Tailing-exact",
+                    "Sentenceboundary",
+                    "Sentenceboundary-exact",
+                    "Comma-exact",
+                    "Semicolon-exact",
+                    "Enumerationcomma-exact",
+                ]),
+                description: "Where the silence is inserted.",
+            },
+            ParamSpec {
+                name: "value",
+                required: true,
+                values: None,
+                description: "How long the silence is, e.g. `200ms`.",
+            },
+        ],
+        has_closing_tag: false,
+        dialect: Dialect::Microsoft,
+        description: "Inserts silence at every place of a type. Only supported by Azure.",
+    },
+    TagSpec {
+        name: "bookmark",
+        params: &[ParamSpec {
+            name: "mark",
+            required: true,
+            values: None,
+            description: "The name of the bookmark, reported back in Azure's bookmark events.",
+        }],
+        has_closing_tag: false,
+        dialect: Dialect::Microsoft,
+        description: "Azure's equivalent of `mark`. Only supported by Azure.",
+    },
+    TagSpec {
+        name: "par",
+        params: &[],
+        has_closing_tag: true,
+        dialect: Dialect::Google,
+        description: "Plays the media elements inside of it at the same time. Only supported \
+                      by Google.",
+    },
+    TagSpec {
+        name: "seq",
+        params: &[],
+        has_closing_tag: true,
+        dialect: Dialect::Google,
+        description: "Plays the media elements inside of it one after another. Only \
+                      supported by Google.",
+    },
+    TagSpec {
+        name: "media",
+        params: &[
+            ParamSpec {
+                name: "id",
+                required: false,
+                values: None,
+                description: "The id other media elements can refer to, e.g. in `begin`.",
+            },
+            ParamSpec {
+                name: "begin",
+                required: false,
+                values: None,
+                description: "When the media starts, e.g. `0.5s`, or `intro.end`.",
+            },
+            ParamSpec {
+                name: "end",
+                required: false,
+                values: None,
+                description: "When the media ends.",
+            },
+            ParamSpec {
+                name: "repeatCount",
+                required: false,
+                values: None,
+                description: "How often the media is repeated.",
+            },
+            ParamSpec {
+                name: "repeatDur",
+                required: false,
+                values: None,
+                description: "How long the media is repeated for.",
+            },
+            ParamSpec {
+                name: "soundLevel",
+                required: false,
+                values: None,
+                description: "The volume of the media, e.g. `-6dB`.",
+            },
+            ParamSpec {
+                name: "fadeInDur",
+                required: false,
+                values: None,
+                description: "How long the media fades in.",
+            },
+            ParamSpec {
+                name: "fadeOutDur",
+                required: false,
+                values: None,
+                description: "How long the media fades out.",
+            },
+        ],
+        has_closing_tag: true,
+        dialect: Dialect::Google,
+        description: "A single element on the timeline of a `par`, or `seq`. Only supported \
+                      by Google.",
+    },
+    TagSpec {
+        name: "express-as",
+        params: &[ParamSpec {
+            name: "type",
+            required: true,
+            values: Some(&["GoodNews", "Apology", "Uncertainty"]),
+            description: "The expressive style to speak in.",
+        }],
+        has_closing_tag: true,
+        dialect: Dialect::Ibm,
+        description: "Speaks the content in an expressive style. Only supported by Watson.",
+    },
+];
+
+/// Returns the description of every tag the markup supports.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::registry::supported_tags;
+/// let names: Vec<&str> = supported_tags().iter().map(|tag| tag.name).collect();
+/// assert!(names.contains(&"prosody"));
+/// ```
+pub fn supported_tags() -> &'static [TagSpec] {
+    SUPPORTED_TAGS
+}
+
+/// Looks up the description of a single tag by its key. Tag keys are case insensitive just
+/// like in the markup.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::registry::find_tag;
+/// let tag = find_tag("Break").unwrap();
+/// assert!(!tag.has_closing_tag);
+/// assert!(find_tag("brake").is_none());
+/// ```
+pub fn find_tag(name: &str) -> Option<&'static TagSpec> {
+    let name = name.to_lowercase();
+    SUPPORTED_TAGS.iter().find(|tag| tag.name == name)
+}
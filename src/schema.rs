@@ -0,0 +1,584 @@
+//! Validates rendered SSML against the SSML 1.1 schema
+//! ([synthesis.xsd](https://www.w3.org/TR/speech-synthesis11/synthesis.xsd)), catching
+//! documents the writer, or the mapping of a tag got wrong in ways string comparisons miss.
+//!
+//! The content model of the schema is bundled below: which elements exist, which
+//! attributes each of them has, which attributes are required, what they can contain, and
+//! the values of the attributes the schema restricts. Elements, and attributes of other
+//! namespaces (e.g. `amazon:effect`) are skipped the way the schema's wildcards skip them,
+//! their content is checked as if it was in their parent.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::error::{Result, SsmlError};
+
+/// Why part of a document doesn't match the schema.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ViolationKind {
+    /// The root element isn't a <speak>.
+    WrongRoot,
+    /// The element isn't part of SSML.
+    UnknownElement,
+    /// The element is part of SSML, but not allowed where it is.
+    UnexpectedElement,
+    /// The element only contains other elements, or nothing, but has text.
+    UnexpectedText,
+    /// The element doesn't have this attribute.
+    UnknownAttribute,
+    /// An attribute the element requires is missing.
+    MissingAttribute,
+    /// The value of an attribute isn't one the schema allows.
+    InvalidAttributeValue,
+}
+
+/// A part of a document that doesn't match the schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// Why it doesn't match.
+    pub kind: ViolationKind,
+    /// The SSML elements from the root down to the element, e.g. `speak/p/s`.
+    pub path: String,
+    /// The attribute involved, if the violation is about a single attribute.
+    pub attribute: Option<String>,
+    /// A human readable description of the problem.
+    pub message: String,
+    /// The byte offset in the document the element, or text starts at.
+    pub offset: usize,
+}
+
+/// What an element can contain.
+#[derive(Clone, Copy)]
+enum Content {
+    /// Nothing but whitespace.
+    Empty,
+    /// Only text.
+    Text,
+    /// Text, and these elements.
+    Mixed(&'static [&'static str]),
+    /// Anything at all, e.g. the RDF inside of <metadata>.
+    Any,
+}
+
+/// An element of the schema.
+struct ElementSchema {
+    name: &'static str,
+    attributes: &'static [&'static str],
+    required: &'static [&'static str],
+    content: Content,
+}
+
+/// The elements that can be inside of a sentence.
+const INLINE: &[&str] = &[
+    "audio", "break", "emphasis", "lang", "mark", "phoneme", "prosody", "say-as", "sub", "token",
+    "voice", "w",
+];
+/// The elements that can be inside of a paragraph.
+const PARAGRAPH: &[&str] = &[
+    "audio", "break", "emphasis", "lang", "mark", "phoneme", "prosody", "s", "say-as", "sub",
+    "token", "voice", "w",
+];
+/// The elements that can be inside of most containers, e.g. <prosody>, or <voice>.
+const STRUCTURE: &[&str] = &[
+    "audio", "break", "emphasis", "lang", "mark", "p", "phoneme", "prosody", "s", "say-as", "sub",
+    "token", "voice", "w",
+];
+/// The elements that can be inside of the root <speak>.
+const SPEAK: &[&str] = &[
+    "audio", "break", "emphasis", "lang", "lexicon", "lookup", "mark", "meta", "metadata", "p",
+    "phoneme", "prosody", "s", "say-as", "sub", "token", "voice", "w",
+];
+/// The elements that can be inside of an <audio>.
+const AUDIO: &[&str] = &[
+    "audio", "break", "desc", "emphasis", "lang", "mark", "p", "phoneme", "prosody", "s", "say-as",
+    "sub", "token", "voice", "w",
+];
+const AUDIO_ATTRIBUTES: &[&str] = &[
+    "src",
+    "fetchtimeout",
+    "fetchhint",
+    "maxage",
+    "maxstale",
+    "clipBegin",
+    "clipEnd",
+    "repeatCount",
+    "repeatDur",
+    "soundLevel",
+    "speed",
+];
+
+const SCHEMA: &[ElementSchema] = &[
+    ElementSchema {
+        name: "speak",
+        attributes: &["version", "xml:lang", "xml:base", "onlangfailure"],
+        required: &["version", "xml:lang"],
+        content: Content::Mixed(SPEAK),
+    },
+    ElementSchema {
+        name: "audio",
+        attributes: AUDIO_ATTRIBUTES,
+        required: &["src"],
+        content: Content::Mixed(AUDIO),
+    },
+    ElementSchema {
+        name: "break",
+        attributes: &["time", "strength"],
+        required: &[],
+        content: Content::Empty,
+    },
+    ElementSchema {
+        name: "desc",
+        attributes: &["xml:lang"],
+        required: &[],
+        content: Content::Text,
+    },
+    ElementSchema {
+        name: "emphasis",
+        attributes: &["level"],
+        required: &[],
+        content: Content::Mixed(INLINE),
+    },
+    ElementSchema {
+        name: "lang",
+        attributes: &["xml:lang", "onlangfailure"],
+        required: &["xml:lang"],
+        content: Content::Mixed(STRUCTURE),
+    },
+    ElementSchema {
+        name: "lexicon",
+        attributes: &[
+            "uri",
+            "xml:id",
+            "type",
+            "fetchtimeout",
+            "fetchhint",
+            "maxage",
+            "maxstale",
+        ],
+        required: &["uri", "xml:id"],
+        content: Content::Empty,
+    },
+    ElementSchema {
+        name: "lookup",
+        attributes: &["ref"],
+        required: &["ref"],
+        content: Content::Mixed(STRUCTURE),
+    },
+    ElementSchema {
+        name: "mark",
+        attributes: &["name"],
+        required: &["name"],
+        content: Content::Empty,
+    },
+    ElementSchema {
+        name: "meta",
+        attributes: &["name", "http-equiv", "content"],
+        required: &["content"],
+        content: Content::Empty,
+    },
+    ElementSchema {
+        name: "metadata",
+        attributes: &[],
+        required: &[],
+        content: Content::Any,
+    },
+    ElementSchema {
+        name: "p",
+        attributes: &["xml:lang", "onlangfailure"],
+        required: &[],
+        content: Content::Mixed(PARAGRAPH),
+    },
+    ElementSchema {
+        name: "phoneme",
+        attributes: &["ph", "alphabet", "type"],
+        required: &["ph"],
+        content: Content::Text,
+    },
+    ElementSchema {
+        name: "prosody",
+        attributes: &["pitch", "contour", "range", "rate", "duration", "volume"],
+        required: &[],
+        content: Content::Mixed(STRUCTURE),
+    },
+    ElementSchema {
+        name: "s",
+        attributes: &["xml:lang", "onlangfailure"],
+        required: &[],
+        content: Content::Mixed(INLINE),
+    },
+    ElementSchema {
+        name: "say-as",
+        attributes: &["interpret-as", "format", "detail"],
+        required: &["interpret-as"],
+        content: Content::Text,
+    },
+    ElementSchema {
+        name: "sub",
+        attributes: &["alias"],
+        required: &["alias"],
+        content: Content::Text,
+    },
+    ElementSchema {
+        name: "token",
+        attributes: &["xml:lang", "role"],
+        required: &[],
+        content: Content::Text,
+    },
+    ElementSchema {
+        name: "voice",
+        attributes: &[
+            "gender",
+            "age",
+            "variant",
+            "name",
+            "languages",
+            "required",
+            "ordering",
+            "onvoicefailure",
+        ],
+        required: &[],
+        content: Content::Mixed(STRUCTURE),
+    },
+    ElementSchema {
+        name: "w",
+        attributes: &["xml:lang", "role"],
+        required: &[],
+        content: Content::Text,
+    },
+];
+
+fn find_element(name: &str) -> Option<&'static ElementSchema> {
+    SCHEMA.iter().find(|element| element.name == name)
+}
+
+/// If the value of an attribute is one the schema allows. Attributes the schema only
+/// types as strings, or URIs accept anything.
+fn is_valid_value(element: &str, attribute: &str, value: &str) -> bool {
+    match (element, attribute) {
+        (_, "onlangfailure") => matches!(
+            value,
+            "changevoice" | "ignoretext" | "ignorelang" | "processorchoice"
+        ),
+        ("speak", "version") => value == "1.0" || value == "1.1",
+        ("break", "strength") => matches!(
+            value,
+            "none" | "x-weak" | "weak" | "medium" | "strong" | "x-strong"
+        ),
+        ("break", "time") | ("prosody", "duration") => is_time(value),
+        ("emphasis", "level") => matches!(value, "strong" | "moderate" | "none" | "reduced"),
+        ("prosody", "volume") => {
+            matches!(
+                value,
+                "silent" | "x-soft" | "soft" | "medium" | "loud" | "x-loud" | "default"
+            ) || value
+                .strip_suffix("dB")
+                .is_some_and(|number| is_number(number, true))
+        }
+        ("prosody", "rate") => {
+            matches!(
+                value,
+                "x-slow" | "slow" | "medium" | "fast" | "x-fast" | "default"
+            ) || is_number(value.strip_suffix('%').unwrap_or(value), false)
+        }
+        ("prosody", "pitch") | ("prosody", "range") => {
+            matches!(
+                value,
+                "x-low" | "low" | "medium" | "high" | "x-high" | "default"
+            ) || ["Hz", "%", "st"].iter().any(|unit| {
+                value
+                    .strip_suffix(unit)
+                    .is_some_and(|number| is_number(number, true))
+            })
+        }
+        ("voice", "gender") => matches!(value, "male" | "female" | "neutral"),
+        ("voice", "age") => !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()),
+        ("voice", "onvoicefailure") => {
+            matches!(value, "priorityselect" | "keepexisting" | "processorchoice")
+        }
+        _ => true,
+    }
+}
+
+/// If a value is a time designation like `250ms`, or `1.5s`.
+fn is_time(value: &str) -> bool {
+    value
+        .strip_suffix("ms")
+        .or_else(|| value.strip_suffix('s'))
+        .is_some_and(|number| is_number(number, false))
+}
+
+/// If a value is a decimal number, optionally with a sign in front of it.
+fn is_number(value: &str, signed: bool) -> bool {
+    let value = match value.strip_prefix(['+', '-']) {
+        Some(unsigned) if signed => unsigned,
+        Some(_) => return false,
+        None => value,
+    };
+    let mut parts = value.splitn(2, '.');
+    let whole = parts.next().unwrap_or("");
+    let fraction = parts.next();
+    let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    all_digits(whole)
+        && fraction.is_none_or(all_digits)
+        && !(whole.is_empty() && fraction.is_none_or(str::is_empty))
+}
+
+/// An SSML element that is currently open.
+struct OpenElement {
+    name: String,
+    content: Content,
+}
+
+/// Collects the violations of a document while it is being read.
+struct Validator {
+    open: Vec<OpenElement>,
+    /// How many elements of other namespaces are open inside of the innermost SSML element,
+    /// to know which end tags close them.
+    foreign: Vec<usize>,
+    /// How many <metadata>, or other elements allowing any content are open.
+    opaque: usize,
+    seen_root: bool,
+    violations: Vec<SchemaViolation>,
+}
+
+impl Validator {
+    fn path(&self, name: Option<&str>) -> String {
+        let mut names = self
+            .open
+            .iter()
+            .map(|element| element.name.as_str())
+            .collect::<Vec<_>>();
+        names.extend(name);
+        names.join("/")
+    }
+
+    fn report(
+        &mut self,
+        kind: ViolationKind,
+        path: String,
+        attribute: Option<&str>,
+        message: String,
+        offset: usize,
+    ) {
+        self.violations.push(SchemaViolation {
+            kind,
+            path,
+            attribute: attribute.map(|attribute| attribute.to_owned()),
+            message,
+            offset,
+        });
+    }
+
+    fn start(&mut self, start: &BytesStart, offset: usize) -> Result<()> {
+        let name = String::from_utf8_lossy(start.name()).into_owned();
+        if self.opaque > 0 {
+            self.opaque += 1;
+            return Ok(());
+        }
+        if name.contains(':') {
+            if let Some(count) = self.foreign.last_mut() {
+                *count += 1;
+            }
+            return Ok(());
+        }
+
+        let path = self.path(Some(&name));
+        let schema = find_element(&name);
+        match self.open.last() {
+            None if self.seen_root => {
+                self.report(
+                    ViolationKind::WrongRoot,
+                    path.clone(),
+                    None,
+                    format!("<{}> is a second root element.", name),
+                    offset,
+                );
+            }
+            None => {
+                self.seen_root = true;
+                if name != "speak" {
+                    self.report(
+                        ViolationKind::WrongRoot,
+                        path.clone(),
+                        None,
+                        format!("The root element is <{}> instead of <speak>.", name),
+                        offset,
+                    );
+                }
+            }
+            Some(parent) => {
+                let allowed = match parent.content {
+                    Content::Mixed(children) => children.contains(&name.as_str()),
+                    Content::Any => true,
+                    Content::Empty | Content::Text => false,
+                };
+                if schema.is_some() && !allowed {
+                    let message = format!("<{}> isn't allowed inside of <{}>.", name, parent.name);
+                    self.report(
+                        ViolationKind::UnexpectedElement,
+                        path.clone(),
+                        None,
+                        message,
+                        offset,
+                    );
+                }
+            }
+        }
+
+        let schema = match schema {
+            Some(schema) => schema,
+            None => {
+                self.report(
+                    ViolationKind::UnknownElement,
+                    path,
+                    None,
+                    format!("<{}> isn't an element of SSML.", name),
+                    offset,
+                );
+                // Its content can't be checked without knowing what it may contain.
+                self.opaque += 1;
+                return Ok(());
+            }
+        };
+
+        let mut present = Vec::new();
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(SsmlError::InvalidSsml)?;
+            let key = String::from_utf8_lossy(attribute.key).into_owned();
+            let value = attribute
+                .unescaped_value()
+                .map_err(SsmlError::InvalidSsml)?;
+            let value = String::from_utf8_lossy(&value).into_owned();
+            if key.contains(':') && !key.starts_with("xml:") {
+                continue;
+            }
+            if key == "xmlns" {
+                continue;
+            }
+            if !schema.attributes.contains(&key.as_str()) {
+                self.report(
+                    ViolationKind::UnknownAttribute,
+                    path.clone(),
+                    Some(&key),
+                    format!("<{}> doesn't have a `{}` attribute.", name, key),
+                    offset,
+                );
+            } else if !is_valid_value(&name, &key, &value) {
+                self.report(
+                    ViolationKind::InvalidAttributeValue,
+                    path.clone(),
+                    Some(&key),
+                    format!(
+                        "`{}` is not a valid value for the `{}` attribute of <{}>.",
+                        value, key, name
+                    ),
+                    offset,
+                );
+            }
+            present.push(key);
+        }
+        for required in schema.required {
+            if !present.iter().any(|key| key == required) {
+                self.report(
+                    ViolationKind::MissingAttribute,
+                    path.clone(),
+                    Some(required),
+                    format!("<{}> requires a `{}` attribute.", name, required),
+                    offset,
+                );
+            }
+        }
+
+        if let Content::Any = schema.content {
+            self.opaque += 1;
+        } else {
+            self.open.push(OpenElement {
+                name,
+                content: schema.content,
+            });
+            self.foreign.push(0);
+        }
+        Ok(())
+    }
+
+    fn end(&mut self) {
+        if self.opaque > 0 {
+            self.opaque -= 1;
+            return;
+        }
+        match self.foreign.last_mut() {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => {
+                self.open.pop();
+                self.foreign.pop();
+            }
+        }
+    }
+
+    fn text(&mut self, text: &[u8], offset: usize) {
+        if self.opaque > 0 || text.iter().all(u8::is_ascii_whitespace) {
+            return;
+        }
+        if let Some(element) = self.open.last() {
+            if let Content::Empty = element.content {
+                let message = format!("<{}> can't contain any text.", element.name);
+                let path = self.path(None);
+                self.report(ViolationKind::UnexpectedText, path, None, message, offset);
+            }
+        }
+    }
+}
+
+/// Validates a rendered SSML document against the SSML 1.1 schema, returning every part of
+/// it that doesn't match. Returns an error if the document isn't well formed XML.
+///
+/// The schema is the W3C standard, so documents written for targets that don't follow it
+/// exactly (e.g. the bare <speak> Alexa wants, which has no `version`) have violations.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::schema::{validate_ssml, ViolationKind};
+/// let violations = validate_ssml(
+///   r#"<speak version="1.1" xml:lang="en-US"><s><p>Hi</p><break strength="loud"/></s></speak>"#,
+/// ).unwrap();
+/// let kinds: Vec<ViolationKind> = violations.iter().map(|violation| violation.kind).collect();
+/// assert_eq!(
+///   kinds,
+///   vec![ViolationKind::UnexpectedElement, ViolationKind::InvalidAttributeValue],
+/// );
+/// assert_eq!(violations[0].path, "speak/s/p");
+/// ```
+pub fn validate_ssml(ssml: &str) -> Result<Vec<SchemaViolation>> {
+    let mut reader = Reader::from_str(ssml);
+    let mut validator = Validator {
+        open: Vec::new(),
+        foreign: Vec::new(),
+        opaque: 0,
+        seen_root: false,
+        violations: Vec::new(),
+    };
+    let mut buf = Vec::new();
+
+    loop {
+        let offset = reader.buffer_position();
+        match reader
+            .read_event(&mut buf)
+            .map_err(SsmlError::InvalidSsml)?
+        {
+            Event::Start(ref start) => validator.start(start, offset)?,
+            Event::Empty(ref start) => {
+                validator.start(start, offset)?;
+                validator.end();
+            }
+            Event::End(_) => validator.end(),
+            Event::Text(ref text) => validator.text(text.escaped(), offset),
+            Event::CData(ref text) => validator.text(text.escaped(), offset),
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(validator.violations)
+}
@@ -0,0 +1,162 @@
+//! A small HTTP service around the parser, so services that aren't written in Rust can
+//! convert markup without linking against it. It powers the `text-to-ssml-server` binary.
+//!
+//! The only route is `POST /convert`, which takes a JSON object with the `markup`, and
+//! optionally some `options`:
+//!
+//! ```json
+//! { "markup": "${s}Hello${/s}", "options": { "target": "alexa", "lang": "en-GB" } }
+//! ```
+//!
+//! The supported options are `target`, `lang`, `strict`, `pretty`, and `ascii_safe`, named
+//! like the fields of `ParseOptions`. It answers with the `ssml`, and the `diagnostics` of
+//! `lint` for the markup, or with an `error`, and the `diagnostics` if it can't be rendered.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::error::{Result, SsmlError};
+use crate::parser::{
+    lint_with_options, Diagnostic, DiagnosticKind, OutputTarget, ParseOptions, Severity,
+};
+
+use std::io::Read;
+
+/// The largest request body that is accepted, in bytes.
+pub const MAX_BODY_LEN: usize = 1024 * 1024;
+
+/// The body of a `POST /convert`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConvertRequest {
+    markup: String,
+    #[serde(default)]
+    options: ConvertOptions,
+}
+
+/// The subset of `ParseOptions` that can be set over HTTP.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConvertOptions {
+    target: Option<String>,
+    lang: Option<String>,
+    strict: bool,
+    pretty: bool,
+    ascii_safe: bool,
+}
+
+/// Listens on an address like `127.0.0.1:8080`, and answers requests until the process
+/// is stopped.
+pub fn serve(address: &str) -> Result<()> {
+    let server = Server::http(address).map_err(|error| SsmlError::Server(error.to_string()))?;
+    serve_with(&server);
+    Ok(())
+}
+
+/// Answers the requests of a server that is already listening until it is unblocked, one
+/// request at a time.
+pub fn serve_with(server: &Server) {
+    for request in server.incoming_requests() {
+        handle(request);
+    }
+}
+
+fn handle(mut request: Request) {
+    let (status, body) = match (request.method(), request.url()) {
+        (Method::Post, "/convert") => {
+            let mut body = String::new();
+            let read = request
+                .as_reader()
+                .take(MAX_BODY_LEN as u64 + 1)
+                .read_to_string(&mut body);
+            match read {
+                Ok(len) if len > MAX_BODY_LEN => (
+                    413,
+                    error(&format!("The body is longer than {} bytes.", MAX_BODY_LEN)),
+                ),
+                Ok(_) => convert(&body),
+                Err(read_error) => (
+                    400,
+                    error(&format!("Failed to read the body: {}", read_error)),
+                ),
+            }
+        }
+        (_, "/convert") => (405, error("Only `POST` is allowed.")),
+        _ => (404, error("The only route is `POST /convert`.")),
+    };
+
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("the header is valid");
+    // A client that hung up before reading the response shouldn't stop the server.
+    let _ = request.respond(
+        Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(content_type),
+    );
+}
+
+/// Answers the body of a `POST /convert`, with the HTTP status, and the JSON to respond
+/// with. `400` if the body isn't a valid request, and `422` if the markup can't be rendered.
+pub fn convert(body: &str) -> (u16, Value) {
+    let request: ConvertRequest = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(parse_error) => {
+            return (
+                400,
+                error(&format!("The body isn't a valid request: {}", parse_error)),
+            )
+        }
+    };
+
+    let mut options = ParseOptions {
+        strict: request.options.strict,
+        pretty: request.options.pretty,
+        ascii_safe: request.options.ascii_safe,
+        ..Default::default()
+    };
+    options.speak.lang = request.options.lang;
+    if let Some(target) = request.options.target {
+        match target.parse::<OutputTarget>() {
+            Ok(target) => options.target = target,
+            Err(target_error) => return (400, error(&target_error.to_string())),
+        }
+    }
+
+    let diagnostics: Vec<Value> = lint_with_options(&request.markup, &options)
+        .iter()
+        .map(diagnostic_json)
+        .collect();
+    match crate::parse_str_with_options(&request.markup, &options) {
+        Ok(ssml) => (200, json!({ "ssml": ssml, "diagnostics": diagnostics })),
+        Err(parse_error) => (
+            422,
+            json!({ "error": parse_error.to_string(), "diagnostics": diagnostics }),
+        ),
+    }
+}
+
+fn error(message: &str) -> Value {
+    json!({ "error": message })
+}
+
+fn diagnostic_json(diagnostic: &Diagnostic) -> Value {
+    let severity = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Hint => "hint",
+    };
+    let kind = match diagnostic.kind {
+        DiagnosticKind::Render(kind) => format!("{:?}", kind),
+        kind => format!("{:?}", kind),
+    };
+    json!({
+        "severity": severity,
+        "kind": kind,
+        "tag": diagnostic.tag,
+        "attribute": diagnostic.attribute,
+        "message": diagnostic.message,
+        "line": diagnostic.span.map(|span| span.line),
+        "column": diagnostic.span.map(|span| span.column),
+    })
+}
@@ -2,13 +2,61 @@
 //! This is meant to be internal, so you should probably never interact with this directly.
 
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
 use std::str::FromStr;
+use std::time::Duration;
+
+/// A value that couldn't be read as one of the types of this module, along with every value
+/// that would have been accepted instead.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("`{value}` is not a valid {kind}, expected one of: {}", .accepted.join(", "))]
+pub struct InvalidValue {
+    /// The value as it was given.
+    pub value: String,
+    /// What the value was read as, e.g. `break strength`.
+    pub kind: &'static str,
+    /// Every value that is accepted, values containing a number are written with a
+    /// placeholder, e.g. `+<decibels>dB`.
+    pub accepted: &'static [&'static str],
+}
+
+impl InvalidValue {
+    fn new(value: &str, kind: &'static str, accepted: &'static [&'static str]) -> InvalidValue {
+        InvalidValue {
+            value: value.to_owned(),
+            kind,
+            accepted,
+        }
+    }
+}
+
+/// Adds `variants`, and `iter` to an enum, listing every one of its named values, so they
+/// don't have to be hard-coded, e.g. in the options of a dropdown.
+macro_rules! impl_variants {
+    ($name:ident: $($variant:ident),* $(,)?) => {
+        impl $name {
+            /// Every named value, in the order they're declared in. Values that carry a
+            /// number, or any other data (e.g. a volume in decibels) aren't included.
+            pub fn variants() -> &'static [$name] {
+                const VARIANTS: &[$name] = &[$($name::$variant),*];
+                VARIANTS
+            }
+
+            /// Iterates over every named value, see `variants`.
+            pub fn iter() -> std::slice::Iter<'static, $name> {
+                $name::variants().iter()
+            }
+        }
+    };
+}
 
 /// Denotes the potential values for the Strength of a Break tag.
 /// These values are straight out of the SSML 1.1 W3C Standard which can be found
 /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_break),
 /// and what they actually do in polly is documented:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#break-tag).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BreakStrength {
     NoStrength,
     XWeak,
@@ -18,49 +66,63 @@ pub enum BreakStrength {
     XStrong,
 }
 
+impl_variants!(BreakStrength: NoStrength, XWeak, Weak, Medium, Strong, XStrong);
+
 impl fmt::Display for BreakStrength {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &BreakStrength::NoStrength => write!(f, "none"),
-            &BreakStrength::XWeak => write!(f, "x-weak"),
-            &BreakStrength::Weak => write!(f, "weak"),
-            &BreakStrength::Medium => write!(f, "medium"),
-            &BreakStrength::Strong => write!(f, "strong"),
-            &BreakStrength::XStrong => write!(f, "x-strong"),
+            BreakStrength::NoStrength => write!(f, "none"),
+            BreakStrength::XWeak => write!(f, "x-weak"),
+            BreakStrength::Weak => write!(f, "weak"),
+            BreakStrength::Medium => write!(f, "medium"),
+            BreakStrength::Strong => write!(f, "strong"),
+            BreakStrength::XStrong => write!(f, "x-strong"),
         }
     }
 }
 
 impl FromStr for BreakStrength {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<BreakStrength, ()> {
+    fn from_str(s: &str) -> Result<BreakStrength, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "break strength",
+                &["none", "x-weak", "weak", "medium", "strong", "x-strong"],
+            )
+        };
         match &*s.to_lowercase() {
-            "break" => Ok(BreakStrength::NoStrength),
+            "none" | "break" => Ok(BreakStrength::NoStrength),
             "x-weak" => Ok(BreakStrength::XWeak),
             "weak" => Ok(BreakStrength::Weak),
             "medium" => Ok(BreakStrength::Medium),
             "strong" => Ok(BreakStrength::Strong),
             "x-strong" => Ok(BreakStrength::XStrong),
-            _ => Err(()),
+            _ => Err(invalid()),
         }
     }
 }
 
-/// Denotes the potential amount of time to Break inside the Break Tag.
+/// Denotes the potential amount of time to Break inside the Break Tag. This is also the
+/// `duration` of a Prosody Tag.
 /// These values are straight out of the SSML 1.1 W3C Standard which can be found
 /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_break)
 /// and what they actually do in polly is documented:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#break-tag).
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct BreakTime {
-    /// The actual value of time to break for.
-    pub time: u32,
+    /// The actual value of time to break for, which can be fractional (e.g. `1.5s`).
+    pub time: f32,
     /// If the break time is in seconds. If this is set to false it is believed to be in
     /// milliseconds.
     pub is_seconds: bool,
 }
 
 impl BreakTime {
+    /// The longest break AWS Polly (and Alexa) accept, in seconds.
+    pub const MAX_POLLY_SECONDS: f32 = 10.0;
+
     /// Constructs a new Break Time.
     ///
     /// # Examples
@@ -72,8 +134,49 @@ impl BreakTime {
     /// ```
     pub fn new(value: u32, is_seconds: bool) -> BreakTime {
         BreakTime {
-            time: value,
-            is_seconds: is_seconds,
+            time: value as f32,
+            is_seconds,
+        }
+    }
+
+    /// Constructs a new Break Time in seconds, which can be fractional.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::ssml_constants::BreakTime;
+    /// assert_eq!(BreakTime::from_secs_f32(1.5).to_string(), "1.5s");
+    /// ```
+    pub fn from_secs_f32(seconds: f32) -> BreakTime {
+        BreakTime {
+            time: seconds,
+            is_seconds: true,
+        }
+    }
+
+    /// The break time in seconds.
+    pub fn as_secs_f32(&self) -> f32 {
+        if self.is_seconds {
+            self.time
+        } else {
+            self.time / 1000.0
+        }
+    }
+}
+
+impl From<Duration> for BreakTime {
+    /// Whole seconds are written in seconds, anything else in milliseconds.
+    fn from(duration: Duration) -> BreakTime {
+        if duration.subsec_nanos() == 0 {
+            BreakTime {
+                time: duration.as_secs() as f32,
+                is_seconds: true,
+            }
+        } else {
+            BreakTime {
+                time: (duration.as_secs_f64() * 1000.0) as f32,
+                is_seconds: false,
+            }
         }
     }
 }
@@ -90,25 +193,62 @@ impl fmt::Display for BreakTime {
 }
 
 impl FromStr for BreakTime {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<BreakTime, ()> {
-        if s.ends_with("ms") && s != "ms" {
-            let mut as_split = s.split("ms");
-            let potential_number = as_split.next().unwrap();
-            let as_num = potential_number.parse::<u32>();
-            if as_num.is_ok() {
-                return Ok(BreakTime::new(as_num.unwrap(), false));
-            }
-        } else if s.ends_with("s") && s != "s" {
-            let mut as_split = s.split("s");
-            let potential_number = as_split.next().unwrap();
-            let as_num = potential_number.parse::<u32>();
-            if as_num.is_ok() {
-                return Ok(BreakTime::new(as_num.unwrap(), true));
-            }
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<BreakTime, InvalidValue> {
+        let invalid = || InvalidValue::new(s, "break time", &["<seconds>s", "<milliseconds>ms"]);
+        let (potential_number, is_seconds) = match s.strip_suffix("ms") {
+            Some(milliseconds) => (milliseconds, false),
+            None => (s.strip_suffix('s').ok_or_else(invalid)?, true),
+        };
+        // Only `digits[.digits]` is a valid time designation, not everything `f32` parses
+        // (e.g. `+5`, `1e30`, or `inf`).
+        let is_digits =
+            |digits: &str| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+        let valid = match potential_number.split_once('.') {
+            Some((whole, fraction)) => is_digits(whole) && is_digits(fraction),
+            None => is_digits(potential_number),
+        };
+        match potential_number.parse::<f32>() {
+            Ok(time) if valid && time.is_finite() => Ok(BreakTime { time, is_seconds }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Denotes how strongly the content of an Emphasis tag is stressed.
+/// These values are straight out of the SSML 1.1 W3C Standard which can be found
+/// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_emphasis).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EmphasisLevel {
+    Strong,
+    Moderate,
+    Reduced,
+}
+
+impl_variants!(EmphasisLevel: Strong, Moderate, Reduced);
+
+impl fmt::Display for EmphasisLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmphasisLevel::Strong => write!(f, "strong"),
+            EmphasisLevel::Moderate => write!(f, "moderate"),
+            EmphasisLevel::Reduced => write!(f, "reduced"),
+        }
+    }
+}
+
+impl FromStr for EmphasisLevel {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<EmphasisLevel, InvalidValue> {
+        let invalid = || InvalidValue::new(s, "emphasis level", &["strong", "moderate", "reduced"]);
+        match &*s.to_lowercase() {
+            "strong" => Ok(EmphasisLevel::Strong),
+            "moderate" => Ok(EmphasisLevel::Moderate),
+            "reduced" => Ok(EmphasisLevel::Reduced),
+            _ => Err(invalid()),
         }
-        return Err(());
     }
 }
 
@@ -116,28 +256,66 @@ impl FromStr for BreakTime {
 /// Documentation on supported alphabets can be found under description of the phoneme
 /// tags on AWS Polly. Those are located:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#phoneme-tag)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PhonemeAlphabet {
     Ipa,
     XSampa,
+    /// Japanese pronunciations in katakana, with pitch accents, for ja-JP voices.
+    Kana,
+    /// Japanese readings in hiragana, for ja-JP voices.
+    Yomigana,
+    /// Mandarin pronunciations in pinyin, for zh-CN voices.
+    Pinyin,
 }
 
+impl_variants!(PhonemeAlphabet: Ipa, XSampa, Kana, Yomigana, Pinyin);
+
 impl fmt::Display for PhonemeAlphabet {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &PhonemeAlphabet::Ipa => write!(f, "ipa"),
-            &PhonemeAlphabet::XSampa => write!(f, "x-sampa"),
+            PhonemeAlphabet::Ipa => write!(f, "ipa"),
+            PhonemeAlphabet::XSampa => write!(f, "x-sampa"),
+            PhonemeAlphabet::Kana => write!(f, "x-amazon-pron-kana"),
+            PhonemeAlphabet::Yomigana => write!(f, "x-amazon-yomigana"),
+            PhonemeAlphabet::Pinyin => write!(f, "x-amazon-pinyin"),
         }
     }
 }
 
+impl PhonemeAlphabet {
+    /// If the alphabet is an AWS Polly extension, instead of a standard alphabet.
+    pub fn is_amazon(&self) -> bool {
+        matches!(
+            self,
+            PhonemeAlphabet::Kana | PhonemeAlphabet::Yomigana | PhonemeAlphabet::Pinyin
+        )
+    }
+}
+
 impl FromStr for PhonemeAlphabet {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<PhonemeAlphabet, ()> {
+    fn from_str(s: &str) -> Result<PhonemeAlphabet, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "phoneme alphabet",
+                &[
+                    "ipa",
+                    "x-sampa",
+                    "x-amazon-pron-kana",
+                    "x-amazon-yomigana",
+                    "x-amazon-pinyin",
+                ],
+            )
+        };
         match &*s.to_lowercase() {
             "ipa" => Ok(PhonemeAlphabet::Ipa),
             "x-sampa" => Ok(PhonemeAlphabet::XSampa),
-            _ => Err(()),
+            "x-amazon-pron-kana" => Ok(PhonemeAlphabet::Kana),
+            "x-amazon-yomigana" => Ok(PhonemeAlphabet::Yomigana),
+            "x-amazon-pinyin" => Ok(PhonemeAlphabet::Pinyin),
+            _ => Err(invalid()),
         }
     }
 }
@@ -145,6 +323,7 @@ impl FromStr for PhonemeAlphabet {
 /// Represents all possible ProsodyRate rates that AWS Polly Supports.
 /// The full documentation on all possible rates are found in AWS Documentation:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#prosody-tag)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ProsodyRate {
     XSlow,
     Slow,
@@ -153,61 +332,421 @@ pub enum ProsodyRate {
     XFast,
 }
 
+impl_variants!(ProsodyRate: XSlow, Slow, Medium, Fast, XFast);
+
 impl fmt::Display for ProsodyRate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &ProsodyRate::XSlow => write!(f, "x-slow"),
-            &ProsodyRate::Slow => write!(f, "slow"),
-            &ProsodyRate::Medium => write!(f, "medium"),
-            &ProsodyRate::Fast => write!(f, "fast"),
-            &ProsodyRate::XFast => write!(f, "x-fast"),
+            ProsodyRate::XSlow => write!(f, "x-slow"),
+            ProsodyRate::Slow => write!(f, "slow"),
+            ProsodyRate::Medium => write!(f, "medium"),
+            ProsodyRate::Fast => write!(f, "fast"),
+            ProsodyRate::XFast => write!(f, "x-fast"),
         }
     }
 }
 
 impl FromStr for ProsodyRate {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<ProsodyRate, ()> {
+    fn from_str(s: &str) -> Result<ProsodyRate, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "prosody rate",
+                &["x-slow", "slow", "medium", "fast", "x-fast"],
+            )
+        };
         match &*s.to_lowercase() {
             "x-slow" => Ok(ProsodyRate::XSlow),
             "slow" => Ok(ProsodyRate::Slow),
             "medium" => Ok(ProsodyRate::Medium),
             "fast" => Ok(ProsodyRate::Fast),
             "x-fast" => Ok(ProsodyRate::XFast),
-            _ => Err(()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Represents all possible Prosody volumes that AWS Polly Supports. Besides the named
+/// volumes a volume can be a change in decibels, relative to the current volume, which
+/// always has a sign (`+6dB`, or `-3dB`).
+/// The full documentation on all possible volumes are found in AWS Documentation:
+/// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#prosody-tag)
+#[derive(Clone, Copy, Debug)]
+pub enum ProsodyVolume {
+    Def,
+    Silent,
+    XSoft,
+    Soft,
+    Medium,
+    Loud,
+    XLoud,
+    Db(f32),
+}
+
+impl_variants!(ProsodyVolume: Def, Silent, XSoft, Soft, Medium, Loud, XLoud);
+
+// Values are compared by the bits of their numbers, so every value equals itself (even
+// `NaN`), and equal values always have the same hash.
+impl PartialEq for ProsodyVolume {
+    fn eq(&self, other: &ProsodyVolume) -> bool {
+        match (self, other) {
+            (ProsodyVolume::Db(a), ProsodyVolume::Db(b)) => a.to_bits() == b.to_bits(),
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for ProsodyVolume {}
+
+impl Hash for ProsodyVolume {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        if let ProsodyVolume::Db(value) = self {
+            value.to_bits().hash(state);
+        }
+    }
+}
+
+impl fmt::Display for ProsodyVolume {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProsodyVolume::Def => write!(f, "default"),
+            ProsodyVolume::Silent => write!(f, "silent"),
+            ProsodyVolume::XSoft => write!(f, "x-soft"),
+            ProsodyVolume::Soft => write!(f, "soft"),
+            ProsodyVolume::Medium => write!(f, "medium"),
+            ProsodyVolume::Loud => write!(f, "loud"),
+            ProsodyVolume::XLoud => write!(f, "x-loud"),
+            ProsodyVolume::Db(db) => write!(f, "{:+}dB", db),
+        }
+    }
+}
+
+impl FromStr for ProsodyVolume {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<ProsodyVolume, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "prosody volume",
+                &[
+                    "default",
+                    "silent",
+                    "x-soft",
+                    "soft",
+                    "medium",
+                    "loud",
+                    "x-loud",
+                    "+<decibels>dB",
+                    "-<decibels>dB",
+                ],
+            )
+        };
+        match &*s.to_lowercase() {
+            "default" => Ok(ProsodyVolume::Def),
+            "silent" => Ok(ProsodyVolume::Silent),
+            "x-soft" => Ok(ProsodyVolume::XSoft),
+            "soft" => Ok(ProsodyVolume::Soft),
+            "medium" => Ok(ProsodyVolume::Medium),
+            "loud" => Ok(ProsodyVolume::Loud),
+            "x-loud" => Ok(ProsodyVolume::XLoud),
+            other => {
+                let db = other.strip_suffix("db").ok_or_else(invalid)?;
+                if !db.starts_with('+') && !db.starts_with('-') {
+                    return Err(invalid());
+                }
+                match db.parse::<f32>() {
+                    Ok(db) if db.is_finite() => Ok(ProsodyVolume::Db(db)),
+                    _ => Err(invalid()),
+                }
+            }
+        }
+    }
+}
+
+/// Represents all possible Prosody pitches. Besides the named pitches a pitch can be a
+/// change in percent, hertz, or semitones relative to the current pitch, which always has
+/// a sign (`+10%`, `-20Hz`, or `+2st`), or an absolute pitch in hertz (`200Hz`), as SSML 1.1
+/// defines them. AWS Polly, and Alexa only accept the named pitches, and changes in percent
+/// between `-33.3%`, and `+50%`, see `ProsodyPitch::is_polly`.
+/// The full documentation on all possible pitches are found in AWS Documentation:
+/// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#prosody-tag)
+#[derive(Clone, Copy, Debug)]
+pub enum ProsodyPitch {
+    Def,
+    XLow,
+    Low,
+    Medium,
+    High,
+    XHigh,
+    Percent(f32),
+    Hertz(f32),
+    HertzChange(f32),
+    Semitones(f32),
+}
+
+impl_variants!(ProsodyPitch: Def, XLow, Low, Medium, High, XHigh);
+
+impl PartialEq for ProsodyPitch {
+    fn eq(&self, other: &ProsodyPitch) -> bool {
+        mem::discriminant(self) == mem::discriminant(other)
+            && self.number().map(f32::to_bits) == other.number().map(f32::to_bits)
+    }
+}
+
+impl Eq for ProsodyPitch {}
+
+impl Hash for ProsodyPitch {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        if let Some(value) = self.number() {
+            value.to_bits().hash(state);
+        }
+    }
+}
+
+impl ProsodyPitch {
+    /// The lowest change in percent AWS Polly accepts.
+    pub const MIN_PERCENT: f32 = -33.3;
+    /// The highest change in percent AWS Polly accepts.
+    pub const MAX_PERCENT: f32 = 50.0;
+
+    /// If AWS Polly, and Alexa accept the pitch: a named pitch, or a change in percent
+    /// between `MIN_PERCENT`, and `MAX_PERCENT`.
+    pub fn is_polly(&self) -> bool {
+        match self {
+            ProsodyPitch::Percent(percent) => {
+                (ProsodyPitch::MIN_PERCENT..=ProsodyPitch::MAX_PERCENT).contains(percent)
+            }
+            ProsodyPitch::Hertz(_) | ProsodyPitch::HertzChange(_) | ProsodyPitch::Semitones(_) => {
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// The error for a pitch AWS Polly, and Alexa don't accept.
+    pub(crate) fn invalid_for_polly(value: &str) -> InvalidValue {
+        InvalidValue::new(
+            value,
+            "prosody pitch",
+            &[
+                "default",
+                "x-low",
+                "low",
+                "medium",
+                "high",
+                "x-high",
+                "+<percent>% (up to +50%)",
+                "-<percent>% (down to -33.3%)",
+            ],
+        )
+    }
+
+    fn number(&self) -> Option<f32> {
+        match self {
+            ProsodyPitch::Percent(value)
+            | ProsodyPitch::Hertz(value)
+            | ProsodyPitch::HertzChange(value)
+            | ProsodyPitch::Semitones(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ProsodyPitch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProsodyPitch::Def => write!(f, "default"),
+            ProsodyPitch::XLow => write!(f, "x-low"),
+            ProsodyPitch::Low => write!(f, "low"),
+            ProsodyPitch::Medium => write!(f, "medium"),
+            ProsodyPitch::High => write!(f, "high"),
+            ProsodyPitch::XHigh => write!(f, "x-high"),
+            ProsodyPitch::Percent(percent) => write!(f, "{:+}%", percent),
+            ProsodyPitch::Hertz(hertz) => write!(f, "{}Hz", hertz),
+            ProsodyPitch::HertzChange(hertz) => write!(f, "{:+}Hz", hertz),
+            ProsodyPitch::Semitones(semitones) => write!(f, "{:+}st", semitones),
+        }
+    }
+}
+
+impl FromStr for ProsodyPitch {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<ProsodyPitch, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "prosody pitch",
+                &[
+                    "default",
+                    "x-low",
+                    "low",
+                    "medium",
+                    "high",
+                    "x-high",
+                    "+<percent>%",
+                    "-<percent>%",
+                    "<hertz>Hz",
+                    "+<hertz>Hz",
+                    "-<hertz>Hz",
+                    "+<semitones>st",
+                    "-<semitones>st",
+                ],
+            )
+        };
+        let lowercase = s.to_lowercase();
+        let (number, unit) = match &*lowercase {
+            "default" => return Ok(ProsodyPitch::Def),
+            "x-low" => return Ok(ProsodyPitch::XLow),
+            "low" => return Ok(ProsodyPitch::Low),
+            "medium" => return Ok(ProsodyPitch::Medium),
+            "high" => return Ok(ProsodyPitch::High),
+            "x-high" => return Ok(ProsodyPitch::XHigh),
+            other => ["%", "hz", "st"]
+                .iter()
+                .find_map(|unit| Some((other.strip_suffix(unit)?, *unit)))
+                .ok_or_else(invalid)?,
+        };
+        let signed = number.starts_with('+') || number.starts_with('-');
+        let value = match number.parse::<f32>() {
+            Ok(value) if value.is_finite() => value,
+            _ => return Err(invalid()),
+        };
+        match (unit, signed) {
+            ("%", true) => Ok(ProsodyPitch::Percent(value)),
+            ("hz", true) => Ok(ProsodyPitch::HertzChange(value)),
+            ("hz", false) => Ok(ProsodyPitch::Hertz(value)),
+            ("st", true) => Ok(ProsodyPitch::Semitones(value)),
+            _ => Err(invalid()),
         }
     }
 }
 
 /// Represents all possible WorldRoles that AWS Polly Supports.
+/// `Sense` picks a specific sense of the word (`amazon:SENSE_2`), and `Custom` is any
+/// other `amazon:` role, which is written exactly as given. `amazon:SENSE_1` is always
+/// `PresentTense`, see `WordRole::sense`.
 /// The full documentation on all possible world roles are found in AWS docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#w-tag)
+#[derive(Clone, Debug)]
 pub enum WordRole {
+    Def,
+    Noun,
     Verb,
     PastTense,
     PresentTense,
+    Sense(u8),
+    Custom(String),
+}
+
+impl_variants!(WordRole: Def, Noun, Verb, PastTense, PresentTense);
+
+impl WordRole {
+    /// A specific sense of the word. The first sense is the present tense, so it is
+    /// `PresentTense`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::ssml_constants::WordRole;
+    /// assert_eq!(WordRole::sense(1), WordRole::PresentTense);
+    /// assert_eq!(WordRole::sense(2).to_string(), "amazon:SENSE_2");
+    /// ```
+    pub fn sense(sense: u8) -> WordRole {
+        if sense == 1 {
+            WordRole::PresentTense
+        } else {
+            WordRole::Sense(sense)
+        }
+    }
+}
+
+// `Sense(1)` is written exactly like `PresentTense`, so they are the same role.
+impl PartialEq for WordRole {
+    fn eq(&self, other: &WordRole) -> bool {
+        match (self, other) {
+            (WordRole::Sense(1), WordRole::PresentTense)
+            | (WordRole::PresentTense, WordRole::Sense(1)) => true,
+            (WordRole::Sense(a), WordRole::Sense(b)) => a == b,
+            (WordRole::Custom(a), WordRole::Custom(b)) => a == b,
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for WordRole {}
+
+impl Hash for WordRole {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            WordRole::Sense(1) => mem::discriminant(&WordRole::PresentTense).hash(state),
+            WordRole::Sense(sense) => {
+                mem::discriminant(self).hash(state);
+                sense.hash(state);
+            }
+            WordRole::Custom(role) => {
+                mem::discriminant(self).hash(state);
+                role.hash(state);
+            }
+            _ => mem::discriminant(self).hash(state),
+        }
+    }
 }
 
 impl fmt::Display for WordRole {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &WordRole::Verb => write!(f, "amazon:VB"),
-            &WordRole::PastTense => write!(f, "amazon:VBD"),
-            &WordRole::PresentTense => write!(f, "amazon:SENSE_1"),
+            WordRole::Def => write!(f, "amazon:DEFAULT"),
+            WordRole::Noun => write!(f, "amazon:NN"),
+            WordRole::Verb => write!(f, "amazon:VB"),
+            WordRole::PastTense => write!(f, "amazon:VBD"),
+            WordRole::PresentTense => write!(f, "amazon:SENSE_1"),
+            WordRole::Sense(sense) => write!(f, "amazon:SENSE_{}", sense),
+            WordRole::Custom(role) => write!(f, "{}", role),
         }
     }
 }
 
 impl FromStr for WordRole {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<WordRole, ()> {
+    fn from_str(s: &str) -> Result<WordRole, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "word role",
+                &[
+                    "amazon:DEFAULT",
+                    "amazon:NN",
+                    "amazon:VB",
+                    "amazon:VBD",
+                    "amazon:SENSE_<number>",
+                    "amazon:<role>",
+                ],
+            )
+        };
         match &*s.to_lowercase() {
+            "amazon:default" => Ok(WordRole::Def),
+            "amazon:nn" => Ok(WordRole::Noun),
             "amazon:vb" => Ok(WordRole::Verb),
             "amazon:vbd" => Ok(WordRole::PastTense),
             "amazon:sense_1" => Ok(WordRole::PresentTense),
-            _ => Err(()),
+            other => {
+                let role = other.strip_prefix("amazon:").ok_or_else(invalid)?;
+                if role.is_empty() {
+                    return Err(invalid());
+                }
+                match role.strip_prefix("sense_").map(|sense| sense.parse::<u8>()) {
+                    Some(Ok(sense)) => Ok(WordRole::sense(sense)),
+                    Some(Err(_)) => Err(invalid()),
+                    None => Ok(WordRole::Custom(s.to_owned())),
+                }
+            }
         }
     }
 }
@@ -215,28 +754,32 @@ impl FromStr for WordRole {
 /// Represents all possible AWS Effects that AWS Polly Supports THAT DO NOT HAVE VALUES.
 /// The full documentation on all possible amazon effects are in the AWS docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum AmazonEffect {
     Whispered,
     Drc,
 }
 
+impl_variants!(AmazonEffect: Whispered, Drc);
+
 impl fmt::Display for AmazonEffect {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &AmazonEffect::Whispered => write!(f, "whispered"),
-            &AmazonEffect::Drc => write!(f, "drc"),
+            AmazonEffect::Whispered => write!(f, "whispered"),
+            AmazonEffect::Drc => write!(f, "drc"),
         }
     }
 }
 
 impl FromStr for AmazonEffect {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<AmazonEffect, ()> {
+    fn from_str(s: &str) -> Result<AmazonEffect, InvalidValue> {
+        let invalid = || InvalidValue::new(s, "effect", &["whispered", "drc"]);
         match &*s.to_lowercase() {
             "whispered" | "whisper" => Ok(AmazonEffect::Whispered),
             "drc" => Ok(AmazonEffect::Drc),
-            _ => Err(()),
+            _ => Err(invalid()),
         }
     }
 }
@@ -244,25 +787,96 @@ impl FromStr for AmazonEffect {
 /// Represents all possible AWS Doman 'name' attributes that AWS Polly Supports.
 /// The full documentation on all possible amazon effects are in the AWS docs:
 /// [HERE](https://docs.aws.amazon.com/polly/latest/dg/supportedtags.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum AmazonDomainNames {
     News,
 }
 
+impl_variants!(AmazonDomainNames: News);
+
 impl fmt::Display for AmazonDomainNames {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &AmazonDomainNames::News => write!(f, "news"),
+            AmazonDomainNames::News => write!(f, "news"),
         }
     }
 }
 
 impl FromStr for AmazonDomainNames {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<AmazonDomainNames, ()> {
+    fn from_str(s: &str) -> Result<AmazonDomainNames, InvalidValue> {
+        let invalid = || InvalidValue::new(s, "domain", &["news"]);
         match &*s.to_lowercase() {
             "news" => Ok(AmazonDomainNames::News),
-            _ => Err(()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Represents all possible emotions of the `amazon:emotion` tag. Emotions are an Alexa
+/// extension for its neural voices, and are documented in the Alexa docs:
+/// [HERE](https://developer.amazon.com/en-US/docs/alexa/custom-skills/speech-synthesis-markup-language-ssml-reference.html#amazon-emotion).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EmotionName {
+    Excited,
+    Disappointed,
+}
+
+impl_variants!(EmotionName: Excited, Disappointed);
+
+impl fmt::Display for EmotionName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmotionName::Excited => write!(f, "excited"),
+            EmotionName::Disappointed => write!(f, "disappointed"),
+        }
+    }
+}
+
+impl FromStr for EmotionName {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<EmotionName, InvalidValue> {
+        let invalid = || InvalidValue::new(s, "emotion", &["excited", "disappointed"]);
+        match &*s.to_lowercase() {
+            "excited" => Ok(EmotionName::Excited),
+            "disappointed" => Ok(EmotionName::Disappointed),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Represents how strongly the emotion of an `amazon:emotion` tag is expressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EmotionIntensity {
+    Low,
+    Medium,
+    High,
+}
+
+impl_variants!(EmotionIntensity: Low, Medium, High);
+
+impl fmt::Display for EmotionIntensity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmotionIntensity::Low => write!(f, "low"),
+            EmotionIntensity::Medium => write!(f, "medium"),
+            EmotionIntensity::High => write!(f, "high"),
+        }
+    }
+}
+
+impl FromStr for EmotionIntensity {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<EmotionIntensity, InvalidValue> {
+        let invalid = || InvalidValue::new(s, "emotion intensity", &["low", "medium", "high"]);
+        match &*s.to_lowercase() {
+            "low" => Ok(EmotionIntensity::Low),
+            "medium" => Ok(EmotionIntensity::Medium),
+            "high" => Ok(EmotionIntensity::High),
+            _ => Err(invalid()),
         }
     }
 }
@@ -270,6 +884,7 @@ impl FromStr for AmazonDomainNames {
 /// Possible volumes of breaths for <amazon:breath>, and <amazon:auto-breaths>.
 /// The full documentation on what this does, and it's values are in AWS Docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BreathVolumes {
     Def,
     XSoft,
@@ -279,23 +894,32 @@ pub enum BreathVolumes {
     XLoud,
 }
 
+impl_variants!(BreathVolumes: Def, XSoft, Soft, Medium, Loud, XLoud);
+
 impl fmt::Display for BreathVolumes {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &BreathVolumes::Def => write!(f, "default"),
-            &BreathVolumes::XSoft => write!(f, "x-soft"),
-            &BreathVolumes::Soft => write!(f, "soft"),
-            &BreathVolumes::Medium => write!(f, "medium"),
-            &BreathVolumes::Loud => write!(f, "loud"),
-            &BreathVolumes::XLoud => write!(f, "x-loud"),
+            BreathVolumes::Def => write!(f, "default"),
+            BreathVolumes::XSoft => write!(f, "x-soft"),
+            BreathVolumes::Soft => write!(f, "soft"),
+            BreathVolumes::Medium => write!(f, "medium"),
+            BreathVolumes::Loud => write!(f, "loud"),
+            BreathVolumes::XLoud => write!(f, "x-loud"),
         }
     }
 }
 
 impl FromStr for BreathVolumes {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<BreathVolumes, ()> {
+    fn from_str(s: &str) -> Result<BreathVolumes, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "breath volume",
+                &["default", "x-soft", "soft", "medium", "loud", "x-loud"],
+            )
+        };
         match &*s.to_lowercase() {
             "default" | "" => Ok(BreathVolumes::Def),
             "x-soft" => Ok(BreathVolumes::XSoft),
@@ -303,7 +927,7 @@ impl FromStr for BreathVolumes {
             "medium" => Ok(BreathVolumes::Medium),
             "loud" => Ok(BreathVolumes::Loud),
             "x-loud" => Ok(BreathVolumes::XLoud),
-            _ => Err(()),
+            _ => Err(invalid()),
         }
     }
 }
@@ -311,6 +935,7 @@ impl FromStr for BreathVolumes {
 /// Possible duration values of breaths for <amazon:breath>, and <amazon:auto-breaths>.
 /// The full documentation on what this does, and it's values are in AWS Docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BreathDuration {
     Def,
     XShort,
@@ -320,23 +945,32 @@ pub enum BreathDuration {
     XLong,
 }
 
+impl_variants!(BreathDuration: Def, XShort, Short, Medium, Long, XLong);
+
 impl fmt::Display for BreathDuration {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &BreathDuration::Def => write!(f, "default"),
-            &BreathDuration::XShort => write!(f, "x-short"),
-            &BreathDuration::Short => write!(f, "short"),
-            &BreathDuration::Medium => write!(f, "medium"),
-            &BreathDuration::Long => write!(f, "long"),
-            &BreathDuration::XLong => write!(f, "x-long"),
+            BreathDuration::Def => write!(f, "default"),
+            BreathDuration::XShort => write!(f, "x-short"),
+            BreathDuration::Short => write!(f, "short"),
+            BreathDuration::Medium => write!(f, "medium"),
+            BreathDuration::Long => write!(f, "long"),
+            BreathDuration::XLong => write!(f, "x-long"),
         }
     }
 }
 
 impl FromStr for BreathDuration {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<BreathDuration, ()> {
+    fn from_str(s: &str) -> Result<BreathDuration, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "breath duration",
+                &["default", "x-short", "short", "medium", "long", "x-long"],
+            )
+        };
         match &*s.to_lowercase() {
             "default" | "" => Ok(BreathDuration::Def),
             "x-short" => Ok(BreathDuration::XShort),
@@ -344,7 +978,7 @@ impl FromStr for BreathDuration {
             "medium" => Ok(BreathDuration::Medium),
             "long" => Ok(BreathDuration::Long),
             "x-long" => Ok(BreathDuration::XLong),
-            _ => Err(()),
+            _ => Err(invalid()),
         }
     }
 }
@@ -352,6 +986,7 @@ impl FromStr for BreathDuration {
 /// Possible frequency values of breaths for <amazon:auto-breaths>.
 /// The full documentation on what this does, and it's values are in AWS Docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum AutoBreathFrequency {
     Def,
     XLow,
@@ -361,23 +996,32 @@ pub enum AutoBreathFrequency {
     XHigh,
 }
 
+impl_variants!(AutoBreathFrequency: Def, XLow, Low, Medium, High, XHigh);
+
 impl fmt::Display for AutoBreathFrequency {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &AutoBreathFrequency::Def => write!(f, "default"),
-            &AutoBreathFrequency::XLow => write!(f, "x-low"),
-            &AutoBreathFrequency::Low => write!(f, "low"),
-            &AutoBreathFrequency::Medium => write!(f, "medium"),
-            &AutoBreathFrequency::High => write!(f, "high"),
-            &AutoBreathFrequency::XHigh => write!(f, "x-high"),
+            AutoBreathFrequency::Def => write!(f, "default"),
+            AutoBreathFrequency::XLow => write!(f, "x-low"),
+            AutoBreathFrequency::Low => write!(f, "low"),
+            AutoBreathFrequency::Medium => write!(f, "medium"),
+            AutoBreathFrequency::High => write!(f, "high"),
+            AutoBreathFrequency::XHigh => write!(f, "x-high"),
         }
     }
 }
 
 impl FromStr for AutoBreathFrequency {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<AutoBreathFrequency, ()> {
+    fn from_str(s: &str) -> Result<AutoBreathFrequency, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "breath frequency",
+                &["default", "x-low", "low", "medium", "high", "x-high"],
+            )
+        };
         match &*s.to_lowercase() {
             "default" | "" => Ok(AutoBreathFrequency::Def),
             "x-low" => Ok(AutoBreathFrequency::XLow),
@@ -385,7 +1029,104 @@ impl FromStr for AutoBreathFrequency {
             "medium" => Ok(AutoBreathFrequency::Medium),
             "high" => Ok(AutoBreathFrequency::High),
             "x-high" => Ok(AutoBreathFrequency::XHigh),
-            _ => Err(()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Represents the `vocal-tract-length` of an `amazon:effect` tag, which changes the timbre
+/// of the voice. It is either an absolute length in percent of the voice's own (`150%`), or
+/// a change to it, which always has a sign (`+10%`, or `-5%`). AWS Polly only accepts
+/// lengths between `50%`, and `200%`, so changes between `-50%`, and `+100%`.
+/// The full documentation is in the AWS docs:
+/// [HERE](https://docs.aws.amazon.com/polly/latest/dg/supportedtags.html#vocaltractlength-tag).
+#[derive(Clone, Copy, Debug)]
+pub enum VocalTractLength {
+    Percent(f32),
+    Change(f32),
+}
+
+impl PartialEq for VocalTractLength {
+    fn eq(&self, other: &VocalTractLength) -> bool {
+        match (self, other) {
+            (VocalTractLength::Percent(a), VocalTractLength::Percent(b))
+            | (VocalTractLength::Change(a), VocalTractLength::Change(b)) => {
+                a.to_bits() == b.to_bits()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for VocalTractLength {}
+
+impl Hash for VocalTractLength {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        let (VocalTractLength::Percent(value) | VocalTractLength::Change(value)) = self;
+        value.to_bits().hash(state);
+    }
+}
+
+impl VocalTractLength {
+    /// The shortest length AWS Polly accepts, in percent.
+    pub const MIN_PERCENT: f32 = 50.0;
+    /// The longest length AWS Polly accepts, in percent.
+    pub const MAX_PERCENT: f32 = 200.0;
+
+    /// If AWS Polly accepts this length.
+    pub fn is_in_range(&self) -> bool {
+        match self {
+            VocalTractLength::Percent(percent) => {
+                (Self::MIN_PERCENT..=Self::MAX_PERCENT).contains(percent)
+            }
+            VocalTractLength::Change(change) => {
+                (Self::MIN_PERCENT - 100.0..=Self::MAX_PERCENT - 100.0).contains(change)
+            }
+        }
+    }
+
+    /// The closest length AWS Polly accepts.
+    pub fn clamped(&self) -> VocalTractLength {
+        match self {
+            VocalTractLength::Percent(percent) => {
+                VocalTractLength::Percent(percent.clamp(Self::MIN_PERCENT, Self::MAX_PERCENT))
+            }
+            VocalTractLength::Change(change) => VocalTractLength::Change(
+                change.clamp(Self::MIN_PERCENT - 100.0, Self::MAX_PERCENT - 100.0),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for VocalTractLength {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VocalTractLength::Percent(percent) => write!(f, "{}%", percent),
+            VocalTractLength::Change(change) => write!(f, "{:+}%", change),
+        }
+    }
+}
+
+impl FromStr for VocalTractLength {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<VocalTractLength, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "vocal tract length",
+                &["<percent>%", "+<percent>%", "-<percent>%"],
+            )
+        };
+        let percent = s.strip_suffix('%').ok_or_else(invalid)?;
+        let is_change = percent.starts_with('+') || percent.starts_with('-');
+        match percent.parse::<f32>() {
+            Ok(percent) if percent.is_finite() && is_change => {
+                Ok(VocalTractLength::Change(percent))
+            }
+            Ok(percent) if percent.is_finite() => Ok(VocalTractLength::Percent(percent)),
+            _ => Err(invalid()),
         }
     }
 }
@@ -393,30 +1134,254 @@ impl FromStr for AutoBreathFrequency {
 /// Represents all possible values for the `amazon:effect phonation` key.
 /// The full documentation on the supported values are in the AWS docs:
 /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PhonationVolume {
     Soft,
 }
 
+impl_variants!(PhonationVolume: Soft);
+
 impl fmt::Display for PhonationVolume {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &PhonationVolume::Soft => write!(f, "soft"),
+            PhonationVolume::Soft => write!(f, "soft"),
         }
     }
 }
 
 impl FromStr for PhonationVolume {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<PhonationVolume, ()> {
+    fn from_str(s: &str) -> Result<PhonationVolume, InvalidValue> {
+        let invalid = || InvalidValue::new(s, "phonation", &["soft"]);
         match &*s.to_lowercase() {
             "soft" => Ok(PhonationVolume::Soft),
-            _ => Err(()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Represents all possible places Azure inserts silence with `mstts:silence`. The `-exact`
+/// types replace the natural silence instead of adding to it.
+/// The full documentation is in the Azure docs:
+/// [HERE](https://learn.microsoft.com/en-us/azure/ai-services/speech-service/speech-synthesis-markup-structure#add-silence).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SilenceType {
+    Leading,
+    LeadingExact,
+    Tailing,
+    TailingExact,
+    Sentenceboundary,
+    SentenceboundaryExact,
+    CommaExact,
+    SemicolonExact,
+    EnumerationcommaExact,
+}
+
+impl_variants!(
+    SilenceType: Leading,
+    LeadingExact,
+    Tailing,
+    TailingExact,
+    Sentenceboundary,
+    SentenceboundaryExact,
+    CommaExact,
+    SemicolonExact,
+    EnumerationcommaExact,
+);
+
+impl fmt::Display for SilenceType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SilenceType::Leading => write!(f, "Leading"),
+            SilenceType::LeadingExact => write!(f, "Leading-exact"),
+            SilenceType::Tailing => write!(f, "Tailing"),
+            SilenceType::TailingExact => write!(f, "Tailing-exact"),
+            SilenceType::Sentenceboundary => write!(f, "Sentenceboundary"),
+            SilenceType::SentenceboundaryExact => write!(f, "Sentenceboundary-exact"),
+            SilenceType::CommaExact => write!(f, "Comma-exact"),
+            SilenceType::SemicolonExact => write!(f, "Semicolon-exact"),
+            SilenceType::EnumerationcommaExact => write!(f, "Enumerationcomma-exact"),
+        }
+    }
+}
+
+impl FromStr for SilenceType {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<SilenceType, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "silence type",
+                &[
+                    "Leading",
+                    "Leading-exact",
+                    "Tailing",
+                    "Tailing-exact",
+                    "Sentenceboundary",
+                    "Sentenceboundary-exact",
+                    "Comma-exact",
+                    "Semicolon-exact",
+                    "Enumerationcomma-exact",
+                ],
+            )
+        };
+        match &*s.to_lowercase() {
+            "leading" => Ok(SilenceType::Leading),
+            "leading-exact" => Ok(SilenceType::LeadingExact),
+            "tailing" => Ok(SilenceType::Tailing),
+            "tailing-exact" => Ok(SilenceType::TailingExact),
+            "sentenceboundary" => Ok(SilenceType::Sentenceboundary),
+            "sentenceboundary-exact" => Ok(SilenceType::SentenceboundaryExact),
+            "comma-exact" => Ok(SilenceType::CommaExact),
+            "semicolon-exact" => Ok(SilenceType::SemicolonExact),
+            "enumerationcomma-exact" => Ok(SilenceType::EnumerationcommaExact),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Represents all possible expressive styles of IBM Watson's `express-as` tag, which only its
+/// expressive neural voices support. They are documented in the Watson docs:
+/// [HERE](https://cloud.ibm.com/docs/text-to-speech?topic=text-to-speech-synthesis-expressive).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExpressAsType {
+    GoodNews,
+    Apology,
+    Uncertainty,
+}
+
+impl_variants!(ExpressAsType: GoodNews, Apology, Uncertainty);
+
+impl fmt::Display for ExpressAsType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpressAsType::GoodNews => write!(f, "GoodNews"),
+            ExpressAsType::Apology => write!(f, "Apology"),
+            ExpressAsType::Uncertainty => write!(f, "Uncertainty"),
+        }
+    }
+}
+
+impl FromStr for ExpressAsType {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<ExpressAsType, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "express-as style",
+                &["GoodNews", "Apology", "Uncertainty"],
+            )
+        };
+        match &*s.to_lowercase() {
+            "goodnews" => Ok(ExpressAsType::GoodNews),
+            "apology" => Ok(ExpressAsType::Apology),
+            "uncertainty" => Ok(ExpressAsType::Uncertainty),
+            _ => Err(invalid()),
         }
     }
 }
 
+/// The engine the SSML is written for. Controls which tags are rendered, and how the
+/// root <speak> tag is written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OutputTarget {
+    /// AWS Polly, a full XML document.
+    #[default]
+    Polly,
+    /// An Alexa skill response, a bare `<speak>` fragment without an XML declaration,
+    /// attributes, or namespaces.
+    Alexa,
+    /// Google Cloud Text-to-Speech, a bare `<speak>` fragment like Alexa.
+    Google,
+    /// Azure Speech, a full XML document with the `mstts` namespace declared.
+    Azure,
+    /// IBM Watson Text to Speech, a bare `<speak>` fragment like Alexa.
+    Watson,
+    /// Any other engine following the W3C SSML v1.1 Spec, e.g. espeak-ng, or MaryTTS. This
+    /// is a pure W3C mode: vendor extensions (every `amazon:*` tag, and value included) are
+    /// dropped, the attributes of the spec AWS Polly doesn't support (e.g. `duration` on
+    /// prosody) are allowed, and <speak> always has the `version` the spec requires.
+    Generic,
+}
+
+impl_variants!(OutputTarget: Polly, Alexa, Google, Azure, Watson, Generic);
+
+impl OutputTarget {
+    /// If the target wants a bare `<speak>` fragment, without an XML declaration,
+    /// attributes, or namespaces.
+    pub fn is_bare(&self) -> bool {
+        matches!(
+            self,
+            OutputTarget::Alexa | OutputTarget::Google | OutputTarget::Watson
+        )
+    }
+
+    /// If the target understands a tag. Tags it doesn't understand are dropped when
+    /// rendering.
+    pub fn supports_tag(&self, tag: &PossibleOpenTags) -> bool {
+        use OutputTarget::*;
+
+        match tag {
+            PossibleOpenTags::Mark => *self != Azure,
+            PossibleOpenTags::Voice => *self != Polly,
+            PossibleOpenTags::Word => matches!(self, Polly | Alexa),
+            PossibleOpenTags::AmazonEffect | PossibleOpenTags::AmazonDomain => {
+                matches!(self, Polly | Alexa)
+            }
+            PossibleOpenTags::AmazonAutoBreaths | PossibleOpenTags::AmazonBreath => *self == Polly,
+            PossibleOpenTags::AmazonEmotion => *self == Alexa,
+            PossibleOpenTags::Silence | PossibleOpenTags::Bookmark => *self == Azure,
+            PossibleOpenTags::Par | PossibleOpenTags::Seq | PossibleOpenTags::Media => {
+                *self == Google
+            }
+            PossibleOpenTags::ExpressAs => *self == Watson,
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for OutputTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputTarget::Polly => write!(f, "polly"),
+            OutputTarget::Alexa => write!(f, "alexa"),
+            OutputTarget::Google => write!(f, "google"),
+            OutputTarget::Azure => write!(f, "azure"),
+            OutputTarget::Watson => write!(f, "watson"),
+            OutputTarget::Generic => write!(f, "generic"),
+        }
+    }
+}
+
+impl FromStr for OutputTarget {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<OutputTarget, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "output target",
+                &["polly", "alexa", "google", "azure", "watson", "generic"],
+            )
+        };
+        match &*s.to_lowercase() {
+            "polly" => Ok(OutputTarget::Polly),
+            "alexa" => Ok(OutputTarget::Alexa),
+            "google" => Ok(OutputTarget::Google),
+            "azure" => Ok(OutputTarget::Azure),
+            "watson" => Ok(OutputTarget::Watson),
+            "generic" => Ok(OutputTarget::Generic),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PossibleClosingTags {
+    Emphasis,
     LangTag,
     Mark,
     Paragraph,
@@ -425,17 +1390,51 @@ pub enum PossibleClosingTags {
     Sentence,
     SayAs,
     Sub,
+    Voice,
     Word,
     AmazonEffect,
     AmazonAutoBreaths,
     AmazonDomain,
+    AmazonEmotion,
+    Par,
+    Seq,
+    Media,
+    ExpressAs,
 }
 
 impl FromStr for PossibleClosingTags {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<PossibleClosingTags, ()> {
+    fn from_str(s: &str) -> Result<PossibleClosingTags, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "closing tag",
+                &[
+                    "emphasis",
+                    "lang",
+                    "mark",
+                    "p",
+                    "phoneme",
+                    "prosody",
+                    "s",
+                    "say-as",
+                    "sub",
+                    "voice",
+                    "w",
+                    "amazon:effect",
+                    "amazon:auto-breaths",
+                    "amazon:domain",
+                    "amazon:emotion",
+                    "par",
+                    "seq",
+                    "media",
+                    "express-as",
+                ],
+            )
+        };
         match &*s.to_lowercase() {
+            "emphasis" => Ok(PossibleClosingTags::Emphasis),
             "lang" => Ok(PossibleClosingTags::LangTag),
             "mark" => Ok(PossibleClosingTags::Mark),
             "p" => Ok(PossibleClosingTags::Paragraph),
@@ -444,17 +1443,25 @@ impl FromStr for PossibleClosingTags {
             "s" => Ok(PossibleClosingTags::Sentence),
             "say-as" => Ok(PossibleClosingTags::SayAs),
             "sub" => Ok(PossibleClosingTags::Sub),
+            "voice" => Ok(PossibleClosingTags::Voice),
             "w" => Ok(PossibleClosingTags::Word),
             "amazon:effect" => Ok(PossibleClosingTags::AmazonEffect),
             "amazon:auto-breaths" => Ok(PossibleClosingTags::AmazonAutoBreaths),
             "amazon:domain" => Ok(PossibleClosingTags::AmazonDomain),
-            _ => Err(()),
+            "amazon:emotion" => Ok(PossibleClosingTags::AmazonEmotion),
+            "par" => Ok(PossibleClosingTags::Par),
+            "seq" => Ok(PossibleClosingTags::Seq),
+            "media" => Ok(PossibleClosingTags::Media),
+            "express-as" => Ok(PossibleClosingTags::ExpressAs),
+            _ => Err(invalid()),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PossibleOpenTags {
     Break,
+    Emphasis,
     LangTag,
     Mark,
     Paragraph,
@@ -463,19 +1470,59 @@ pub enum PossibleOpenTags {
     Sentence,
     SayAs,
     Sub,
+    Voice,
     Word,
     AmazonEffect,
     AmazonAutoBreaths,
     AmazonBreath,
     AmazonDomain,
+    AmazonEmotion,
+    Silence,
+    Bookmark,
+    Par,
+    Seq,
+    Media,
+    ExpressAs,
 }
 
 impl FromStr for PossibleOpenTags {
-    type Err = ();
+    type Err = InvalidValue;
 
-    fn from_str(s: &str) -> Result<PossibleOpenTags, ()> {
+    fn from_str(s: &str) -> Result<PossibleOpenTags, InvalidValue> {
+        let invalid = || {
+            InvalidValue::new(
+                s,
+                "tag",
+                &[
+                    "break",
+                    "emphasis",
+                    "lang",
+                    "mark",
+                    "p",
+                    "phoneme",
+                    "prosody",
+                    "s",
+                    "say-as",
+                    "sub",
+                    "voice",
+                    "w",
+                    "amazon:effect",
+                    "amazon:auto-breaths",
+                    "amazon:breath",
+                    "amazon:domain",
+                    "amazon:emotion",
+                    "silence",
+                    "bookmark",
+                    "par",
+                    "seq",
+                    "media",
+                    "express-as",
+                ],
+            )
+        };
         match &*s.to_lowercase() {
             "break" => Ok(PossibleOpenTags::Break),
+            "emphasis" => Ok(PossibleOpenTags::Emphasis),
             "lang" => Ok(PossibleOpenTags::LangTag),
             "mark" => Ok(PossibleOpenTags::Mark),
             "p" => Ok(PossibleOpenTags::Paragraph),
@@ -484,12 +1531,67 @@ impl FromStr for PossibleOpenTags {
             "s" => Ok(PossibleOpenTags::Sentence),
             "say-as" => Ok(PossibleOpenTags::SayAs),
             "sub" => Ok(PossibleOpenTags::Sub),
+            "voice" => Ok(PossibleOpenTags::Voice),
             "w" => Ok(PossibleOpenTags::Word),
             "amazon:effect" => Ok(PossibleOpenTags::AmazonEffect),
             "amazon:auto-breaths" => Ok(PossibleOpenTags::AmazonAutoBreaths),
             "amazon:breath" => Ok(PossibleOpenTags::AmazonBreath),
             "amazon:domain" => Ok(PossibleOpenTags::AmazonDomain),
-            _ => Err(()),
+            "amazon:emotion" => Ok(PossibleOpenTags::AmazonEmotion),
+            "silence" => Ok(PossibleOpenTags::Silence),
+            "bookmark" => Ok(PossibleOpenTags::Bookmark),
+            "par" => Ok(PossibleOpenTags::Par),
+            "seq" => Ok(PossibleOpenTags::Seq),
+            "media" => Ok(PossibleOpenTags::Media),
+            "express-as" => Ok(PossibleOpenTags::ExpressAs),
+            _ => Err(invalid()),
         }
     }
 }
+
+/// Serializes values as the strings they're written as in SSML (e.g. `x-strong`), and
+/// reads them back the same way `FromStr` does, so they can be stored in configuration
+/// files as is.
+#[cfg(feature = "serde")]
+macro_rules! serde_as_ssml_string {
+    ($($name:ident),* $(,)?) => {$(
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<$name, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "serde")]
+serde_as_ssml_string!(
+    BreakStrength,
+    BreakTime,
+    EmphasisLevel,
+    PhonemeAlphabet,
+    ProsodyRate,
+    ProsodyVolume,
+    ProsodyPitch,
+    WordRole,
+    AmazonEffect,
+    AmazonDomainNames,
+    EmotionName,
+    EmotionIntensity,
+    BreathVolumes,
+    BreathDuration,
+    AutoBreathFrequency,
+    VocalTractLength,
+    PhonationVolume,
+    SilenceType,
+    ExpressAsType,
+    OutputTarget,
+);
@@ -0,0 +1,283 @@
+//! The voices of AWS Polly, and what each of them supports. Not every tag works with every
+//! voice, e.g. the newscaster style of `${amazon:domain|name=news}` only exists for a
+//! handful of them. The full list is in the AWS docs:
+//! [HERE](https://docs.aws.amazon.com/polly/latest/dg/available-voices.html).
+
+use crate::error::Result;
+use crate::parser::engine;
+use crate::parser::{parse_as_ssml_with_report, Engine, ParseOptions, Warning, WarningKind};
+use crate::ssml_constants::{AmazonDomainNames, AmazonEffect, PossibleOpenTags};
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// A voice of AWS Polly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Voice {
+    /// The id polly knows the voice by, e.g. `Joanna`.
+    pub id: &'static str,
+    /// The languages the voice speaks, its main language first. Only bilingual voices speak
+    /// more than one.
+    pub languages: &'static [&'static str],
+    /// The engines the voice is available with.
+    pub engines: &'static [Engine],
+    /// The speaking styles of `${amazon:domain}` the voice has.
+    pub domains: &'static [AmazonDomainNames],
+}
+
+const STANDARD: &[Engine] = &[Engine::Standard];
+const NEURAL: &[Engine] = &[Engine::Neural];
+const LONG_FORM: &[Engine] = &[Engine::LongForm];
+const STANDARD_NEURAL: &[Engine] = &[Engine::Standard, Engine::Neural];
+const STANDARD_NEURAL_GENERATIVE: &[Engine] =
+    &[Engine::Standard, Engine::Neural, Engine::Generative];
+const NEURAL_GENERATIVE: &[Engine] = &[Engine::Neural, Engine::Generative];
+const NEURAL_LONG_FORM: &[Engine] = &[Engine::Neural, Engine::LongForm];
+const NEURAL_LONG_FORM_GENERATIVE: &[Engine] =
+    &[Engine::Neural, Engine::LongForm, Engine::Generative];
+
+const NEWS: &[AmazonDomainNames] = &[AmazonDomainNames::News];
+
+const fn voice(
+    id: &'static str,
+    languages: &'static [&'static str],
+    engines: &'static [Engine],
+) -> Voice {
+    Voice {
+        id,
+        languages,
+        engines,
+        domains: &[],
+    }
+}
+
+const fn voice_with_domains(
+    id: &'static str,
+    languages: &'static [&'static str],
+    engines: &'static [Engine],
+    domains: &'static [AmazonDomainNames],
+) -> Voice {
+    Voice {
+        id,
+        languages,
+        engines,
+        domains,
+    }
+}
+
+/// Every voice of AWS Polly, as of the time of writing, grouped by their main language.
+pub const VOICES: &[Voice] = &[
+    voice("Zeina", &["arb"], STANDARD),
+    voice("Hala", &["ar-AE"], NEURAL),
+    voice("Zayd", &["ar-AE"], NEURAL),
+    voice("Arlet", &["ca-ES"], NEURAL),
+    voice("Zhiyu", &["cmn-CN"], STANDARD_NEURAL),
+    voice("Jitka", &["cs-CZ"], NEURAL),
+    voice("Gwyneth", &["cy-GB"], STANDARD),
+    voice("Naja", &["da-DK"], STANDARD),
+    voice("Mads", &["da-DK"], STANDARD),
+    voice("Sofie", &["da-DK"], NEURAL),
+    voice("Hannah", &["de-AT"], NEURAL),
+    voice("Sabrina", &["de-CH"], NEURAL),
+    voice("Marlene", &["de-DE"], STANDARD),
+    voice("Vicki", &["de-DE"], STANDARD_NEURAL_GENERATIVE),
+    voice("Hans", &["de-DE"], STANDARD),
+    voice("Daniel", &["de-DE"], NEURAL_GENERATIVE),
+    voice("Nicole", &["en-AU"], STANDARD),
+    voice("Russell", &["en-AU"], STANDARD),
+    voice("Olivia", &["en-AU"], NEURAL_GENERATIVE),
+    voice_with_domains("Amy", &["en-GB"], STANDARD_NEURAL_GENERATIVE, NEWS),
+    voice("Emma", &["en-GB"], STANDARD_NEURAL),
+    voice("Brian", &["en-GB"], STANDARD_NEURAL),
+    voice("Arthur", &["en-GB"], NEURAL),
+    voice("Geraint", &["en-GB-WLS"], STANDARD),
+    voice("Niamh", &["en-IE"], NEURAL),
+    voice("Aditi", &["en-IN", "hi-IN"], STANDARD),
+    voice("Raveena", &["en-IN"], STANDARD),
+    voice("Kajal", &["en-IN", "hi-IN"], NEURAL),
+    voice("Aria", &["en-NZ"], NEURAL),
+    voice("Jasmine", &["en-SG"], NEURAL),
+    voice("Ivy", &["en-US"], STANDARD_NEURAL),
+    voice_with_domains("Joanna", &["en-US"], STANDARD_NEURAL_GENERATIVE, NEWS),
+    voice("Kendra", &["en-US"], STANDARD_NEURAL),
+    voice("Kimberly", &["en-US"], STANDARD_NEURAL),
+    voice("Salli", &["en-US"], STANDARD_NEURAL_GENERATIVE),
+    voice("Joey", &["en-US"], STANDARD_NEURAL),
+    voice("Justin", &["en-US"], STANDARD_NEURAL),
+    voice("Kevin", &["en-US"], NEURAL),
+    voice_with_domains("Matthew", &["en-US"], STANDARD_NEURAL_GENERATIVE, NEWS),
+    voice("Ruth", &["en-US"], NEURAL_LONG_FORM_GENERATIVE),
+    voice("Stephen", &["en-US"], NEURAL_GENERATIVE),
+    voice("Danielle", &["en-US"], NEURAL_LONG_FORM_GENERATIVE),
+    voice("Gregory", &["en-US"], NEURAL_LONG_FORM),
+    voice("Patrick", &["en-US"], LONG_FORM),
+    voice("Ayanda", &["en-ZA"], NEURAL),
+    voice("Conchita", &["es-ES"], STANDARD),
+    voice("Lucia", &["es-ES"], STANDARD_NEURAL_GENERATIVE),
+    voice("Enrique", &["es-ES"], STANDARD),
+    voice("Sergio", &["es-ES"], NEURAL_GENERATIVE),
+    voice("Mia", &["es-MX"], STANDARD_NEURAL_GENERATIVE),
+    voice("Andres", &["es-MX"], NEURAL_GENERATIVE),
+    voice_with_domains("Lupe", &["es-US"], STANDARD_NEURAL_GENERATIVE, NEWS),
+    voice("Penelope", &["es-US"], STANDARD),
+    voice("Miguel", &["es-US"], STANDARD),
+    voice("Pedro", &["es-US"], NEURAL_GENERATIVE),
+    voice("Suvi", &["fi-FI"], NEURAL),
+    voice("Isabelle", &["fr-BE"], NEURAL),
+    voice("Chantal", &["fr-CA"], STANDARD),
+    voice("Gabrielle", &["fr-CA"], NEURAL_GENERATIVE),
+    voice("Liam", &["fr-CA"], NEURAL),
+    voice("Celine", &["fr-FR"], STANDARD),
+    voice("Lea", &["fr-FR"], STANDARD_NEURAL_GENERATIVE),
+    voice("Mathieu", &["fr-FR"], STANDARD),
+    voice("Remi", &["fr-FR"], NEURAL_GENERATIVE),
+    voice("Dora", &["is-IS"], STANDARD),
+    voice("Karl", &["is-IS"], STANDARD),
+    voice("Carla", &["it-IT"], STANDARD),
+    voice("Bianca", &["it-IT"], STANDARD_NEURAL_GENERATIVE),
+    voice("Giorgio", &["it-IT"], STANDARD),
+    voice("Adriano", &["it-IT"], NEURAL),
+    voice("Mizuki", &["ja-JP"], STANDARD),
+    voice("Takumi", &["ja-JP"], STANDARD_NEURAL),
+    voice("Kazuha", &["ja-JP"], NEURAL),
+    voice("Tomoko", &["ja-JP"], NEURAL),
+    voice("Seoyeon", &["ko-KR"], STANDARD_NEURAL),
+    voice("Liv", &["nb-NO"], STANDARD),
+    voice("Ida", &["nb-NO"], NEURAL),
+    voice("Lisa", &["nl-BE"], NEURAL),
+    voice("Lotte", &["nl-NL"], STANDARD),
+    voice("Ruben", &["nl-NL"], STANDARD),
+    voice("Laura", &["nl-NL"], NEURAL),
+    voice("Ewa", &["pl-PL"], STANDARD),
+    voice("Maja", &["pl-PL"], STANDARD),
+    voice("Jacek", &["pl-PL"], STANDARD),
+    voice("Jan", &["pl-PL"], STANDARD),
+    voice("Ola", &["pl-PL"], NEURAL),
+    voice("Camila", &["pt-BR"], STANDARD_NEURAL),
+    voice("Vitoria", &["pt-BR"], STANDARD_NEURAL),
+    voice("Ricardo", &["pt-BR"], STANDARD),
+    voice("Thiago", &["pt-BR"], NEURAL),
+    voice("Ines", &["pt-PT"], STANDARD_NEURAL),
+    voice("Cristiano", &["pt-PT"], STANDARD),
+    voice("Carmen", &["ro-RO"], STANDARD),
+    voice("Tatyana", &["ru-RU"], STANDARD),
+    voice("Maxim", &["ru-RU"], STANDARD),
+    voice("Astrid", &["sv-SE"], STANDARD),
+    voice("Elin", &["sv-SE"], NEURAL),
+    voice("Filiz", &["tr-TR"], STANDARD),
+    voice("Burcu", &["tr-TR"], NEURAL),
+    voice("Hiujin", &["yue-CN"], NEURAL),
+];
+
+impl Voice {
+    /// The main language the voice speaks.
+    pub fn language(&self) -> &'static str {
+        self.languages[0]
+    }
+
+    /// If the voice speaks a language. Case doesn't matter.
+    pub fn speaks(&self, lang: &str) -> bool {
+        self.languages
+            .iter()
+            .any(|language| language.eq_ignore_ascii_case(lang))
+    }
+
+    /// If the voice is available with an engine.
+    pub fn supports_engine(&self, engine: Engine) -> bool {
+        self.engines.contains(&engine)
+    }
+
+    /// If the voice has a speaking style of `${amazon:domain}`.
+    pub fn supports_domain(&self, domain: AmazonDomainNames) -> bool {
+        self.domains.contains(&domain)
+    }
+
+    /// If any engine the voice is available with supports an `${amazon:effect}`.
+    pub fn supports_effect(&self, effect: AmazonEffect) -> bool {
+        let mut params = BTreeMap::new();
+        params.insert(Cow::Borrowed("name"), Cow::Owned(effect.to_string()));
+        self.unsupported(&PossibleOpenTags::AmazonEffect, &params)
+            .is_none()
+    }
+
+    /// What of a tag the voice doesn't support: `Some(None)` if none of its engines
+    /// support the tag at all, `Some(Some(parameter))` for a parameter it doesn't support,
+    /// and `None` if it supports everything with at least one of its engines.
+    pub(crate) fn unsupported<'a>(
+        &self,
+        tag: &PossibleOpenTags,
+        params: &'a BTreeMap<Cow<str>, Cow<str>>,
+    ) -> Option<Option<&'a str>> {
+        if *tag == PossibleOpenTags::AmazonDomain {
+            if let Some((name, value)) = params.get_key_value("name") {
+                if let Ok(domain) = value.parse::<AmazonDomainNames>() {
+                    if !self.supports_domain(domain) {
+                        return Some(Some(name.as_ref()));
+                    }
+                }
+            }
+        }
+
+        let mut unsupported_param = None;
+        for engine in self.engines {
+            match engine::unsupported(tag, params, *engine) {
+                None => return None,
+                Some(Some(param)) => unsupported_param = unsupported_param.or(Some(param)),
+                Some(None) => {}
+            }
+        }
+        Some(unsupported_param)
+    }
+}
+
+/// Finds a voice by its id, e.g. `Joanna`. Case doesn't matter.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::voices::find_voice;
+/// let voice = find_voice("joanna").unwrap();
+/// assert_eq!(voice.language(), "en-US");
+/// ```
+pub fn find_voice(id: &str) -> Option<&'static Voice> {
+    VOICES
+        .iter()
+        .find(|voice| voice.id.eq_ignore_ascii_case(id))
+}
+
+/// Every voice that speaks a language, e.g. `de-DE`. Case doesn't matter.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::voices::voices_for_language;
+/// let voices = voices_for_language("hi-IN");
+/// assert!(voices.iter().any(|voice| voice.id == "Aditi"));
+/// ```
+pub fn voices_for_language(lang: &str) -> Vec<&'static Voice> {
+    VOICES.iter().filter(|voice| voice.speaks(lang)).collect()
+}
+
+/// Checks the tags of some markup against a voice, returning a warning for every tag, or
+/// parameter the voice doesn't support. Use `ParseOptions::voice` to get the same warnings
+/// while rendering with other options.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::voices::{check_voice, find_voice};
+/// let voice = find_voice("Brian").unwrap();
+/// let warnings = check_voice("${amazon:domain|name=news}Hi${/amazon:domain}", voice);
+/// assert_eq!(warnings.unwrap().len(), 1);
+/// ```
+pub fn check_voice(markup: &str, voice: &Voice) -> Result<Vec<Warning>> {
+    let options = ParseOptions {
+        voice: Some(*voice),
+        ..Default::default()
+    };
+    Ok(parse_as_ssml_with_report(markup, &options)?
+        .warnings
+        .into_iter()
+        .filter(|warning| warning.kind == WarningKind::UnsupportedByVoice)
+        .collect())
+}
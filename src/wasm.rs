@@ -0,0 +1,103 @@
+//! Bindings for JavaScript, so the parser can run in the browser, e.g. for a live preview
+//! of the SSML while the markup is written. Build them with `wasm-pack build -- --features wasm`.
+//!
+//! ```js
+//! import { parseStr, parseStrWithOptions, ParseOptions } from "text-to-polly-ssml";
+//!
+//! const options = new ParseOptions();
+//! options.target = "alexa";
+//! options.pretty = true;
+//! const ssml = parseStrWithOptions("${s}Hello${/s}", options);
+//! ```
+//!
+//! Errors are thrown as a JavaScript `Error` with the message of the `SsmlError`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::parser::OutputTarget;
+
+/// Parses markup into SSML with the default options.
+#[wasm_bindgen(js_name = parseStr)]
+pub fn parse_str(markup: &str) -> Result<String, JsError> {
+    crate::parse_str(markup).map_err(JsError::from)
+}
+
+/// Parses markup into SSML with the given options.
+#[wasm_bindgen(js_name = parseStrWithOptions)]
+pub fn parse_str_with_options(markup: &str, options: &ParseOptions) -> Result<String, JsError> {
+    crate::parse_str_with_options(markup, &options.inner).map_err(JsError::from)
+}
+
+/// The options of `parseStrWithOptions` that can be set from JavaScript. Everything else
+/// keeps the defaults of `ParseOptions`.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct ParseOptions {
+    inner: crate::ParseOptions,
+}
+
+#[wasm_bindgen]
+impl ParseOptions {
+    /// The default options.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ParseOptions {
+        ParseOptions::default()
+    }
+
+    /// The engine the SSML is for, one of `polly`, `alexa`, `google`, `azure`, `watson`, or
+    /// `generic`.
+    #[wasm_bindgen(getter)]
+    pub fn target(&self) -> String {
+        self.inner.target.to_string()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_target(&mut self, target: &str) -> Result<(), JsError> {
+        self.inner.target = target.parse::<OutputTarget>().map_err(JsError::from)?;
+        Ok(())
+    }
+
+    /// The `xml:lang` of the `<speak>` tag, e.g. `en-GB`.
+    #[wasm_bindgen(getter)]
+    pub fn lang(&self) -> Option<String> {
+        self.inner.speak.lang.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_lang(&mut self, lang: Option<String>) {
+        self.inner.speak.lang = lang;
+    }
+
+    /// Fail on unknown, unbalanced, or misnested tags instead of ignoring them.
+    #[wasm_bindgen(getter)]
+    pub fn strict(&self) -> bool {
+        self.inner.strict
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_strict(&mut self, strict: bool) {
+        self.inner.strict = strict;
+    }
+
+    /// Indent the SSML.
+    #[wasm_bindgen(getter)]
+    pub fn pretty(&self) -> bool {
+        self.inner.pretty
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_pretty(&mut self, pretty: bool) {
+        self.inner.pretty = pretty;
+    }
+
+    /// Write every non-ASCII character as a numeric character reference.
+    #[wasm_bindgen(getter = asciiSafe)]
+    pub fn ascii_safe(&self) -> bool {
+        self.inner.ascii_safe
+    }
+
+    #[wasm_bindgen(setter = asciiSafe)]
+    pub fn set_ascii_safe(&mut self, ascii_safe: bool) {
+        self.inner.ascii_safe = ascii_safe;
+    }
+}
@@ -1,22 +1,210 @@
 //! Controls writing of the XML part of SSML. This contains all low level bindings in a sense
 //! to the tags. You should probably never use this directly.
 
-use color_eyre::{eyre::eyre, Result};
+use crate::analysis::SsmlStats;
+use crate::error::{Result, SsmlError};
+use crate::language::is_valid_language_tag;
+use crate::post_process::pretty_print;
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::Writer;
 
-use std::io::Cursor;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Write};
 
 use crate::ssml_constants::*;
 
+/// Options controlling how an XmlWriter renders its document.
+#[derive(Clone, Debug, Default)]
+pub struct WriterOptions {
+    /// If set every non-ASCII character in text, and attribute values is written out as a
+    /// numeric character reference (e.g. `&#x259;`) instead of raw UTF-8. Useful when the
+    /// SSML has to pass through systems that mangle anything outside of ASCII.
+    pub ascii_safe: bool,
+    /// The `<?xml version="1.0"?>` declaration at the start of the document.
+    pub declaration: DeclarationOptions,
+    /// If set the document is rendered with every element on its own line, and indented
+    /// by `PRETTY_INDENT_SIZE` spaces per level of nesting. See
+    /// `post_process::pretty_print`.
+    pub pretty: bool,
+    /// If set every `xml:lang` is checked before it is written, and an
+    /// `SsmlError::InvalidLanguage` is returned for any tag that isn't valid BCP-47, see
+    /// `language::is_valid_language_tag`.
+    pub validate_languages: bool,
+}
+
+/// How many spaces each level of nesting is indented by in pretty printed SSML.
+pub const PRETTY_INDENT_SIZE: usize = 2;
+
+/// Controls the `<?xml ...?>` declaration at the start of the document. By default it is
+/// `<?xml version="1.0"?>`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::xml_writer::{DeclarationOptions, WriterOptions, XmlWriter};
+/// let mut xml_writer = XmlWriter::with_options(WriterOptions {
+///   declaration: DeclarationOptions {
+///     encoding: Some("UTF-8".to_owned()),
+///     standalone: Some(true),
+///     ..Default::default()
+///   },
+///   ..Default::default()
+/// }).unwrap();
+/// assert_eq!(xml_writer.render(), r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DeclarationOptions {
+    /// Don't write the declaration at all, e.g. for Alexa which only accepts a bare
+    /// `<speak>` fragment.
+    pub omit: bool,
+    /// The `encoding` of the declaration. SSML is always written as UTF-8, so this should
+    /// only ever be `UTF-8`.
+    pub encoding: Option<String>,
+    /// The `standalone` flag of the declaration, written as `yes`, or `no`.
+    pub standalone: Option<bool>,
+}
+
+/// The namespace every SSML document lives in.
+pub const SSML_NAMESPACE: &str = "http://www.w3.org/2001/10/synthesis";
+/// The XML Schema instance namespace, conventionally bound to the `xsi` prefix.
+pub const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+/// The namespace of Azure's extensions, conventionally bound to the `mstts` prefix.
+pub const MSTTS_NAMESPACE: &str = "https://www.w3.org/2001/mstts";
+/// The `xsi:schemaLocation` of SSML v1.1, the SSML namespace followed by its schema.
+pub const SSML_SCHEMA_LOCATION: &str =
+    "http://www.w3.org/2001/10/synthesis http://www.w3.org/TR/speech-synthesis11/synthesis.xsd";
+
+/// The attributes, and namespace declarations to put on the root <speak> tag.
+///
+/// Different engines want different namespaces declared on the root element, so these
+/// can be freely added or removed. By default the SSML namespace is declared as the
+/// default namespace, and the XML Schema instance namespace is bound to `xsi`.
+///
+/// # Examples
+///
+/// ```rust
+/// use text_to_polly_ssml::xml_writer::SpeakOptions;
+/// let mut options = SpeakOptions::default();
+/// options.remove_namespace("xsi");
+/// options.add_namespace("mstts", "https://www.w3.org/2001/mstts");
+/// ```
+///
+/// Some strict SSML processors also want the SSML version, and the schema location:
+///
+/// ```rust
+/// use text_to_polly_ssml::xml_writer::{SpeakOptions, SSML_SCHEMA_LOCATION};
+/// let options = SpeakOptions {
+///   version: Some("1.1".to_owned()),
+///   schema_location: Some(SSML_SCHEMA_LOCATION.to_owned()),
+///   ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpeakOptions {
+    /// The `version` of SSML the document is written in. Not written if it isn't set.
+    pub version: Option<String>,
+    /// The `xml:lang` of the document, defaults to `en-US`.
+    pub lang: Option<String>,
+    /// The `onlangfailure` of the document, defaults to `processorchoice`.
+    pub onlangfailure: Option<String>,
+    /// The namespace declarations as `(prefix, uri)` pairs, written out in order. An empty
+    /// prefix declares the default namespace (`xmlns="..."`).
+    pub namespaces: Vec<(String, String)>,
+    /// The `xsi:schemaLocation` of the document, usually `SSML_SCHEMA_LOCATION`. Not
+    /// written if it isn't set. Needs the `xsi` namespace to be declared.
+    pub schema_location: Option<String>,
+}
+
+impl Default for SpeakOptions {
+    fn default() -> SpeakOptions {
+        SpeakOptions {
+            version: None,
+            lang: None,
+            onlangfailure: None,
+            namespaces: vec![
+                ("".to_owned(), SSML_NAMESPACE.to_owned()),
+                ("xsi".to_owned(), XSI_NAMESPACE.to_owned()),
+            ],
+            schema_location: None,
+        }
+    }
+}
+
+impl SpeakOptions {
+    /// Declares a namespace on the <speak> tag. If the prefix is already declared its uri
+    /// is replaced. Use an empty prefix for the default namespace.
+    pub fn add_namespace(&mut self, prefix: &str, uri: &str) {
+        match self.namespaces.iter_mut().find(|(ns, _)| ns == prefix) {
+            Some(existing) => existing.1 = uri.to_owned(),
+            None => self.namespaces.push((prefix.to_owned(), uri.to_owned())),
+        }
+    }
+
+    /// Removes a namespace declaration from the <speak> tag. Use an empty prefix for the
+    /// default namespace.
+    pub fn remove_namespace(&mut self, prefix: &str) {
+        self.namespaces.retain(|(ns, _)| ns != prefix);
+    }
+}
+
+/// The attributes of a <prosody> tag. Every attribute is optional, but at least one has
+/// to be set for the tag to be valid.
+#[derive(Default)]
+pub struct ProsodyOptions {
+    /// The volume of the speech, e.g. `+6dB`.
+    pub volume: Option<ProsodyVolume>,
+    /// The speaking rate of the speech.
+    pub rate: Option<ProsodyRate>,
+    /// The pitch of the speech, e.g. `+10%`.
+    pub pitch: Option<ProsodyPitch>,
+    /// How long the content should take to speak, e.g. `2500ms`. This is from the W3C
+    /// SSML v1.1 Spec, AWS Polly does not support it.
+    pub duration: Option<BreakTime>,
+    /// The longest the content may take to speak, written as `amazon:max-duration`. Alexa
+    /// speeds the speech up to fit. This is an Alexa extension, AWS Polly does not support
+    /// it.
+    pub max_duration: Option<BreakTime>,
+}
+
+/// The attributes of a Google <media> tag, every one of them is optional. Times are SMIL
+/// clock values, e.g. `2.5s`, and `begin`, and `end` can also be relative to another media
+/// element, e.g. `intro.end-0.5s`.
+#[derive(Default)]
+pub struct MediaOptions {
+    /// The id other media elements refer to this one with, written as `xml:id`.
+    pub id: Option<String>,
+    /// When the media starts.
+    pub begin: Option<String>,
+    /// When the media ends.
+    pub end: Option<String>,
+    /// How often the media is repeated, written as `repeatCount`.
+    pub repeat_count: Option<String>,
+    /// How long the media is repeated for, written as `repeatDur`.
+    pub repeat_dur: Option<String>,
+    /// The volume of the media relative to the rest, e.g. `-6dB`, written as `soundLevel`.
+    pub sound_level: Option<String>,
+    /// How long the media fades in, written as `fadeInDur`.
+    pub fade_in_dur: Option<String>,
+    /// How long the media fades out, written as `fadeOutDur`.
+    pub fade_out_dur: Option<String>,
+}
+
 /// An XML Writer. Used for manual manipulation of the SSML Output (which uses XML).
 ///
 /// You should probably never use this directly, instead interacting with the parser,
 /// however if you'd like to build your own parser, and just reuse the XML Rendering
 /// then you'd want to use this.
-pub struct XmlWriter {
-    /// The XML Writer instance. The thing that actually writes the XML.
-    pub writer: Writer<Cursor<Vec<u8>>>,
+///
+/// By default it writes into memory, use `XmlWriter::from_writer` to write straight into a
+/// file, socket, or any other `std::io::Write` instead.
+pub struct XmlWriter<W: Write = Cursor<Vec<u8>>> {
+    /// The XML Writer instance. The thing that actually writes the XML. It is private so
+    /// everything written goes through the methods below, and shows up in the stats.
+    writer: Writer<Sink<W>>,
+    /// The options this writer was created with.
+    options: WriterOptions,
+    /// The text characters, and elements written so far, for `ParseReport::stats`.
+    stats: SsmlStats,
 }
 
 impl XmlWriter {
@@ -42,9 +230,86 @@ impl XmlWriter {
     /// assert!(result.is_ok());
     /// ```
     pub fn new() -> Result<XmlWriter> {
-        let mut writer = Writer::new(Cursor::new(Vec::new()));
-        writer.write_event(Event::Decl(BytesDecl::new(b"1.0", None, None)))?;
-        Ok(XmlWriter { writer: writer })
+        XmlWriter::with_options(WriterOptions::default())
+    }
+
+    /// Creates a new XML Writer with the specified options. Other than the options this
+    /// behaves exactly like `XmlWriter::new`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::{WriterOptions, XmlWriter};
+    /// let result = XmlWriter::with_options(WriterOptions {
+    ///   ascii_safe: true,
+    ///   ..Default::default()
+    /// });
+    /// assert!(result.is_ok());
+    /// ```
+    pub fn with_options(options: WriterOptions) -> Result<XmlWriter> {
+        XmlWriter::start(
+            Writer::new(Sink::new(Cursor::new(Vec::new()), false)),
+            options,
+        )
+    }
+}
+
+impl<W: Write> XmlWriter<W> {
+    /// Creates a new XML Writer that writes straight into `sink`, without holding the
+    /// document in memory. Other than that it behaves exactly like `XmlWriter::with_options`,
+    /// but as nothing is rendered at the end, `pretty` indents the SSML, and `ascii_safe`
+    /// escapes it as it is written.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::{WriterOptions, XmlWriter};
+    /// let mut xml_writer = XmlWriter::from_writer(Vec::new(), WriterOptions::default()).unwrap();
+    /// xml_writer.start_ssml_speak(None, None).unwrap();
+    /// xml_writer.end_ssml_speak().unwrap();
+    /// let ssml = String::from_utf8(xml_writer.into_writer()).unwrap();
+    /// assert!(ssml.ends_with("</speak>"));
+    /// ```
+    pub fn from_writer(sink: W, options: WriterOptions) -> Result<XmlWriter<W>> {
+        let sink = Sink::new(sink, options.ascii_safe);
+        let writer = if options.pretty {
+            Writer::new_with_indent(sink, b' ', PRETTY_INDENT_SIZE)
+        } else {
+            Writer::new(sink)
+        };
+        // The SSML is already indented, and escaped, so rendering it mustn't process it again.
+        XmlWriter::start(
+            writer,
+            WriterOptions {
+                pretty: false,
+                ascii_safe: false,
+                ..options
+            },
+        )
+    }
+
+    /// Writes the XML declaration the options ask for.
+    fn start(mut writer: Writer<Sink<W>>, options: WriterOptions) -> Result<XmlWriter<W>> {
+        let declaration = &options.declaration;
+        if !declaration.omit {
+            let standalone =
+                declaration
+                    .standalone
+                    .map(|standalone| if standalone { &b"yes"[..] } else { &b"no"[..] });
+            writer.write_event(Event::Decl(BytesDecl::new(
+                b"1.0",
+                declaration
+                    .encoding
+                    .as_ref()
+                    .map(|encoding| encoding.as_bytes()),
+                standalone,
+            )))?;
+        }
+        Ok(XmlWriter {
+            writer,
+            options,
+            stats: SsmlStats::default(),
+        })
     }
 
     /// Starts an SSML <speak> tag. For AWS Polly this is the root tag, and should only have one
@@ -86,15 +351,177 @@ impl XmlWriter {
         lang: Option<String>,
         onlangfailure: Option<String>,
     ) -> Result<()> {
+        self.start_ssml_speak_with_options(&SpeakOptions {
+            lang,
+            onlangfailure,
+            ..Default::default()
+        })
+    }
+
+    /// Starts an SSML <speak> tag, exactly like `start_ssml_speak` but with full control
+    /// over the namespaces that get declared, and the optional `version`, and
+    /// `xsi:schemaLocation` attributes.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::{SpeakOptions, XmlWriter};
+    /// let mut options = SpeakOptions::default();
+    /// options.remove_namespace("xsi");
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_speak_result = new_xml_writer.unwrap().start_ssml_speak_with_options(&options);
+    /// assert!(start_speak_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <speak xml:lang="en-US" onlangfailure="processorchoice"
+    ///    xmlns="http://www.w3.org/2001/10/synthesis">
+    /// ```
+    pub fn start_ssml_speak_with_options(&mut self, options: &SpeakOptions) -> Result<()> {
+        self.write_speak(options, Some("processorchoice"))
+    }
+
+    /// Starts the SSML <speak> tag the way an output target wants it:
+    ///
+    /// * `Polly` writes it exactly like `start_ssml_speak_with_options`.
+    /// * `Generic` does as well, but the `version` the W3C SSML v1.1 Spec requires defaults
+    ///   to `1.1`.
+    /// * `Alexa`, `Google`, and `Watson` write a bare <speak> like `start_ssml_speak_bare`,
+    ///   ignoring the options.
+    /// * `Azure` requires a `version`, so it defaults to `1.0`, declares the `mstts`
+    ///   namespace, and only writes `onlangfailure` if it was set.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::ssml_constants::OutputTarget;
+    /// use text_to_polly_ssml::xml_writer::{SpeakOptions, XmlWriter};
+    /// let mut options = SpeakOptions::default();
+    /// options.remove_namespace("xsi");
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_speak_result = new_xml_writer.unwrap()
+    ///   .start_ssml_speak_for_target(&options, OutputTarget::Azure);
+    /// assert!(start_speak_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <speak version="1.0" xml:lang="en-US" xmlns="http://www.w3.org/2001/10/synthesis"
+    ///    xmlns:mstts="https://www.w3.org/2001/mstts">
+    /// ```
+    pub fn start_ssml_speak_for_target(
+        &mut self,
+        options: &SpeakOptions,
+        target: OutputTarget,
+    ) -> Result<()> {
+        match target {
+            OutputTarget::Polly => self.start_ssml_speak_with_options(options),
+            OutputTarget::Generic => {
+                let mut options = options.clone();
+                if options.version.is_none() {
+                    options.version = Some("1.1".to_owned());
+                }
+                self.start_ssml_speak_with_options(&options)
+            }
+            OutputTarget::Alexa | OutputTarget::Google | OutputTarget::Watson => {
+                self.start_ssml_speak_bare()
+            }
+            OutputTarget::Azure => {
+                let mut options = options.clone();
+                if options.version.is_none() {
+                    options.version = Some("1.0".to_owned());
+                }
+                if !options
+                    .namespaces
+                    .iter()
+                    .any(|(prefix, _)| prefix == "mstts")
+                {
+                    options.add_namespace("mstts", MSTTS_NAMESPACE);
+                }
+                self.write_speak(&options, None)
+            }
+        }
+    }
+
+    /// Writes a <speak> tag with the options, and `onlangfailure` falling back to
+    /// `default_onlangfailure` if it isn't set.
+    fn write_speak(
+        &mut self,
+        options: &SpeakOptions,
+        default_onlangfailure: Option<&str>,
+    ) -> Result<()> {
+        if let Some(ref lang) = options.lang {
+            self.check_language(lang)?;
+        }
         let mut elem = BytesStart::owned(b"speak".to_vec(), "speak".len());
-        elem.push_attribute(("xml:lang", &*lang.unwrap_or("en-US".to_owned())));
-        elem.push_attribute((
-            "onlangfailure",
-            &*onlangfailure.unwrap_or("processorchoice".to_owned()),
-        ));
-        elem.push_attribute(("xmlns", "http://www.w3.org/2001/10/synthesis"));
-        elem.push_attribute(("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        if let Some(ref version) = options.version {
+            elem.push_attribute(("version", version.as_str()));
+        }
+        elem.push_attribute(("xml:lang", options.lang.as_deref().unwrap_or("en-US")));
+        if let Some(onlangfailure) = options.onlangfailure.as_deref().or(default_onlangfailure) {
+            elem.push_attribute(("onlangfailure", onlangfailure));
+        }
+        for (prefix, uri) in &options.namespaces {
+            if prefix.is_empty() {
+                elem.push_attribute(("xmlns", uri.as_str()));
+            } else {
+                elem.push_attribute((format!("xmlns:{}", prefix).as_str(), uri.as_str()));
+            }
+        }
+        if let Some(ref schema_location) = options.schema_location {
+            elem.push_attribute(("xsi:schemaLocation", schema_location.as_str()));
+        }
+        self.write_event(Event::Start(elem))
+    }
+
+    /// Returns an error for a language tag that isn't valid BCP-47, if the options ask for
+    /// languages to be validated.
+    fn check_language(&self, lang: &str) -> Result<()> {
+        if self.options.validate_languages && !is_valid_language_tag(lang) {
+            return Err(SsmlError::InvalidLanguage(lang.to_owned()));
+        }
+        Ok(())
+    }
+
+    /// Starts an SSML <speak> tag without any attributes, or namespace declarations. This is
+    /// the only form Alexa accepts in skill responses.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::{DeclarationOptions, WriterOptions, XmlWriter};
+    /// let mut new_xml_writer = XmlWriter::with_options(WriterOptions {
+    ///   declaration: DeclarationOptions { omit: true, ..Default::default() },
+    ///   ..Default::default()
+    /// });
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_speak_result = new_xml_writer.unwrap().start_ssml_speak_bare();
+    /// assert!(start_speak_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <speak>
+    /// ```
+    pub fn start_ssml_speak_bare(&mut self) -> Result<()> {
+        self.write_event(Event::Start(BytesStart::owned(
+            b"speak".to_vec(),
+            "speak".len(),
+        )))
     }
 
     /// Ends an SSML <speak> tag. For AWS Polly this should be the root tag, and you
@@ -119,9 +546,7 @@ impl XmlWriter {
     /// </speak>
     /// ```
     pub fn end_ssml_speak(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"speak")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"speak")))
     }
 
     /// Creates an SSML <break> tag. AWS Polly follows the W3C SSMLv1.1 standard for
@@ -182,14 +607,72 @@ impl XmlWriter {
     ) -> Result<()> {
         let mut elem = BytesStart::owned(b"break".to_vec(), "break".len());
 
-        if strength.is_some() {
-            elem.push_attribute(("strength", &*format!("{}", strength.unwrap())));
+        if let Some(strength) = strength {
+            elem.push_attribute(("strength", &*format!("{}", strength)));
+        }
+        if let Some(time) = time {
+            elem.push_attribute(("time", &*format!("{}", time)));
         }
-        if time.is_some() {
-            elem.push_attribute(("time", &*format!("{}", time.unwrap())));
+
+        self.write_event(Event::Empty(elem))
+    }
+
+    /// Starts an SSML Emphasis tag. The Emphasis tag stresses the content, or with a
+    /// level of `reduced` de-emphasizes it. Without a level engines use `moderate`. This
+    /// follows the W3C SSML v1.1 Spec:
+    /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_emphasis).
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// use text_to_polly_ssml::ssml_constants::EmphasisLevel;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_emphasis_result = new_xml_writer
+    ///   .unwrap()
+    ///   .start_ssml_emphasis(Some(EmphasisLevel::Strong));
+    /// assert!(start_emphasis_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <emphasis level="strong">
+    /// ```
+    pub fn start_ssml_emphasis(&mut self, level: Option<EmphasisLevel>) -> Result<()> {
+        let mut elem = BytesStart::owned(b"emphasis".to_vec(), "emphasis".len());
+        if let Some(level) = level {
+            elem.push_attribute(("level", &*format!("{}", level)));
         }
+        self.write_event(Event::Start(elem))
+    }
 
-        Ok(self.writer.write_event(Event::Empty(elem))?)
+    /// Ends an SSML Emphasis tag.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let end_emphasis_result = new_xml_writer.unwrap().end_ssml_emphasis();
+    /// assert!(end_emphasis_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// </emphasis>
+    /// ```
+    pub fn end_ssml_emphasis(&mut self) -> Result<()> {
+        self.write_event(Event::End(BytesEnd::borrowed(b"emphasis")))
     }
 
     /// Starts an SSML Lang tag. The Lang tag is useful for telling say
@@ -240,13 +723,14 @@ impl XmlWriter {
     /// <lang xml:lang="fr-FR" onlangfailure="changevoice">
     /// ```
     pub fn start_ssml_lang(&mut self, lang: String, onlangfailure: Option<String>) -> Result<()> {
+        self.check_language(&lang)?;
         let mut elem = BytesStart::owned(b"lang".to_vec(), "lang".len());
         elem.push_attribute(("xml:lang", &*lang));
         elem.push_attribute((
             "onlangfailure",
             &*onlangfailure.unwrap_or("processorchoice".to_owned()),
         ));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <lang> tag.
@@ -270,9 +754,36 @@ impl XmlWriter {
     /// </lang>
     /// ```
     pub fn end_ssml_lang(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"lang")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"lang")))
+    }
+
+    /// Creates an SSML <mark> tag. Although this will make no difference in the voice of the
+    /// text, this will place a marker inside the SSML Metadata returned from Polly. The
+    /// W3C SSML v1.1 Spec defines mark as an empty element:
+    /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_mark).
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let mark_result = new_xml_writer.unwrap().ssml_mark("animal".to_owned());
+    /// assert!(mark_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <mark name="animal"/>
+    /// ```
+    pub fn ssml_mark(&mut self, name: String) -> Result<()> {
+        let mut elem = BytesStart::owned(b"mark".to_vec(), "mark".len());
+        elem.push_attribute(("name", &*name));
+        self.write_event(Event::Empty(elem))
     }
 
     /// Starts an SSML Mark tag. Although this will make no difference in the voice
@@ -305,7 +816,7 @@ impl XmlWriter {
     pub fn start_ssml_mark(&mut self, name: String) -> Result<()> {
         let mut elem = BytesStart::owned(b"mark".to_vec(), "mark".len());
         elem.push_attribute(("name", &*name));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <mark> tag.
@@ -329,9 +840,7 @@ impl XmlWriter {
     /// </mark>
     /// ```
     pub fn end_ssml_mark(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"mark")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"mark")))
     }
 
     /// Starts an SSML Paragraph Tag. The Paragraph Tag is useful for breaking
@@ -361,9 +870,38 @@ impl XmlWriter {
     /// <p>
     /// ```
     pub fn start_ssml_paragraph(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::Start(BytesStart::owned(b"p".to_vec(), "p".len())))?)
+        self.write_event(Event::Start(BytesStart::owned(b"p".to_vec(), "p".len())))
+    }
+
+    /// Starts an SSML Paragraph tag spoken in another language, exactly like
+    /// `start_ssml_paragraph` but with an `xml:lang` attribute. This saves wrapping a short
+    /// foreign language paragraph in an extra Lang tag.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_p_result = new_xml_writer
+    ///   .unwrap()
+    ///   .start_ssml_paragraph_with_lang("fr-FR".to_owned());
+    /// assert!(start_p_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <p xml:lang="fr-FR">
+    /// ```
+    pub fn start_ssml_paragraph_with_lang(&mut self, lang: String) -> Result<()> {
+        self.check_language(&lang)?;
+        let mut elem = BytesStart::owned(b"p".to_vec(), "p".len());
+        elem.push_attribute(("xml:lang", &*lang));
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <p> tag.
@@ -387,9 +925,7 @@ impl XmlWriter {
     /// </p>
     /// ```
     pub fn end_ssml_paragraph(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"p")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"p")))
     }
 
     /// Starts an SSML Phoneme Tag. The Phoneme Tag is useful for custom pronunciation for words.
@@ -427,7 +963,7 @@ impl XmlWriter {
         let mut elem = BytesStart::owned(b"phoneme".to_vec(), "phoneme".len());
         elem.push_attribute(("alphabet", &*format!("{}", alphabet)));
         elem.push_attribute(("ph", &*ph));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <phoneme> tag.
@@ -451,9 +987,7 @@ impl XmlWriter {
     /// </phoneme>
     /// ```
     pub fn end_ssml_phoneme(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"phoneme")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"phoneme")))
     }
 
     /// Starts an SSML Prosody Tag. The prosody tag seems to be the one that derives the most
@@ -468,9 +1002,10 @@ impl XmlWriter {
     ///
     /// ```rust
     /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// use text_to_polly_ssml::ssml_constants::ProsodyVolume;
     /// let mut new_xml_writer = XmlWriter::new();
     /// assert!(new_xml_writer.is_ok());
-    /// let start_prosody_result = new_xml_writer.unwrap().start_ssml_prosody(Some("+5db".to_owned()), None, None);
+    /// let start_prosody_result = new_xml_writer.unwrap().start_ssml_prosody(Some(ProsodyVolume::Db(6.0)), None, None);
     /// assert!(start_prosody_result.is_ok());
     /// ```
     ///
@@ -478,7 +1013,7 @@ impl XmlWriter {
     ///
     /// ```text
     /// <?xml version="1.0"?>
-    /// <prosody volume="+6db">
+    /// <prosody volume="+6dB">
     /// ```
     ///
     /// ---
@@ -487,12 +1022,12 @@ impl XmlWriter {
     ///
     /// ```rust
     /// use text_to_polly_ssml::xml_writer::XmlWriter;
-    /// use text_to_polly_ssml::ssml_constants::ProsodyRate;
+    /// use text_to_polly_ssml::ssml_constants::{ProsodyPitch, ProsodyRate, ProsodyVolume};
     /// let mut new_xml_writer = XmlWriter::new();
     /// assert!(new_xml_writer.is_ok());
     /// let start_prosody_result = new_xml_writer.unwrap()
-    ///   .start_ssml_prosody(Some("+6dB".to_owned()), Some(ProsodyRate::XFast),
-    ///    Some("+100%".to_owned()));
+    ///   .start_ssml_prosody(Some(ProsodyVolume::Loud), Some(ProsodyRate::XFast),
+    ///    Some(ProsodyPitch::Percent(20.0)));
     /// assert!(start_prosody_result.is_ok());
     /// ```
     ///
@@ -500,28 +1035,81 @@ impl XmlWriter {
     ///
     /// ```text
     /// <?xml version="1.0"?>
-    /// <prosody volume="+6db" rate="x-fast" pitch="+100%">
+    /// <prosody volume="loud" rate="x-fast" pitch="+20%">
     /// ```
     pub fn start_ssml_prosody(
         &mut self,
-        volume: Option<String>,
+        volume: Option<ProsodyVolume>,
         rate: Option<ProsodyRate>,
-        pitch: Option<String>,
+        pitch: Option<ProsodyPitch>,
     ) -> Result<()> {
+        self.start_ssml_prosody_with_options(ProsodyOptions {
+            volume,
+            rate,
+            pitch,
+            ..Default::default()
+        })
+    }
+
+    /// Starts an SSML Prosody Tag, exactly like `start_ssml_prosody` but taking all of the
+    /// attributes as a single `ProsodyOptions`. At least one attribute has to be set.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::{ProsodyOptions, XmlWriter};
+    /// use text_to_polly_ssml::ssml_constants::ProsodyRate;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_prosody_result = new_xml_writer.unwrap().start_ssml_prosody_with_options(
+    ///   ProsodyOptions { rate: Some(ProsodyRate::Slow), ..Default::default() },
+    /// );
+    /// assert!(start_prosody_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <prosody rate="slow">
+    /// ```
+    pub fn start_ssml_prosody_with_options(&mut self, options: ProsodyOptions) -> Result<()> {
         let mut elem = BytesStart::owned(b"prosody".to_vec(), "prosody".len());
-        if volume.is_none() && rate.is_none() && pitch.is_none() {
-            return Err(eyre!("Prosody Tag was supplied no values."));
+        if options.volume.is_none()
+            && options.rate.is_none()
+            && options.pitch.is_none()
+            && options.duration.is_none()
+            && options.max_duration.is_none()
+        {
+            return Err(SsmlError::MissingAnyAttribute {
+                tag: "prosody".to_owned(),
+                attributes: vec![
+                    "volume".to_owned(),
+                    "rate".to_owned(),
+                    "pitch".to_owned(),
+                    "duration".to_owned(),
+                    "max-duration".to_owned(),
+                ],
+            });
         }
-        if volume.is_some() {
-            elem.push_attribute(("volume", &*volume.unwrap()));
+        if let Some(volume) = options.volume {
+            elem.push_attribute(("volume", &*format!("{}", volume)));
         }
-        if rate.is_some() {
-            elem.push_attribute(("rate", &*format!("{}", rate.unwrap())));
+        if let Some(rate) = options.rate {
+            elem.push_attribute(("rate", &*format!("{}", rate)));
         }
-        if pitch.is_some() {
-            elem.push_attribute(("pitch", &*pitch.unwrap()));
+        if let Some(pitch) = options.pitch {
+            elem.push_attribute(("pitch", &*format!("{}", pitch)));
         }
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        if let Some(duration) = options.duration {
+            elem.push_attribute(("duration", &*format!("{}", duration)));
+        }
+        if let Some(max_duration) = options.max_duration {
+            elem.push_attribute(("amazon:max-duration", &*format!("{}", max_duration)));
+        }
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <prosody> tag.
@@ -545,9 +1133,7 @@ impl XmlWriter {
     /// </prosody>
     /// ```
     pub fn end_ssml_prosody(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"prosody")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"prosody")))
     }
 
     /// Starts an SSML Sentence Tag. The Sentence Tag is useful for breaking
@@ -577,9 +1163,38 @@ impl XmlWriter {
     /// <s>
     /// ```
     pub fn start_ssml_sentence(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::Start(BytesStart::owned(b"s".to_vec(), "s".len())))?)
+        self.write_event(Event::Start(BytesStart::owned(b"s".to_vec(), "s".len())))
+    }
+
+    /// Starts an SSML Sentence tag spoken in another language, exactly like
+    /// `start_ssml_sentence` but with an `xml:lang` attribute. This saves wrapping a short
+    /// foreign language sentence in an extra Lang tag.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_s_result = new_xml_writer
+    ///   .unwrap()
+    ///   .start_ssml_sentence_with_lang("fr-FR".to_owned());
+    /// assert!(start_s_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <s xml:lang="fr-FR">
+    /// ```
+    pub fn start_ssml_sentence_with_lang(&mut self, lang: String) -> Result<()> {
+        self.check_language(&lang)?;
+        let mut elem = BytesStart::owned(b"s".to_vec(), "s".len());
+        elem.push_attribute(("xml:lang", &*lang));
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <s> tag.
@@ -603,15 +1218,14 @@ impl XmlWriter {
     /// </s>
     /// ```
     pub fn end_ssml_sentence(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"s")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"s")))
     }
 
     /// Starts an SSML say-as Tag. The say-as tag is used for determing how a body of text
     /// should be interpreted, for example a phone number, or if you want something spelled
     /// out letter by letter. However AWS polly only supports the `interpret-as` attribute
-    /// which is required, and does not support the `format`, and `detail` attributes.
+    /// which is required, and the `format` attribute of dates (see
+    /// `start_ssml_say_as_with_format`), and does not support the `detail` attribute.
     /// However for posterity you can read the W3C SSML v1.1 Spec:
     /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_say-as).
     /// It should be noted the parameter for interpret-as is kept dynamic, since in the
@@ -641,10 +1255,11 @@ impl XmlWriter {
     pub fn start_ssml_say_as(&mut self, interpret_as: String) -> Result<()> {
         let mut elem = BytesStart::owned(b"say-as".to_vec(), "say-as".len());
         elem.push_attribute(("interpret-as", &*interpret_as));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
-    /// Ends an SSML <say-as> tag.
+    /// Starts an SSML say-as Tag with a `format`, e.g. the order of the day, month, and
+    /// year of a date (`mdy`, `dmy`, `ymd`, and so on).
     ///
     /// # Examples
     ///
@@ -654,26 +1269,57 @@ impl XmlWriter {
     /// use text_to_polly_ssml::xml_writer::XmlWriter;
     /// let mut new_xml_writer = XmlWriter::new();
     /// assert!(new_xml_writer.is_ok());
-    /// let end_say_as_result = new_xml_writer.unwrap().end_ssml_say_as();
-    /// assert!(end_say_as_result.is_ok());
+    /// let start_say_as_result = new_xml_writer
+    ///   .unwrap()
+    ///   .start_ssml_say_as_with_format("date".to_owned(), "mdy".to_owned());
+    /// assert!(start_say_as_result.is_ok());
     /// ```
     ///
     /// Generated SSML:
     ///
     /// ```text
     /// <?xml version="1.0"?>
-    /// </say-as>
+    /// <say-as interpret-as="date" format="mdy">
     /// ```
-    pub fn end_ssml_say_as(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"say-as")))?)
+    pub fn start_ssml_say_as_with_format(
+        &mut self,
+        interpret_as: String,
+        format: String,
+    ) -> Result<()> {
+        let mut elem = BytesStart::owned(b"say-as".to_vec(), "say-as".len());
+        elem.push_attribute(("interpret-as", &*interpret_as));
+        elem.push_attribute(("format", &*format));
+        self.write_event(Event::Start(elem))
     }
 
-    /// Starts an SSML sub Tag. The sub tag is used for a substitution of a word.
-    /// For example in elememtal symbols you may want to show the elememtal symbol, but have
-    /// the engine say the actual element name. AWS polly follows the W3C SSML v1.1 Spec:
-    /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_sub).
+    /// Ends an SSML <say-as> tag.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let end_say_as_result = new_xml_writer.unwrap().end_ssml_say_as();
+    /// assert!(end_say_as_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// </say-as>
+    /// ```
+    pub fn end_ssml_say_as(&mut self) -> Result<()> {
+        self.write_event(Event::End(BytesEnd::borrowed(b"say-as")))
+    }
+
+    /// Starts an SSML sub Tag. The sub tag is used for a substitution of a word.
+    /// For example in elememtal symbols you may want to show the elememtal symbol, but have
+    /// the engine say the actual element name. AWS polly follows the W3C SSML v1.1 Spec:
+    /// [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_sub).
     ///
     /// You can find the AWS Documentation that mentions the sub tag:
     /// [HERE](http://docs.aws.amazon.com/polly/latest/dg/supported-ssml.html#sub-tag).
@@ -699,7 +1345,7 @@ impl XmlWriter {
     pub fn start_ssml_sub(&mut self, alias: String) -> Result<()> {
         let mut elem = BytesStart::owned(b"sub".to_vec(), "sub".len());
         elem.push_attribute(("alias", &*alias));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <sub> tag.
@@ -723,9 +1369,60 @@ impl XmlWriter {
     /// </sub>
     /// ```
     pub fn end_ssml_sub(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"sub")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"sub")))
+    }
+
+    /// Starts an SSML Voice tag. The Voice tag switches the voice the content is spoken
+    /// in, which is how Alexa, and most engines besides AWS Polly change voices in the
+    /// middle of a document. AWS Polly does not support it. This follows the W3C SSML v1.1
+    /// Spec: [HERE](https://www.w3.org/TR/2010/REC-speech-synthesis11-20100907/#edef_voice).
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_voice_result = new_xml_writer.unwrap().start_ssml_voice("Brian".to_owned());
+    /// assert!(start_voice_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <voice name="Brian">
+    /// ```
+    pub fn start_ssml_voice(&mut self, name: String) -> Result<()> {
+        let mut elem = BytesStart::owned(b"voice".to_vec(), "voice".len());
+        elem.push_attribute(("name", &*name));
+        self.write_event(Event::Start(elem))
+    }
+
+    /// Ends an SSML Voice tag.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let end_voice_result = new_xml_writer.unwrap().end_ssml_voice();
+    /// assert!(end_voice_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// </voice>
+    /// ```
+    pub fn end_ssml_voice(&mut self) -> Result<()> {
+        self.write_event(Event::End(BytesEnd::borrowed(b"voice")))
     }
 
     /// Starts an SSML Word/Token tag. The Word/Token tag for AWS Polly also deviates pretty
@@ -755,7 +1452,7 @@ impl XmlWriter {
     pub fn start_ssml_w(&mut self, role: WordRole) -> Result<()> {
         let mut elem = BytesStart::owned(b"w".to_vec(), "w".len());
         elem.push_attribute(("role", &*format!("{}", role)));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <w> tag.
@@ -779,9 +1476,7 @@ impl XmlWriter {
     /// </w>
     /// ```
     pub fn end_ssml_w(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"w")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"w")))
     }
 
     /// Starts an SSML amazon domain tag. These tags are unique to AWS Polly. As such
@@ -811,7 +1506,7 @@ impl XmlWriter {
     pub fn start_ssml_amazon_domain(&mut self, name: AmazonDomainNames) -> Result<()> {
         let mut elem = BytesStart::owned(b"amazon:domain".to_vec(), "amazon:domain".len());
         elem.push_attribute(("name", &*format!("{}", name)));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <amazon:domain> tag.
@@ -835,9 +1530,66 @@ impl XmlWriter {
     /// </amazon:domain>
     /// ```
     pub fn end_ssml_amazon_domain(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"amazon:domain")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"amazon:domain")))
+    }
+
+    /// Starts an SSML amazon emotion tag. Emotions are an Alexa extension, which only its
+    /// neural voices support, and are documented in the Alexa docs:
+    /// [HERE](https://developer.amazon.com/en-US/docs/alexa/custom-skills/speech-synthesis-markup-language-ssml-reference.html#amazon-emotion).
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// use text_to_polly_ssml::ssml_constants::{EmotionIntensity, EmotionName};
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_amazon_emotion_result = new_xml_writer.unwrap()
+    ///   .start_ssml_amazon_emotion(EmotionName::Excited, EmotionIntensity::Medium);
+    /// assert!(start_amazon_emotion_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <amazon:emotion name="excited" intensity="medium">
+    /// ```
+    pub fn start_ssml_amazon_emotion(
+        &mut self,
+        name: EmotionName,
+        intensity: EmotionIntensity,
+    ) -> Result<()> {
+        let mut elem = BytesStart::owned(b"amazon:emotion".to_vec(), "amazon:emotion".len());
+        elem.push_attribute(("name", &*format!("{}", name)));
+        elem.push_attribute(("intensity", &*format!("{}", intensity)));
+        self.write_event(Event::Start(elem))
+    }
+
+    /// Ends an SSML <amazon:emotion> tag.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let end_amazon_emotion_result = new_xml_writer.unwrap().end_ssml_amazon_emotion();
+    /// assert!(end_amazon_emotion_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// </amazon:emotion>
+    /// ```
+    pub fn end_ssml_amazon_emotion(&mut self) -> Result<()> {
+        self.write_event(Event::End(BytesEnd::borrowed(b"amazon:emotion")))
     }
 
     /// Starts an SSML amazon effect tag. These tags are unique to AWS Polly. As such
@@ -867,7 +1619,7 @@ impl XmlWriter {
     pub fn start_ssml_amazon_effect(&mut self, name: AmazonEffect) -> Result<()> {
         let mut elem = BytesStart::owned(b"amazon:effect".to_vec(), "amazon:effect".len());
         elem.push_attribute(("name", &*format!("{}", name)));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <amazon:effect> tag.
@@ -891,9 +1643,7 @@ impl XmlWriter {
     /// </amazon:effect>
     /// ```
     pub fn end_ssml_amazon_effect(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"amazon:effect")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"amazon:effect")))
     }
 
     /// Starts an SSML vocal tract tag. These tags are unique to AWS Polly. As such
@@ -906,10 +1656,11 @@ impl XmlWriter {
     ///
     /// ```rust
     /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// use text_to_polly_ssml::ssml_constants::VocalTractLength;
     /// let mut new_xml_writer = XmlWriter::new();
     /// assert!(new_xml_writer.is_ok());
     /// let start_amazon_effect_result = new_xml_writer.unwrap()
-    ///   .start_ssml_vocal_tract_length("+10%".to_owned());
+    ///   .start_ssml_vocal_tract_length(VocalTractLength::Change(10.0));
     /// assert!(start_amazon_effect_result.is_ok());
     /// ```
     ///
@@ -919,10 +1670,10 @@ impl XmlWriter {
     /// <?xml version="1.0"?>
     /// <amazon:effect vocal-tract-length="+10%">
     /// ```
-    pub fn start_ssml_vocal_tract_length(&mut self, factor: String) -> Result<()> {
+    pub fn start_ssml_vocal_tract_length(&mut self, factor: VocalTractLength) -> Result<()> {
         let mut elem = BytesStart::owned(b"amazon:effect".to_vec(), "amazon:effect".len());
         elem.push_attribute(("vocal-tract-length", &*format!("{}", factor)));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
     /// Starts an SSML phonation tag. These tags are unique to AWS Polly. As such
@@ -952,7 +1703,7 @@ impl XmlWriter {
     pub fn start_ssml_phonation(&mut self, volume: PhonationVolume) -> Result<()> {
         let mut elem = BytesStart::owned(b"amazon:effect".to_vec(), "amazon:effect".len());
         elem.push_attribute(("phonation", &*format!("{}", volume)));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
     /// Starts an SSML <amazon:auto-breaths> tag.
@@ -991,7 +1742,7 @@ impl XmlWriter {
         elem.push_attribute(("volume", &*format!("{}", volume)));
         elem.push_attribute(("frequency", &*format!("{}", frequency)));
         elem.push_attribute(("duration", &*format!("{}", duration)));
-        Ok(self.writer.write_event(Event::Start(elem))?)
+        self.write_event(Event::Start(elem))
     }
 
     /// Ends an SSML <amazon:auto-breaths> tag.
@@ -1015,9 +1766,7 @@ impl XmlWriter {
     /// </amazon:auto-breaths>
     /// ```
     pub fn end_ssml_amazon_auto_breaths(&mut self) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::End(BytesEnd::borrowed(b"amazon:auto-breaths")))?)
+        self.write_event(Event::End(BytesEnd::borrowed(b"amazon:auto-breaths")))
     }
 
     /// Starts an SSML <amazon:breath> tag.
@@ -1053,20 +1802,604 @@ impl XmlWriter {
         elem.push_attribute(("volume", &*format!("{}", volume)));
         elem.push_attribute(("duration", &*format!("{}", duration)));
 
-        Ok(self.writer.write_event(Event::Empty(elem))?)
+        self.write_event(Event::Empty(elem))
+    }
+
+    /// Creates an Azure <mstts:silence> tag, which inserts silence at every place of the
+    /// type, e.g. between sentences. Azure honors it where it ignores some breaks. It is
+    /// documented in the Azure docs:
+    /// [HERE](https://learn.microsoft.com/en-us/azure/ai-services/speech-service/speech-synthesis-markup-structure#add-silence).
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// use text_to_polly_ssml::ssml_constants::{BreakTime, SilenceType};
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let silence_result = new_xml_writer.unwrap()
+    ///   .ssml_mstts_silence(SilenceType::Sentenceboundary, BreakTime::new(200, false));
+    /// assert!(silence_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <mstts:silence type="Sentenceboundary" value="200ms"/>
+    /// ```
+    pub fn ssml_mstts_silence(
+        &mut self,
+        silence_type: SilenceType,
+        value: BreakTime,
+    ) -> Result<()> {
+        let mut elem = BytesStart::owned(b"mstts:silence".to_vec(), "mstts:silence".len());
+        elem.push_attribute(("type", &*format!("{}", silence_type)));
+        elem.push_attribute(("value", &*format!("{}", value)));
+        self.write_event(Event::Empty(elem))
+    }
+
+    /// Creates an Azure <bookmark> tag, Azure's equivalent of <mark>. Azure raises a
+    /// bookmark event when it reaches it, so audio can be correlated with the text. It is
+    /// documented in the Azure docs:
+    /// [HERE](https://learn.microsoft.com/en-us/azure/ai-services/speech-service/speech-synthesis-markup-structure#bookmark-element).
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let bookmark_result = new_xml_writer.unwrap().ssml_bookmark("intro_done".to_owned());
+    /// assert!(bookmark_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <bookmark mark="intro_done"/>
+    /// ```
+    pub fn ssml_bookmark(&mut self, mark: String) -> Result<()> {
+        let mut elem = BytesStart::owned(b"bookmark".to_vec(), "bookmark".len());
+        elem.push_attribute(("mark", &*mark));
+        self.write_event(Event::Empty(elem))
+    }
+
+    /// Starts a Google <par> tag, which plays its media elements at the same time. This is
+    /// one of Google Cloud Text-to-Speech's timeline extensions, documented in the Google
+    /// docs: [HERE](https://cloud.google.com/text-to-speech/docs/ssml#par).
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_par_result = new_xml_writer.unwrap().start_ssml_par();
+    /// assert!(start_par_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <par>
+    /// ```
+    pub fn start_ssml_par(&mut self) -> Result<()> {
+        self.write_event(Event::Start(BytesStart::owned(
+            b"par".to_vec(),
+            "par".len(),
+        )))
+    }
+
+    /// Ends a Google <par> tag.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let end_par_result = new_xml_writer.unwrap().end_ssml_par();
+    /// assert!(end_par_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// </par>
+    /// ```
+    pub fn end_ssml_par(&mut self) -> Result<()> {
+        self.write_event(Event::End(BytesEnd::borrowed(b"par")))
+    }
+
+    /// Starts a Google <seq> tag, which plays its media elements one after another. This is
+    /// one of Google Cloud Text-to-Speech's timeline extensions, documented in the Google
+    /// docs: [HERE](https://cloud.google.com/text-to-speech/docs/ssml#seq).
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_seq_result = new_xml_writer.unwrap().start_ssml_seq();
+    /// assert!(start_seq_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <seq>
+    /// ```
+    pub fn start_ssml_seq(&mut self) -> Result<()> {
+        self.write_event(Event::Start(BytesStart::owned(
+            b"seq".to_vec(),
+            "seq".len(),
+        )))
+    }
+
+    /// Ends a Google <seq> tag.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let end_seq_result = new_xml_writer.unwrap().end_ssml_seq();
+    /// assert!(end_seq_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// </seq>
+    /// ```
+    pub fn end_ssml_seq(&mut self) -> Result<()> {
+        self.write_event(Event::End(BytesEnd::borrowed(b"seq")))
+    }
+
+    /// Starts a Google <media> tag, a single element on the timeline of a <par>, or <seq>.
+    /// This is one of Google Cloud Text-to-Speech's timeline extensions, documented in the
+    /// Google docs: [HERE](https://cloud.google.com/text-to-speech/docs/ssml#media).
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::{MediaOptions, XmlWriter};
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_media_result = new_xml_writer.unwrap().start_ssml_media(&MediaOptions {
+    ///   begin: Some("0.5s".to_owned()),
+    ///   sound_level: Some("-6dB".to_owned()),
+    ///   ..Default::default()
+    /// });
+    /// assert!(start_media_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <media begin="0.5s" soundLevel="-6dB">
+    /// ```
+    pub fn start_ssml_media(&mut self, options: &MediaOptions) -> Result<()> {
+        let mut elem = BytesStart::owned(b"media".to_vec(), "media".len());
+        for (name, value) in &[
+            ("xml:id", &options.id),
+            ("begin", &options.begin),
+            ("end", &options.end),
+            ("repeatCount", &options.repeat_count),
+            ("repeatDur", &options.repeat_dur),
+            ("soundLevel", &options.sound_level),
+            ("fadeInDur", &options.fade_in_dur),
+            ("fadeOutDur", &options.fade_out_dur),
+        ] {
+            if let Some(value) = value {
+                elem.push_attribute((*name, value.as_str()));
+            }
+        }
+        self.write_event(Event::Start(elem))
+    }
+
+    /// Ends a Google <media> tag.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let end_media_result = new_xml_writer.unwrap().end_ssml_media();
+    /// assert!(end_media_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// </media>
+    /// ```
+    pub fn end_ssml_media(&mut self) -> Result<()> {
+        self.write_event(Event::End(BytesEnd::borrowed(b"media")))
+    }
+
+    /// Starts an IBM Watson <express-as> tag, which speaks the content in an expressive
+    /// style. Only Watson's expressive neural voices support it, it is documented in the
+    /// Watson docs:
+    /// [HERE](https://cloud.ibm.com/docs/text-to-speech?topic=text-to-speech-synthesis-expressive).
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// use text_to_polly_ssml::ssml_constants::ExpressAsType;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let start_express_as_result = new_xml_writer.unwrap()
+    ///   .start_ssml_express_as(ExpressAsType::GoodNews);
+    /// assert!(start_express_as_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <express-as type="GoodNews">
+    /// ```
+    pub fn start_ssml_express_as(&mut self, express_as_type: ExpressAsType) -> Result<()> {
+        let mut elem = BytesStart::owned(b"express-as".to_vec(), "express-as".len());
+        elem.push_attribute(("type", &*format!("{}", express_as_type)));
+        self.write_event(Event::Start(elem))
+    }
+
+    /// Ends an IBM Watson <express-as> tag.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let end_express_as_result = new_xml_writer.unwrap().end_ssml_express_as();
+    /// assert!(end_express_as_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// </express-as>
+    /// ```
+    pub fn end_ssml_express_as(&mut self) -> Result<()> {
+        self.write_event(Event::End(BytesEnd::borrowed(b"express-as")))
+    }
+
+    /// Starts an arbitrary element this crate doesn't model, e.g. a vendor extension. The
+    /// name, and every attribute name have to be valid XML names, nothing else about them
+    /// is checked.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let mut attributes = BTreeMap::new();
+    /// attributes.insert("attr".to_owned(), "val".to_owned());
+    /// let start_result = new_xml_writer.unwrap().start_generic_element("x:custom", &attributes);
+    /// assert!(start_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// <x:custom attr="val">
+    /// ```
+    pub fn start_generic_element<K: AsRef<str>, V: AsRef<str>>(
+        &mut self,
+        name: &str,
+        attributes: &BTreeMap<K, V>,
+    ) -> Result<()> {
+        check_name(name)?;
+        let mut elem = BytesStart::owned(name.as_bytes().to_vec(), name.len());
+        for (key, value) in attributes {
+            check_name(key.as_ref())?;
+            elem.push_attribute((key.as_ref(), value.as_ref()));
+        }
+        self.write_event(Event::Start(elem))
+    }
+
+    /// Ends an arbitrary element started with `start_generic_element`.
+    ///
+    /// # Examples
+    ///
+    /// Rust Code:
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut new_xml_writer = XmlWriter::new();
+    /// assert!(new_xml_writer.is_ok());
+    /// let end_result = new_xml_writer.unwrap().end_generic_element("x:custom");
+    /// assert!(end_result.is_ok());
+    /// ```
+    ///
+    /// Generated SSML:
+    ///
+    /// ```text
+    /// <?xml version="1.0"?>
+    /// </x:custom>
+    /// ```
+    pub fn end_generic_element(&mut self, name: &str) -> Result<()> {
+        check_name(name)?;
+        self.write_event(Event::End(BytesEnd::borrowed(name.as_bytes())))
     }
 
     /// Writes some raw text to the XML Document. Should only be used inbetween <p> tags.
     pub fn write_text(&mut self, text: &str) -> Result<()> {
-        Ok(self
-            .writer
-            .write_event(Event::Text(BytesText::from_plain_str(text)))?)
+        self.stats.billed_characters += text.chars().count();
+        self.write_event(Event::Text(BytesText::from_plain_str(text)))
+    }
+
+    /// The billed characters, and elements written so far, counting starts over after. Only
+    /// counts what was written through the methods of the writer, and leaves the totals of
+    /// the document to `SsmlStats::with_document`.
+    pub(crate) fn take_stats(&mut self) -> SsmlStats {
+        std::mem::take(&mut self.stats)
+    }
+
+    /// Writes an event, counting the elements other than the root <speak> tag.
+    fn write_event(&mut self, event: Event<'_>) -> Result<()> {
+        if let Event::Start(ref start) | Event::Empty(ref start) = event {
+            if start.name() != b"speak" {
+                let name = String::from_utf8_lossy(start.name()).into_owned();
+                *self.stats.tag_counts.entry(name).or_insert(0) += 1;
+            }
+        }
+        Ok(self.writer.write_event(event)?)
+    }
+
+    /// The sink the SSML was written into. It is your job to flush it.
+    pub fn into_writer(self) -> W {
+        self.writer.into_inner().inner
+    }
+}
+
+impl XmlWriter {
+    /// The amount of bytes written so far, before any of the processing `render` does.
+    pub fn written_len(&mut self) -> usize {
+        self.writer.inner().inner.get_ref().len()
+    }
+
+    /// The XML written so far, borrowed from the writer, before any of the processing
+    /// `render` does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut xml_writer = XmlWriter::new().unwrap();
+    /// xml_writer.start_ssml_speak(None, None).unwrap();
+    /// assert!(xml_writer.peek().contains("<speak"));
+    /// ```
+    pub fn peek(&mut self) -> &str {
+        std::str::from_utf8(self.writer.inner().inner.get_ref()).expect("SSML is not valid UTF-8!")
     }
 
     /// Renders the XML document in it's current state. This expects the document
     /// to be completely valid UTF-8, and will do no closing of tags for you.
+    ///
+    /// If the writer was created with `pretty` the document is pretty printed, and with
+    /// `ascii_safe` every non-ASCII character is turned into a numeric character reference
+    /// here.
+    ///
+    /// This copies the document so the writer can keep being written to, use
+    /// `into_string` when it is done.
     pub fn render(&mut self) -> String {
-        String::from_utf8(self.writer.clone().into_inner().into_inner())
-            .expect("SSML is not valid UTF-8!")
+        let mut rendered = String::new();
+        self.render_into(&mut rendered);
+        rendered
+    }
+
+    /// Renders the XML document like `render`, appending it to a buffer instead of
+    /// allocating a new one. Clear the buffer between documents to reuse it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut buffer = String::new();
+    /// for _ in 0..3 {
+    ///     let mut xml_writer = XmlWriter::new().unwrap();
+    ///     xml_writer.start_ssml_speak(None, None).unwrap();
+    ///     xml_writer.end_ssml_speak().unwrap();
+    ///     buffer.clear();
+    ///     xml_writer.render_into(&mut buffer);
+    ///     assert!(buffer.ends_with("</speak>"));
+    /// }
+    /// ```
+    pub fn render_into(&mut self, buffer: &mut String) {
+        let written = std::str::from_utf8(self.writer.inner().inner.get_ref())
+            .expect("SSML is not valid UTF-8!");
+        buffer.reserve(written.len());
+        process(written, &self.options, |part| buffer.push_str(part));
+    }
+
+    /// Renders the XML document like `render_into`, appending its UTF-8 bytes to a buffer.
+    pub fn render_into_vec(&mut self, buffer: &mut Vec<u8>) {
+        let written = std::str::from_utf8(self.writer.inner().inner.get_ref())
+            .expect("SSML is not valid UTF-8!");
+        buffer.reserve(written.len());
+        process(written, &self.options, |part| {
+            buffer.extend_from_slice(part.as_bytes())
+        });
+    }
+
+    /// Renders the XML document like `render`, without copying it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use text_to_polly_ssml::xml_writer::XmlWriter;
+    /// let mut xml_writer = XmlWriter::new().unwrap();
+    /// xml_writer.start_ssml_speak(None, None).unwrap();
+    /// xml_writer.end_ssml_speak().unwrap();
+    /// assert!(xml_writer.into_string().ends_with("</speak>"));
+    /// ```
+    pub fn into_string(self) -> String {
+        let XmlWriter {
+            writer, options, ..
+        } = self;
+        let written = String::from_utf8(writer.into_inner().inner.into_inner())
+            .expect("SSML is not valid UTF-8!");
+        if !options.pretty && !options.ascii_safe {
+            return written;
+        }
+        let mut rendered = String::with_capacity(written.len());
+        process(&written, &options, |part| rendered.push_str(part));
+        rendered
+    }
+
+    /// The bytes written, before any of the processing `render` does.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.writer.into_inner().inner.into_inner()
+    }
+
+    /// Takes the bytes written so far out of the writer, leaving it empty to keep writing.
+    #[cfg(feature = "async")]
+    pub(crate) fn take_written(&mut self) -> Vec<u8> {
+        let buffer = &mut self.writer.inner().inner;
+        buffer.set_position(0);
+        std::mem::take(buffer.get_mut())
+    }
+}
+
+/// The sink an `XmlWriter` writes into. For `XmlWriter::from_writer` with `ascii_safe` it
+/// escapes every non-ASCII character on the way, as there is no `render` to do it later.
+struct Sink<W: Write> {
+    inner: W,
+    ascii_safe: bool,
+    /// The start of a character split between two writes.
+    pending: Vec<u8>,
+}
+
+impl<W: Write> Sink<W> {
+    fn new(inner: W, ascii_safe: bool) -> Sink<W> {
+        Sink {
+            inner,
+            ascii_safe,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<W: Write> Write for Sink<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if !self.ascii_safe {
+            return self.inner.write(buf);
+        }
+        self.pending.extend_from_slice(buf);
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(written) => written.len(),
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, error)),
+        };
+        let written = std::str::from_utf8(&self.pending[..complete]).expect("Checked above");
+        let inner = &mut self.inner;
+        let mut result = Ok(());
+        escape_non_ascii(written, |part| {
+            if result.is_ok() {
+                result = inner.write_all(part.as_bytes());
+            }
+        });
+        self.pending.drain(..complete);
+        result.map(|_| buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Does the processing `render` promises on the XML written, handing the rendered document
+/// to `push` in parts.
+fn process(written: &str, options: &WriterOptions, mut push: impl FnMut(&str)) {
+    let pretty;
+    let written = if options.pretty {
+        pretty = pretty_print(written, PRETTY_INDENT_SIZE)
+            .expect("The SSML written is not well formed XML!");
+        &pretty
+    } else {
+        written
+    };
+    if options.ascii_safe {
+        escape_non_ascii(written, push);
+    } else {
+        push(written);
+    }
+}
+
+/// Makes sure a name can be used as an element, or attribute name. This is a conservative
+/// subset of the XML name rules: an ASCII letter, `_`, or `:` followed by ASCII letters,
+/// digits, `_`, `:`, `.`, or `-`.
+fn check_name(name: &str) -> Result<()> {
+    let mut characters = name.chars();
+    let valid = match characters.next() {
+        Some(first) => {
+            (first.is_ascii_alphabetic() || first == '_' || first == ':')
+                && characters.all(|character| {
+                    character.is_ascii_alphanumeric() || matches!(character, '_' | ':' | '.' | '-')
+                })
+        }
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(SsmlError::InvalidName(name.to_owned()))
+    }
+}
+
+/// Replaces every non-ASCII character with a hexadecimal numeric character reference.
+/// Element names we write are always ASCII, so anything outside of ASCII can only live in
+/// text, or attribute values where character references are valid.
+fn escape_non_ascii(rendered: &str, mut push: impl FnMut(&str)) {
+    let mut ascii_start = 0;
+    for (index, character) in rendered.char_indices() {
+        if !character.is_ascii() {
+            push(&rendered[ascii_start..index]);
+            push(&format!("&#x{:X};", character as u32));
+            ascii_start = index + character.len_utf8();
+        }
     }
+    push(&rendered[ascii_start..]);
 }
@@ -0,0 +1,56 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{spell_out_acronyms, AcronymRules, Substitutions};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+fn spell_out(markup: &str, rules: &AcronymRules) -> String {
+    let result = spell_out_acronyms(markup, rules);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_spell_out_acronyms() {
+    let rules = AcronymRules::default();
+    assert_eq!(
+        spell_out("I use the API, an MP3, and NASA's APIs. A 42 ÉU", &rules),
+        "I use the ${say-as|interpret-as=spell-out}API${/say-as}, an ${say-as|interpret-as=spell-out}MP3${/say-as}, and NASA's APIs. A 42 ${say-as|interpret-as=spell-out}ÉU${/say-as}"
+    );
+    assert_eq!(
+        spell_out(
+            "${say-as|interpret-as=characters}ABC${/say-as} ${emphasis}FBI${/emphasis}",
+            &rules
+        ),
+        "${say-as|interpret-as=characters}ABC${/say-as} ${emphasis}${say-as|interpret-as=spell-out}FBI${/say-as}${/emphasis}"
+    );
+}
+
+#[test]
+fn test_acronym_rules() {
+    let rules = AcronymRules {
+        min_len: 3,
+        exceptions: vec!["SQL".to_owned()],
+    };
+    assert_eq!(
+        spell_out("UK SQL NASA", &rules),
+        "UK SQL ${say-as|interpret-as=spell-out}NASA${/say-as}"
+    );
+}
+
+#[test]
+fn test_acronyms_option() {
+    let mut substitutions = Substitutions::default();
+    substitutions.define("W3C", "World Wide Web Consortium");
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        substitutions,
+        acronyms: Some(AcronymRules::default()),
+        ..Default::default()
+    };
+    let result = parse_str_with_options("The W3C, and the IETF.", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak>The <sub alias="World Wide Web Consortium">W3C</sub>, and the <say-as interpret-as="spell-out">IETF</say-as>.</speak>"#
+    );
+}
@@ -0,0 +1,163 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::analysis::{
+    analyze, estimate_duration, ssml_stats, DEFAULT_WORDS_PER_MINUTE,
+};
+use text_to_polly_ssml::parser::parse_tree;
+
+use std::time::Duration;
+use text_to_polly_ssml::{parse_with_report, ParseOptions};
+
+#[test]
+fn test_analyze_counts_words_and_sentences() {
+    let result = analyze(
+        r#"Hello, My name is justin. ${break} Is this thing on?! ${p}${prosody|volume=loud}Yes it is.${/prosody}${/p}"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let stats = result.unwrap();
+    assert_eq!(stats.word_count, 12);
+    assert_eq!(stats.sentence_count, 3);
+    assert_eq!(
+        stats.longest_sentence,
+        Some("Hello, My name is justin.".to_owned())
+    );
+}
+
+#[test]
+fn test_analyze_tag_usage_and_languages() {
+    let result = analyze(
+        r#"${lang|lang=fr-FR}Bonjour${/lang} ${lang|lang=de-DE}Hallo${/lang} ${LANG|lang=fr-FR}Salut${/lang} ${brake}"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let stats = result.unwrap();
+    assert_eq!(stats.tag_usage.get("lang"), Some(&3));
+    assert_eq!(stats.tag_usage.get("brake"), Some(&1));
+    assert_eq!(
+        stats.languages.into_iter().collect::<Vec<_>>(),
+        vec!["de-DE".to_owned(), "fr-FR".to_owned()]
+    );
+}
+
+#[test]
+fn test_analyze_empty_text() {
+    let stats = analyze("").unwrap();
+    assert_eq!(stats.word_count, 0);
+    assert_eq!(stats.sentence_count, 0);
+    assert_eq!(stats.longest_sentence, None);
+}
+
+#[test]
+fn test_ssml_stats() {
+    let result = ssml_stats(
+        r#"<?xml version="1.0"?><speak xml:lang="en-US"><p>Tom &amp; Jerry</p><p>Go<break time="1s"/>!</p><!-- not billed --></speak>"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let stats = result.unwrap();
+    assert_eq!(stats.billed_characters, 14);
+    assert_eq!(stats.tag_counts.get("p"), Some(&2));
+    assert_eq!(stats.tag_counts.get("break"), Some(&1));
+    assert_eq!(stats.tag_counts.get("speak"), None);
+    assert_eq!(stats.requests_needed, 1);
+    assert!(ssml_stats("<speak><p>hey</s></speak>").is_err());
+}
+
+#[test]
+fn test_ssml_stats_requests_needed() {
+    let text = "a".repeat(7000);
+    let report = parse_with_report(&text, &ParseOptions::default()).unwrap();
+    assert_eq!(report.stats.billed_characters, 7000);
+    assert_eq!(report.stats.requests_needed, 3);
+    assert_eq!(report.stats.total_characters, report.ssml.chars().count());
+
+    let tags = "${break}".repeat(800);
+    let report = parse_with_report(&tags, &ParseOptions::default()).unwrap();
+    assert_eq!(report.stats.billed_characters, 0);
+    assert_eq!(report.stats.tag_counts.get("break"), Some(&800));
+    assert_eq!(report.stats.requests_needed, 2);
+}
+
+#[test]
+fn test_ssml_stats_unbalanced_markup() {
+    // Outside of strict mode unbalanced tags are rendered as they are, so counting what was
+    // rendered must not fail on them.
+    for (markup, tag, count) in [
+        ("${s}a", "s", Some(&1)),
+        ("hi${/p}", "p", None),
+        ("${p}${s}a${/p}", "s", Some(&1)),
+    ] {
+        let result = parse_with_report(markup, &ParseOptions::default());
+        assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+        let report = result.unwrap();
+        assert_eq!(report.stats.tag_counts.get(tag), count);
+        assert_eq!(report.stats.total_characters, report.ssml.chars().count());
+    }
+}
+
+fn estimate(markup: &str, words_per_minute: f32) -> f32 {
+    estimate_duration(&parse_tree(markup).unwrap(), words_per_minute)
+        .unwrap()
+        .as_secs_f32()
+}
+
+#[test]
+fn test_estimate_duration() {
+    assert_eq!(estimate("", DEFAULT_WORDS_PER_MINUTE), 0.0);
+    // 60 words per minute makes every word take a second.
+    assert_eq!(estimate("one two three", 60.0), 3.0);
+    assert_eq!(estimate("one ${break|time=500ms} two ${break}", 60.0), 3.0);
+    assert_eq!(estimate("one ${break|strength=x-strong} two", 60.0), 3.0);
+    assert_eq!(
+        estimate("${silence|type=Sentenceboundary|value=2s}one", 60.0),
+        3.0
+    );
+    assert_eq!(
+        estimate("${prosody|rate=x-slow}one two${/prosody} three", 60.0),
+        5.0
+    );
+    assert_eq!(
+        estimate(
+            "${prosody|rate=slow}${prosody|rate=x-slow}one two three${/prosody}${/prosody}",
+            60.0
+        ),
+        8.0
+    );
+    assert_eq!(
+        estimate(
+            "${prosody|max-duration=2s}one two three four${/prosody}",
+            60.0
+        ),
+        2.0
+    );
+    assert_eq!(estimate("${sub|alias=World Wide Web}WWW${/sub}", 60.0), 3.0);
+    assert_eq!(
+        estimate("${say-as|interpret-as=characters}AWS${/say-as}", 60.0),
+        3.0
+    );
+}
+
+#[test]
+fn test_estimate_duration_default_rate() {
+    let tree = parse_tree(&"word ".repeat(155)).unwrap();
+    let duration = estimate_duration(&tree, DEFAULT_WORDS_PER_MINUTE).unwrap();
+    assert!(duration > Duration::from_millis(59_900) && duration < Duration::from_millis(60_100));
+}
+
+#[test]
+fn test_estimate_duration_invalid_rate() {
+    let tree = parse_tree("one two three").unwrap();
+    for words_per_minute in &[0.0, -60.0, f32::NAN, f32::INFINITY] {
+        assert_eq!(estimate_duration(&tree, *words_per_minute), None);
+    }
+}
+
+#[test]
+fn test_estimate_duration_saturates() {
+    let markup = format!("hi ${{break|time={}s}} there", "9".repeat(38));
+    let tree = parse_tree(&markup).unwrap();
+    for words_per_minute in &[1.0, DEFAULT_WORDS_PER_MINUTE, 1e30] {
+        assert_eq!(
+            estimate_duration(&tree, *words_per_minute),
+            Some(Duration::MAX)
+        );
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "async")]
+
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::async_writer::AsyncXmlWriter;
+use text_to_polly_ssml::xml_writer::{WriterOptions, XmlWriter};
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+fn test_async_writer() {
+    let options = WriterOptions {
+        pretty: true,
+        ..Default::default()
+    };
+    let result = block_on(async {
+        let mut xml_writer = AsyncXmlWriter::new(Vec::new(), options.clone())?;
+        xml_writer.start_ssml_speak(None, None)?;
+        xml_writer.start_ssml_sentence()?;
+        xml_writer.write_text("Hello")?;
+        assert!(xml_writer.buffered_len() > 0);
+        xml_writer.flush().await?;
+        assert_eq!(xml_writer.buffered_len(), 0);
+        xml_writer.end_ssml_sentence()?;
+        xml_writer.end_ssml_speak()?;
+        xml_writer.finish().await
+    });
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result.err());
+
+    let mut expected = XmlWriter::with_options(options).unwrap();
+    expected.start_ssml_speak(None, None).unwrap();
+    expected.start_ssml_sentence().unwrap();
+    expected.write_text("Hello").unwrap();
+    expected.end_ssml_sentence().unwrap();
+    expected.end_ssml_speak().unwrap();
+    assert_eq!(
+        String::from_utf8(result.unwrap()).unwrap(),
+        expected.render()
+    );
+}
+
+#[test]
+fn test_async_writer_ascii_safe() {
+    let options = WriterOptions {
+        ascii_safe: true,
+        ..Default::default()
+    };
+    let result = block_on(async {
+        let mut xml_writer = AsyncXmlWriter::new(Vec::new(), options)?;
+        xml_writer.write_text("Café")?;
+        xml_writer.flush().await?;
+        xml_writer.write_text("über")?;
+        xml_writer.finish().await
+    });
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result.err());
+    assert_eq!(
+        String::from_utf8(result.unwrap()).unwrap(),
+        "<?xml version=\"1.0\"?>Caf&#xE9;&#xFC;ber"
+    );
+}
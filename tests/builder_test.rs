@@ -0,0 +1,54 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::builder::SsmlBuilder;
+use text_to_polly_ssml::ssml_constants::*;
+use text_to_polly_ssml::xml_writer::ProsodyOptions;
+
+#[test]
+fn test_builder_nesting() {
+    let mut builder = SsmlBuilder::new().unwrap();
+    builder
+        .paragraph(|p| {
+            p.sentence(|s| {
+                s.text("Hello ")
+                    .ssml_break(Some(BreakStrength::Strong), None)
+                    .lang("fr-FR", None, |l| {
+                        l.text("bonjour");
+                    });
+            })
+            .sentence(|s| {
+                s.amazon_effect(AmazonEffect::Whispered, |w| {
+                    w.text("secret");
+                });
+            });
+        })
+        .text("done");
+    let result = builder.build();
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><p><s>Hello <break strength="strong"/><lang xml:lang="fr-FR" onlangfailure="processorchoice">bonjour</lang></s><s><amazon:effect name="whispered">secret</amazon:effect></s></p>done</speak>"#
+    );
+}
+
+#[test]
+fn test_builder_reports_errors() {
+    let mut builder = SsmlBuilder::new().unwrap();
+    builder.prosody(ProsodyOptions::default(), |p| {
+        p.text("never written");
+    });
+    assert!(builder.build().is_err());
+}
+
+#[test]
+fn test_builder_marks() {
+    let mut builder = SsmlBuilder::new().unwrap();
+    builder.mark("a").text("Hello ").wrapping_mark("b", |m| {
+        m.text("world");
+    });
+    let result = builder.build();
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result
+        .unwrap()
+        .ends_with(r#"<mark name="a"/>Hello <mark name="b">world</mark></speak>"#));
+}
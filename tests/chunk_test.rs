@@ -0,0 +1,97 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::compare::canonicalize;
+use text_to_polly_ssml::parser::{parse_as_ssml_chunked, ParseOptions, POLLY_MAX_CHARACTERS};
+use text_to_polly_ssml::{parse_str, parse_str_chunked, OutputTarget, SsmlError};
+
+fn bare_options() -> ParseOptions {
+    ParseOptions {
+        target: OutputTarget::Google,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_chunking_fits_in_one() {
+    let result = parse_str_chunked("${p}Hello there.${/p}", POLLY_MAX_CHARACTERS);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        vec![parse_str("${p}Hello there.${/p}").unwrap()]
+    );
+}
+
+#[test]
+fn test_chunking_reopens_tags() {
+    let result = parse_as_ssml_chunked(
+        "${prosody|rate=slow}${p}First one. Second one.${/p}${p}Third one.${/p}${/prosody}",
+        70,
+        &bare_options(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            r#"<speak><prosody rate="slow"><p>First one. </p></prosody></speak>"#,
+            r#"<speak><prosody rate="slow"><p>Second one.</p></prosody></speak>"#,
+            r#"<speak><prosody rate="slow"><p>Third one.</p></prosody></speak>"#,
+        ]
+    );
+}
+
+#[test]
+fn test_chunking_splits_long_sentences_between_words() {
+    let result = parse_as_ssml_chunked("${s}one two three four${/s}", 32, &bare_options());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            "<speak><s>one two </s></speak>",
+            "<speak><s>three four</s></speak>",
+        ]
+    );
+}
+
+#[test]
+fn test_chunking_keeps_unsplittable_tags_together() {
+    let result = parse_as_ssml_chunked(
+        "Hi. ${sub|alias=World Wide Web}W W W${/sub} Bye.",
+        60,
+        &bare_options(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            r#"<speak>Hi. <sub alias="World Wide Web">W W W</sub></speak>"#,
+            r#"<speak> Bye.</speak>"#,
+        ]
+    );
+    assert!(matches!(
+        parse_as_ssml_chunked(
+            "${sub|alias=World Wide Web}W W W${/sub}",
+            40,
+            &bare_options()
+        ),
+        Err(SsmlError::ChunkLimitTooSmall(40))
+    ));
+}
+
+#[test]
+fn test_chunking_long_text() {
+    let sentence = "The quick brown fox jumps over the lazy dog. ";
+    let text = format!("${{p}}{}${{/p}}", sentence.repeat(200));
+    let result = parse_str_chunked(&text, POLLY_MAX_CHARACTERS);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let chunks = result.unwrap();
+    assert!(chunks.len() > 1);
+    for chunk in &chunks {
+        assert!(chunk.chars().count() <= POLLY_MAX_CHARACTERS);
+        assert!(canonicalize(chunk).is_ok(), "Not well formed: {}", chunk);
+    }
+    let spoken: usize = chunks
+        .iter()
+        .map(|chunk| chunk.matches("lazy dog").count())
+        .sum();
+    assert_eq!(spoken, 200);
+}
@@ -0,0 +1,253 @@
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn text_to_ssml(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_text-to-ssml"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_cli_stdin_to_stdout() {
+    let output = text_to_ssml(&["--lang", "en-GB"], "${s}Hello${/s}");
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "<?xml version=\"1.0\"?><speak xml:lang=\"en-GB\" onlangfailure=\"processorchoice\" xmlns=\"http://www.w3.org/2001/10/synthesis\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"><s>Hello</s></speak>\n"
+    );
+}
+
+#[test]
+fn test_cli_files() {
+    let dir = std::env::temp_dir().join(format!("text-to-ssml-cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("prompt.txt");
+    let output_path = dir.join("prompt.ssml");
+    fs::write(&input, "${p}Hi${/p}").unwrap();
+
+    let output = text_to_ssml(
+        &[
+            "--pretty",
+            "--target",
+            "generic",
+            input.to_str().unwrap(),
+            "-o",
+            output_path.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert!(output.status.success(), "{:?}", output);
+    assert!(output.stdout.is_empty());
+    let ssml = fs::read_to_string(&output_path).unwrap();
+    assert!(ssml.contains("\n  <p>Hi</p>\n"), "{}", ssml);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_errors() {
+    let output = text_to_ssml(&["--strict"], "${p}Hi");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "text-to-ssml: `${p}` is never closed.\n"
+    );
+
+    let output = text_to_ssml(&["--target", "siri"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("text-to-ssml: `siri` is not a valid output target"));
+
+    let output = text_to_ssml(&["--help"], "");
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("Converts markup into SSML."));
+}
+
+#[test]
+fn test_cli_batch() {
+    let dir = std::env::temp_dir().join(format!("text-to-ssml-batch-{}", std::process::id()));
+    let out_dir = dir.join("out");
+    fs::create_dir_all(dir.join("prompts/nested")).unwrap();
+    fs::write(dir.join("prompts/hello.txt"), "${s}Hello${/s}").unwrap();
+    fs::write(dir.join("prompts/nested/pause.txt"), "${break|time=20s}").unwrap();
+    fs::write(dir.join("prompts/notes.md"), "not markup").unwrap();
+
+    let pattern = format!("{}/prompts/**/*.txt", dir.display());
+    let output = text_to_ssml(&["--out-dir", out_dir.to_str().unwrap(), &pattern], "");
+    assert!(output.status.success(), "{:?}", output);
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("nested/pause.txt: 1 warning\n  "),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("hello.txt"), "{}", stderr);
+    assert!(
+        stderr.ends_with("Converted 2 of 2 files, 1 warning.\n"),
+        "{}",
+        stderr
+    );
+
+    assert!(fs::read_to_string(out_dir.join("hello.ssml"))
+        .unwrap()
+        .contains("<s>Hello</s>"));
+    assert!(out_dir.join("pause.ssml").is_file());
+    assert!(!out_dir.join("notes.ssml").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_batch_errors() {
+    let output = text_to_ssml(&["one.txt", "two.txt"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("text-to-ssml: More than one input needs an `--out-dir`."));
+
+    let dir = std::env::temp_dir().join(format!("text-to-ssml-failed-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let good = dir.join("good.txt");
+    fs::write(&good, "${p}Hi${/p}").unwrap();
+    let missing = dir.join("missing.txt");
+
+    let output = text_to_ssml(
+        &[
+            "-d",
+            dir.to_str().unwrap(),
+            missing.to_str().unwrap(),
+            good.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("missing.txt: Failed to read: "),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.ends_with("Converted 1 of 2 files, 0 warnings.\ntext-to-ssml: 1 file failed.\n"),
+        "{}",
+        stderr
+    );
+    assert!(dir.join("good.ssml").is_file());
+
+    let pattern = format!("{}/*.nothing", dir.display());
+    let output = text_to_ssml(&["-d", dir.to_str().unwrap(), &pattern], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .ends_with("doesn't match any files.\n"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_batch_output_names() {
+    let dir = std::env::temp_dir().join(format!("text-to-ssml-names-{}", std::process::id()));
+    let out_dir = dir.join("out");
+    fs::create_dir_all(dir.join("a")).unwrap();
+    fs::create_dir_all(dir.join("b")).unwrap();
+    fs::write(dir.join("a/chapter.1.txt"), "One").unwrap();
+    fs::write(dir.join("a/chapter.2.txt"), "Two").unwrap();
+    fs::write(dir.join("b/chapter.1.txt"), "Other").unwrap();
+
+    let pattern = format!("{}/a/*.txt", dir.display());
+    let output = text_to_ssml(&["-d", out_dir.to_str().unwrap(), &pattern, &pattern], "");
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .ends_with("Converted 2 of 2 files, 0 warnings.\n"));
+    assert!(fs::read_to_string(out_dir.join("chapter.1.ssml"))
+        .unwrap()
+        .contains("One"));
+    assert!(fs::read_to_string(out_dir.join("chapter.2.ssml"))
+        .unwrap()
+        .contains("Two"));
+
+    fs::remove_dir_all(&out_dir).unwrap();
+    let pattern = format!("{}/*/chapter.1.txt", dir.display());
+    let output = text_to_ssml(&["-d", out_dir.to_str().unwrap(), &pattern], "");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("would both be written to") && stderr.contains("chapter.1.ssml"),
+        "{}",
+        stderr
+    );
+    assert!(!out_dir.exists());
+
+    // Converting files in place would overwrite them with their own output.
+    fs::write(dir.join("a/notes.ssml"), "Notes").unwrap();
+    let pattern = format!("{}/a/*.ssml", dir.display());
+    let in_place = dir.join("a/../a");
+    let output = text_to_ssml(&["-d", in_place.to_str().unwrap(), &pattern], "");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("would overwrite the input"), "{}", stderr);
+    assert_eq!(
+        fs::read_to_string(dir.join("a/notes.ssml")).unwrap(),
+        "Notes"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "jsonl")]
+#[test]
+fn test_cli_jsonl() {
+    let input = concat!(
+        "{\"id\": 1, \"markup\": \"${s}Hi${/s}\"}\n",
+        "\n",
+        "{\"id\": 2, \"markup\": \"${break|time=20s}\"}\n",
+        "{\"id\": 3}\n",
+        "not json\n",
+    );
+    let output = text_to_ssml(&["--jsonl"], input);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "text-to-ssml: 2 records failed.\n"
+    );
+
+    let records: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 4);
+    assert_eq!(records[0]["id"], 1);
+    assert!(records[0]["ssml"].as_str().unwrap().contains("<s>Hi</s>"));
+    assert_eq!(records[0]["warnings"], serde_json::json!([]));
+
+    let warnings = records[1]["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["kind"], "OutOfRangeAttributeValue");
+    assert_eq!(warnings[0]["attribute"], "time");
+    assert_eq!(warnings[0]["line"], 1);
+
+    assert_eq!(records[2]["id"], 3);
+    assert_eq!(
+        records[2]["error"],
+        "The record doesn't have a `markup` string."
+    );
+    assert_eq!(records[3]["line"], 5);
+    assert!(records[3]["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid JSON: "));
+}
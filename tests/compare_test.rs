@@ -0,0 +1,79 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::compare::{canonicalize, ssml_eq};
+
+#[test]
+fn test_ssml_eq_ignores_cosmetics() {
+    let result = ssml_eq(
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice"><p>I&apos;m   here</p><mark name="a"></mark></speak>"#,
+        r#"<speak onlangfailure="processorchoice" xml:lang="en-US">
+  <p>
+    I'm here
+  </p>
+  <mark name="a"/>
+</speak>"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result.unwrap());
+}
+
+#[test]
+fn test_ssml_eq_detects_differences() {
+    assert!(!ssml_eq(
+        r#"<speak><p>hey</p></speak>"#,
+        r#"<speak><s>hey</s></speak>"#
+    )
+    .unwrap());
+    assert!(!ssml_eq(
+        r#"<speak><break time="1s"/></speak>"#,
+        r#"<speak><break time="2s"/></speak>"#
+    )
+    .unwrap());
+    assert!(!ssml_eq(
+        r#"<speak><break/>hey</speak>"#,
+        r#"<speak>hey<break/></speak>"#
+    )
+    .unwrap());
+}
+
+#[test]
+fn test_ssml_eq_malformed() {
+    assert!(ssml_eq("<speak><p></s></speak>", "<speak/>").is_err());
+}
+
+#[cfg(feature = "testing")]
+#[test]
+#[should_panic]
+fn test_assert_ssml_eq_panics() {
+    text_to_polly_ssml::compare::assert_ssml_eq("<speak>hey</speak>", "<speak>hi</speak>");
+}
+
+#[test]
+fn test_canonicalize() {
+    let result = canonicalize(
+        r#"<?xml version="1.0"?>
+<speak xml:lang="en-US" onlangfailure="processorchoice">
+  <!-- greeting -->
+  <sub alias="Tom &amp; Jerry">T&amp;J</sub>
+  <mark name="a"/>
+</speak>"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak onlangfailure="processorchoice" xml:lang="en-US"><sub alias="Tom &amp; Jerry">T&amp;J</sub><mark name="a"></mark></speak>"#
+    );
+    assert!(canonicalize("<speak><p>hey</s></speak>").is_err());
+}
+
+#[test]
+fn test_canonicalize_agrees_with_ssml_eq() {
+    let left = r#"<speak><p rate="fast" volume="loud">hey <break/> you</p></speak>"#;
+    let right = r#"<speak>
+  <p volume="loud" rate="fast">hey<break></break>you</p>
+</speak>"#;
+    assert_eq!(
+        canonicalize(left).unwrap() == canonicalize(right).unwrap(),
+        ssml_eq(left, right).unwrap()
+    );
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "config")]
+
+extern crate text_to_polly_ssml;
+
+use std::fs;
+use text_to_polly_ssml::parser::Cast;
+use text_to_polly_ssml::{ParseOptions, SsmlError};
+
+#[test]
+fn test_aliases_from_path() {
+    let directory = std::env::temp_dir().join("text-to-polly-ssml-config-test");
+    fs::create_dir_all(&directory).unwrap();
+    let toml_path = directory.join("aliases.toml");
+    fs::write(
+        &toml_path,
+        "shout = \"${prosody|volume=x-loud|rate=fast}\"\n",
+    )
+    .unwrap();
+    let json_path = directory.join("aliases.json");
+    fs::write(
+        &json_path,
+        r#"{"aside": "${amazon:effect|name=whispered}"}"#,
+    )
+    .unwrap();
+
+    let options = ParseOptions::default()
+        .with_aliases_from_path(&toml_path)
+        .and_then(|options| options.with_aliases_from_path(&json_path));
+    assert!(options.is_ok(), "Result is not okay:\n\n{:?}", options);
+    let result = text_to_polly_ssml::parse_str_with_options(
+        r#"${shout}Hey${/shout} ${aside}psst${/aside}"#,
+        &options.unwrap(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><prosody volume="x-loud" rate="fast">Hey</prosody> <amazon:effect name="whispered">psst</amazon:effect></speak>"#
+    );
+
+    let invalid_path = directory.join("invalid.toml");
+    fs::write(&invalid_path, "shout = 1\n").unwrap();
+    let invalid = ParseOptions::default().with_aliases_from_path(&invalid_path);
+    assert!(
+        matches!(invalid, Err(SsmlError::InvalidConfig { .. })),
+        "{:?}",
+        invalid
+    );
+}
+
+#[test]
+fn test_substitutions_from_path() {
+    let directory = std::env::temp_dir().join("text-to-polly-ssml-config-test");
+    fs::create_dir_all(&directory).unwrap();
+    let toml_path = directory.join("substitutions.toml");
+    fs::write(&toml_path, "NaCl = \"sodium chloride\"\n").unwrap();
+
+    let options = ParseOptions::default().with_substitutions_from_path(&toml_path);
+    assert!(options.is_ok(), "Result is not okay:\n\n{:?}", options);
+    let result = text_to_polly_ssml::parse_str_with_options("Add NaCl.", &options.unwrap());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result
+        .unwrap()
+        .ends_with(r#">Add <sub alias="sodium chloride">NaCl</sub>.</speak>"#));
+}
+
+#[test]
+fn test_lexicon_from_path() {
+    let directory = std::env::temp_dir().join("text-to-polly-ssml-config-test");
+    fs::create_dir_all(&directory).unwrap();
+    let json_path = directory.join("lexicon.json");
+    fs::write(
+        &json_path,
+        r#"{"pecan": {"alphabet": "ipa", "ph": "pɪˈkɑːn"}}"#,
+    )
+    .unwrap();
+
+    let options = ParseOptions::default().with_lexicon_from_path(&json_path);
+    assert!(options.is_ok(), "Result is not okay:\n\n{:?}", options);
+    let result = text_to_polly_ssml::parse_str_with_options("pecan", &options.unwrap());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result
+        .unwrap()
+        .ends_with(r#"><phoneme alphabet="ipa" ph="pɪˈkɑːn">pecan</phoneme></speak>"#));
+
+    let invalid_path = directory.join("invalid_lexicon.toml");
+    fs::write(
+        &invalid_path,
+        "pecan = { alphabet = \"klingon\", ph = \"x\" }\n",
+    )
+    .unwrap();
+    let invalid = ParseOptions::default().with_lexicon_from_path(&invalid_path);
+    assert!(
+        matches!(invalid, Err(SsmlError::InvalidConfig { .. })),
+        "{:?}",
+        invalid
+    );
+}
+
+#[test]
+fn test_cast_from_path() {
+    let directory = std::env::temp_dir().join("text-to-polly-ssml-config-test");
+    fs::create_dir_all(&directory).unwrap();
+    let toml_path = directory.join("cast.toml");
+    fs::write(
+        &toml_path,
+        "Alice = { voice = \"Joanna\", rate = \"slow\" }\n\"Dr. Bob\" = { pitch = \"low\" }\n",
+    )
+    .unwrap();
+
+    let mut cast = Cast::default();
+    let result = cast.define_from_path(&toml_path).map(|cast| cast.clone());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(cast.get("alice").unwrap().voice.as_deref(), Some("Joanna"));
+    assert_eq!(cast.get("Dr. Bob").unwrap().pitch.as_deref(), Some("low"));
+
+    let invalid_path = directory.join("invalid_cast.json");
+    fs::write(&invalid_path, r#"{"Alice": {"accent": "posh"}}"#).unwrap();
+    let invalid = Cast::default()
+        .define_from_path(&invalid_path)
+        .map(|cast| cast.clone());
+    assert!(
+        matches!(invalid, Err(SsmlError::InvalidConfig { .. })),
+        "{:?}",
+        invalid
+    );
+}
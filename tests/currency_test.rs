@@ -0,0 +1,67 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{detect_currency, Currency, CurrencyRules, NumberRules};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+fn detect(markup: &str, rules: &CurrencyRules) -> String {
+    let result = detect_currency(markup, rules);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_detect_currency() {
+    let rules = CurrencyRules::for_locale("en-US");
+    assert_eq!(
+        detect("$12.50, € 3,000, 1£ and US$5", &rules),
+        "${say-as|interpret-as=cardinal}12.50${/say-as} ${sub|alias=dollars}$${/sub}, ${say-as|interpret-as=cardinal}3,000${/say-as} ${sub|alias=euros}€${/sub}, ${say-as|interpret-as=cardinal}1${/say-as}${sub|alias=pound}£${/sub} and US$5"
+    );
+    assert_eq!(
+        detect("$ alone, 12 apples, $5x", &rules),
+        "$ alone, 12 apples, $5x"
+    );
+    assert_eq!(
+        detect("${sub|alias=five bucks}$5${/sub}", &rules),
+        "${sub|alias=five bucks}$5${/sub}"
+    );
+}
+
+#[test]
+fn test_currency_rules() {
+    assert_eq!(
+        detect("Nur 3.000,50 € oder $1", &CurrencyRules::for_locale("de-DE")),
+        "Nur ${say-as|interpret-as=cardinal}3.000,50${/say-as} ${sub|alias=Euro}€${/sub} oder ${say-as|interpret-as=cardinal}1${/say-as} ${sub|alias=Dollar}$${/sub}"
+    );
+
+    let rules = CurrencyRules {
+        currencies: vec![
+            Currency::new("CHF", "franc", "francs"),
+            Currency::new("C$", "Canadian dollar", "Canadian dollars"),
+        ],
+        numbers: NumberRules {
+            digits_from: None,
+            group_separator: '\'',
+            decimal_separator: '.',
+        },
+    };
+    assert_eq!(
+        detect("CHF 1'000 or C$2", &rules),
+        "${say-as|interpret-as=cardinal}1'000${/say-as} ${sub|alias=francs}CHF${/sub} or ${say-as|interpret-as=cardinal}2${/say-as} ${sub|alias=Canadian dollars}C$${/sub}"
+    );
+}
+
+#[test]
+fn test_currency_option() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        currency: Some(CurrencyRules::for_locale("en-US")),
+        numbers: Some(NumberRules::default()),
+        ..Default::default()
+    };
+    let result = parse_str_with_options("Pay $1,200 by 5.", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak>Pay <say-as interpret-as="cardinal">1,200</say-as> <sub alias="dollars">$</sub> by <say-as interpret-as="cardinal">5</say-as>.</speak>"#
+    );
+}
@@ -0,0 +1,65 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{detect_dates, DateOrder, DateRules, NumberRules};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+fn detect(markup: &str, locale: &str) -> String {
+    let result = detect_dates(markup, &DateRules::for_locale(locale));
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_numeric_dates() {
+    assert_eq!(
+        detect("2024-05-01 and 2024/13/01", "en-US"),
+        "${say-as|format=ymd|interpret-as=date}2024-05-01${/say-as} and 2024/13/01"
+    );
+    assert_eq!(
+        detect("05/01/2024, 5-1-24, 13/01/2024", "en-US"),
+        "${say-as|format=mdy|interpret-as=date}05/01/2024${/say-as}, ${say-as|format=mdy|interpret-as=date}5-1-24${/say-as}, 13/01/2024"
+    );
+    assert_eq!(
+        detect("13.01.2024, 1/2/3, 05/01/2024x", "de-DE"),
+        "${say-as|format=dmy|interpret-as=date}13.01.2024${/say-as}, 1/2/3, 05/01/2024x"
+    );
+}
+
+#[test]
+fn test_named_dates() {
+    assert_eq!(
+        detect("On May 1st, 2024 or SEPT 3, you may 2 Mayday 4.", "en-US"),
+        "On ${say-as|format=mdy|interpret-as=date}May 1st, 2024${/say-as} or ${say-as|format=md|interpret-as=date}SEPT 3${/say-as}, you may 2 Mayday 4."
+    );
+    assert_eq!(
+        detect("1 May 2024, 2nd June, and June 31st", "en-GB"),
+        "${say-as|format=dmy|interpret-as=date}1 May 2024${/say-as}, ${say-as|format=dm|interpret-as=date}2nd June${/say-as}, and ${say-as|format=md|interpret-as=date}June 31st${/say-as}"
+    );
+    assert_eq!(
+        detect("${sub|alias=today}May 1${/sub}", "en-US"),
+        "${sub|alias=today}May 1${/sub}"
+    );
+}
+
+#[test]
+fn test_date_rules() {
+    assert_eq!(DateRules::for_locale("en").order, DateOrder::Mdy);
+    assert_eq!(DateRules::for_locale("en_AU").order, DateOrder::Dmy);
+    assert_eq!(DateRules::for_locale("fr-FR").order, DateOrder::Dmy);
+}
+
+#[test]
+fn test_dates_option() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        dates: Some(DateRules::for_locale("en-US")),
+        numbers: Some(NumberRules::default()),
+        ..Default::default()
+    };
+    let result = parse_str_with_options("Ship 3 on 2024-05-01.", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak>Ship <say-as interpret-as="cardinal">3</say-as> on <say-as interpret-as="date" format="ymd">2024-05-01</say-as>.</speak>"#
+    );
+}
@@ -0,0 +1,103 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{
+    dialogue_to_markup, dialogue_turns, parse_dialogue, Cast, DialogueLine, SpeakerStyle,
+};
+use text_to_polly_ssml::{from_dialogue, from_dialogue_by_turn, OutputTarget, ParseOptions};
+
+fn cast() -> Cast {
+    let mut cast = Cast::default();
+    cast.define("Alice", SpeakerStyle::voice("Joanna"));
+    cast.define(
+        "Dr. Bob",
+        SpeakerStyle {
+            rate: Some("slow".to_owned()),
+            volume: Some("loud".to_owned()),
+            ..SpeakerStyle::voice("Matthew")
+        },
+    );
+    cast
+}
+
+#[test]
+fn test_parse_dialogue() {
+    assert_eq!(
+        parse_dialogue("Scene one.\n\nAlice: Hi ${emphasis}Bob${/emphasis}!\n  Dr. Bob:Hi.\nNote: at 10:30.\n\nwe left\nBob:\n"),
+        vec![
+            DialogueLine {
+                speaker: None,
+                text: "Scene one.".to_owned(),
+            },
+            DialogueLine {
+                speaker: Some("Alice".to_owned()),
+                text: "Hi ${emphasis}Bob${/emphasis}!\nDr. Bob:Hi.".to_owned(),
+            },
+            DialogueLine {
+                speaker: Some("Note".to_owned()),
+                text: "at 10:30.\nwe left".to_owned(),
+            },
+            DialogueLine {
+                speaker: Some("Bob".to_owned()),
+                text: String::new(),
+            },
+        ]
+    );
+    assert_eq!(parse_dialogue(""), Vec::new());
+}
+
+#[test]
+fn test_dialogue_to_markup() {
+    let script = "ALICE: Hi.\nalice: It's me.\nDr. Bob: ${emphasis}Hello${/emphasis}.\nCarol: Hey.";
+    assert_eq!(
+        dialogue_to_markup(script, &cast(), OutputTarget::Azure).unwrap(),
+        "${voice|name=Joanna}${p}Hi.\nIt's me.${/p}${/voice}\
+         ${voice|name=Matthew}${p}${emphasis}Hello${/emphasis}.${/p}${/voice}${p}Hey.${/p}"
+    );
+    assert_eq!(
+        dialogue_to_markup(script, &cast(), OutputTarget::Polly).unwrap(),
+        "${p}Hi.\nIt's me.${/p}\
+         ${p}${prosody|rate=slow|volume=loud}${emphasis}Hello${/emphasis}.${/prosody}${/p}${p}Hey.${/p}"
+    );
+}
+
+#[test]
+fn test_dialogue_turns() {
+    let result = dialogue_turns(
+        "Alice: One.\nDr. Bob: Two ${emphasis}three.\nAlice: Four.",
+        &cast(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let turns = result.unwrap();
+    assert_eq!(turns.len(), 3);
+    assert_eq!(turns[1].speaker.as_deref(), Some("Dr. Bob"));
+    assert_eq!(turns[1].voice.as_deref(), Some("Matthew"));
+    assert_eq!(
+        turns[1].markup,
+        "${prosody|rate=slow|volume=loud}Two ${emphasis}three.${/emphasis}${/prosody}"
+    );
+    assert_eq!(turns[2].markup, "Four.");
+}
+
+#[test]
+fn test_from_dialogue() {
+    let options = ParseOptions {
+        target: OutputTarget::Alexa,
+        ..Default::default()
+    };
+    let result = from_dialogue("Alice: Hi.\nCarol: Bye.", &cast(), &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "<speak><voice name=\"Joanna\"><p>Hi.</p></voice><p>Bye.</p></speak>"
+    );
+
+    let result = from_dialogue_by_turn("Alice: Hi.\nDr. Bob: Bye.", &cast(), &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let turns = result.unwrap();
+    assert_eq!(turns[0].0.voice.as_deref(), Some("Joanna"));
+    assert_eq!(turns[0].1, "<speak>Hi.</speak>");
+    assert_eq!(
+        turns[1].1,
+        "<speak><prosody volume=\"loud\" rate=\"slow\">Bye.</prosody></speak>"
+    );
+}
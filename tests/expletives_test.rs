@@ -0,0 +1,51 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{AcronymRules, Expletives};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+#[test]
+fn test_mask_expletives() {
+    let mut expletives = Expletives::default();
+    assert!(expletives.is_empty());
+    expletives.add("Darn").add("heck");
+    assert!(!expletives.is_empty());
+
+    let result = expletives
+        .apply("DARN, heck! Heckle ${sub|alias=darn}x${/sub} ${w|role=amazon:VB}heck${/w}");
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "${say-as|interpret-as=expletive}DARN${/say-as}, ${say-as|interpret-as=expletive}heck${/say-as}! Heckle ${sub|alias=darn}x${/sub} ${w|role=amazon:VB}heck${/w}"
+    );
+}
+
+#[test]
+fn test_expletive_filter() {
+    let mut expletives = Expletives::default();
+    expletives.set_filter(|word| word.len() == 4 && word.ends_with("ck"));
+    assert!(!expletives.is_expletive("dang"));
+    assert!(expletives.is_expletive("muck"));
+
+    let result = expletives.apply("Muck and luck, but not stuck.");
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "${say-as|interpret-as=expletive}Muck${/say-as} and ${say-as|interpret-as=expletive}luck${/say-as}, but not stuck."
+    );
+}
+
+#[test]
+fn test_expletives_option() {
+    let mut options = ParseOptions {
+        target: OutputTarget::Google,
+        acronyms: Some(AcronymRules::default()),
+        ..Default::default()
+    };
+    options.expletives.add("WTF");
+    let result = parse_str_with_options("WTF is an API?", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak><say-as interpret-as="expletive">WTF</say-as> is an <say-as interpret-as="spell-out">API</say-as>?</speak>"#
+    );
+}
@@ -0,0 +1,105 @@
+extern crate text_to_polly_ssml;
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use text_to_polly_ssml::parser::TagHandler;
+use text_to_polly_ssml::ssml_constants::AmazonEffect;
+use text_to_polly_ssml::xml_writer::XmlWriter;
+use text_to_polly_ssml::{ParseOptions, Result};
+
+struct Whisper;
+
+impl TagHandler for Whisper {
+    fn start(
+        &self,
+        writer: &mut XmlWriter,
+        _params: &BTreeMap<Cow<str>, Cow<str>>,
+    ) -> Result<bool> {
+        writer.start_ssml_amazon_effect(AmazonEffect::Whispered)?;
+        Ok(true)
+    }
+
+    fn end(&self, writer: &mut XmlWriter) -> Result<()> {
+        writer.end_ssml_amazon_effect()
+    }
+}
+
+struct Bell;
+
+impl TagHandler for Bell {
+    fn start(
+        &self,
+        writer: &mut XmlWriter,
+        _params: &BTreeMap<Cow<str>, Cow<str>>,
+    ) -> Result<bool> {
+        writer.ssml_mark("bell".to_owned())?;
+        Ok(true)
+    }
+
+    fn has_closing_tag(&self) -> bool {
+        false
+    }
+}
+
+struct Skip;
+
+impl TagHandler for Skip {
+    fn start(
+        &self,
+        _writer: &mut XmlWriter,
+        _params: &BTreeMap<Cow<str>, Cow<str>>,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+#[test]
+fn test_custom_tag_handler() {
+    let mut options = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+    options.handlers.register("quiet", Whisper);
+    let result =
+        text_to_polly_ssml::parse_str_with_options(r#"${s}${QUIET}psst${/quiet}${/s}"#, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><s><amazon:effect name="whispered">psst</amazon:effect></s></speak>"#
+    );
+}
+
+#[test]
+fn test_custom_tag_handler_overrides_builtin() {
+    let mut options = ParseOptions::default();
+    options.handlers.register("s", Skip);
+    let result = text_to_polly_ssml::parse_str_with_options(r#"${p}${s}hey${/s}${/p}"#, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><p>hey</p></speak>"#
+    );
+}
+
+#[test]
+fn test_custom_tag_handler_without_end_tag_in_tree() {
+    use text_to_polly_ssml::parser::{build_tree_with_options, tokenize, SsmlElement, SsmlNode};
+
+    let mut options = ParseOptions::default();
+    options.handlers.register("bell", Bell);
+    let tree = build_tree_with_options(tokenize("${bell}  Ding   dong").unwrap(), &options);
+    assert_eq!(
+        tree,
+        vec![
+            SsmlNode::Element(SsmlElement::new("bell")),
+            SsmlNode::Text("  Ding   dong".to_owned()),
+        ]
+    );
+
+    options.whitespace.collapse = true;
+    let result = text_to_polly_ssml::parse_str_with_options("${bell}  Ding   dong", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result
+        .unwrap()
+        .ends_with(r#"<mark name="bell"/> Ding dong</speak>"#));
+}
@@ -0,0 +1,69 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::html_to_markup;
+use text_to_polly_ssml::{from_html, OutputTarget, ParseOptions};
+
+#[test]
+fn test_paragraphs_and_emphasis() {
+    assert_eq!(
+        html_to_markup("<P class=\"lead\">One <em>two</em>\n  <b>three</b>.</P>\n\n<p>Four<i>!</i>"),
+        "${p}One ${emphasis|level=moderate}two${/emphasis} ${emphasis|level=strong}three${/emphasis}.${/p}${p}Four${emphasis|level=moderate}!${/emphasis}${/p}"
+    );
+    assert_eq!(html_to_markup(""), "");
+}
+
+#[test]
+fn test_breaks_and_headings() {
+    assert_eq!(
+        html_to_markup("<h2>Setup</h2>Line<br>break<br/>again<hr>"),
+        "${p}${emphasis|level=strong}Setup${/emphasis}${/p}${break|strength=strong}Line${break|strength=medium}break${break|strength=medium}again${break|strength=x-strong}"
+    );
+}
+
+#[test]
+fn test_lists() {
+    assert_eq!(
+        html_to_markup("<ul>\n  <li>One\n  <li><p>Two</p><ol><li>nested</li></ol></li>\n</ul>"),
+        "${p}${s}One ${/s}${s}Two nested ${/s}${/p}"
+    );
+    assert_eq!(
+        html_to_markup("<p>Before<ul><li>item</ul>after"),
+        "${p}Before${/p}${p}${s}item${/s}${/p}after"
+    );
+}
+
+#[test]
+fn test_unsafe_content_is_stripped() {
+    assert_eq!(
+        html_to_markup(
+            "<!DOCTYPE html><html><head><title>T</title><style>p { x: '</p>' }</style></head>\
+             <body><!-- note --><div>Hi<script>alert(\"<p>\")</SCRIPT></div><div>there\
+             <img src=\"a.png\" alt=\"A\"><iframe src=\"x\">frame</iframe></div></body></html>"
+        ),
+        "Hi there "
+    );
+}
+
+#[test]
+fn test_text_is_escaped() {
+    assert_eq!(
+        html_to_markup("1 &lt; 2 &amp;&amp; ${p} &#8217;&#x41;&bogus; & a < b</em>"),
+        "1 < 2 && \\${p} ’A&bogus; & a < b"
+    );
+}
+
+#[test]
+fn test_from_html() {
+    let result = from_html(
+        "<p>Fish &amp; <strong>chips</strong></p><p>Done.</p>",
+        &ParseOptions {
+            target: OutputTarget::Google,
+            ..Default::default()
+        },
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "<speak><p>Fish &amp; <emphasis level=\"strong\">chips</emphasis></p><p>Done.</p></speak>"
+    );
+}
@@ -0,0 +1,79 @@
+extern crate text_to_polly_ssml;
+
+use std::fs;
+use std::path::PathBuf;
+use text_to_polly_ssml::parser::IncludeOptions;
+use text_to_polly_ssml::{ParseOptions, SsmlError};
+
+fn fragments() -> PathBuf {
+    let root = std::env::temp_dir()
+        .join("text-to-polly-ssml-include-test")
+        .join("fragments");
+    fs::create_dir_all(root.join("nested")).unwrap();
+    fs::write(
+        root.join("intro.txt"),
+        "${s}Welcome ${include|file=nested/name.txt}${/s}",
+    )
+    .unwrap();
+    fs::write(root.join("nested").join("name.txt"), "back${break}").unwrap();
+    fs::write(root.join("loop.txt"), "again ${include|file=loop.txt}").unwrap();
+    fs::write(root.join("..").join("secret.txt"), "secret").unwrap();
+    root
+}
+
+fn options(root: PathBuf) -> ParseOptions {
+    ParseOptions {
+        include: IncludeOptions {
+            root: Some(root),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_include_parsing() {
+    let options = options(fragments());
+    let result = text_to_polly_ssml::parse_str_with_options(
+        r#"${p}${include|file=intro.txt}${/p}"#,
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><p><s>Welcome back<break/></s></p></speak>"#
+    );
+}
+
+#[test]
+fn test_include_limits() {
+    let root = fragments();
+
+    let disabled = text_to_polly_ssml::parse_str_with_options(
+        "${include|file=intro.txt}",
+        &Default::default(),
+    );
+    assert!(
+        matches!(disabled, Err(SsmlError::IncludeNotAllowed(_))),
+        "{:?}",
+        disabled
+    );
+
+    let outside = text_to_polly_ssml::parse_str_with_options(
+        "${include|file=../secret.txt}",
+        &options(root.clone()),
+    );
+    assert!(
+        matches!(outside, Err(SsmlError::IncludeNotAllowed(_))),
+        "{:?}",
+        outside
+    );
+
+    let looping =
+        text_to_polly_ssml::parse_str_with_options("${include|file=loop.txt}", &options(root));
+    assert!(
+        matches!(looping, Err(SsmlError::IncludeTooDeep(_))),
+        "{:?}",
+        looping
+    );
+}
@@ -0,0 +1,135 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::language::{
+    is_polly_language, is_valid_language_tag, suggest_language_tag,
+};
+use text_to_polly_ssml::parser::parse_as_ssml_with_report;
+use text_to_polly_ssml::xml_writer::{SpeakOptions, WriterOptions, XmlWriter};
+use text_to_polly_ssml::{OutputTarget, ParseOptions, SsmlError, WarningKind};
+
+#[test]
+fn test_valid_language_tags() {
+    for tag in &[
+        "en",
+        "en-US",
+        "EN-us",
+        "arb",
+        "cmn-CN",
+        "zh-yue-HK",
+        "zh-Hant-TW",
+        "es-419",
+        "sl-rozaj-biske",
+        "de-CH-1901",
+        "en-US-u-ca-gregory",
+        "en-x-private",
+        "x-whatever",
+        "i-klingon",
+    ] {
+        assert!(is_valid_language_tag(tag), "`{}` should be valid", tag);
+    }
+}
+
+#[test]
+fn test_invalid_language_tags() {
+    for tag in &[
+        "", "fr_FR", "123", "e", "en-", "-US", "en--US", "en-US-u", "en-x", "fr FR", "en-US-é",
+    ] {
+        assert!(!is_valid_language_tag(tag), "`{}` should be invalid", tag);
+    }
+}
+
+#[test]
+fn test_suggest_language_tag() {
+    assert_eq!(suggest_language_tag("fr_FR"), Some("fr-FR".to_owned()));
+    assert_eq!(suggest_language_tag("en_us"), Some("en-US".to_owned()));
+    assert_eq!(
+        suggest_language_tag("ZH_hant_tw"),
+        Some("zh-Hant-TW".to_owned())
+    );
+    assert_eq!(suggest_language_tag("en-US"), None);
+    assert_eq!(suggest_language_tag("english please"), None);
+}
+
+#[test]
+fn test_polly_languages() {
+    assert!(is_polly_language("en-GB-WLS"));
+    assert!(is_polly_language("fr-ca"));
+    assert!(!is_polly_language("fr-LU"));
+}
+
+#[test]
+fn test_invalid_language_warnings() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        ..Default::default()
+    };
+    let result = parse_as_ssml_with_report(
+        "${lang|lang=fr_FR}Bonjour${/lang} ${p|lang=de-DE}Hallo${/p} ${s|lang=xx}Hi${/s}",
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<speak><lang xml:lang="fr_FR" onlangfailure="processorchoice">Bonjour</lang> <p xml:lang="de-DE">Hallo</p> <s xml:lang="xx">Hi</s></speak>"#
+    );
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].kind, WarningKind::InvalidLanguage);
+    assert_eq!(report.warnings[0].tag, "lang");
+    assert_eq!(
+        report.warnings[0].message,
+        "`fr_FR` is not a valid language for the `lang` parameter of `lang`, did you mean `fr-FR`?"
+    );
+}
+
+#[test]
+fn test_polly_language_warnings() {
+    let options = ParseOptions {
+        polly_languages: true,
+        speak: SpeakOptions {
+            lang: Some("en-LU".to_owned()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result =
+        parse_as_ssml_with_report("${s|lang=xx}Hi${/s} ${s|lang=fr-CA}Salut${/s}", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let warnings: Vec<(WarningKind, &str)> = result
+        .as_ref()
+        .unwrap()
+        .warnings
+        .iter()
+        .map(|warning| (warning.kind, warning.tag.as_str()))
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (WarningKind::InvalidLanguage, "speak"),
+            (WarningKind::InvalidLanguage, "s"),
+        ]
+    );
+}
+
+#[test]
+fn test_writer_validates_languages() {
+    let mut xml_writer = XmlWriter::with_options(WriterOptions {
+        validate_languages: true,
+        ..Default::default()
+    })
+    .unwrap();
+    let result = xml_writer.start_ssml_speak(Some("fr_FR".to_owned()), None);
+    assert!(matches!(result, Err(SsmlError::InvalidLanguage(ref lang)) if lang == "fr_FR"));
+    assert!(xml_writer
+        .start_ssml_speak(Some("fr-FR".to_owned()), None)
+        .is_ok());
+    assert!(xml_writer
+        .start_ssml_lang("de_DE".to_owned(), None)
+        .is_err());
+    assert!(xml_writer
+        .start_ssml_sentence_with_lang("de-DE".to_owned())
+        .is_ok());
+
+    let mut xml_writer = XmlWriter::new().unwrap();
+    assert!(xml_writer.start_ssml_lang("de_DE".to_owned(), None).is_ok());
+}
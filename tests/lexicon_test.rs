@@ -0,0 +1,80 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{Lexicon, Substitutions};
+use text_to_polly_ssml::ssml_constants::PhonemeAlphabet;
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions, SsmlError};
+
+fn lexicon() -> Lexicon {
+    let mut lexicon = Lexicon::default();
+    lexicon
+        .define("pecan", PhonemeAlphabet::Ipa, "pɪˈkɑːn")
+        .define("SQL", PhonemeAlphabet::XSampa, "\"si:kw@l");
+    lexicon
+}
+
+fn apply(lexicon: &Lexicon, markup: &str) -> String {
+    let result = lexicon.apply(markup);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_apply_lexicon() {
+    let lexicon = lexicon();
+    assert_eq!(
+        apply(&lexicon, "Pecan pecans, sql."),
+        "${phoneme|alphabet=ipa|ph=pɪˈkɑːn}Pecan${/phoneme} pecans, ${phoneme|alphabet=x-sampa|ph=\"si:kw@l}sql${/phoneme}."
+    );
+    assert_eq!(
+        apply(&lexicon, "${say-as|interpret-as=characters}SQL${/say-as}"),
+        "${say-as|interpret-as=characters}SQL${/say-as}"
+    );
+}
+
+#[test]
+fn test_lexicon_matching_options() {
+    let mut lexicon = lexicon();
+    lexicon.case_sensitive = true;
+    lexicon.whole_word = false;
+    assert_eq!(
+        apply(&lexicon, "Pecan pecans, sql."),
+        "Pecan ${phoneme|alphabet=ipa|ph=pɪˈkɑːn}pecan${/phoneme}s, sql."
+    );
+}
+
+#[test]
+fn test_lexicon_option() {
+    let mut substitutions = Substitutions::default();
+    substitutions.define("pecan pie", "dessert");
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        substitutions,
+        lexicon: lexicon(),
+        ..Default::default()
+    };
+    let result = parse_str_with_options("pecan pie, pecan", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak><sub alias="dessert">pecan pie</sub>, <phoneme alphabet="ipa" ph="pɪˈkɑːn">pecan</phoneme></speak>"#
+    );
+}
+
+#[test]
+fn test_lexicon_to_alphabet() {
+    let converted = lexicon().to_alphabet(PhonemeAlphabet::Ipa);
+    assert!(converted.is_ok(), "Result is not okay:\n\n{:?}", converted);
+    let converted = converted.unwrap();
+    assert_eq!(converted.get("SQL").unwrap().ph, "ˈsiːkwəl");
+    assert_eq!(converted.get("SQL").unwrap().alphabet, PhonemeAlphabet::Ipa);
+    assert_eq!(converted.get("pecan").unwrap().ph, "pɪˈkɑːn");
+
+    let mut lexicon = Lexicon::default();
+    lexicon.define("ni", PhonemeAlphabet::Pinyin, "ni3");
+    let result = lexicon.to_alphabet(PhonemeAlphabet::Ipa);
+    assert!(
+        matches!(result, Err(SsmlError::UnsupportedConversion { .. })),
+        "{:?}",
+        result
+    );
+}
@@ -1,6 +1,7 @@
 extern crate text_to_polly_ssml;
 
 #[test]
+#[rustfmt::skip]
 fn test_simple_parsing() {
     let result = text_to_polly_ssml::parse_str(
         r#"${amazon:effect|name=whisper}test${/amazon:effect}"#,
@@ -23,6 +24,89 @@ fn test_only_text_parsing() {
 }
 
 #[test]
+fn test_emphasis_parsing() {
+    let result = text_to_polly_ssml::parse_str(
+        r#"${emphasis|level=strong}really${/emphasis} ${emphasis}quite${/emphasis} ${emphasis|level=loud}not${/emphasis}"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><emphasis level="strong">really</emphasis> <emphasis>quite</emphasis> <emphasis>not</emphasis></speak>"#
+    );
+}
+
+#[test]
+fn test_voice_parsing() {
+    let options = text_to_polly_ssml::ParseOptions {
+        target: text_to_polly_ssml::OutputTarget::Alexa,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(
+        r#"${voice|name=Brian}Hello${/voice} ${voice}nobody${/voice}"#,
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak><voice name="Brian">Hello</voice> nobody</speak>"#
+    );
+}
+
+#[test]
+fn test_paragraph_lang_parsing() {
+    let result = text_to_polly_ssml::parse_str(
+        r#"${p|lang=fr-FR}${s}Bonjour.${/s}${s|lang=de-DE}Hallo.${/s}${/p}"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><p xml:lang="fr-FR"><s>Bonjour.</s><s xml:lang="de-DE">Hallo.</s></p></speak>"#
+    );
+}
+
+#[test]
+fn test_mark_parsing() {
+    let result =
+        text_to_polly_ssml::parse_str(r#"${mark|name=a}Hello ${mark|name=b}world${/mark}"#);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><mark name="a"/>Hello <mark name="b"/>world</speak>"#
+    );
+
+    let options = text_to_polly_ssml::ParseOptions {
+        wrapping_marks: true,
+        strict: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(
+        r#"${mark|name=a}Hello${/mark} world"#,
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><mark name="a">Hello</mark> world</speak>"#
+    );
+}
+
+#[test]
+fn test_wrapping_mark_restructured_parsing() {
+    let mut options = text_to_polly_ssml::ParseOptions {
+        wrapping_marks: true,
+        ..Default::default()
+    };
+    options.whitespace.collapse = true;
+    let result =
+        text_to_polly_ssml::parse_str_with_options(r#"${mark|name=a}hi${/mark}   there"#, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result
+        .unwrap()
+        .ends_with(r#"<mark name="a">hi</mark> there</speak>"#));
+}
+
+#[test]
+#[rustfmt::skip]
 fn test_complex_parsing() {
     let result = text_to_polly_ssml::parse_str(r#"Hello, My name is justin.
 I'm going to stop talking for a bit. ${break} now even longer... ${break|strength=strong|time=4s}
@@ -50,10 +134,10 @@ Finally a newscaster voice! ${amazon:domain|name=news}This is newsworthy!${/amaz
         r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">Hello, My name is justin.
 I&apos;m going to stop talking for a bit. <break/> now even longer... <break strength="strong" time="4s"/>
 I&apos;m going to switch my language. <lang xml:lang="fr_FR" onlangfailure="processorchoice"> hey </lang>, now with an optional fallback: <lang xml:lang="fr_FR" onlangfailure="changevoice"> </lang>
-How about a mark? <mark name="markName"> a name </mark>.
+How about a mark? <mark name="markName"/> a name .
 How about my own paragraph? <p> test </p>
 How about a phoneme? <phoneme alphabet="ipa" ph="pɪˈkɑːn"> pecan </phoneme>
-Now lets go to Prosody. <prosody volume="+6dB"> loud </prosody> Now even more <prosody volume="+6db" rate="x-fast" pitch="+4%"> coffee </prosody>
+Now lets go to Prosody. <prosody volume="+6dB"> loud </prosody> Now even more <prosody volume="+6dB" rate="x-fast" pitch="+4%"> coffee </prosody>
 Now lets go to a sentence. <s> some words. </s>
 Now lets go to say-as: <say-as interpret-as="spell-out"> abc </say-as>.
 What about a Sub? <sub alias="mercury"> hg </sub>
@@ -69,3 +153,1287 @@ Or an even more complex breath! <amazon:breath volume="x-loud" duration="x-long"
 Finally a newscaster voice! <amazon:domain name="news">This is newsworthy!</amazon:domain></speak>"#
     );
 }
+
+#[test]
+fn test_ascii_safe_parsing() {
+    let options = text_to_polly_ssml::ParseOptions {
+        ascii_safe: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(
+        r#"Café ${phoneme|alphabet=ipa|ph=pɪˈkɑːn}pecan${/phoneme}"#,
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">Caf&#xE9; <phoneme alphabet="ipa" ph="p&#x26A;&#x2C8;k&#x251;&#x2D0;n">pecan</phoneme></speak>"#
+    );
+}
+
+#[test]
+fn test_speak_namespace_parsing() {
+    let mut options = text_to_polly_ssml::ParseOptions::default();
+    options.speak.remove_namespace("xsi");
+    options
+        .speak
+        .add_namespace("mstts", "https://www.w3.org/2001/mstts");
+    let result = text_to_polly_ssml::parse_str_with_options("hey world", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:mstts="https://www.w3.org/2001/mstts">hey world</speak>"#
+    );
+}
+
+#[test]
+fn test_speak_version_parsing() {
+    let mut options = text_to_polly_ssml::ParseOptions::default();
+    options.speak.version = Some("1.1".to_owned());
+    options.speak.schema_location =
+        Some(text_to_polly_ssml::xml_writer::SSML_SCHEMA_LOCATION.to_owned());
+    let result = text_to_polly_ssml::parse_str_with_options("hey world", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak version="1.1" xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://www.w3.org/2001/10/synthesis http://www.w3.org/TR/speech-synthesis11/synthesis.xsd">hey world</speak>"#
+    );
+}
+
+#[test]
+fn test_pretty_parsing() {
+    let options = text_to_polly_ssml::ParseOptions {
+        pretty: true,
+        drop_empty_elements: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(
+        "${p}${s}Hello ${break}world${/s}${s}${/s}${/p}",
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?>
+<speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <p>
+    <s>Hello <break/>world</s>
+  </p>
+</speak>"#
+    );
+}
+
+#[test]
+fn test_writer_into_string() {
+    use text_to_polly_ssml::xml_writer::{DeclarationOptions, WriterOptions, XmlWriter};
+
+    let mut xml_writer = XmlWriter::with_options(WriterOptions {
+        pretty: true,
+        ascii_safe: true,
+        declaration: DeclarationOptions {
+            omit: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .unwrap();
+    xml_writer.start_ssml_sentence().unwrap();
+    xml_writer.write_text("Café").unwrap();
+    assert_eq!(xml_writer.peek(), "<s>Café");
+    xml_writer.end_ssml_sentence().unwrap();
+
+    // Rendering doesn't stop the writer from being written to.
+    assert_eq!(xml_writer.render(), "<s>Caf&#xE9;</s>");
+    xml_writer.start_ssml_sentence().unwrap();
+    xml_writer.write_text("Bye").unwrap();
+    xml_writer.end_ssml_sentence().unwrap();
+    assert_eq!(xml_writer.render(), "<s>Caf&#xE9;</s>\n<s>Bye</s>");
+    assert_eq!(xml_writer.into_string(), "<s>Caf&#xE9;</s>\n<s>Bye</s>");
+
+    let mut xml_writer = XmlWriter::with_options(WriterOptions {
+        ascii_safe: true,
+        ..Default::default()
+    })
+    .unwrap();
+    xml_writer.write_text("Café").unwrap();
+    assert!(String::from_utf8(xml_writer.into_inner())
+        .unwrap()
+        .ends_with("Café"));
+}
+
+#[test]
+fn test_writer_render_into() {
+    use text_to_polly_ssml::xml_writer::{DeclarationOptions, WriterOptions, XmlWriter};
+
+    let mut string_buffer = String::new();
+    let mut vec_buffer = Vec::new();
+    for (text, expected) in [("Café", "<s>Caf&#xE9;</s>"), ("über", "<s>&#xFC;ber</s>")] {
+        let mut xml_writer = XmlWriter::with_options(WriterOptions {
+            ascii_safe: true,
+            declaration: DeclarationOptions {
+                omit: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+        xml_writer.start_ssml_sentence().unwrap();
+        xml_writer.write_text(text).unwrap();
+        xml_writer.end_ssml_sentence().unwrap();
+
+        string_buffer.clear();
+        xml_writer.render_into(&mut string_buffer);
+        assert_eq!(string_buffer, expected);
+        vec_buffer.clear();
+        xml_writer.render_into_vec(&mut vec_buffer);
+        assert_eq!(vec_buffer, expected.as_bytes());
+    }
+
+    // The document is appended to what's already in the buffer.
+    let mut xml_writer = XmlWriter::new().unwrap();
+    xml_writer.write_text("Hi").unwrap();
+    xml_writer.render_into(&mut string_buffer);
+    assert_eq!(string_buffer, "<s>&#xFC;ber</s><?xml version=\"1.0\"?>Hi");
+}
+
+#[test]
+fn test_writer_from_writer() {
+    use std::io::Cursor;
+    use text_to_polly_ssml::xml_writer::{WriterOptions, XmlWriter};
+
+    fn write<W: std::io::Write>(xml_writer: &mut XmlWriter<W>) {
+        xml_writer.start_ssml_speak(None, None).unwrap();
+        xml_writer.start_ssml_paragraph().unwrap();
+        xml_writer.start_ssml_sentence().unwrap();
+        xml_writer.write_text("Hello ").unwrap();
+        xml_writer.ssml_break(None, None).unwrap();
+        xml_writer.write_text("wörld").unwrap();
+        xml_writer.end_ssml_sentence().unwrap();
+        xml_writer.end_ssml_paragraph().unwrap();
+        xml_writer.end_ssml_speak().unwrap();
+    }
+
+    for (pretty, ascii_safe) in [(false, false), (true, false), (false, true), (true, true)] {
+        let options = WriterOptions {
+            pretty,
+            ascii_safe,
+            ..Default::default()
+        };
+        let mut in_memory = XmlWriter::with_options(options.clone()).unwrap();
+        write(&mut in_memory);
+        let mut streamed = XmlWriter::from_writer(Vec::new(), options.clone()).unwrap();
+        write(&mut streamed);
+        assert_eq!(
+            String::from_utf8(streamed.into_writer()).unwrap(),
+            in_memory.render()
+        );
+
+        // Rendering a writer that was processed while writing doesn't process it again.
+        let mut cursor = XmlWriter::from_writer(Cursor::new(Vec::new()), options).unwrap();
+        write(&mut cursor);
+        assert_eq!(cursor.into_string(), in_memory.into_string());
+    }
+}
+
+#[test]
+fn test_prosody_volume_parsing() {
+    use text_to_polly_ssml::WarningKind;
+
+    let result = text_to_polly_ssml::parse_with_report(
+        r#"${prosody|volume=-3.5DB}a${/prosody}${prosody|volume=silent}b${/prosody}${prosody|volume=6dB|rate=slow}c${/prosody}${prosody|volume=loudest}d${/prosody}"#,
+        &Default::default(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><prosody volume="-3.5dB">a</prosody><prosody volume="silent">b</prosody><prosody rate="slow">c</prosody>d</speak>"#
+    );
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            WarningKind::InvalidAttributeValue,
+            WarningKind::InvalidAttributeValue,
+            WarningKind::MissingAttribute,
+        ]
+    );
+    assert_eq!(
+        report.warnings[1].message,
+        "`loudest` is not a valid value for the `volume` parameter of `prosody`, expected one \
+         of: default, silent, x-soft, soft, medium, loud, x-loud, +<decibels>dB, -<decibels>dB."
+    );
+}
+
+#[test]
+fn test_break_time_parsing() {
+    use std::time::Duration;
+    use text_to_polly_ssml::ssml_constants::BreakTime;
+
+    let result = text_to_polly_ssml::parse_str(
+        r#"${break|time=1.5s}${break|time=250.5ms}${break|time=-1s}${break|time=1.5}"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><break time="1.5s"/><break time="250.5ms"/><break/><break/></speak>"#
+    );
+
+    assert_eq!(BreakTime::from(Duration::from_secs(2)).to_string(), "2s");
+    assert_eq!(
+        BreakTime::from(Duration::from_millis(1500)).to_string(),
+        "1500ms"
+    );
+    assert_eq!(BreakTime::from_secs_f32(0.25).to_string(), "0.25s");
+
+    for time in &[
+        "1e30s", "+5s", "-0s", "infs", "NaNms", ".5s", "5.s", "1_000ms",
+    ] {
+        assert!(time.parse::<BreakTime>().is_err(), "{} was accepted", time);
+    }
+    assert_eq!(
+        "0.5s".parse::<BreakTime>(),
+        Ok(BreakTime::from_secs_f32(0.5))
+    );
+}
+
+#[test]
+fn test_break_time_range_parsing() {
+    use text_to_polly_ssml::{OutputTarget, WarningKind};
+
+    let markup = r#"${break|time=10s}${break|time=12.5s}${break|time=20000ms}"#;
+    let result = text_to_polly_ssml::parse_with_report(markup, &Default::default());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><break time="10s"/><break time="10s"/><break time="10s"/></speak>"#
+    );
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            WarningKind::OutOfRangeAttributeValue,
+            WarningKind::OutOfRangeAttributeValue,
+        ]
+    );
+
+    let options = text_to_polly_ssml::ParseOptions {
+        target: OutputTarget::Generic,
+        strict: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result
+        .unwrap()
+        .ends_with(r#"<break time="10s"/><break time="12.5s"/><break time="20000ms"/></speak>"#));
+}
+
+#[test]
+fn test_engine_parsing() {
+    use text_to_polly_ssml::{Engine, WarningKind};
+
+    let markup = r#"${amazon:effect|name=whispered}psst${/amazon:effect} ${amazon:effect|name=drc}loud${/amazon:effect} ${prosody|rate=fast|pitch=high}hi${/prosody} ${amazon:domain|name=news}news${/amazon:domain}"#;
+    let options = text_to_polly_ssml::ParseOptions {
+        engine: Some(Engine::Neural),
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_with_report(markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert!(report
+        .ssml
+        .contains(r#"<amazon:effect name="whispered">psst</amazon:effect>"#));
+    let warnings: Vec<(WarningKind, &str, Option<&str>)> = report
+        .warnings
+        .iter()
+        .map(|warning| {
+            (
+                warning.kind,
+                warning.tag.as_str(),
+                warning.attribute.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (
+                WarningKind::UnsupportedByEngine,
+                "amazon:effect",
+                Some("name")
+            ),
+            (WarningKind::UnsupportedByEngine, "prosody", Some("pitch")),
+        ]
+    );
+
+    let options = text_to_polly_ssml::ParseOptions {
+        engine: Some(Engine::Standard),
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_with_report(markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].tag, "amazon:domain");
+    assert_eq!(report.warnings[0].attribute, None);
+}
+
+#[test]
+fn test_vocal_tract_length_parsing() {
+    use text_to_polly_ssml::WarningKind;
+
+    let result = text_to_polly_ssml::parse_with_report(
+        r#"${amazon:effect|vocal-tract-length=150%}a${/amazon:effect}${amazon:effect|vocal-tract-length=-60%}b${/amazon:effect}${amazon:effect|vocal-tract-length=250%}c${/amazon:effect}${amazon:effect|vocal-tract-length=long}d${/amazon:effect}"#,
+        &Default::default(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><amazon:effect vocal-tract-length="150%">a</amazon:effect><amazon:effect vocal-tract-length="-50%">b</amazon:effect><amazon:effect vocal-tract-length="200%">c</amazon:effect>d</speak>"#
+    );
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            WarningKind::OutOfRangeAttributeValue,
+            WarningKind::OutOfRangeAttributeValue,
+            WarningKind::InvalidAttributeValue,
+        ]
+    );
+}
+
+#[test]
+fn test_emotion_parsing() {
+    use text_to_polly_ssml::WarningKind;
+
+    let options = text_to_polly_ssml::ParseOptions {
+        target: text_to_polly_ssml::OutputTarget::Alexa,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_with_report(
+        r#"${amazon:emotion|name=excited|intensity=medium}We won!${/amazon:emotion} ${amazon:emotion|name=disappointed}We lost.${/amazon:emotion}"#,
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<speak><amazon:emotion name="excited" intensity="medium">We won!</amazon:emotion> We lost.</speak>"#
+    );
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(kinds, vec![WarningKind::MissingAttribute]);
+}
+
+#[test]
+fn test_phoneme_alphabet_parsing() {
+    let result = text_to_polly_ssml::parse_str(
+        r#"${phoneme|alphabet=x-amazon-yomigana|ph=かしゅう}華州${/phoneme}${phoneme|alphabet=X-AMAZON-PINYIN|ph=bo2}薄${/phoneme}"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><phoneme alphabet="x-amazon-yomigana" ph="かしゅう">華州</phoneme><phoneme alphabet="x-amazon-pinyin" ph="bo2">薄</phoneme></speak>"#
+    );
+}
+
+#[test]
+fn test_word_role_parsing() {
+    use text_to_polly_ssml::WarningKind;
+
+    let result = text_to_polly_ssml::parse_with_report(
+        r#"${w|role=amazon:DEFAULT}a${/w}${w|role=amazon:nn}b${/w}${w|role=amazon:SENSE_3}c${/w}${w|role=amazon:JJ}d${/w}${w|role=amazon:SENSE_x}e${/w}${w|role=VB}f${/w}"#,
+        &Default::default(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><w role="amazon:DEFAULT">a</w><w role="amazon:NN">b</w><w role="amazon:SENSE_3">c</w><w role="amazon:JJ">d</w>ef</speak>"#
+    );
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            WarningKind::InvalidAttributeValue,
+            WarningKind::InvalidAttributeValue,
+        ]
+    );
+}
+
+#[test]
+fn test_prosody_pitch_parsing() {
+    use text_to_polly_ssml::WarningKind;
+
+    let result = text_to_polly_ssml::parse_with_report(
+        r#"${prosody|pitch=-33.3%}a${/prosody}${prosody|pitch=X-HIGH}b${/prosody}${prosody|pitch=+51%|rate=fast}c${/prosody}${prosody|pitch=10%}d${/prosody}"#,
+        &Default::default(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><prosody pitch="-33.3%">a</prosody><prosody pitch="x-high">b</prosody><prosody rate="fast">c</prosody>d</speak>"#
+    );
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            WarningKind::InvalidAttributeValue,
+            WarningKind::InvalidAttributeValue,
+            WarningKind::MissingAttribute,
+        ]
+    );
+
+    // Only AWS Polly, and Alexa limit pitches to the named ones, and a range in percent.
+    let markup = r#"${prosody|pitch=200Hz}a${/prosody}${prosody|pitch=-2st}b${/prosody}${prosody|pitch=+80%}c${/prosody}"#;
+    let generic = text_to_polly_ssml::ParseOptions {
+        target: text_to_polly_ssml::OutputTarget::Generic,
+        strict: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_with_report(markup, &generic);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    assert!(report.ssml.ends_with(
+        r#"<prosody pitch="200Hz">a</prosody><prosody pitch="-2st">b</prosody><prosody pitch="+80%">c</prosody></speak>"#
+    ));
+
+    let report = text_to_polly_ssml::parse_with_report(markup, &Default::default()).unwrap();
+    let invalid = report
+        .warnings
+        .iter()
+        .filter(|warning| warning.kind == WarningKind::InvalidAttributeValue)
+        .count();
+    assert_eq!(invalid, 3);
+    assert!(report.ssml.ends_with("abc</speak>"));
+    let strict = text_to_polly_ssml::ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+    assert!(text_to_polly_ssml::parse_str_with_options(markup, &strict).is_err());
+}
+
+#[test]
+fn test_prosody_duration_parsing() {
+    use text_to_polly_ssml::{OutputTarget, WarningKind};
+
+    let markup = r#"${prosody|duration=2500ms}slowly${/prosody}"#;
+    let options = text_to_polly_ssml::ParseOptions {
+        target: OutputTarget::Generic,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak version="1.1" xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><prosody duration="2500ms">slowly</prosody></speak>"#
+    );
+
+    let result = text_to_polly_ssml::parse_with_report(markup, &Default::default());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert!(report.ssml.ends_with(">slowly</speak>"));
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            WarningKind::UnsupportedAttribute,
+            WarningKind::MissingAttribute
+        ]
+    );
+}
+
+#[test]
+fn test_prosody_max_duration_parsing() {
+    use text_to_polly_ssml::{OutputTarget, WarningKind};
+
+    let markup = r#"${prosody|max-duration=1500ms}quickly${/prosody}"#;
+    let options = text_to_polly_ssml::ParseOptions {
+        target: OutputTarget::Alexa,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak><prosody amazon:max-duration="1500ms">quickly</prosody></speak>"#
+    );
+
+    let options = text_to_polly_ssml::ParseOptions {
+        target: OutputTarget::Generic,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_with_report(markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert!(report.ssml.ends_with(">quickly</speak>"));
+    assert_eq!(report.warnings[0].kind, WarningKind::UnsupportedAttribute);
+}
+
+#[test]
+fn test_declaration_parsing() {
+    use text_to_polly_ssml::xml_writer::DeclarationOptions;
+
+    let mut options = text_to_polly_ssml::ParseOptions {
+        declaration: DeclarationOptions {
+            encoding: Some("UTF-8".to_owned()),
+            standalone: Some(false),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    options.speak.namespaces.clear();
+    let result = text_to_polly_ssml::parse_str_with_options("hey", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><speak xml:lang="en-US" onlangfailure="processorchoice">hey</speak>"#
+    );
+
+    options.declaration.omit = true;
+    let result = text_to_polly_ssml::parse_str_with_options("hey", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak xml:lang="en-US" onlangfailure="processorchoice">hey</speak>"#
+    );
+}
+
+#[test]
+fn test_alexa_parsing() {
+    let options = text_to_polly_ssml::ParseOptions {
+        target: text_to_polly_ssml::OutputTarget::Alexa,
+        drop_empty_elements: true,
+        ..Default::default()
+    };
+    let result =
+        text_to_polly_ssml::parse_str_with_options("${s}${/s}hey ${p}world${/p}", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(result.unwrap(), r#"<speak>hey <p>world</p></speak>"#);
+}
+
+#[test]
+fn test_target_parsing() {
+    use text_to_polly_ssml::{OutputTarget, WarningKind};
+
+    let markup = r#"${voice|name=en-US-JennyNeural}${mark|name=start}Hi ${amazon:effect|name=whispered}there${/amazon:effect}${/voice}"#;
+    let ssml_for = |target: OutputTarget| {
+        let options = text_to_polly_ssml::ParseOptions {
+            target,
+            ..Default::default()
+        };
+        text_to_polly_ssml::parse_with_report(markup, &options)
+    };
+
+    let result = ssml_for(OutputTarget::Google);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<speak><voice name="en-US-JennyNeural"><mark name="start"/>Hi there</voice></speak>"#
+    );
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(kinds, vec![WarningKind::UnsupportedTag]);
+
+    let result = ssml_for(OutputTarget::Azure);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap().ssml,
+        r#"<?xml version="1.0"?><speak version="1.0" xml:lang="en-US" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:mstts="https://www.w3.org/2001/mstts"><voice name="en-US-JennyNeural">Hi there</voice></speak>"#
+    );
+
+    let result = ssml_for(OutputTarget::Polly);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result.unwrap().ssml.ends_with(
+        r#"><mark name="start"/>Hi <amazon:effect name="whispered">there</amazon:effect></speak>"#
+    ));
+
+    let options = text_to_polly_ssml::ParseOptions {
+        target: OutputTarget::Polly,
+        strict: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(markup, &options);
+    assert!(result.is_err(), "{} should not parse", markup);
+}
+
+#[test]
+fn test_silence_parsing() {
+    use text_to_polly_ssml::{OutputTarget, WarningKind};
+
+    let markup = r#"${silence|type=Sentenceboundary|value=200ms}One. Two.${silence|type=leading}"#;
+    let options = text_to_polly_ssml::ParseOptions {
+        target: OutputTarget::Azure,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_with_report(markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert!(report
+        .ssml
+        .ends_with(r#"><mstts:silence type="Sentenceboundary" value="200ms"/>One. Two.</speak>"#));
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(kinds, vec![WarningKind::MissingAttribute]);
+
+    let result = text_to_polly_ssml::parse_with_report(markup, &Default::default());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert!(report.ssml.ends_with(r#">One. Two.</speak>"#));
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![WarningKind::UnsupportedTag, WarningKind::UnsupportedTag]
+    );
+}
+
+#[test]
+fn test_bookmark_parsing() {
+    let options = text_to_polly_ssml::ParseOptions {
+        target: text_to_polly_ssml::OutputTarget::Azure,
+        strict: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(
+        r#"Welcome.${bookmark|mark=intro_done} Let's start."#,
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result
+        .unwrap()
+        .ends_with(r#">Welcome.<bookmark mark="intro_done"/> Let&apos;s start.</speak>"#));
+}
+
+#[test]
+fn test_timeline_parsing() {
+    let markup = r#"${par}${media|id=question|begin=0.5s}Who is it?${/media}${media|begin=question.end+1s|soundLevel=-6dB}${seq}${media}Me.${/media}${/seq}${/media}${/par}"#;
+    let options = text_to_polly_ssml::ParseOptions {
+        target: text_to_polly_ssml::OutputTarget::Google,
+        strict: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak><par><media xml:id="question" begin="0.5s">Who is it?</media><media begin="question.end+1s" soundLevel="-6dB"><seq><media>Me.</media></seq></media></par></speak>"#
+    );
+
+    let result = text_to_polly_ssml::parse_str(markup);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result.unwrap().ends_with(r#">Who is it?Me.</speak>"#));
+}
+
+#[test]
+fn test_express_as_parsing() {
+    let markup = r#"${express-as|type=Apology}Sorry, we're closed.${/express-as}"#;
+    let options = text_to_polly_ssml::ParseOptions {
+        target: text_to_polly_ssml::OutputTarget::Watson,
+        strict: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak><express-as type="Apology">Sorry, we&apos;re closed.</express-as></speak>"#
+    );
+
+    let result = text_to_polly_ssml::parse_str(markup);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result
+        .unwrap()
+        .ends_with(r#">Sorry, we&apos;re closed.</speak>"#));
+}
+
+#[test]
+fn test_generic_parsing() {
+    use text_to_polly_ssml::WarningKind;
+
+    let options = text_to_polly_ssml::ParseOptions {
+        target: text_to_polly_ssml::OutputTarget::Generic,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_with_report(
+        r#"${amazon:effect|name=whispered}${w|role=amazon:VB}read${/w} ${phoneme|alphabet=x-amazon-pinyin|ph=bo2}bo${/phoneme} ${phoneme|alphabet=ipa|ph=pɪˈkɑːn}pecan${/phoneme}${/amazon:effect}${amazon:breath}"#,
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<?xml version="1.0"?><speak version="1.1" xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">read bo <phoneme alphabet="ipa" ph="pɪˈkɑːn">pecan</phoneme></speak>"#
+    );
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|warning| warning.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            WarningKind::UnsupportedTag,
+            WarningKind::UnsupportedTag,
+            WarningKind::UnsupportedAttribute,
+            WarningKind::UnsupportedTag,
+        ]
+    );
+}
+
+#[test]
+fn test_drop_empty_elements_parsing() {
+    let options = text_to_polly_ssml::ParseOptions {
+        drop_empty_elements: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(
+        r#"${p}${s}${/s}${/p}Hello${mark|name=here}${/mark} ${prosody|rate=fast}${/prosody}${s}world${/s}"#,
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">Hello<mark name="here"/> <s>world</s></speak>"#
+    );
+}
+
+#[test]
+fn test_multiple_documents_parsing() {
+    let result = text_to_polly_ssml::parse_documents(
+        "Hello ${break}\n${---}\n${p}World${/p}\n  ${---}  \n\n${---}\n",
+        &Default::default(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">Hello <break/></speak>"#.to_owned(),
+            r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><p>World</p></speak>"#.to_owned(),
+        ]
+    );
+}
+
+#[test]
+fn test_strict_parsing() {
+    let options = text_to_polly_ssml::ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+    let valid = text_to_polly_ssml::parse_str_with_options(
+        r#"${p}${s}Hello ${break}${amazon:breath}${/s}${/p}"#,
+        &options,
+    );
+    assert!(valid.is_ok(), "Result is not okay:\n\n{:?}", valid);
+
+    for invalid in &[
+        r#"${p}never closed"#,
+        r#"closed without opening${/p}"#,
+        r#"${p}${s}out of order${/p}${/s}"#,
+        r#"${brake}typo"#,
+        r#"${p}typo${/pp}"#,
+        r#"${break|time=1s|time=2s}"#,
+        r#"${break|tiem=1s}"#,
+        r#"${break|time=10001ms}"#,
+        r#"${amazon:effect|vocal-tract-length=+101%}too long${/amazon:effect}"#,
+    ] {
+        let result = text_to_polly_ssml::parse_str_with_options(invalid, &options);
+        assert!(result.is_err(), "{} should not parse", invalid);
+    }
+}
+
+#[test]
+fn test_report_parsing() {
+    use text_to_polly_ssml::WarningKind;
+
+    let result = text_to_polly_ssml::parse_with_report(
+        r#"${lang}Hallo${/lang} ${break|strength=loud}${brake}${prosody}fast${/prosody}"#,
+        &Default::default(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">Hallo <break/>fast</speak>"#
+    );
+
+    let warnings: Vec<(WarningKind, &str, Option<&str>)> = report
+        .warnings
+        .iter()
+        .map(|warning| {
+            (
+                warning.kind,
+                warning.tag.as_str(),
+                warning.attribute.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (WarningKind::MissingAttribute, "lang", Some("lang")),
+            (
+                WarningKind::InvalidAttributeValue,
+                "break",
+                Some("strength")
+            ),
+            (WarningKind::UnknownTag, "brake", None),
+            (WarningKind::MissingAttribute, "prosody", None),
+        ]
+    );
+}
+
+#[test]
+fn test_source_map() {
+    let markup = "${p}Tom & Jerry${/p} ${break|time=1s}${brake}";
+    let result = text_to_polly_ssml::parse_with_report(markup, &Default::default());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    let mapped: Vec<(&str, &str)> = report
+        .source_map
+        .iter()
+        .map(|mapping| {
+            (
+                &report.ssml[mapping.output.clone()],
+                mapping.input.snippet(markup),
+            )
+        })
+        .collect();
+    assert_eq!(
+        mapped,
+        vec![
+            ("<p>", "${p}"),
+            ("Tom &amp; Jerry", "Tom & Jerry"),
+            ("</p>", "${/p}"),
+            (" ", " "),
+            (r#"<break time="1s"/>"#, "${break|time=1s}"),
+        ]
+    );
+    let offset = report.ssml.find("Jerry").unwrap();
+    assert_eq!(
+        report.source_span(offset).map(|span| span.snippet(markup)),
+        Some("Tom & Jerry")
+    );
+    assert_eq!(report.source_span(0), None);
+
+    let options = text_to_polly_ssml::ParseOptions {
+        pretty: true,
+        ..Default::default()
+    };
+    let report = text_to_polly_ssml::parse_with_report(markup, &options).unwrap();
+    assert!(report.source_map.is_empty());
+}
+
+#[test]
+fn test_restructured_source_map() {
+    let markup = "Hi  ${brake}\n  ${p}Tom   &  Jerry${/p}";
+    let mut options = text_to_polly_ssml::ParseOptions::default();
+    options.whitespace.collapse = true;
+    let result = text_to_polly_ssml::parse_with_report(markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    let span = report.warnings[0].span.expect("warning has no span");
+    assert_eq!((span.line, span.column), (1, 5));
+    assert_eq!(span.snippet(markup), "${brake}");
+    let mapped: Vec<(&str, &str)> = report
+        .source_map
+        .iter()
+        .map(|mapping| {
+            (
+                &report.ssml[mapping.output.clone()],
+                mapping.input.snippet(markup),
+            )
+        })
+        .collect();
+    assert_eq!(
+        mapped,
+        vec![
+            ("Hi ", "Hi  "),
+            (" ", "\n  "),
+            ("<p>", "${p}"),
+            ("Tom &amp; Jerry", "Tom   &  Jerry"),
+            ("</p>", "${/p}"),
+        ]
+    );
+}
+
+#[test]
+fn test_auto_marks() {
+    use text_to_polly_ssml::parser::{parse_tree, render_tree, AutoMarks};
+
+    let markup = "Hi there. ${sub|alias=World Wide Web}WWW${/sub} rocks!  ${p}New${/p}";
+    let words = text_to_polly_ssml::ParseOptions {
+        target: text_to_polly_ssml::OutputTarget::Google,
+        auto_marks: AutoMarks::Words,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(markup, &words);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak><mark name="w0"/>Hi <mark name="w1"/>there. <sub alias="World Wide Web">WWW</sub> <mark name="w2"/>rocks!  <p><mark name="w3"/>New</p></speak>"#
+    );
+
+    let sentences = text_to_polly_ssml::ParseOptions {
+        auto_marks: AutoMarks::Sentences,
+        ..words
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(markup, &sentences);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let expected = r#"<speak><mark name="s0"/>Hi there. <sub alias="World Wide Web">WWW</sub> <mark name="s1"/>rocks!  <p><mark name="s2"/>New</p></speak>"#;
+    assert_eq!(result.unwrap(), expected);
+    assert_eq!(
+        render_tree(&parse_tree(markup).unwrap(), &sentences).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_error_kinds() {
+    use text_to_polly_ssml::SsmlError;
+
+    let options = text_to_polly_ssml::ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+    let unknown = text_to_polly_ssml::parse_str_with_options("${brake}typo", &options);
+    assert!(
+        matches!(unknown, Err(SsmlError::UnknownTag(ref tag)) if tag == "brake"),
+        "{:?}",
+        unknown
+    );
+    let unclosed = text_to_polly_ssml::parse_str_with_options("${p}never closed", &options);
+    assert!(
+        matches!(unclosed, Err(SsmlError::UnclosedTag(ref tag)) if tag == "p"),
+        "{:?}",
+        unclosed
+    );
+    let misnested =
+        text_to_polly_ssml::parse_str_with_options("${p}${s}out of order${/p}${/s}", &options);
+    assert!(
+        matches!(misnested, Err(SsmlError::MisnestedTag { .. })),
+        "{:?}",
+        misnested
+    );
+}
+
+#[test]
+fn test_parse_error_position() {
+    use text_to_polly_ssml::SsmlError;
+
+    let options = text_to_polly_ssml::ParseOptions {
+        unterminated: text_to_polly_ssml::UnterminatedPolicy::Error,
+        ..Default::default()
+    };
+    let result =
+        text_to_polly_ssml::parse_str_with_options("line one\nprice ${p}is ${10 dollars", &options);
+    match result {
+        Err(SsmlError::ParseError {
+            line,
+            column,
+            ref snippet,
+            ..
+        }) => {
+            assert_eq!((line, column), (2, 14));
+            assert_eq!(snippet, "${10 dollars");
+        }
+        other => panic!("Expected a parse error, got: {:?}", other),
+    }
+
+    let report = text_to_polly_ssml::parse_with_report("Hi\n  ${brake}there", &Default::default());
+    assert!(report.is_ok(), "Result is not okay:\n\n{:?}", report);
+    let warning = &report.unwrap().warnings[0];
+    let span = warning.span.expect("warning has no span");
+    assert_eq!((span.line, span.column), (2, 3));
+    assert_eq!(span.snippet("Hi\n  ${brake}there"), "${brake}");
+}
+
+#[test]
+fn test_trailing_text_parsing() {
+    let result = text_to_polly_ssml::parse_str(r#"${s}hi${/s} bye"#);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><s>hi</s> bye</speak>"#
+    );
+}
+
+#[test]
+fn test_unterminated_parsing() {
+    let result = text_to_polly_ssml::parse_str(r#"${s}price is ${10${/s} dollars ${"#);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><s>price is ${10</s> dollars ${</speak>"#
+    );
+}
+
+#[test]
+fn test_attribute_report_parsing() {
+    use text_to_polly_ssml::WarningKind;
+
+    let result = text_to_polly_ssml::parse_with_report(
+        r#"${break|time=1s|time=2s|tiem=3s}${s|voice=fr}Salut${/s}"#,
+        &Default::default(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><break time="2s"/><s>Salut</s></speak>"#
+    );
+
+    let warnings: Vec<(WarningKind, &str, Option<&str>)> = report
+        .warnings
+        .iter()
+        .map(|warning| {
+            (
+                warning.kind,
+                warning.tag.as_str(),
+                warning.attribute.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (WarningKind::DuplicateAttribute, "break", Some("time")),
+            (WarningKind::UnknownAttribute, "break", Some("tiem")),
+            (WarningKind::UnknownAttribute, "s", Some("voice")),
+        ]
+    );
+
+    // Restructuring renders from the tree, which reports the same warnings.
+    let mut options = text_to_polly_ssml::ParseOptions::default();
+    options.whitespace.collapse = true;
+    let result = text_to_polly_ssml::parse_with_report("${break|time=1s|time=2s} hi", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].kind, WarningKind::DuplicateAttribute);
+    assert_eq!(report.warnings[0].attribute.as_deref(), Some("time"));
+    let span = report.warnings[0].span.expect("warning has no span");
+    assert_eq!(span.offset, 0);
+}
+
+#[test]
+fn test_escape_parsing() {
+    let result = text_to_polly_ssml::parse_str(
+        r#"${sub|alias=a\|b\}}\${price}${/sub} costs \\${break} or $\{legacy} C:\dir"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><sub alias="a|b}">${price}</sub> costs \<break/> or ${legacy} C:\dir</speak>"#
+    );
+}
+
+#[test]
+fn test_escape_round_trip() {
+    use text_to_polly_ssml::parser::{escape_param, escape_text, tokenize};
+
+    let text = r#"${not a tag} \${ \\ $\{ C:\dir\"#;
+    let escaped = escape_text(text);
+    let items = tokenize(&escaped).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].data.as_deref(), Some(text));
+
+    let value = r#"a|b=c}${d}\"#;
+    let markup = format!("${{sub|alias={}}}", escape_param(value));
+    let items = tokenize(&markup).unwrap();
+    assert_eq!(items.len(), 1);
+    let start_tag = items[0].start_tag.as_ref().unwrap();
+    assert_eq!(
+        start_tag.params.get("alias").map(AsRef::as_ref),
+        Some(value)
+    );
+}
+
+#[test]
+fn test_tokenize_borrows() {
+    use std::borrow::Cow;
+    use text_to_polly_ssml::parser::tokenize;
+
+    let markup = r#"${prosody|rate=slow}Hi ${10 there${/prosody} ${sub|alias=a\|b}x\${y}${/sub}"#;
+    let items = tokenize(markup).unwrap();
+    let start_tag = items[0].start_tag.as_ref().unwrap();
+    assert!(matches!(start_tag.tag_key, Cow::Borrowed("prosody")));
+    assert!(matches!(
+        start_tag.params.get_key_value("rate"),
+        Some((Cow::Borrowed("rate"), Cow::Borrowed("slow")))
+    ));
+    // A `${` that is never closed is merged into the text around it, without copying it.
+    assert!(matches!(
+        items[1].data,
+        Some(Cow::Borrowed("Hi ${10 there"))
+    ));
+    assert!(matches!(
+        items[2].end_tag.as_ref().unwrap().tag_key,
+        Cow::Borrowed("prosody")
+    ));
+
+    // Only what contains escapes is copied.
+    let start_tag = items[4].start_tag.as_ref().unwrap();
+    assert!(matches!(start_tag.params.get("alias"), Some(Cow::Owned(alias)) if alias == "a|b"));
+    assert!(matches!(items[5].data, Some(Cow::Owned(ref text)) if text == "x${y}"));
+}
+
+#[test]
+fn test_ssml_events() {
+    use text_to_polly_ssml::parser::{
+        tokenize, tokenize_with_policy, SsmlEvent, SsmlEvents, UnterminatedPolicy,
+    };
+    use text_to_polly_ssml::SsmlError;
+
+    for markup in [
+        "",
+        "plain \\\\ text",
+        "${s}Hello${/s}",
+        "Hi ${10 there${s}x\\${y}${/s}${",
+        "${sub|alias=a\\|b}${ ${ ${/sub}",
+    ] {
+        let items = tokenize(markup).unwrap();
+        let events = SsmlEvents::new(markup).collect::<Result<Vec<_>, _>>();
+        assert!(events.is_ok(), "Result is not okay:\n\n{:?}", events);
+        let events = events.unwrap();
+        assert_eq!(events.len(), items.len(), "{}", markup);
+        for ((event, span), item) in events.into_iter().zip(items) {
+            assert_eq!(span, item.span);
+            match event {
+                SsmlEvent::Start(start_tag) => {
+                    let item_tag = item.start_tag.unwrap();
+                    assert_eq!(start_tag.tag_key, item_tag.tag_key);
+                    assert_eq!(start_tag.params, item_tag.params);
+                }
+                SsmlEvent::End(end_tag) => {
+                    assert_eq!(end_tag.tag_key, item.end_tag.unwrap().tag_key)
+                }
+                SsmlEvent::Text(text) => assert_eq!(Some(text), item.data),
+            }
+        }
+    }
+
+    // Only as much is tokenized as is iterated, so the events before an error are yielded.
+    let markup = "${s}Hi${/s} ${s";
+    assert!(tokenize_with_policy(markup, UnterminatedPolicy::Error).is_err());
+    let mut events = SsmlEvents::with_policy(markup, UnterminatedPolicy::Error);
+    assert!(matches!(events.next(), Some(Ok((SsmlEvent::Start(_), _)))));
+    assert_eq!(events.remaining(), "Hi${/s} ${s");
+    assert!(matches!(events.nth(2), Some(Ok((SsmlEvent::Text(text), _))) if text == " "));
+    assert!(matches!(
+        events.next(),
+        Some(Err(SsmlError::ParseError { column: 13, .. }))
+    ));
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn test_unknown_tag_policy_parsing() {
+    use text_to_polly_ssml::{SsmlError, UnknownTagPolicy};
+
+    let literal = text_to_polly_ssml::ParseOptions {
+        unknown_tags: UnknownTagPolicy::Literal,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(
+        r#"${s}Hello ${brake|time=1s}world${/brake}${/s}"#,
+        &literal,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><s>Hello ${brake|time=1s}world${/brake}</s></speak>"#
+    );
+
+    // Rendering from the tree when restructuring keeps the tags exactly as written too.
+    let mut restructured = literal.clone();
+    restructured.whitespace.collapse = true;
+    for markup in &["Price ${foo} bar", r#"${foo|b=1|a=\}}x${/FOO} y"#] {
+        assert_eq!(
+            text_to_polly_ssml::parse_str_with_options(markup, &restructured).unwrap(),
+            text_to_polly_ssml::parse_str_with_options(markup, &literal).unwrap()
+        );
+    }
+
+    let error = text_to_polly_ssml::ParseOptions {
+        unknown_tags: UnknownTagPolicy::Error,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(r#"Hello ${brake}world"#, &error);
+    assert!(
+        matches!(result, Err(SsmlError::UnknownTag(ref tag)) if tag == "brake"),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn test_passthrough_parsing() {
+    use text_to_polly_ssml::{SsmlError, UnknownTagPolicy};
+
+    let options = text_to_polly_ssml::ParseOptions {
+        unknown_tags: UnknownTagPolicy::Passthrough,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(
+        r#"${s}${x:custom|attr=val}Hello${/x:custom}${/s}"#,
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><s><x:custom attr="val">Hello</x:custom></s></speak>"#
+    );
+
+    let result = text_to_polly_ssml::parse_str_with_options(r#"${a b}Hello${/a b}"#, &options);
+    assert!(
+        matches!(result, Err(SsmlError::InvalidName(ref name)) if name == "a b"),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn test_macro_parsing() {
+    let mut options = text_to_polly_ssml::ParseOptions::default();
+    options
+        .macros
+        .define("shout", "${prosody|volume=x-loud|rate=fast}")
+        .define("aside", "${p}${amazon:effect|name=whispered}");
+    let result = text_to_polly_ssml::parse_str_with_options(
+        r#"${shout|rate=slow}Hey${/shout} ${Aside}psst${/aside}"#,
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><prosody volume="x-loud" rate="slow">Hey</prosody> <p><amazon:effect name="whispered">psst</amazon:effect></p></speak>"#
+    );
+}
+
+#[test]
+fn test_max_input_len() {
+    let options = text_to_polly_ssml::ParseOptions {
+        max_input_len: Some(16),
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options("${s}Short${/s}", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+
+    let result = text_to_polly_ssml::parse_str_with_options("${s}Much too long${/s}", &options);
+    assert!(matches!(
+        result,
+        Err(text_to_polly_ssml::SsmlError::InputTooLong {
+            len: 22,
+            max_len: 16
+        })
+    ));
+
+    // The whole input counts, even if every document in it is short enough.
+    let result = text_to_polly_ssml::parse_documents("One\n${---}\nTwo\n${---}\nThree", &options);
+    assert!(matches!(
+        result,
+        Err(text_to_polly_ssml::SsmlError::InputTooLong { .. })
+    ));
+    let result =
+        text_to_polly_ssml::parser::parse_as_ssml_chunked("${s}Much too long${/s}", 3000, &options);
+    assert!(result.is_err());
+}
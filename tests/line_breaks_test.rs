@@ -0,0 +1,67 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{break_lines, LineBreaks, SentenceRules};
+use text_to_polly_ssml::ssml_constants::{BreakStrength, BreakTime};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+fn break_with(markup: &str, line_breaks: &LineBreaks) -> String {
+    let result = break_lines(markup, line_breaks);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_break_plain_text() {
+    let line_breaks = LineBreaks::default();
+    assert_eq!(
+        break_with("\nOne,\r\n  two.\n\nThree \n four\n", &line_breaks),
+        "\nOne,\r\n  ${break|strength=medium}two.\n\nThree \n ${break|strength=medium}four\n"
+    );
+    assert_eq!(break_with("One line.", &line_breaks), "One line.");
+    assert_eq!(break_with("\n", &line_breaks), "\n");
+}
+
+#[test]
+fn test_break_with_tags() {
+    let line_breaks = LineBreaks {
+        strength: None,
+        time: Some(BreakTime::new(300, false)),
+    };
+    assert_eq!(
+        break_with(
+            "${p}\nOne\ntwo\n${/p}\n${sub|alias=A\nB}x\ny${/sub}",
+            &line_breaks
+        ),
+        "${p}\nOne\n${break|time=300ms}two\n${/p}\n${break|time=300ms}${sub|alias=A\nB}x\ny${/sub}"
+    );
+}
+
+#[test]
+fn test_line_breaks_option() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        line_breaks: Some(LineBreaks {
+            strength: Some(BreakStrength::Strong),
+            time: None,
+        }),
+        ..Default::default()
+    };
+    let result = parse_str_with_options("One.\nTwo.", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "<speak>One.\n<break strength=\"strong\"/>Two.</speak>"
+    );
+
+    let options = ParseOptions {
+        paragraphs: true,
+        sentences: Some(SentenceRules::for_locale("en-US")),
+        ..options
+    };
+    let result = parse_str_with_options("One.\nTwo.\n\nThree.", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "<speak><p><s>One.</s>\n<break strength=\"strong\"/><s>Two.</s></p>\n\n<p><s>Three.</s></p></speak>"
+    );
+}
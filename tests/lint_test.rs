@@ -0,0 +1,170 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::lint_with_options;
+use text_to_polly_ssml::{
+    lint, Diagnostic, DiagnosticKind, ParseOptions, Severity, UnknownTagPolicy, UnterminatedPolicy,
+    WarningKind,
+};
+
+fn summary(diagnostics: &[Diagnostic]) -> Vec<(Severity, DiagnosticKind, Option<&str>)> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.severity,
+                diagnostic.kind,
+                diagnostic.tag.as_deref(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_lint_clean_markup() {
+    let diagnostics = lint("${p}${s}Hello ${emphasis|level=strong}there${/emphasis}.${/s}${/p}");
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+}
+
+#[test]
+fn test_lint_unbalanced_tags() {
+    let markup = "${p}${s}One${/p} two${/emphasis} ${prosody|rate=slow}three";
+    let diagnostics = lint(markup);
+    assert_eq!(
+        summary(&diagnostics),
+        vec![
+            (Severity::Error, DiagnosticKind::MisnestedTag, Some("p")),
+            (
+                Severity::Error,
+                DiagnosticKind::UnopenedTag,
+                Some("emphasis")
+            ),
+            (
+                Severity::Error,
+                DiagnosticKind::UnclosedTag,
+                Some("prosody")
+            ),
+        ]
+    );
+    assert_eq!(
+        diagnostics[0].message,
+        "`p` is closed while `s` is still open inside of it."
+    );
+    let span = diagnostics[2].span.unwrap();
+    assert_eq!(span.snippet(markup), "${prosody|rate=slow}");
+    assert_eq!((span.line, span.column), (1, 34));
+}
+
+#[test]
+fn test_lint_rendering_warnings() {
+    let diagnostics = lint(
+        "${break|time=20s|size=big} ${say-as}123${/say-as} ${emphasis|level=loud}Hi${/emphasis} \
+         ${s}${p}Nested${/p}${/s} ${unknown}dropped${/unknown}",
+    );
+    let kinds: Vec<(Severity, DiagnosticKind, Option<&str>)> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.severity,
+                diagnostic.kind,
+                diagnostic.attribute.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (
+                Severity::Warning,
+                DiagnosticKind::Render(WarningKind::UnknownAttribute),
+                Some("size")
+            ),
+            (
+                Severity::Warning,
+                DiagnosticKind::Render(WarningKind::OutOfRangeAttributeValue),
+                Some("time")
+            ),
+            (
+                Severity::Error,
+                DiagnosticKind::Render(WarningKind::MissingAttribute),
+                Some("interpret-as")
+            ),
+            (
+                Severity::Warning,
+                DiagnosticKind::Render(WarningKind::InvalidAttributeValue),
+                Some("level")
+            ),
+            (
+                Severity::Error,
+                DiagnosticKind::Render(WarningKind::IllegalNesting),
+                None
+            ),
+            (
+                Severity::Warning,
+                DiagnosticKind::Render(WarningKind::UnknownTag),
+                None
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_lint_deprecated_spellings() {
+    let markup = "${amazon:effect|name=whisper}psst${/amazon:effect} $\\{not a tag}";
+    let diagnostics = lint(markup);
+    assert_eq!(
+        summary(&diagnostics),
+        vec![
+            (
+                Severity::Hint,
+                DiagnosticKind::Deprecated,
+                Some("amazon:effect")
+            ),
+            (Severity::Hint, DiagnosticKind::Deprecated, None),
+        ]
+    );
+    assert_eq!(
+        diagnostics[0].message,
+        "`whisper` is a deprecated value for the `name` parameter of `amazon:effect`, use one of: whispered, drc."
+    );
+    assert_eq!(diagnostics[0].attribute.as_deref(), Some("name"));
+    assert_eq!(diagnostics[0].span.unwrap().snippet(markup), "name=whisper");
+}
+
+#[test]
+fn test_lint_syntax_error() {
+    let options = ParseOptions {
+        unterminated: UnterminatedPolicy::Error,
+        ..Default::default()
+    };
+    let markup = "line one\nprice ${p}is ${10 dollars";
+    let diagnostics = lint_with_options(markup, &options);
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[0].kind, DiagnosticKind::Syntax);
+    let span = diagnostics[0].span.unwrap();
+    assert_eq!((span.line, span.column), (2, 14));
+    assert_eq!(span.snippet(markup), "${10 dollars");
+}
+
+#[test]
+fn test_lint_unknown_tag_policies() {
+    let markup = "${x:custom}Hi${/x:custom}";
+    let options = ParseOptions {
+        unknown_tags: UnknownTagPolicy::Passthrough,
+        ..Default::default()
+    };
+    assert!(lint_with_options(markup, &options).is_empty());
+
+    let options = ParseOptions {
+        unknown_tags: UnknownTagPolicy::Error,
+        ..Default::default()
+    };
+    assert_eq!(
+        summary(&lint_with_options(markup, &options)),
+        vec![(
+            Severity::Error,
+            DiagnosticKind::Render(WarningKind::UnknownTag),
+            Some("x:custom")
+        )]
+    );
+}
@@ -0,0 +1,197 @@
+#![cfg(feature = "lsp")]
+
+extern crate text_to_polly_ssml;
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId};
+use lsp_types::{
+    CompletionTextEdit, DiagnosticSeverity, HoverContents, Position, PublishDiagnosticsParams,
+    Range,
+};
+use text_to_polly_ssml::lsp::{completions, diagnostics, hover, serve};
+use text_to_polly_ssml::ParseOptions;
+
+use std::thread;
+
+fn labels(text: &str, line: u32, character: u32) -> Vec<String> {
+    completions(text, Position::new(line, character))
+        .into_iter()
+        .map(|item| item.label)
+        .collect()
+}
+
+#[test]
+fn test_diagnostics() {
+    let text = "${p}Hi\n${s}thére ${break|time=20s}";
+    let diagnostics = diagnostics(text, &ParseOptions::default());
+    let summary: Vec<(Option<DiagnosticSeverity>, Range)> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.severity, diagnostic.range))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                Some(DiagnosticSeverity::WARNING),
+                Range::new(Position::new(1, 18), Position::new(1, 26))
+            ),
+            (
+                Some(DiagnosticSeverity::ERROR),
+                Range::new(Position::new(0, 0), Position::new(0, 4))
+            ),
+            (
+                Some(DiagnosticSeverity::ERROR),
+                Range::new(Position::new(1, 0), Position::new(1, 4))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_tag_completions() {
+    let names = labels("Hi ${", 0, 5);
+    assert!(names.contains(&"prosody".to_owned()));
+    assert!(names.contains(&"amazon:effect".to_owned()));
+
+    let items = completions("Hi ${pro", Position::new(0, 8));
+    let prosody = items.iter().find(|item| item.label == "prosody").unwrap();
+    match prosody.text_edit {
+        Some(CompletionTextEdit::Edit(ref edit)) => {
+            assert_eq!(
+                edit.range,
+                Range::new(Position::new(0, 5), Position::new(0, 8))
+            );
+            assert_eq!(edit.new_text, "prosody");
+        }
+        ref other => panic!("Expected a text edit, got: {:?}", other),
+    }
+
+    // Nothing to complete outside of a tag.
+    assert!(labels("${p}Hi", 0, 6).is_empty());
+}
+
+#[test]
+fn test_param_completions() {
+    assert_eq!(labels("${emphasis|", 0, 11), vec!["level"]);
+    assert_eq!(
+        labels("${emphasis|level=", 0, 17),
+        vec!["strong", "moderate", "reduced"]
+    );
+    let names = labels("${prosody|rate=slow|", 0, 20);
+    assert!(!names.contains(&"rate".to_owned()));
+    assert!(names.contains(&"volume".to_owned()));
+}
+
+#[test]
+fn test_end_tag_completions() {
+    let names = labels("${p}${emphasis}Hi ${/", 0, 21);
+    assert_eq!(&names[..2], &["emphasis".to_owned(), "p".to_owned()]);
+    assert!(!names.contains(&"break".to_owned()));
+}
+
+#[test]
+fn test_hover() {
+    let text = "Hi ${prosody|rate=slow}there${/prosody}";
+    let tag_hover = hover(text, Position::new(0, 6)).unwrap();
+    assert_eq!(
+        tag_hover.range,
+        Some(Range::new(Position::new(0, 3), Position::new(0, 23)))
+    );
+    match tag_hover.contents {
+        HoverContents::Markup(ref markup) => assert!(markup.value.starts_with("**`${prosody}`**")),
+        ref other => panic!("Expected markup, got: {:?}", other),
+    }
+
+    let param_hover = hover(text, Position::new(0, 19)).unwrap();
+    assert_eq!(
+        param_hover.range,
+        Some(Range::new(Position::new(0, 13), Position::new(0, 22)))
+    );
+    match param_hover.contents {
+        HoverContents::Markup(ref markup) => {
+            assert!(markup
+                .value
+                .starts_with("**`rate`** parameter of `${prosody}`"))
+        }
+        ref other => panic!("Expected markup, got: {:?}", other),
+    }
+
+    assert!(hover(text, Position::new(0, 26)).is_none());
+    assert!(hover(text, Position::new(0, 30)).is_some());
+    assert!(hover(text, Position::new(0, 1)).is_none());
+}
+
+#[test]
+fn test_server() {
+    let (server, client) = Connection::memory();
+    let server = thread::spawn(move || serve(&server));
+
+    let initialize = Request::new(
+        RequestId::from(1),
+        "initialize".to_owned(),
+        serde_json::json!({ "capabilities": {} }),
+    );
+    client.sender.send(initialize.into()).unwrap();
+    match client.receiver.recv().unwrap() {
+        Message::Response(response) => {
+            let result = response.result.unwrap();
+            assert_eq!(result["capabilities"]["hoverProvider"], true);
+        }
+        other => panic!("Expected a response, got: {:?}", other),
+    }
+    client
+        .sender
+        .send(Notification::new("initialized".to_owned(), serde_json::json!({})).into())
+        .unwrap();
+
+    let open = Notification::new(
+        "textDocument/didOpen".to_owned(),
+        serde_json::json!({
+            "textDocument": {
+                "uri": "file:///prompt.ssml",
+                "languageId": "ssml",
+                "version": 1,
+                "text": "${s}Hello",
+            },
+        }),
+    );
+    client.sender.send(open.into()).unwrap();
+    match client.receiver.recv().unwrap() {
+        Message::Notification(notification) => {
+            assert_eq!(notification.method, "textDocument/publishDiagnostics");
+            let params: PublishDiagnosticsParams =
+                serde_json::from_value(notification.params).unwrap();
+            assert_eq!(params.diagnostics.len(), 1);
+            assert_eq!(
+                params.diagnostics[0].message,
+                "`s` is opened, but never closed."
+            );
+        }
+        other => panic!("Expected a notification, got: {:?}", other),
+    }
+
+    let completion = Request::new(
+        RequestId::from(2),
+        "textDocument/completion".to_owned(),
+        serde_json::json!({
+            "textDocument": { "uri": "file:///prompt.ssml" },
+            "position": { "line": 0, "character": 2 },
+        }),
+    );
+    client.sender.send(completion.into()).unwrap();
+    match client.receiver.recv().unwrap() {
+        Message::Response(response) => {
+            assert_eq!(response.id, RequestId::from(2));
+            assert!(!response.result.unwrap().as_array().unwrap().is_empty());
+        }
+        other => panic!("Expected a response, got: {:?}", other),
+    }
+
+    let shutdown = Request::new(RequestId::from(3), "shutdown".to_owned(), ());
+    client.sender.send(shutdown.into()).unwrap();
+    client
+        .sender
+        .send(Notification::new("exit".to_owned(), ()).into())
+        .unwrap();
+    let result = server.join().unwrap();
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "markdown")]
+
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::markdown_to_markup;
+use text_to_polly_ssml::{from_markdown, OutputTarget, ParseOptions};
+
+fn google() -> ParseOptions {
+    ParseOptions {
+        target: OutputTarget::Google,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_paragraphs_and_emphasis() {
+    assert_eq!(
+        markdown_to_markup("One *two*\nthree.\n\n**Four** and `five`."),
+        "${p}One ${emphasis|level=moderate}two${/emphasis} three.${/p}${p}${emphasis|level=strong}Four${/emphasis} and five.${/p}"
+    );
+    assert_eq!(markdown_to_markup(""), "");
+}
+
+#[test]
+fn test_headings_and_links() {
+    assert_eq!(
+        markdown_to_markup("## Setup\nSee [the guide](https://example.com/guide)."),
+        "${p}${emphasis|level=strong}Setup${/emphasis}${/p}${break|strength=strong}${p}See the guide.${/p}"
+    );
+}
+
+#[test]
+fn test_lists_and_breaks() {
+    assert_eq!(
+        markdown_to_markup("- One\n- Two\n\n---\n\nLine  \nbreak"),
+        "${p}${s}One${/s}${s}Two${/s}${/p}${break|strength=x-strong}${p}Line${break|strength=medium}break${/p}"
+    );
+    assert_eq!(
+        markdown_to_markup("1. First\n\n2. Second\n"),
+        "${p}${s}First${/s}${s}Second${/s}${/p}"
+    );
+}
+
+#[test]
+fn test_skipped_content() {
+    assert_eq!(
+        markdown_to_markup(
+            "Text ![alt](a.png)\n\n```\ncode();\n```\n\n<div>html</div>\n\n> Quote ${p}"
+        ),
+        "${p}Text ${/p}${p}Quote \\${p}${/p}"
+    );
+}
+
+#[test]
+fn test_from_markdown() {
+    let result = from_markdown("# Title\n\nSome *text* & more.", &google());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "<speak><p><emphasis level=\"strong\">Title</emphasis></p><break strength=\"strong\"/><p>Some <emphasis level=\"moderate\">text</emphasis> &amp; more.</p></speak>"
+    );
+}
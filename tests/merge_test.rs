@@ -0,0 +1,92 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::merge::merge_documents;
+use text_to_polly_ssml::parser::{parse_as_ssml_joined, ParseOptions};
+use text_to_polly_ssml::ssml_constants::BreakTime;
+use text_to_polly_ssml::SsmlError;
+
+#[test]
+fn test_merge_documents() {
+    let result = merge_documents(
+        &[
+            "<?xml version=\"1.0\"?><speak xml:lang=\"en-US\"><p>One</p></speak>\n",
+            "<?xml version=\"1.0\"?>\n<!-- two -->\n<speak xml:lang=\"fr-FR\"><p>Two<mark name=\"a\"/></p></speak>",
+            "<speak/>",
+        ],
+        None,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US"><p>One</p><p>Two<mark name="a"/></p></speak>"#
+    );
+}
+
+#[test]
+fn test_merge_documents_separator() {
+    let result = merge_documents(
+        &[
+            "<speak>One</speak>",
+            "<speak>Two</speak>",
+            "<speak>Three</speak>",
+        ],
+        Some(BreakTime::new(2, true)),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak>One<break time="2s"/>Two<break time="2s"/>Three</speak>"#
+    );
+}
+
+#[test]
+fn test_merge_documents_unbalanced() {
+    let result = merge_documents(&["<speak><p>One</speak>", "<speak>Two</p></speak>"], None);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(result.unwrap(), "<speak><p>OneTwo</p></speak>");
+}
+
+#[test]
+fn test_merge_documents_invalid() {
+    assert_eq!(merge_documents::<&str>(&[], None).unwrap(), "");
+    assert!(matches!(
+        merge_documents(&["<speak>One</speak>", "<p>Two</p>"], None),
+        Err(SsmlError::MissingSpeak)
+    ));
+    assert!(matches!(
+        merge_documents(&["<speak>One</speak>", "just text"], None),
+        Err(SsmlError::MissingSpeak)
+    ));
+    assert!(matches!(
+        merge_documents(&["<speak><p>One</speak>", "<speak><!-- Two</speak>"], None),
+        Err(SsmlError::InvalidSsml(_))
+    ));
+}
+
+#[test]
+fn test_parse_as_ssml_joined() {
+    let result = parse_as_ssml_joined(
+        &["${p}One${/p}", "${p}Two${/p}"],
+        Some(BreakTime::new(300, false)),
+        &ParseOptions::default(),
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><p>One</p><break time="300ms"/><p>Two</p></speak>"#
+    );
+}
+
+#[test]
+fn test_parse_as_ssml_joined_pretty() {
+    let options = ParseOptions {
+        pretty: true,
+        ..Default::default()
+    };
+    let result = parse_as_ssml_joined(&["One", "Two"], None, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        parse_as_ssml_joined(&["OneTwo"], None, &options).unwrap()
+    );
+}
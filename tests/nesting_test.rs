@@ -0,0 +1,146 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::parse_as_ssml_with_report;
+use text_to_polly_ssml::{OutputTarget, ParseOptions, SsmlError, UnknownTagPolicy, WarningKind};
+
+fn nesting_warnings(markup: &str, options: &ParseOptions) -> Vec<(String, String)> {
+    let result = parse_as_ssml_with_report(markup, options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result
+        .unwrap()
+        .warnings
+        .into_iter()
+        .filter(|warning| warning.kind == WarningKind::IllegalNesting)
+        .map(|warning| (warning.tag, warning.message))
+        .collect()
+}
+
+#[test]
+fn test_legal_nesting() {
+    let warnings = nesting_warnings(
+        "${p}${s}One ${emphasis}two${/emphasis}${break|time=1s}${/s}${s}Three${/s}${/p}${p}Four${/p}",
+        &ParseOptions::default(),
+    );
+    assert!(warnings.is_empty(), "{:?}", warnings);
+}
+
+#[test]
+fn test_illegal_nesting_warnings() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        ..Default::default()
+    };
+    let result = parse_as_ssml_with_report(
+        "${s}One ${emphasis}${p}two${/p}${/emphasis}${/s} ${p}${p}three${/p}${/p} \
+         ${s}${s}four${/s}${/s} ${sub|alias=five}${break}5${/sub}",
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert_eq!(
+        report.ssml,
+        "<speak><s>One <emphasis><p>two</p></emphasis></s> <p><p>three</p></p> \
+         <s><s>four</s></s> <sub alias=\"five\"><break/>5</sub></speak>"
+    );
+    let warnings: Vec<(&str, &str)> = report
+        .warnings
+        .iter()
+        .map(|warning| (warning.tag.as_str(), warning.message.as_str()))
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            ("p", "`p` isn't allowed inside of `s`."),
+            ("p", "`p` isn't allowed inside of `p`."),
+            ("s", "`s` isn't allowed inside of `s`."),
+            ("break", "`break` isn't allowed inside of `sub`."),
+        ]
+    );
+    assert_eq!(report.warnings[0].span.unwrap().offset, 19);
+}
+
+#[test]
+fn test_dropped_tags_are_not_checked() {
+    // Unknown tags are dropped, so they may be inside of tags that only contain text.
+    let warnings = nesting_warnings(
+        "${say-as|interpret-as=digits}${unknown}123${/unknown}${/say-as}",
+        &ParseOptions::default(),
+    );
+    assert!(warnings.is_empty(), "{:?}", warnings);
+
+    let options = ParseOptions {
+        unknown_tags: UnknownTagPolicy::Passthrough,
+        ..Default::default()
+    };
+    let warnings = nesting_warnings("${speak}Hi${/speak}", &options);
+    assert_eq!(
+        warnings,
+        vec![(
+            "speak".to_owned(),
+            "`speak` isn't allowed inside of `speak`.".to_owned()
+        )]
+    );
+}
+
+#[test]
+fn test_strict_illegal_nesting() {
+    let options = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options("${s}One ${p}two${/p}${/s}", &options);
+    match result {
+        Err(SsmlError::IllegalNesting { tag, parent }) => {
+            assert_eq!(tag, "p");
+            assert_eq!(parent, "s");
+        }
+        other => panic!("Expected an illegal nesting error, got: {:?}", other),
+    }
+
+    let result = text_to_polly_ssml::parse_str_with_options("${p}${s}One${/s}${/p}", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+}
+
+#[test]
+fn test_max_depth() {
+    let options = ParseOptions {
+        max_depth: Some(3),
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(
+        "${p}${s}${emphasis}One${/emphasis}${/s}${s}${prosody|rate=slow}Two${/prosody}${/s}${/p}",
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+
+    let result = text_to_polly_ssml::parse_str_with_options(
+        "${p}${s}${emphasis}${prosody|rate=slow}One${/prosody}${/emphasis}${/s}${/p}",
+        &options,
+    );
+    match result {
+        Err(SsmlError::NestingTooDeep { tag, max_depth }) => {
+            assert_eq!(tag, "prosody");
+            assert_eq!(max_depth, 3);
+        }
+        other => panic!("Expected a nesting too deep error, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_max_depth_unclosed_tags() {
+    let options = ParseOptions {
+        max_depth: Some(64),
+        ..Default::default()
+    };
+    let markup = "${p}".repeat(10_000);
+    let result = text_to_polly_ssml::parse_str_with_options(&markup, &options);
+    assert!(matches!(
+        result,
+        Err(SsmlError::NestingTooDeep { max_depth: 64, .. })
+    ));
+
+    // Only tags that are open at the same time count, and empty tags don't count at all.
+    let markup = "${p}${s}Hi${break}${/s}${/p}".repeat(100);
+    let result = text_to_polly_ssml::parse_str_with_options(&markup, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+}
@@ -0,0 +1,59 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{normalize_numbers, NumberRules, SentenceRules};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+fn normalize(markup: &str, rules: &NumberRules) -> String {
+    let result = normalize_numbers(markup, rules);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_normalize_numbers() {
+    let rules = NumberRules::default();
+    assert_eq!(
+        normalize("42 apples, 007, 3.14, 1,000,000, 5.", &rules),
+        "${say-as|interpret-as=cardinal}42${/say-as} apples, ${say-as|interpret-as=digits}007${/say-as}, ${say-as|interpret-as=cardinal}3.14${/say-as}, ${say-as|interpret-as=cardinal}1,000,000${/say-as}, ${say-as|interpret-as=cardinal}5${/say-as}."
+    );
+    assert_eq!(
+        normalize("A380 3rd 1,2,3 12,34", &rules),
+        "A380 3rd ${say-as|interpret-as=cardinal}1${/say-as},${say-as|interpret-as=cardinal}2${/say-as},${say-as|interpret-as=cardinal}3${/say-as} ${say-as|interpret-as=cardinal}12${/say-as},${say-as|interpret-as=cardinal}34${/say-as}"
+    );
+    assert_eq!(
+        normalize(
+            "${sub|alias=two}2${/sub} ${emphasis}1234${/emphasis}",
+            &rules
+        ),
+        "${sub|alias=two}2${/sub} ${emphasis}${say-as|interpret-as=digits}1234${/say-as}${/emphasis}"
+    );
+}
+
+#[test]
+fn test_number_rules() {
+    let rules = NumberRules {
+        digits_from: None,
+        group_separator: '.',
+        decimal_separator: ',',
+    };
+    assert_eq!(
+        normalize("1.000,5 und 12345", &rules),
+        "${say-as|interpret-as=cardinal}1.000,5${/say-as} und ${say-as|interpret-as=cardinal}12345${/say-as}"
+    );
+}
+
+#[test]
+fn test_numbers_option() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        numbers: Some(NumberRules::default()),
+        sentences: Some(SentenceRules::for_locale("en-US")),
+        ..Default::default()
+    };
+    let result = parse_str_with_options("Pi is 3.14. Call 5551234.", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak><s>Pi is <say-as interpret-as="cardinal">3.14</say-as>.</s> <s>Call <say-as interpret-as="digits">5551234</say-as>.</s></speak>"#
+    );
+}
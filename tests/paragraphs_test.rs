@@ -0,0 +1,55 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{wrap_paragraphs, SentenceRules};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+fn wrap(markup: &str) -> String {
+    let result = wrap_paragraphs(markup);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_wrap_plain_text() {
+    assert_eq!(
+        wrap("\n  First line,\nstill first.  \n \n\n Second.\r\n\r\nThird.  "),
+        "\n  ${p}First line,\nstill first.${/p}  \n \n\n ${p}Second.${/p}\r\n\r\n${p}Third.${/p}  "
+    );
+    assert_eq!(wrap("Just one."), "${p}Just one.${/p}");
+    assert_eq!(wrap(""), "");
+    assert_eq!(wrap("\n\n  \n"), "\n\n  \n");
+}
+
+#[test]
+fn test_wrap_with_tags() {
+    assert_eq!(
+        wrap("${p}Already.${/p}\n\nNot ${emphasis}yet\n\nhere${/emphasis}.\n\n${break|time=1s}"),
+        "${p}Already.${/p}\n\n${p}Not ${emphasis}yet\n\nhere${/emphasis}.${/p}\n\n${break|time=1s}"
+    );
+}
+
+#[test]
+fn test_paragraphs_option() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        paragraphs: true,
+        ..Default::default()
+    };
+    let result = parse_str_with_options("One. Two.\n\nThree.", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "<speak><p>One. Two.</p>\n\n<p>Three.</p></speak>"
+    );
+
+    let options = ParseOptions {
+        sentences: Some(SentenceRules::for_locale("en-US")),
+        ..options
+    };
+    let result = parse_str_with_options("One. Two.\n\nThree.", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "<speak><p><s>One.</s> <s>Two.</s></p>\n\n<p><s>Three.</s></p></speak>"
+    );
+}
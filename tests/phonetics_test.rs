@@ -0,0 +1,79 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::phonetics::{
+    arpabet_to_ipa, convert_phonemes, ipa_to_xsampa, xsampa_to_ipa,
+};
+use text_to_polly_ssml::ssml_constants::PhonemeAlphabet;
+use text_to_polly_ssml::SsmlError;
+
+fn to_ipa(arpabet: &str) -> String {
+    let result = arpabet_to_ipa(arpabet);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_arpabet_to_ipa() {
+    assert_eq!(to_ipa("HH AH0 L OW1"), "həˈloʊ");
+    assert_eq!(to_ipa("K AE1 T"), "ˈkæt");
+    assert_eq!(to_ipa("  s t r iy1 t  "), "ˈstɹit");
+    assert_eq!(to_ipa("IH0 N S T R AH1 K SH AH0 N"), "ɪnˈstɹʌkʃən");
+    assert_eq!(to_ipa("B ER1 D ER0"), "ˈbɝdɚ");
+    assert_eq!(to_ipa("S IH1 NG ER0"), "ˈsɪŋɚ");
+    assert_eq!(to_ipa("K AA2 N V ER0 S EY1 SH AH0 N"), "ˌkɑnvɚˈseɪʃən");
+    assert_eq!(to_ipa("JH AH1 JH"), "ˈdʒʌdʒ");
+    assert_eq!(to_ipa(""), "");
+}
+
+#[test]
+fn test_unknown_phones() {
+    let result = arpabet_to_ipa("K AE1 XX");
+    assert!(
+        matches!(result, Err(SsmlError::UnknownPhone(ref phone)) if phone == "XX"),
+        "{:?}",
+        result
+    );
+    let result = arpabet_to_ipa("K1 AE1 T");
+    assert!(
+        matches!(result, Err(SsmlError::UnknownPhone(ref phone)) if phone == "K1"),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn test_xsampa_ipa_round_trip() {
+    let ipa = xsampa_to_ipa("\"{r\\@`  pI\"kA:n tS_hE5 m=");
+    assert!(ipa.is_ok(), "Result is not okay:\n\n{:?}", ipa);
+    let ipa = ipa.unwrap();
+    assert_eq!(ipa, "ˈæɹɚ  pɪˈkɑːn tʃʰɛɫ m\u{329}");
+    assert_eq!(ipa_to_xsampa(&ipa).unwrap(), "\"{r\\@`  pI\"kA:n tS_hE5 m=");
+    assert_eq!(ipa_to_xsampa("ɡg").unwrap(), "gg");
+    assert_eq!(xsampa_to_ipa("").unwrap(), "");
+}
+
+#[test]
+fn test_unmappable_symbols() {
+    let result = xsampa_to_ipa("ab$");
+    assert!(
+        matches!(result, Err(SsmlError::UnmappableSymbol { ref symbol, ref alphabet }) if symbol == "$" && alphabet == "x-sampa"),
+        "{:?}",
+        result
+    );
+    let result = ipa_to_xsampa("ʧ");
+    assert!(
+        matches!(result, Err(SsmlError::UnmappableSymbol { ref symbol, .. }) if symbol == "ʧ"),
+        "{:?}",
+        result
+    );
+    let result = convert_phonemes("a", PhonemeAlphabet::Pinyin, PhonemeAlphabet::Ipa);
+    assert!(
+        matches!(result, Err(SsmlError::UnsupportedConversion { .. })),
+        "{:?}",
+        result
+    );
+    assert_eq!(
+        convert_phonemes("ni3", PhonemeAlphabet::Pinyin, PhonemeAlphabet::Pinyin).unwrap(),
+        "ni3"
+    );
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "python")]
+
+extern crate text_to_polly_ssml;
+
+use pyo3::ffi::c_str;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyModule};
+
+use std::ffi::CStr;
+
+/// Runs Python code with the module imported as `ssml`.
+fn run(code: &CStr) {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "text_to_polly_ssml").unwrap();
+        text_to_polly_ssml::python::text_to_polly_ssml(&module).unwrap();
+        let locals = PyDict::new(py);
+        locals.set_item("ssml", module).unwrap();
+        let result = py.run(code, None, Some(&locals));
+        assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    });
+}
+
+#[test]
+fn test_python_parse_str() {
+    run(c_str!(
+        r#"
+assert "<s>Hello</s>" in ssml.parse_str("${s}Hello${/s}")
+
+options = ssml.ParseOptions(target="generic", lang="en-GB")
+options.pretty = True
+assert options.target == "generic"
+assert repr(options) == 'ParseOptions(target="generic", lang="en-GB", strict=False, pretty=True, ascii_safe=False)', repr(options)
+rendered = ssml.parse_str("${p}Hi${/p}", options)
+assert 'xml:lang="en-GB"' in rendered, rendered
+assert "\n  <p>Hi</p>\n" in rendered, rendered
+"#
+    ));
+}
+
+#[test]
+fn test_python_errors() {
+    run(c_str!(
+        r#"
+try:
+    ssml.parse_str("${p}Hi", ssml.ParseOptions(strict=True))
+    raise AssertionError("expected an SsmlError")
+except ssml.SsmlError as error:
+    assert str(error) == "`${p}` is never closed.", str(error)
+
+try:
+    ssml.ParseOptions(target="siri")
+    raise AssertionError("expected a ValueError")
+except ValueError as error:
+    assert str(error).startswith("`siri` is not a valid output target"), str(error)
+"#
+    ));
+}
+
+#[test]
+fn test_python_lint() {
+    run(c_str!(
+        r#"
+diagnostics = ssml.lint("${p}Hi\n${emphasis|level=loud}there${/emphasis}")
+assert [(d.severity, d.kind) for d in diagnostics] == [
+    ("warning", "InvalidAttributeValue"),
+    ("error", "UnclosedTag"),
+], diagnostics
+assert diagnostics[0].attribute == "level"
+assert (diagnostics[0].line, diagnostics[0].column) == (2, 12), diagnostics[0].column
+assert diagnostics[1].tag == "p"
+assert str(diagnostics[1]) == "`p` is opened, but never closed."
+"#
+    ));
+}
@@ -0,0 +1,71 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::registry::{find_tag, supported_tags};
+use text_to_polly_ssml::ssml_constants::*;
+
+use std::str::FromStr;
+
+fn all_parse<T: FromStr>(values: &[&str]) -> bool {
+    values.iter().all(|value| value.parse::<T>().is_ok())
+}
+
+#[test]
+fn test_registry_matches_parser_tags() {
+    for tag in supported_tags() {
+        assert!(
+            tag.name.parse::<PossibleOpenTags>().is_ok(),
+            "{} is not an open tag",
+            tag.name
+        );
+        // `${/mark}` is still understood for `ParseOptions::wrapping_marks`.
+        assert_eq!(
+            tag.name.parse::<PossibleClosingTags>().is_ok(),
+            tag.has_closing_tag || tag.name == "mark",
+            "{} closing tag mismatch",
+            tag.name
+        );
+    }
+}
+
+#[test]
+fn test_registry_values_parse() {
+    let check = |tag: &str, param: &str| {
+        find_tag(tag)
+            .and_then(|tag| tag.param(param))
+            .and_then(|param| param.values)
+            .unwrap()
+    };
+    assert!(all_parse::<BreakStrength>(check("break", "strength")));
+    assert!(all_parse::<EmphasisLevel>(check("emphasis", "level")));
+    assert!(all_parse::<PhonemeAlphabet>(check("phoneme", "alphabet")));
+    assert!(all_parse::<ProsodyRate>(check("prosody", "rate")));
+    assert!(all_parse::<WordRole>(check("w", "role")));
+    assert!(all_parse::<AmazonEffect>(check("amazon:effect", "name")));
+    assert!(all_parse::<EmotionName>(check("amazon:emotion", "name")));
+    assert!(all_parse::<SilenceType>(check("silence", "type")));
+    assert!(all_parse::<ExpressAsType>(check("express-as", "type")));
+    assert!(all_parse::<EmotionIntensity>(check(
+        "amazon:emotion",
+        "intensity"
+    )));
+    assert!(all_parse::<PhonationVolume>(check(
+        "amazon:effect",
+        "phonation"
+    )));
+    assert!(all_parse::<BreathVolumes>(check(
+        "amazon:auto-breaths",
+        "volume"
+    )));
+    assert!(all_parse::<AutoBreathFrequency>(check(
+        "amazon:auto-breaths",
+        "frequency"
+    )));
+    assert!(all_parse::<BreathDuration>(check(
+        "amazon:breath",
+        "duration"
+    )));
+    assert!(all_parse::<AmazonDomainNames>(check(
+        "amazon:domain",
+        "name"
+    )));
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "schema")]
+
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::schema::{validate_ssml, ViolationKind};
+use text_to_polly_ssml::{OutputTarget, ParseOptions};
+
+#[test]
+fn test_rendered_generic_ssml_is_valid() {
+    let options = ParseOptions {
+        target: OutputTarget::Generic,
+        ..Default::default()
+    };
+    let result = text_to_polly_ssml::parse_str_with_options(
+        "${p}${s}One ${emphasis|level=strong}two${/emphasis}${break|time=500ms}${/s}\
+         ${s|lang=fr-FR}${prosody|rate=slow|volume=+6dB|pitch=-10%}trois${/prosody}${/s}${/p}\
+         ${lang|lang=de-DE}${say-as|interpret-as=digits}123${/say-as}${/lang}\
+         ${sub|alias=World Wide Web}WWW${/sub}${mark|name=end}\
+         ${phoneme|alphabet=ipa|ph=pɪˈkɑːn}pecan${/phoneme}",
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let violations = validate_ssml(&result.unwrap());
+    assert!(
+        violations.is_ok(),
+        "Result is not okay:\n\n{:?}",
+        violations
+    );
+    assert_eq!(violations.unwrap(), vec![]);
+}
+
+#[test]
+fn test_schema_violations() {
+    let result = validate_ssml(
+        r#"<speak xml:lang="en-US"><p><p>Nested</p></p><break time="soon">late</break><sub>x</sub><blink/><s onlangfailure="giveup" foo="bar">Hi</s></speak>"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let violations: Vec<(ViolationKind, String, Option<String>)> = result
+        .unwrap()
+        .into_iter()
+        .map(|violation| (violation.kind, violation.path, violation.attribute))
+        .collect();
+    assert_eq!(
+        violations,
+        vec![
+            (
+                ViolationKind::MissingAttribute,
+                "speak".to_owned(),
+                Some("version".to_owned())
+            ),
+            (
+                ViolationKind::UnexpectedElement,
+                "speak/p/p".to_owned(),
+                None
+            ),
+            (
+                ViolationKind::InvalidAttributeValue,
+                "speak/break".to_owned(),
+                Some("time".to_owned())
+            ),
+            (
+                ViolationKind::UnexpectedText,
+                "speak/break".to_owned(),
+                None
+            ),
+            (
+                ViolationKind::MissingAttribute,
+                "speak/sub".to_owned(),
+                Some("alias".to_owned())
+            ),
+            (
+                ViolationKind::UnknownElement,
+                "speak/blink".to_owned(),
+                None
+            ),
+            (
+                ViolationKind::InvalidAttributeValue,
+                "speak/s".to_owned(),
+                Some("onlangfailure".to_owned())
+            ),
+            (
+                ViolationKind::UnknownAttribute,
+                "speak/s".to_owned(),
+                Some("foo".to_owned())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_schema_skips_other_namespaces() {
+    let result = validate_ssml(
+        r#"<speak version="1.1" xml:lang="en-US" xmlns:amazon="https://amazon.com"><amazon:effect name="whispered"><p>Hi<p>there</p></p></amazon:effect><s amazon:x="1">Hey</s></speak>"#,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let violations = result.unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].kind, ViolationKind::UnexpectedElement);
+    assert_eq!(violations[0].path, "speak/p/p");
+    assert_eq!(violations[0].offset, 109);
+}
+
+#[test]
+fn test_schema_wrong_root() {
+    let result = validate_ssml("<p>Hi</p>");
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(result.unwrap()[0].kind, ViolationKind::WrongRoot);
+
+    assert!(validate_ssml("<speak><p></speak>").is_err());
+}
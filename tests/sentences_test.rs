@@ -0,0 +1,80 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{segment_sentences, SentenceRules};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+fn segment(markup: &str, locale: &str) -> String {
+    let result = segment_sentences(markup, &SentenceRules::for_locale(locale));
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_segment_plain_text() {
+    assert_eq!(
+        segment("  Hello there!  How are you? I'm fine.  ", "en-US"),
+        "  ${s}Hello there!${/s}  ${s}How are you?${/s} ${s}I'm fine.${/s}  "
+    );
+    assert_eq!(
+        segment(
+            r#"He said "Stop." Then left. Pi is 3.14 J. R. R. Tolkien"#,
+            "en-US"
+        ),
+        r#"${s}He said "Stop."${/s} ${s}Then left.${/s} ${s}Pi is 3.14 J. R. R. Tolkien${/s}"#
+    );
+    assert_eq!(segment("", "en-US"), "");
+    assert_eq!(segment("   ", "en-US"), "   ");
+}
+
+#[test]
+fn test_segment_per_locale() {
+    assert_eq!(
+        segment("Das ist z.B. gut. Ja.", "de-DE"),
+        "${s}Das ist z.B. gut.${/s} ${s}Ja.${/s}"
+    );
+    assert_eq!(
+        segment("Das ist z.B. gut. Ja.", "en-US"),
+        "${s}Das ist z.B.${/s} ${s}gut.${/s} ${s}Ja.${/s}"
+    );
+    assert_eq!(
+        segment("こんにちは。元気ですか？はい。", "ja-JP"),
+        "${s}こんにちは。${/s}${s}元気ですか？${/s}${s}はい。${/s}"
+    );
+}
+
+#[test]
+fn test_segment_with_tags() {
+    assert_eq!(
+        segment(
+            "${p}One. ${emphasis}Two${/emphasis} too.${/p}${p}Three.${/p}",
+            "en-US"
+        ),
+        "${p}${s}One.${/s} ${s}${emphasis}Two${/emphasis} too.${/s}${/p}${p}${s}Three.${/s}${/p}"
+    );
+    assert_eq!(
+        segment(
+            "${s}Already. Done.${/s} ${break|time=1s}${prosody|rate=slow}Slow. Down.${/prosody}",
+            "en-US"
+        ),
+        "${s}Already. Done.${/s} ${break|time=1s}${prosody|rate=slow}${s}Slow.${/s} ${s}Down.${/s}${/prosody}"
+    );
+    assert_eq!(
+        segment("See ${sub|alias=Doctor. Who}Dr. Who${/sub}. Now.", "en-US"),
+        "${s}See ${sub|alias=Doctor. Who}Dr. Who${/sub}.${/s} ${s}Now.${/s}"
+    );
+}
+
+#[test]
+fn test_segment_option() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        sentences: Some(SentenceRules::for_locale("en-US")),
+        ..Default::default()
+    };
+    let result = parse_str_with_options("Hi. ${p}Bye. Now.${/p}", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "<speak><s>Hi.</s> <p><s>Bye.</s> <s>Now.</s></p></speak>"
+    );
+}
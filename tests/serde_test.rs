@@ -0,0 +1,89 @@
+#![cfg(feature = "serde")]
+
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{parse_tree, render_tree, tokenize, OneItem, SsmlNode};
+use text_to_polly_ssml::ssml_constants::{
+    BreakStrength, BreakTime, BreathVolumes, OutputTarget, ProsodyPitch, ProsodyRate, ProsodyVolume,
+};
+use text_to_polly_ssml::ParseOptions;
+
+#[test]
+fn test_tree_round_trip() {
+    let tree = parse_tree("Hi ${prosody|rate=fast}there${break|time=1s}${/prosody}.").unwrap();
+    let json = serde_json::to_string(&tree).unwrap();
+    assert_eq!(
+        json,
+        r#"[{"Text":"Hi "},{"Element":{"name":"prosody","params":{"rate":"fast"},"children":[{"Text":"there"},{"Element":{"name":"break","params":{"time":"1s"},"children":[]}}]}},{"Text":"."}]"#
+    );
+
+    let result = serde_json::from_str::<Vec<SsmlNode>>(&json);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let read_back = result.unwrap();
+    assert_eq!(read_back, tree);
+    assert_eq!(
+        render_tree(&read_back, &ParseOptions::default()).unwrap(),
+        render_tree(&tree, &ParseOptions::default()).unwrap()
+    );
+}
+
+#[test]
+fn test_items_round_trip() {
+    let items = tokenize("${emphasis|level=strong}Hi${/emphasis}").unwrap();
+    let json = serde_json::to_string(&items).unwrap();
+    let result = serde_json::from_str::<Vec<OneItem>>(&json);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let read_back = result.unwrap();
+
+    assert_eq!(read_back.len(), items.len());
+    let start_tag = read_back[0].start_tag.as_ref().unwrap();
+    assert_eq!(start_tag.tag_key, "emphasis");
+    assert_eq!(
+        start_tag.params.get("level").map(AsRef::as_ref),
+        Some("strong")
+    );
+    assert_eq!(read_back[1].data.as_deref(), Some("Hi"));
+    assert_eq!(read_back[1].span, items[1].span);
+    assert_eq!(read_back[2].end_tag.as_ref().unwrap().tag_key, "emphasis");
+}
+
+#[test]
+fn test_constants_as_ssml_strings() {
+    assert_eq!(
+        serde_json::to_string(&BreakStrength::XStrong).unwrap(),
+        r#""x-strong""#
+    );
+    assert_eq!(
+        serde_json::to_string(&BreakTime::new(500, false)).unwrap(),
+        r#""500ms""#
+    );
+    assert_eq!(
+        serde_json::to_string(&ProsodyVolume::Db(-3.0)).unwrap(),
+        r#""-3dB""#
+    );
+    assert_eq!(
+        serde_json::to_string(&OutputTarget::Azure).unwrap(),
+        r#""azure""#
+    );
+
+    let rate = serde_json::from_str::<ProsodyRate>(r#""x-slow""#);
+    assert!(
+        rate.is_ok(),
+        "Result is not okay:\n\n{:?}",
+        rate.map(|rate| rate.to_string())
+    );
+    assert_eq!(rate.unwrap().to_string(), "x-slow");
+    let pitch = serde_json::from_str::<ProsodyPitch>(r#""+10%""#).unwrap();
+    assert_eq!(pitch.to_string(), "+10%");
+    assert_eq!(
+        serde_json::from_str::<BreakStrength>(r#""Medium""#).unwrap(),
+        BreakStrength::Medium
+    );
+
+    let invalid = serde_json::from_str::<BreathVolumes>(r#""deafening""#);
+    assert_eq!(
+        invalid.err().unwrap().to_string(),
+        "`deafening` is not a valid breath volume, expected one of: default, x-soft, soft, \
+         medium, loud, x-loud"
+    );
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "server")]
+
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::server::{convert, serve_with};
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread;
+
+fn request(address: &str, head: &str, body: &str) -> String {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "{}\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+        head,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn test_convert() {
+    let (status, response) = convert(
+        r#"{"markup": "${s}Hi ${break|time=20s}${/s}", "options": {"target": "generic", "lang": "en-GB"}}"#,
+    );
+    assert_eq!(status, 200, "{}", response);
+    let ssml = response["ssml"].as_str().unwrap();
+    assert!(ssml.contains("xml:lang=\"en-GB\""), "{}", ssml);
+    assert!(ssml.contains("<s>Hi <break time=\"20s\"/></s>"), "{}", ssml);
+    assert_eq!(response["diagnostics"], serde_json::json!([]));
+
+    let (status, response) =
+        convert(r#"{"markup": "${break|time=20s} ${emphasis|level=loud}Hi${/emphasis}"}"#);
+    assert_eq!(status, 200, "{}", response);
+    let kinds: Vec<&str> = response["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|diagnostic| diagnostic["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec!["OutOfRangeAttributeValue", "InvalidAttributeValue"]
+    );
+    assert_eq!(response["diagnostics"][1]["severity"], "warning");
+    assert_eq!(response["diagnostics"][1]["column"], 30);
+}
+
+#[test]
+fn test_convert_errors() {
+    let (status, response) = convert(r#"{"markup": "${p}Hi", "options": {"strict": true}}"#);
+    assert_eq!(status, 422);
+    assert_eq!(response["error"], "`${p}` is never closed.");
+    assert_eq!(response["diagnostics"].as_array().unwrap().len(), 1);
+
+    let (status, response) = convert(r#"{"markup": "Hi", "options": {"target": "siri"}}"#);
+    assert_eq!(status, 400);
+    assert!(response["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("`siri` is not a valid output target"));
+
+    let (status, response) = convert(r#"{"text": "Hi"}"#);
+    assert_eq!(status, 400);
+    assert!(response["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("The body isn't a valid request: "));
+}
+
+#[test]
+fn test_server() {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let address = server.server_addr().to_ip().unwrap().to_string();
+    thread::spawn(move || serve_with(&server));
+
+    let response = request(
+        &address,
+        "POST /convert HTTP/1.1",
+        r#"{"markup": "${s}Hello${/s}"}"#,
+    );
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(
+        response.contains("Content-Type: application/json"),
+        "{}",
+        response
+    );
+    let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+    let body: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert!(body["ssml"].as_str().unwrap().contains("<s>Hello</s>"));
+
+    let response = request(&address, "GET /convert HTTP/1.1", "");
+    assert!(response.starts_with("HTTP/1.1 405"), "{}", response);
+    let response = request(&address, "POST /render HTTP/1.1", "{}");
+    assert!(response.starts_with("HTTP/1.1 404"), "{}", response);
+}
@@ -0,0 +1,122 @@
+extern crate text_to_polly_ssml;
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use text_to_polly_ssml::ssml_constants::*;
+
+/// Every variant is written as a different value, which is read back as the same variant.
+fn assert_round_trips<T: Display + FromStr>(variants: &[T]) {
+    let written = variants.iter().map(ToString::to_string).collect::<Vec<_>>();
+    for (idx, value) in written.iter().enumerate() {
+        assert!(
+            !written[..idx].contains(value),
+            "`{}` is listed twice",
+            value
+        );
+        let read = value.parse::<T>();
+        assert!(read.is_ok(), "`{}` can't be read back", value);
+        assert_eq!(&read.ok().unwrap().to_string(), value);
+    }
+}
+
+#[test]
+fn test_variants() {
+    assert_eq!(
+        BreakStrength::iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec!["none", "x-weak", "weak", "medium", "strong", "x-strong"]
+    );
+    assert_eq!(
+        ProsodyVolume::iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec!["default", "silent", "x-soft", "soft", "medium", "loud", "x-loud"]
+    );
+    assert_eq!(WordRole::variants().len(), 5);
+
+    assert_round_trips(BreakStrength::variants());
+    assert_round_trips(EmphasisLevel::variants());
+    assert_round_trips(PhonemeAlphabet::variants());
+    assert_round_trips(ProsodyRate::variants());
+    assert_round_trips(ProsodyVolume::variants());
+    assert_round_trips(ProsodyPitch::variants());
+    assert_round_trips(WordRole::variants());
+    assert_round_trips(AmazonEffect::variants());
+    assert_round_trips(AmazonDomainNames::variants());
+    assert_round_trips(EmotionName::variants());
+    assert_round_trips(EmotionIntensity::variants());
+    assert_round_trips(BreathVolumes::variants());
+    assert_round_trips(BreathDuration::variants());
+    assert_round_trips(AutoBreathFrequency::variants());
+    assert_round_trips(PhonationVolume::variants());
+    assert_round_trips(SilenceType::variants());
+    assert_round_trips(ExpressAsType::variants());
+    assert_round_trips(OutputTarget::variants());
+}
+
+#[test]
+fn test_common_derives() {
+    let mut volumes = HashSet::new();
+    volumes.insert(ProsodyVolume::Loud);
+    volumes.insert(ProsodyVolume::Db(-3.0));
+    volumes.insert(ProsodyVolume::Db(-3.0));
+    volumes.insert(ProsodyVolume::Db(f32::NAN));
+    volumes.insert(ProsodyVolume::Db(f32::NAN));
+    assert_eq!(volumes.len(), 3);
+    assert!(volumes.contains(&"-3dB".parse::<ProsodyVolume>().unwrap()));
+
+    assert_eq!("x-slow".parse::<ProsodyRate>(), Ok(ProsodyRate::XSlow));
+    assert_ne!(ProsodyPitch::Percent(10.0), ProsodyPitch::Percent(-10.0));
+    assert_ne!(
+        VocalTractLength::Percent(10.0),
+        VocalTractLength::Change(10.0)
+    );
+    assert_eq!(
+        WordRole::Custom("amazon:NN".to_owned()).clone(),
+        WordRole::Custom("amazon:NN".to_owned())
+    );
+
+    let mut roles = HashSet::new();
+    roles.insert(WordRole::Sense(1));
+    roles.insert(WordRole::PresentTense);
+    roles.insert(WordRole::Sense(2));
+    assert_eq!(roles.len(), 2);
+    for role in &[WordRole::Sense(1), WordRole::sense(1), WordRole::Sense(2)] {
+        assert_eq!(&role.to_string().parse::<WordRole>().unwrap(), role);
+    }
+    assert_eq!(
+        "amazon:SENSE_01".parse::<WordRole>(),
+        Ok(WordRole::PresentTense)
+    );
+
+    let rate = ProsodyRate::Fast;
+    let copied = rate;
+    assert_eq!(format!("{:?}", (rate, copied)), "(Fast, Fast)");
+}
+
+#[test]
+fn test_invalid_values() {
+    let error = "x-quick".parse::<ProsodyRate>().unwrap_err();
+    assert_eq!(error.value, "x-quick");
+    assert_eq!(error.kind, "prosody rate");
+    assert_eq!(
+        error.accepted,
+        &["x-slow", "slow", "medium", "fast", "x-fast"]
+    );
+    assert_eq!(
+        error.to_string(),
+        "`x-quick` is not a valid prosody rate, expected one of: x-slow, slow, medium, fast, x-fast"
+    );
+
+    assert_eq!("5".parse::<BreakTime>().unwrap_err().kind, "break time");
+    assert_eq!("80%".parse::<ProsodyPitch>().unwrap_err().value, "80%");
+    assert_eq!("amazon:".parse::<WordRole>().unwrap_err().kind, "word role");
+    assert!("bogus"
+        .parse::<PossibleOpenTags>()
+        .unwrap_err()
+        .accepted
+        .contains(&"say-as"));
+}
@@ -0,0 +1,50 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{SentenceRules, Substitutions};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+fn glossary() -> Substitutions {
+    let mut substitutions = Substitutions::default();
+    substitutions
+        .define("CO", "carbon monoxide")
+        .define("CO2", "carbon dioxide")
+        .define("Acme Inc.", "Acme Incorporated")
+        .define("Ünï", "unicode");
+    substitutions
+}
+
+fn apply(markup: &str) -> String {
+    let result = glossary().apply(markup);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_apply_substitutions() {
+    assert_eq!(
+        apply("CO2, CO, Co, COST, and ÜnïX, Ünï."),
+        "${sub|alias=carbon dioxide}CO2${/sub}, ${sub|alias=carbon monoxide}CO${/sub}, Co, COST, and ÜnïX, ${sub|alias=unicode}Ünï${/sub}."
+    );
+    assert_eq!(
+        apply("${emphasis}CO${/emphasis} ${sub|alias=gas}CO${/sub} C${break}O"),
+        "${emphasis}${sub|alias=carbon monoxide}CO${/sub}${/emphasis} ${sub|alias=gas}CO${/sub} C${break}O"
+    );
+    assert_eq!(apply(""), "");
+    assert_eq!(Substitutions::default().apply("CO").unwrap(), "CO");
+}
+
+#[test]
+fn test_substitutions_option() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        substitutions: glossary(),
+        sentences: Some(SentenceRules::for_locale("en-US")),
+        ..Default::default()
+    };
+    let result = parse_str_with_options("Ask Acme Inc. about CO2.", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<speak><s>Ask <sub alias="Acme Incorporated">Acme Inc.</sub> about <sub alias="carbon dioxide">CO2</sub>.</s></speak>"#
+    );
+}
@@ -0,0 +1,93 @@
+extern crate text_to_polly_ssml;
+
+use std::time::Duration;
+
+use text_to_polly_ssml::error::SsmlError;
+use text_to_polly_ssml::parser::{parse_subtitles, subtitles_to_markup, Cue};
+use text_to_polly_ssml::{from_subtitles, OutputTarget, ParseOptions};
+
+const SRT: &str = "\u{feff}1\r\n00:00:01,000 --> 00:00:02,500\r\n<i>Hello,</i>\r\nworld.\r\n\r\n\
+                   2\r\n00:00:02,500 --> 00:00:04,000\r\nStill here.\r\n\r\n\
+                   3\r\n00:00:16,000 --> 00:00:17,000\r\nA &amp; B ${x}\r\n";
+
+#[test]
+fn test_parse_srt() {
+    let result = parse_subtitles(SRT);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let cues = result.unwrap();
+    assert_eq!(cues.len(), 3);
+    assert_eq!(
+        cues[0],
+        Cue {
+            start: Duration::from_millis(1000),
+            end: Duration::from_millis(2500),
+            text: "Hello, world.".to_owned(),
+        }
+    );
+    assert_eq!(cues[2].text, "A & B ${x}");
+}
+
+#[test]
+fn test_parse_vtt() {
+    let result = parse_subtitles(
+        "WEBVTT - A title\nKind: captions\n\nNOTE a comment\nover two lines\n\n\
+         STYLE\n::cue { color: red }\n\nintro\n01:02.5 --> 01:04.250 align:start\n\
+         <v Roger>Hi <00:01:03.000><c.loud>there</c></v>\n\n\
+         1:00:00.000 --> 1:00:01.000\nLater\n",
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            Cue {
+                start: Duration::from_millis(62_500),
+                end: Duration::from_millis(64_250),
+                text: "Hi there".to_owned(),
+            },
+            Cue {
+                start: Duration::from_secs(3600),
+                end: Duration::from_secs(3601),
+                text: "Later".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_subtitles() {
+    assert!(matches!(
+        parse_subtitles("1\nnot a timing\nText\n"),
+        Err(SsmlError::InvalidSubtitles { line: 1, .. })
+    ));
+    assert!(matches!(
+        parse_subtitles("\n\n1\n00:00:01,000 --> 00:0x:02,000\nText\n"),
+        Err(SsmlError::InvalidSubtitles { line: 4, .. })
+    ));
+    assert_eq!(parse_subtitles("").unwrap(), Vec::new());
+}
+
+#[test]
+fn test_gaps_become_breaks() {
+    let result = subtitles_to_markup(SRT);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "Hello, world. Still here.${break|time=10s}${break|time=2s}A & B \\${x}"
+    );
+}
+
+#[test]
+fn test_from_subtitles() {
+    let result = from_subtitles(
+        "00:00.000 --> 00:01.000\nOne\n\n00:01.500 --> 00:02.000\nTwo\n",
+        &ParseOptions {
+            target: OutputTarget::Google,
+            ..Default::default()
+        },
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "<speak>One<break time=\"500ms\"/>Two</speak>"
+    );
+}
@@ -0,0 +1,157 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::compare::ssml_eq;
+use text_to_polly_ssml::parser::{
+    parse_as_ssml_with_options, parse_tree, render_tree, ssml_to_markup, SsmlElement, SsmlNode,
+};
+use text_to_polly_ssml::{OutputTarget, ParseOptions, UnknownTagPolicy};
+
+#[test]
+fn test_tree_structure() {
+    let result =
+        parse_tree(r#"Hi ${p}${s|x=y}one ${break|time=1s}${/s}${s}two${/p} after ${/prosody}"#);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let mut first_sentence = SsmlElement::new("s");
+    first_sentence.params.insert("x".to_owned(), "y".to_owned());
+    let mut time_break = SsmlElement::new("break");
+    time_break.params.insert("time".to_owned(), "1s".to_owned());
+    first_sentence.children = vec![
+        SsmlNode::Text("one ".to_owned()),
+        SsmlNode::Element(time_break),
+    ];
+    let mut second_sentence = SsmlElement::new("s");
+    second_sentence.children = vec![SsmlNode::Text("two".to_owned())];
+    let mut paragraph = SsmlElement::new("p");
+    paragraph.children = vec![
+        SsmlNode::Element(first_sentence),
+        SsmlNode::Element(second_sentence),
+    ];
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            SsmlNode::Text("Hi ".to_owned()),
+            SsmlNode::Element(paragraph),
+            SsmlNode::Text(" after ".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_tree_manipulation_and_rendering() {
+    let mut tree = parse_tree(r#"${p}Hello${/p}"#).unwrap();
+    if let SsmlNode::Element(ref mut paragraph) = tree[0] {
+        let mut whisper = SsmlElement::new("amazon:effect");
+        whisper
+            .params
+            .insert("name".to_owned(), "whispered".to_owned());
+        whisper.children = paragraph.children.drain(..).collect();
+        paragraph.children.push(SsmlNode::Element(whisper));
+    }
+    let result = render_tree(&tree, &Default::default());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><p><amazon:effect name="whispered">Hello</amazon:effect></p></speak>"#
+    );
+}
+
+#[test]
+fn test_tree_unknown_tag_rendering() {
+    let tree = parse_tree(r#"${p}${brake|time=1s}Hello${/brake}${/p}"#).unwrap();
+    let options = ParseOptions {
+        unknown_tags: UnknownTagPolicy::Literal,
+        ..Default::default()
+    };
+    let result = render_tree(&tree, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        r#"<?xml version="1.0"?><speak xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><p>${brake|time=1s}Hello${/brake}</p></speak>"#
+    );
+}
+
+#[test]
+fn test_tree_markup_round_trip() {
+    let mut element = SsmlElement::new("sub");
+    element
+        .params
+        .insert("alias".to_owned(), r#"a|b=c}${d}"#.to_owned());
+    let tree = parse_tree(&format!("{}Hi${{/sub}}", element.markup())).unwrap();
+    match tree.first() {
+        Some(SsmlNode::Element(parsed)) => assert_eq!(parsed.params, element.params),
+        other => panic!("Expected an element, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_ssml_to_markup() {
+    let ssml = "<?xml version=\"1.0\"?>\n<speak xml:lang=\"en-US\" xmlns=\"http://www.w3.org/2001/10/synthesis\">\n<p xml:lang=\"fr-FR\">Tom &amp; ${Jerry}<mark name=\"x\"/></p><mstts:silence type=\"Leading\" value=\"1s\"/><!-- note --><s>two</s>\n</speak>\n";
+    let result = ssml_to_markup(ssml);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let markup = result.unwrap();
+    assert_eq!(
+        markup,
+        r#"${p|lang=fr-FR}Tom & \${Jerry}${mark|name=x}${/p}${silence|type=Leading|value=1s}${s}two${/s}"#
+    );
+
+    let options = ParseOptions {
+        target: OutputTarget::Generic,
+        ..Default::default()
+    };
+    let rendered = parse_as_ssml_with_options(&markup, &options);
+    assert!(rendered.is_ok(), "Result is not okay:\n\n{:?}", rendered);
+    assert!(ssml_eq(
+        &rendered.unwrap(),
+        r#"<speak version="1.1" xml:lang="en-US" onlangfailure="processorchoice" xmlns="http://www.w3.org/2001/10/synthesis" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><p xml:lang="fr-FR">Tom &amp; ${Jerry}<mark name="x"/></p><s>two</s></speak>"#,
+    )
+    .unwrap());
+}
+
+#[test]
+fn test_ssml_to_markup_invalid() {
+    assert!(ssml_to_markup("<speak><p>hey</s></speak>").is_err());
+}
+
+#[test]
+fn test_tree_spans() {
+    let markup = "Hi ${p}${prosody|rate=slow|volume=loud}one${/prosody}\n${s}two${/p}";
+    let tree = parse_tree(markup).unwrap();
+    let paragraph = match &tree[1] {
+        SsmlNode::Element(paragraph) => paragraph,
+        SsmlNode::Text(_) => panic!("expected an element"),
+    };
+    assert_eq!(paragraph.span.unwrap().snippet(markup), "${p}");
+    assert_eq!(paragraph.end_span.unwrap().snippet(markup), "${/p}");
+    assert_eq!(paragraph.full_span().unwrap().snippet(markup), &markup[3..]);
+
+    let prosody = match &paragraph.children[0] {
+        SsmlNode::Element(prosody) => prosody,
+        SsmlNode::Text(_) => panic!("expected an element"),
+    };
+    assert_eq!(
+        prosody.span.unwrap().snippet(markup),
+        "${prosody|rate=slow|volume=loud}"
+    );
+    assert_eq!(prosody.end_span.unwrap().snippet(markup), "${/prosody}");
+    let volume = prosody.param_spans["volume"];
+    assert_eq!(volume.snippet(markup), "volume=loud");
+    assert_eq!((volume.line, volume.column), (1, 28));
+
+    // The sentence is closed by the end of the paragraph, so it has no end tag of its own.
+    let sentence = match &paragraph.children[2] {
+        SsmlNode::Element(sentence) => sentence,
+        SsmlNode::Text(_) => panic!("expected an element"),
+    };
+    assert_eq!(
+        (sentence.span.unwrap().line, sentence.span.unwrap().column),
+        (2, 1)
+    );
+    assert_eq!(sentence.end_span, None);
+    assert_eq!(sentence.full_span(), sentence.span);
+
+    // Spans don't matter when comparing trees.
+    let mut created = SsmlElement::new("s");
+    created.children = vec![SsmlNode::Text("two".to_owned())];
+    assert_eq!(*sentence, created);
+    assert_eq!(created.span, None);
+}
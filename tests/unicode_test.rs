@@ -0,0 +1,38 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{parse_as_ssml_chunked, NormalizationForm};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+#[test]
+fn test_normalize() {
+    assert_eq!(NormalizationForm::Nfc.normalize("Cafe\u{301}"), "Caf\u{e9}");
+    assert_eq!(NormalizationForm::Nfc.normalize("\u{fb01}"), "\u{fb01}");
+    assert_eq!(NormalizationForm::Nfkc.normalize("\u{ff21}\u{fb01}"), "Afi");
+    assert_eq!(NormalizationForm::Nfkc.normalize(""), "");
+}
+
+#[test]
+fn test_normalization_option() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        normalization: Some(NormalizationForm::Nfc),
+        ..Default::default()
+    };
+    let result = parse_str_with_options(
+        "${phoneme|alphabet=ipa|ph=kafe\u{301}}Cafe\u{301}${/phoneme}",
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(
+        result.unwrap(),
+        "<speak><phoneme alphabet=\"ipa\" ph=\"kaf\u{e9}\">Caf\u{e9}</phoneme></speak>"
+    );
+
+    let options = ParseOptions {
+        normalization: Some(NormalizationForm::Nfkc),
+        ..options
+    };
+    let result = parse_as_ssml_chunked("\u{fb01}ne", 100, &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(result.unwrap(), vec!["<speak>fine</speak>"]);
+}
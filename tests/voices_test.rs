@@ -0,0 +1,95 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::parse_as_ssml_with_report;
+use text_to_polly_ssml::ssml_constants::{AmazonDomainNames, AmazonEffect};
+use text_to_polly_ssml::voices::{check_voice, find_voice, voices_for_language, VOICES};
+use text_to_polly_ssml::{Engine, ParseOptions, WarningKind};
+
+#[test]
+fn test_voice_catalog() {
+    for voice in VOICES {
+        assert!(!voice.languages.is_empty(), "{} has no language", voice.id);
+        assert!(!voice.engines.is_empty(), "{} has no engine", voice.id);
+        assert_eq!(find_voice(voice.id), Some(voice));
+    }
+    assert_eq!(find_voice("Nobody"), None);
+
+    let joanna = find_voice("JOANNA").unwrap();
+    assert_eq!(joanna.language(), "en-US");
+    assert!(joanna.supports_engine(Engine::Neural));
+    assert!(!joanna.supports_engine(Engine::LongForm));
+    assert!(joanna.supports_domain(AmazonDomainNames::News));
+    assert!(joanna.supports_effect(AmazonEffect::Whispered));
+
+    let kevin = find_voice("Kevin").unwrap();
+    assert!(!kevin.supports_domain(AmazonDomainNames::News));
+    assert!(!kevin.supports_effect(AmazonEffect::Whispered));
+    assert!(kevin.supports_effect(AmazonEffect::Drc));
+}
+
+#[test]
+fn test_voices_for_language() {
+    let ids: Vec<&str> = voices_for_language("hi-in")
+        .iter()
+        .map(|voice| voice.id)
+        .collect();
+    assert_eq!(ids, vec!["Aditi", "Kajal"]);
+    assert!(voices_for_language("xx-XX").is_empty());
+}
+
+#[test]
+fn test_check_voice() {
+    let markup = "${amazon:domain|name=news}Breaking.${/amazon:domain} \
+                  ${amazon:effect|name=whispered}psst${/amazon:effect} \
+                  ${emphasis}Now${/emphasis}";
+
+    let result = check_voice(markup, find_voice("Matthew").unwrap());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert!(result.unwrap().is_empty());
+
+    let result = check_voice(markup, find_voice("Kevin").unwrap());
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let warnings: Vec<(WarningKind, String, Option<String>)> = result
+        .unwrap()
+        .into_iter()
+        .map(|warning| (warning.kind, warning.tag, warning.attribute))
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (
+                WarningKind::UnsupportedByVoice,
+                "amazon:domain".to_owned(),
+                Some("name".to_owned())
+            ),
+            (
+                WarningKind::UnsupportedByVoice,
+                "amazon:effect".to_owned(),
+                Some("name".to_owned())
+            ),
+            (WarningKind::UnsupportedByVoice, "emphasis".to_owned(), None),
+        ]
+    );
+}
+
+#[test]
+fn test_voice_option_still_renders() {
+    let options = ParseOptions {
+        voice: find_voice("Brian").copied(),
+        ..Default::default()
+    };
+    let result = parse_as_ssml_with_report(
+        "${amazon:domain|name=news}Breaking.${/amazon:domain}",
+        &options,
+    );
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    let report = result.unwrap();
+    assert!(report
+        .ssml
+        .contains(r#"<amazon:domain name="news">Breaking.</amazon:domain>"#));
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(
+        report.warnings[0].message,
+        "The `name` parameter of `amazon:domain` isn't supported by the Brian voice."
+    );
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "wasm")]
+
+extern crate text_to_polly_ssml;
+
+// Only the paths that don't create JavaScript values can run outside of a wasm runtime, so
+// errors aren't covered here.
+use text_to_polly_ssml::wasm::{parse_str, parse_str_with_options, ParseOptions};
+
+#[test]
+fn test_wasm_parse_str() {
+    let ssml = parse_str("${s}Hello${/s}").unwrap_or_else(|_| panic!("Failed to parse"));
+    assert!(ssml.contains("<s>Hello</s>"), "{}", ssml);
+}
+
+#[test]
+fn test_wasm_parse_options() {
+    let mut options = ParseOptions::new();
+    assert_eq!(options.target(), "polly");
+    options
+        .set_target("generic")
+        .unwrap_or_else(|_| panic!("Failed to set the target"));
+    options.set_lang(Some("en-GB".to_owned()));
+    options.set_pretty(true);
+    assert_eq!(options.target(), "generic");
+    assert_eq!(options.lang().as_deref(), Some("en-GB"));
+    assert!(options.pretty());
+
+    let ssml = parse_str_with_options("${p}Hi${/p}", &options)
+        .unwrap_or_else(|_| panic!("Failed to parse"));
+    assert!(ssml.contains("xml:lang=\"en-GB\""), "{}", ssml);
+    assert!(ssml.contains("\n  <p>Hi</p>\n"), "{}", ssml);
+}
@@ -0,0 +1,50 @@
+extern crate text_to_polly_ssml;
+
+use text_to_polly_ssml::parser::{normalize_whitespace, WhitespaceOptions};
+use text_to_polly_ssml::{parse_str_with_options, OutputTarget, ParseOptions};
+
+fn normalize(markup: &str, collapse: bool, trim: bool) -> String {
+    let result = normalize_whitespace(markup, &WhitespaceOptions { collapse, trim });
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    result.unwrap()
+}
+
+#[test]
+fn test_collapse() {
+    assert_eq!(
+        normalize(" Some \t words,\n\n  more ${s} words ${/s} ", true, false),
+        " Some words, more ${s} words ${/s} "
+    );
+    assert_eq!(normalize("", true, false), "");
+}
+
+#[test]
+fn test_trim() {
+    assert_eq!(
+        normalize(
+            "\n ${s} some  words. ${/s} ${s}${emphasis} more ${/emphasis} words${/s}\n",
+            false,
+            true
+        ),
+        "${s}some  words.${/s} ${s}${emphasis}more${/emphasis} words${/s}"
+    );
+    assert_eq!(
+        normalize("${p}\n\t${/p}${break|time=1s}", false, true),
+        "${p}${/p}${break|time=1s}"
+    );
+}
+
+#[test]
+fn test_whitespace_option() {
+    let options = ParseOptions {
+        target: OutputTarget::Google,
+        whitespace: WhitespaceOptions {
+            collapse: true,
+            trim: true,
+        },
+        ..Default::default()
+    };
+    let result = parse_str_with_options("${s} some\n words. ${/s}", &options);
+    assert!(result.is_ok(), "Result is not okay:\n\n{:?}", result);
+    assert_eq!(result.unwrap(), "<speak><s>some words.</s></speak>");
+}